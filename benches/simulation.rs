@@ -86,12 +86,12 @@ fn create_kaula_universe() -> posidonius::Universe {
     //////////////////////////////////////////////////////////////////////////////////
     // Load star data
     let star_file = "./input/love_numbers/Aurelie_Stellar/alpha0.51600_P1p2_Ek1p5em6.txt";
-    let star_tidal_model_params = common::load_kaula_parameters(star_file).unwrap();
-    let star_tides = posidonius::Tides::new(posidonius::TidesEffect::CentralBody(posidonius::TidalModel::Kaula(star_tidal_model_params)));
+    let star_love_number_data = common::load_love_number_data(star_file).unwrap();
+    let star_tides = posidonius::Tides::new(posidonius::TidesEffect::CentralBody(posidonius::TidalModel::Kaula(posidonius::KaulaParameters {})));
     // Load planet data
     let planet_file = "./input/love_numbers/TRAPPIST-1_Earth-like/Results_Trappist1_b_Fe_90_Si_02_670K_freq_Imk2_posidonius.txt";
-    let planet_tidal_model_params = common::load_kaula_parameters(planet_file).unwrap();
-    let planet_tides = posidonius::Tides::new(posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::Kaula(planet_tidal_model_params)));
+    let planet_love_number_data = common::load_love_number_data(planet_file).unwrap();
+    let planet_tides = posidonius::Tides::new(posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::Kaula(posidonius::KaulaParameters {})));
     //
     if let Some((star, planets)) = particles.split_first_mut() {
         // Change from constant time lag to kaula:
@@ -102,6 +102,10 @@ fn create_kaula_universe() -> posidonius::Universe {
     }
     //////////////////////////////////////////////////////////////////////////////////
     let mut universe = posidonius::Universe::new(initial_time, time_limit, particles, consider_effects);
+    universe.set_love_number_table(posidonius::LoveNumberTable::new(0, star_love_number_data, posidonius::LoveNumberInterpolation::Linear)).unwrap();
+    for planet_index in 1..universe.n_particles {
+        universe.set_love_number_table(posidonius::LoveNumberTable::new(planet_index, planet_love_number_data.clone(), posidonius::LoveNumberInterpolation::Linear)).unwrap();
+    }
     let current_time = 0.;
     // initialize_physical_values
     let evolution = true;
@@ -220,5 +224,17 @@ fn criterion_benchmark_universe(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark_universe);
+fn criterion_benchmark_kaula_functions(c: &mut Criterion) {
+    // Computed for every Kaula and creep tidal force unless they are kept in the cache
+    let inclination = 0.1;
+    let mut group = c.benchmark_group("kaula_functions");
+    for eccentricity in [0.01, 0.1, 0.5].iter() {
+        group.bench_function(format!("new_e{}", eccentricity), |b| b.iter(|| posidonius::KaulaFunctions::new(2, *eccentricity, inclination, posidonius::constants::KAULA_ECCENTRICITY_FUNCTION_TOLERANCE)));
+        let mut kaula_functions_cache = posidonius::KaulaFunctionsCache::default();
+        group.bench_function(format!("cached_e{}", eccentricity), |b| b.iter(|| kaula_functions_cache.kaula_functions(1, 0, 2, *eccentricity, inclination).q_max));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark_kaula_functions, criterion_benchmark_universe);
 criterion_main!(benches);
@@ -174,8 +174,8 @@ if __name__ == "__main__":
 
 
     planets = ['b', 'c', 'd', 'e', 'f', 'g', 'h']
-    for planet, r, m, t, i, l, e, p, n, w_lmpq, ImK2, ReK2, size, gyration_radius_2 in zip(
-        planets, planet_radiuses, planet_masses, planet_period, planet_i, planet_l, planet_e, planet_p, planet_n, planet_w_lm, planet_Imk2, planet_Rek2, planet_nm_data, planet_gyration_radius_squared):
+    for planet_index, (planet, r, m, t, i, l, e, p, n, w_lmpq, ImK2, ReK2, size, gyration_radius_2) in enumerate(zip(
+        planets, planet_radiuses, planet_masses, planet_period, planet_i, planet_l, planet_e, planet_p, planet_n, planet_w_lm, planet_Imk2, planet_Rek2, planet_nm_data, planet_gyration_radius_squared), start=1): # The star is the first particle
         #print(f"\n ===================== Planet {planet} ===================== ")
         m = m *posidonius.constants.M_SUN
         planet_mass = m /posidonius.constants.M_SUN # Solar masses (3.0e-6 solar masses = 1 earth mass)
@@ -217,20 +217,8 @@ if __name__ == "__main__":
         #     "love_number": 0.4 ,#0.299,
         # }
 
-        expected_size = 1024
-        if size > expected_size:
-            raise Exception("This should not happen")
-
-        # --- Kaula parameters
-        planet_kaula_tidal_parameters_love_numbers = {
-            "love_number_excitation_frequency": w_lmpq.tolist() + [0] * (expected_size - len(w_lmpq)),
-            "imaginary_part_love_number": ImK2.tolist() + [0] * (expected_size - len(ImK2)),
-            "real_part_love_number": ReK2.tolist() + [0] * (expected_size - len(ReK2)),
-            "num_datapoints": float(expected_size),
-        }
-
         # --- Choose the tidal model to use :
-        planet_tides_model = posidonius.effects.tides.Kaula(planet_kaula_tidal_parameters_love_numbers)
+        planet_tides_model = posidonius.effects.tides.Kaula({}) # Love numbers given by the table set below
         # planet_tides_model = posidonius.effects.tides.ConstantTimeLag(planet_tides_parameters)
 
         # --- Choose the type of particle (central body or orbiting body) :
@@ -290,6 +278,7 @@ if __name__ == "__main__":
         planet.set_disk(planet_disk)
         planet.set_evolution(planet_evolution)
         universe.add_particle(planet)
+        universe.set_love_number_table(planet_index, excitation_frequency=w_lmpq, real_part=ReK2, imaginary_part=ImK2)
 
 
     ############################################################################
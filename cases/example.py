@@ -145,6 +145,8 @@ if __name__ == "__main__":
         "love_number": 0.305,
     }
     planet_tides_model = posidonius.effects.tides.ConstantTimeLag(planet_tides_parameters)
+    #planet_tides_model = posidonius.effects.tides.ConstantPhaseLag({"quality_factor": 100., "love_number": 0.305}) # Constant Q
+    #planet_tides_model = posidonius.effects.tides.Kaula({}) # Love numbers computed from the interior structure (see below)
     #planet_tides = posidonius.effects.tides.CentralBody(planet_tides_model)
     planet_tides = posidonius.effects.tides.OrbitingBody(planet_tides_model)
     #planet_tides = posidonius.effects.tides.Disabled()
@@ -199,6 +201,14 @@ if __name__ == "__main__":
     planet.set_disk(planet_disk)
     planet.set_evolution(planet_evolution)
     universe.add_particle(planet)
+    # Interior structure for the Kaula model: liquid core and Andrade mantle (S.I. units)
+    #planet_radius_m = planet_radius * posidonius.constants.AU
+    #universe.set_interior_structure(1, [
+    #    posidonius.effects.rheology.Layer(0.55*planet_radius_m, 10750., 0., 0., posidonius.effects.rheology.Fluid()),
+    #    posidonius.effects.rheology.Layer(planet_radius_m, 4400., 8.0e10, 1.0e21, posidonius.effects.rheology.Andrade(0.3, 1.0e21/8.0e10)),
+    #])
+    # or precomputed Love numbers of any resolution (columns: frequency [rad/s], -Im(k2), -Re(k2))
+    #universe.set_love_number_table(1, filename="love_numbers.txt", interpolation="LogLog")
 
     whfast_alternative_coordinates="DemocraticHeliocentric"
     #whfast_alternative_coordinates="WHDS"
@@ -1,10 +1,12 @@
 import os
+import json
 import pandas as pd
 import numpy as np
 from numpy.lib.recfunctions import append_fields
 import struct
 import posidonius.tools
 import posidonius.constants
+import posidonius.integrator.stream
 from posidonius.particles.axes import Axes
 
 #-------------------------------------------------------------------------------
@@ -18,6 +20,10 @@ def read(filename):
     if not os.path.exists(filename):
         raise Exception("File does not exists!")
 
+    # 'love_number' is the Love number of the constant time lag and constant phase lag models (zero for
+    # the other models, older versions only filled it for the constant time lag model) and 'denergy_dt'
+    # of the constant phase lag, creep coplanar, creep and Kaula models is the power of the tidal force and torque,
+    # which only equals the dissipated power on average over an orbit
     fields = ('current_time', 'time_step', 'particle', 'position_x', 'position_y', 'position_z', 'spin_x', 'spin_y', 'spin_z', 'velocity_x', 'velocity_y', 'velocity_z', 'mass', 'radius', 'radius_of_gyration_2', 'love_number', 'scaled_dissipation_factor', 'lag_angle', 'denergy_dt', 'migration_timescale', )
 
     data = []
@@ -35,6 +41,17 @@ def read(filename):
     if len(data) == 0:
         raise Exception("Empty file!")
 
+    particle_set_changes = read_particle_set_changes(filename)
+    if len(particle_set_changes) > 0:
+        # Particles were added/removed: the number of lines per snapshot changes,
+        # discard the last snapshot only if it is incomplete
+        n_particles = particle_set_changes[-1]['n_particles']
+        last_snapshot = data['current_time'] == data.iloc[-1]['current_time']
+        if last_snapshot.sum() < n_particles:
+            data = data[~last_snapshot]
+        data = data.to_records()
+        return n_particles, data
+
     # Force to always have N lines per snapshot corresponding to N particles
     n_particles = int(data['particle'].max())+1
     outer_particles = n_particles-1
@@ -45,6 +62,70 @@ def read(filename):
     data = data.to_records()
     return n_particles, data
 
+def read_particle_set_changes(filename):
+    """
+    Read the particles added/removed during the simulation (e.g., case_history.particle_set_changes.json
+    next to case_history.bin), particle identifiers are always the position in the particles array
+    so they are shifted after every removal. Returns an empty list if nothing changed.
+    """
+    particle_set_changes_filename = os.path.splitext(filename)[0] + ".particle_set_changes.json"
+    if not os.path.exists(particle_set_changes_filename):
+        return []
+    with open(particle_set_changes_filename, "r") as f:
+        return json.load(f)
+
+def read_applied_events(filename):
+    """
+    Read the scheduled events applied during the simulation (e.g., case_history.events.json
+    next to case_history.bin). Returns an empty list if no event was applied.
+    """
+    applied_events_filename = os.path.splitext(filename)[0] + ".events.json"
+    if not os.path.exists(applied_events_filename):
+        return []
+    with open(applied_events_filename, "r") as f:
+        return json.load(f)
+
+def read_output_stream(filename, fields, averaged=False):
+    """
+    Read a file generated by an output stream (e.g., case_history.dense.bin),
+    'fields' must be the same list (and order) used to define the stream.
+    Averaged streams have three columns per value with suffixes _mean, _min and _max.
+    """
+    if not os.path.exists(filename):
+        raise Exception("File does not exists!")
+
+    columns = ['current_time', 'time_step', 'particle']
+    for field in fields:
+        # From CamelCase to snake_case
+        name = "".join(["_"+c.lower() if c.isupper() else c for c in field]).lstrip("_")
+        if posidonius.integrator.stream.FIELDS[field] == 3:
+            names = [name+"_x", name+"_y", name+"_z"]
+        else:
+            names = [name]
+        if averaged:
+            for name in names:
+                columns += [name+"_mean", name+"_min", name+"_max"]
+        else:
+            columns += names
+
+    f = open(filename, "rb")
+    data = []
+    while True:
+        try:
+            row = f.read(8+8+4+8*(len(columns)-3))
+            vrow = struct.unpack('< d d i' + ' d'*(len(columns)-3), row)
+        except:
+            break
+        else:
+            data.append(vrow)
+    f.close()
+
+    data = pd.DataFrame(data, columns=columns, index=np.arange(len(data)))
+    if len(data) == 0:
+        raise Exception("Empty file!")
+    n_particles = int(data['particle'].max())+1
+    return n_particles, data.to_records()
+
 def classify(n_particles, data, reference_particle_index=0, discard_first_hundred_years=False):
     # Ignore first 100 years
     data['current_time'] /= 365.25 # From days to years
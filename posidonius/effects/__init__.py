@@ -2,5 +2,6 @@ import posidonius.effects.disk
 import posidonius.effects.general_relativity
 import posidonius.effects.rotational_flattening
 import posidonius.effects.tides
+import posidonius.effects.rheology
 import posidonius.effects.wind
 import posidonius.effects.evolution
@@ -0,0 +1,74 @@
+# Interior structure (from the center to the surface) used to compute the Love numbers of the Kaula
+# tidal model, all the values are in S.I. units (m, kg/m^3, Pa, Pa.s, s)
+
+class Fluid(object):
+    # Only for the innermost layer (i.e., liquid core)
+    def __init__(self):
+        self._data = "Fluid"
+
+    def get(self):
+        return self._data
+
+class Maxwell(object):
+    def __init__(self):
+        self._data = "Maxwell"
+
+    def get(self):
+        return self._data
+
+class Burgers(object):
+    def __init__(self, transient_rigidity, transient_viscosity):
+        self._data = {
+            "Burgers": {
+                "transient_rigidity": float(transient_rigidity),
+                "transient_viscosity": float(transient_viscosity),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class Andrade(object):
+    def __init__(self, alpha, andrade_timescale):
+        if not 0. < alpha < 1.:
+            raise Exception("The Andrade exponent must be between 0 and 1 ({})".format(alpha))
+        self._data = {
+            "Andrade": {
+                "alpha": float(alpha),
+                "andrade_timescale": float(andrade_timescale),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class SundbergCooper(object):
+    def __init__(self, alpha, andrade_timescale, transient_rigidity, transient_viscosity):
+        if not 0. < alpha < 1.:
+            raise Exception("The Andrade exponent must be between 0 and 1 ({})".format(alpha))
+        self._data = {
+            "SundbergCooper": {
+                "alpha": float(alpha),
+                "andrade_timescale": float(andrade_timescale),
+                "transient_rigidity": float(transient_rigidity),
+                "transient_viscosity": float(transient_viscosity),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class Layer(object):
+    # Zero bulk modulus for incompressible layers, rigidity and viscosity are ignored for fluid layers
+    def __init__(self, outer_radius, density, rigidity, viscosity, rheology, bulk_modulus=0.):
+        self._data = {
+            "outer_radius": float(outer_radius),
+            "density": float(density),
+            "rigidity": float(rigidity),
+            "viscosity": float(viscosity),
+            "bulk_modulus": float(bulk_modulus),
+            "rheology": rheology.get(),
+        }
+
+    def get(self):
+        return self._data.copy()
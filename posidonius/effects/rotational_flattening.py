@@ -93,3 +93,23 @@ class CreepCoplanar(object):
         else:
             return self._data.copy()
 
+
+class Creep(object):
+    def __init__(self, input_parameters):
+        self._data = {
+            "Creep": {
+                "uniform_viscosity_coefficient": 0.0,
+            },
+        }
+        # Update default values, ignore non-recognised keys
+        for key, value in six.iteritems(input_parameters):
+            if key in self._data["Creep"]:
+                self._data["Creep"][key] = float(value)
+            else:
+                print("Ignored parameter: {}".format(key))
+
+    def get(self):
+        if type(self._data) == str:
+            return self._data
+        else:
+            return self._data.copy()
@@ -80,6 +80,27 @@ class ConstantTimeLag(object):
         else:
             return self._data.copy()
 
+class ConstantPhaseLag(object):
+    def __init__(self, input_parameters):
+        self._data = {
+            "ConstantPhaseLag": {
+                "quality_factor": 0.0,
+                "love_number": 0.0,
+            },
+        }
+        # Update default values, ignore non-recognised keys
+        for key, value in six.iteritems(input_parameters):
+            if key in self._data["ConstantPhaseLag"]:
+                self._data["ConstantPhaseLag"][key] = float(value)
+            else:
+                print("Ignored parameter: {}".format(key))
+
+    def get(self):
+        if type(self._data) == str:
+            return self._data
+        else:
+            return self._data.copy()
+
 class CreepCoplanar(object):
     def __init__(self, input_parameters):
         self._data = {
@@ -100,24 +121,17 @@ class CreepCoplanar(object):
         else:
             return self._data.copy()
 
-class Kaula(object):
+class Creep(object):
     def __init__(self, input_parameters):
         self._data = {
-            "Kaula": {
-                "love_number_excitation_frequency": [0.] * 1024,
-                "imaginary_part_love_number": [0.] * 1024,
-                "real_part_love_number": [0.] * 1024,
-                "num_datapoints": 0.0,
-                "kaula_tidal_force": Axes( 0.0, 0.0, 0.0).get(),
+            "Creep": {
+                "uniform_viscosity_coefficient": 0.0,
             },
         }
         # Update default values, ignore non-recognised keys
         for key, value in six.iteritems(input_parameters):
-            if key in self._data["Kaula"]:
-                if isinstance(value, (tuple, list)):
-                    self._data["Kaula"][key] = [float(v) for v in value]
-                else:
-                    self._data["Kaula"][key] = float(value)
+            if key in self._data["Creep"]:
+                self._data["Creep"][key] = float(value)
             else:
                 print("Ignored parameter: {}".format(key))
 
@@ -126,3 +140,19 @@ class Kaula(object):
             return self._data
         else:
             return self._data.copy()
+
+class Kaula(object):
+    def __init__(self, input_parameters=None):
+        # The Love numbers are given per particle with Universe.set_love_number_table or computed
+        # from its interior structure (Universe.set_interior_structure)
+        self._data = {
+            "Kaula": {},
+        }
+        for key in (input_parameters or {}):
+            print("Ignored parameter: {}".format(key))
+
+    def get(self):
+        if type(self._data) == str:
+            return self._data
+        else:
+            return self._data.copy()
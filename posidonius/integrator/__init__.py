@@ -3,3 +3,6 @@ from posidonius.integrator.leapfrog import LeapFrog
 from posidonius.integrator.whfast import WHFast
 from posidonius.integrator.ias15 import Ias15
 
+from posidonius.integrator.stream import OutputStream, LinearSchedule, LogarithmicSchedule, ExplicitSchedule
+from posidonius.integrator.stopping import SemiMajorAxisBelow, SemiMajorAxisAbove, EccentricityAbove, SpinSynchronization, PeriodRatioWindow, EnergyError, WallTime
+from posidonius.integrator.events import ScheduledEvent, DisableEffect, SetDissipationFactor, SetTidesEffect, SetTimeStep, SetHistoricSnapshotPeriod, AddParticle, RemoveParticle
@@ -12,7 +12,31 @@ class Integrator(object):
         self._data['last_recovery_snapshot_time'] = -1.0
         self._data['last_historic_snapshot_time'] = -1.0
         self._data['n_historic_snapshots'] = 0
-        self._data['hash'] = 0
+
+    def set_output_streams(self, output_streams):
+        # Only included when used to keep the JSON of classic cases unchanged
+        if len(output_streams) > 0:
+            self._data['output_streams'] = [output_stream.get() for output_stream in output_streams]
+
+    def set_event_timeline(self, events):
+        # Only included when used to keep the JSON of classic cases unchanged
+        if len(events) > 0:
+            self._data['event_timeline'] = {
+                # Stable sort: events with the same time are applied in the given order
+                "events": [event.get() for event in sorted(events, key=lambda event: event.get()['time'])],
+                "next_index": 0,
+            }
+
+    def set_stopping_conditions(self, stopping_conditions, evaluation):
+        # Only included when used to keep the JSON of classic cases unchanged
+        if len(stopping_conditions) > 0:
+            self._data['stopping_conditions'] = {
+                "conditions": [stopping_condition.get() for stopping_condition in stopping_conditions],
+                "evaluation": evaluation,
+                "reference_energy": None,
+                "elapsed_wall_time": 0.0,
+                "stop_reason": None,
+            }
 
     def write(self, filename):
         json.dump(self._data, open(filename, "w"), indent=2, sort_keys=True)
@@ -0,0 +1,132 @@
+EFFECTS = ("Tides", "RotationalFlattening", "GeneralRelativity", "Disk", "Wind", "Evolution")
+ORBITAL_ELEMENTS = ("semi_major_axis", "eccentricity", "inclination", "argument_of_perihelion", "longitude_of_ascending_node", "mean_anomaly")
+
+class DisableEffect(object):
+    def __init__(self, effect):
+        if effect not in EFFECTS:
+            raise Exception("Unknown effect '{}' (it should be one of {})".format(effect, ", ".join(EFFECTS)))
+        self._data = {
+            "DisableEffect": {
+                "effect": effect,
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class SetDissipationFactor(object):
+    # Only for particles with the constant time lag tidal model
+    def __init__(self, particle_index, dissipation_factor):
+        if dissipation_factor < 0:
+            raise Exception("The dissipation factor cannot be negative")
+        self._data = {
+            "SetDissipationFactor": {
+                "particle": int(particle_index),
+                "dissipation_factor": float(dissipation_factor),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class SetTidesEffect(object):
+    # The tides argument is a posidonius.effects.tides object (e.g., OrbitingBody)
+    def __init__(self, particle_index, tides):
+        self._data = {
+            "SetTidesEffect": {
+                "particle": int(particle_index),
+                "effect": tides.get()["effect"],
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class SetViscosity(object):
+    # Only for particles with an interior structure (see Universe.set_interior_structure), the layer index
+    # goes from the center to the surface and the viscosity is in Pa.s
+    def __init__(self, particle_index, layer_index, viscosity):
+        if viscosity <= 0:
+            raise Exception("The viscosity must be positive")
+        self._data = {
+            "SetViscosity": {
+                "particle": int(particle_index),
+                "layer": int(layer_index),
+                "viscosity": float(viscosity),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class SetTimeStep(object):
+    def __init__(self, time_step):
+        if time_step <= 0:
+            raise Exception("The time step must be positive")
+        self._data = {
+            "SetTimeStep": {
+                "time_step": float(time_step),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class SetHistoricSnapshotPeriod(object):
+    def __init__(self, historic_snapshot_period):
+        if historic_snapshot_period <= 0:
+            raise Exception("The historic snapshot period must be positive")
+        self._data = {
+            "SetHistoricSnapshotPeriod": {
+                "historic_snapshot_period": float(historic_snapshot_period),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class AddParticle(object):
+    # The particle is a posidonius.Particle with its heliocentric position/velocity, unless orbital
+    # elements around its particle of reference are provided (AU and radians):
+    # semi_major_axis, eccentricity, inclination, argument_of_perihelion, longitude_of_ascending_node, mean_anomaly
+    # The new particle is always the last one
+    def __init__(self, particle, orbital_elements=None):
+        if orbital_elements is not None:
+            orbital_elements = {key: float(orbital_elements[key]) for key in ORBITAL_ELEMENTS}
+            if orbital_elements["semi_major_axis"] <= 0 or not 0 <= orbital_elements["eccentricity"] < 1:
+                raise Exception("Only bound orbits can be used to add a particle")
+        self._data = {
+            "AddParticle": {
+                "particle": particle.get(),
+                "orbital_elements": orbital_elements,
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class RemoveParticle(object):
+    # The following particles are shifted one position (also in later events)
+    def __init__(self, particle_index):
+        self._data = {
+            "RemoveParticle": {
+                "particle": int(particle_index),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class ScheduledEvent(object):
+    def __init__(self, time, action):
+        if time != time:
+            raise Exception("The event time cannot be NaN")
+        if time < 0:
+            raise Exception("The event time cannot be negative")
+        self._data = {
+            "time": float(time),
+            "action": action.get(),
+        }
+
+    def get(self):
+        return self._data.copy()
@@ -0,0 +1,78 @@
+EVALUATIONS = ("EveryStep", "HistoricSnapshot")
+
+class StoppingCondition(object):
+    def __init__(self, variant, data):
+        self._data = {
+            variant: data,
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class SemiMajorAxisBelow(StoppingCondition):
+    def __init__(self, particle_index, semi_major_axis):
+        if semi_major_axis <= 0:
+            raise Exception("The semi-major axis must be positive")
+        super(SemiMajorAxisBelow, self).__init__("SemiMajorAxisBelow", {
+            "particle": int(particle_index),
+            "semi_major_axis": float(semi_major_axis),
+        })
+
+class SemiMajorAxisAbove(StoppingCondition):
+    # Unbound orbits (eccentricity >= 1) are also considered above the threshold
+    def __init__(self, particle_index, semi_major_axis):
+        if semi_major_axis <= 0:
+            raise Exception("The semi-major axis must be positive")
+        super(SemiMajorAxisAbove, self).__init__("SemiMajorAxisAbove", {
+            "particle": int(particle_index),
+            "semi_major_axis": float(semi_major_axis),
+        })
+
+class EccentricityAbove(StoppingCondition):
+    def __init__(self, particle_index, eccentricity):
+        if eccentricity < 0:
+            raise Exception("The eccentricity cannot be negative")
+        super(EccentricityAbove, self).__init__("EccentricityAbove", {
+            "particle": int(particle_index),
+            "eccentricity": float(eccentricity),
+        })
+
+class SpinSynchronization(StoppingCondition):
+    # Relative difference between the spin rate and the orbital mean motion
+    def __init__(self, particle_index, tolerance=0.01):
+        if tolerance <= 0:
+            raise Exception("The tolerance must be positive")
+        super(SpinSynchronization, self).__init__("SpinSynchronization", {
+            "particle": int(particle_index),
+            "tolerance": float(tolerance),
+        })
+
+class PeriodRatioWindow(StoppingCondition):
+    # Orbital period of the outer particle divided by the one of the inner particle
+    def __init__(self, inner_particle_index, outer_particle_index, min_period_ratio, max_period_ratio):
+        if min_period_ratio <= 0 or min_period_ratio > max_period_ratio:
+            raise Exception("Invalid period ratio window")
+        super(PeriodRatioWindow, self).__init__("PeriodRatioWindow", {
+            "inner_particle": int(inner_particle_index),
+            "outer_particle": int(outer_particle_index),
+            "min_period_ratio": float(min_period_ratio),
+            "max_period_ratio": float(max_period_ratio),
+        })
+
+class EnergyError(StoppingCondition):
+    # Relative to the total energy at the beginning of the simulation
+    def __init__(self, tolerance):
+        if tolerance <= 0:
+            raise Exception("The tolerance must be positive")
+        super(EnergyError, self).__init__("EnergyError", {
+            "tolerance": float(tolerance),
+        })
+
+class WallTime(StoppingCondition):
+    # Execution time in seconds accumulated over the start and all the resumes
+    def __init__(self, seconds):
+        if seconds <= 0:
+            raise Exception("The wall time must be positive")
+        super(WallTime, self).__init__("WallTime", {
+            "seconds": float(seconds),
+        })
@@ -0,0 +1,89 @@
+import six
+
+FIELDS = {
+    # name: number of stored values
+    "Position": 3,
+    "Velocity": 3,
+    "HeliocentricPosition": 3,
+    "HeliocentricVelocity": 3,
+    "Spin": 3,
+    "Mass": 1,
+    "Radius": 1,
+    "RadiusOfGyration2": 1,
+    "LoveNumber": 1,
+    "ScaledDissipationFactor": 1,
+    "LagAngle": 1,
+    "DenergyDt": 1,
+    "MigrationTimescale": 1,
+    "SemiMajorAxis": 1,
+    "Eccentricity": 1,
+    "Inclination": 1,
+    "Obliquity": 1,
+    "SpinRate": 1,
+}
+
+class LinearSchedule(object):
+    def __init__(self, period):
+        if period <= 0:
+            raise Exception("The period must be positive")
+        self._data = {
+            "Linear": {
+                "period": float(period),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class LogarithmicSchedule(object):
+    # Snapshots at start_time + first_time * 10**(k/snapshots_per_decade) for k = 0, 1, 2...
+    def __init__(self, first_time, snapshots_per_decade):
+        if first_time <= 0 or snapshots_per_decade <= 0:
+            raise Exception("The first time and the number of snapshots per decade must be positive")
+        self._data = {
+            "Logarithmic": {
+                "first_time": float(first_time),
+                "snapshots_per_decade": float(snapshots_per_decade),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class ExplicitSchedule(object):
+    def __init__(self, times):
+        if any(t != t for t in times):
+            raise Exception("The times cannot be NaN")
+        self._data = {
+            "Explicit": {
+                "times": sorted([float(t) for t in times]),
+            }
+        }
+
+    def get(self):
+        return self._data.copy()
+
+class OutputStream(object):
+    def __init__(self, name, schedule, fields, start_time=0., stop_time=-1., averaged=False):
+        # Averaged streams store the time-weighted mean, minimum and maximum of each value
+        # over every output interval instead of the instantaneous value
+        if len(name) == 0 or "." in name or "/" in name:
+            raise Exception("Invalid output stream name '{}' (it cannot be empty nor contain dots or path separators)".format(name))
+        if len(fields) == 0:
+            raise Exception("Output stream '{}' does not have any field to store".format(name))
+        for field in fields:
+            if field not in FIELDS:
+                raise Exception("Unknown output stream field '{}'".format(field))
+        self._data = {
+            "name": name,
+            "schedule": schedule.get(),
+            "fields": list(fields),
+            "start_time": float(start_time),
+            "stop_time": float(stop_time),
+            "next_index": 0,
+            "n_snapshots": 0,
+            "averaged": bool(averaged),
+        }
+
+    def get(self):
+        return self._data.copy()
@@ -8,24 +8,9 @@ from posidonius import effects
 
 class Reference(object):
     def __init__(self, variant, index=None):
-        self._data = {
-            "effect": "Disabled",
-            "parameters": {
-                "input": {
-                    "k_factor": 0.0,
-                    "rotation_saturation": 0.0,
-                },
-                "internal": {
-                    "rotation_saturation_2": 0.0,
-                },
-                "output": {
-                    "factor": 0.0,
-                },
-            },
-        }
         if variant in ("Particle", ):
-            self._data[variant] = int(index)
-        if variant in ("MostMassiveParticle", ):
+            self._data = {variant: int(index)}
+        elif variant in ("MostMassiveParticle", ):
             self._data = variant
         else:
             raise Exception("Unknown variant '{}'".format(variant))
@@ -42,7 +27,7 @@ class MostMassiveParticle(Reference):
 
 class ReferenceParticle(Reference):
     def __init__(self, index):
-        super(ReferenceParticle, self).__init__("ReferenceParticle", index=index)
+        super(ReferenceParticle, self).__init__("Particle", index=index)
 
 
 class Particle(object):
@@ -123,20 +108,29 @@ class Particle(object):
         self.check_uniform_viscosity_coefficient()
 
     def check_uniform_viscosity_coefficient(self):
-        # If creep coplanar tides and rotational flattening are set, both need to have the same
-        # uniform viscosity coefficient parameter
+        # If creep (coplanar or not) tides and rotational flattening are set, both need to use the
+        # same creep model with the same uniform viscosity coefficient parameter
         if self._effects["tides"] and self._effects["rotational_flattening"]:
             disabled_tides = isinstance(self._effects["tides"], effects.tides.Disabled)
             disabled_rotational_flattening = isinstance(self._effects["rotational_flattening"], effects.rotational_flattening.Disabled)
             if not disabled_tides and not disabled_rotational_flattening:
-                creep_coplanar_tides = isinstance(self._effects["tides"]._model, effects.tides.CreepCoplanar)
-                creep_coplanar_rotational_flattening = isinstance(self._effects["rotational_flattening"]._model, effects.rotational_flattening.CreepCoplanar)
-                if (creep_coplanar_tides and not creep_coplanar_rotational_flattening) or (not creep_coplanar_tides and creep_coplanar_rotational_flattening):
-                    raise Exception("When using Creep Coplanar Tidal or rotational flattening effects, both effects need to be Creep Coplanar and not just one of them (e.g., it cannot be mixed with ConstantTimeLag or OblateSpheroid).")
-                elif creep_coplanar_tides and creep_coplanar_rotational_flattening:
-                    uniform_viscosity_coefficient_diff = self._effects["rotational_flattening"]._model._data['CreepCoplanar']['uniform_viscosity_coefficient'] - self._effects["tides"]._model._data['CreepCoplanar']['uniform_viscosity_coefficient']
+                creep_models = {"CreepCoplanar": "Creep Coplanar", "Creep": "Creep"}
+                creep_tides = None
+                creep_rotational_flattening = None
+                for creep_model in creep_models:
+                    if isinstance(self._effects["tides"]._model, getattr(effects.tides, creep_model)):
+                        creep_tides = creep_model
+                    if isinstance(self._effects["rotational_flattening"]._model, getattr(effects.rotational_flattening, creep_model)):
+                        creep_rotational_flattening = creep_model
+                if (creep_tides and not creep_rotational_flattening) or (not creep_tides and creep_rotational_flattening):
+                    creep_model = creep_models[creep_tides or creep_rotational_flattening]
+                    raise Exception("When using {0} Tidal or rotational flattening effects, both effects need to be {0} and not just one of them (e.g., it cannot be mixed with ConstantTimeLag or OblateSpheroid).".format(creep_model))
+                elif creep_tides and creep_rotational_flattening and creep_tides != creep_rotational_flattening:
+                    raise Exception("When using creep Tidal and rotational flattening effects, both effects need to use the same model (i.e., {} tides cannot be mixed with {} rotational flattening).".format(creep_models[creep_tides], creep_models[creep_rotational_flattening]))
+                elif creep_tides and creep_rotational_flattening:
+                    uniform_viscosity_coefficient_diff = self._effects["rotational_flattening"]._model._data[creep_rotational_flattening]['uniform_viscosity_coefficient'] - self._effects["tides"]._model._data[creep_tides]['uniform_viscosity_coefficient']
                     if abs(uniform_viscosity_coefficient_diff) > 1.e-16:
-                        raise Exception("When using Creep Coplanar Tidal and rotational flattening effects, the uniform viscosity coefficient must be identical.")
+                        raise Exception("When using {} Tidal and rotational flattening effects, the uniform viscosity coefficient must be identical.".format(creep_models[creep_tides]))
 
     def set_general_relativity(self, general_relativity):
         self._data["general_relativity"] = general_relativity.get()
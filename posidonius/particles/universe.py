@@ -4,12 +4,21 @@ import math
 import datetime
 from posidonius.particles.axes import Axes
 from posidonius.integrator import WHFast, Ias15, LeapFrog
+from posidonius.integrator.stopping import EVALUATIONS
 from posidonius.constants import *
 from posidonius.effects.evolution import NonEvolving, Leconte2011, Baraffe2015, Baraffe1998, LeconteChabrier2013, BolmontMathis2016, GalletBolmont2017
 from posidonius.tools import calculate_spin, mass_radius_relation, calculate_center_of_mass, linear_interpolation
 import posidonius.effects as effects
 from posidonius.particles.particle import Particle, DummyParticle
 
+def _content_hash(data):
+    # 64-bit FNV-1a hash, the same used by posidonius to verify the files referenced by the simulations
+    content_hash = 0xcbf29ce484222325
+    for byte in bytearray(data):
+        content_hash ^= byte
+        content_hash = (content_hash * 0x100000001b3) & 0xffffffffffffffff
+    return content_hash
+
 class ConsiderEffects(object):
     def __init__(self, input_properties):
         self._data = {
@@ -37,6 +46,11 @@ class Universe(object):
         self._time_step = time_step
         self._recovery_snapshot_period = recovery_snapshot_period
         self._historic_snapshot_period = historic_snapshot_period
+        self._output_streams = []
+        self._events = []
+        self._stopping_conditions = []
+        self._stopping_conditions_evaluation = "EveryStep"
+        self._tidal_hosts = []
         self._data = {
             "consider_effects": consider_effects.get(),
             "general_relativity_implementation": "Disabled",
@@ -63,9 +77,125 @@ class Universe(object):
             "pair_dependent_scaled_dissipation_factor": {},
             "roche_radiuses": [0.,] *  MAX_PARTICLES * MAX_PARTICLES,
             "time_limit": float(time_limit),
+            "tidal_pairs": [],
+            "interior_structures": [],
+            "love_number_tables": [],
         }
 
 
+    def add_output_stream(self, output_stream):
+        for existing_output_stream in self._output_streams:
+            if existing_output_stream.get()['name'] == output_stream.get()['name']:
+                raise Exception("Output stream '{}' already exists".format(output_stream.get()['name']))
+        self._output_streams.append(output_stream)
+
+    def add_event(self, event):
+        # Particle indices in the event actions follow the order in which particles were added
+        if event.get()['time'] > self._data['time_limit']:
+            raise Exception("The event at {} days is beyond the time limit".format(event.get()['time']))
+        self._events.append(event)
+
+    def add_stopping_condition(self, stopping_condition):
+        # Particle indices follow the order in which particles were added
+        self._stopping_conditions.append(stopping_condition)
+
+    def add_tidal_pair(self, host_index, orbiting_index, host_tidal_model, orbiting_tidal_model):
+        # Tides between two particles other than the central body (e.g., second star of a circumbinary
+        # system, planet-planet or planet-moon), particle indices follow the order in which particles were added
+        if host_index == orbiting_index:
+            raise Exception("A particle cannot raise tides on itself ({})".format(host_index))
+        for tidal_pair in self._data['tidal_pairs']:
+            if set((tidal_pair['host'], tidal_pair['orbiting'])) == set((host_index, orbiting_index)):
+                raise Exception("Tides between particles {} and {} were already added".format(host_index, orbiting_index))
+        self._data['tidal_pairs'].append({
+            "host": host_index,
+            "orbiting": orbiting_index,
+            "host_tidal_model": host_tidal_model.get(),
+            "orbiting_tidal_model": orbiting_tidal_model.get(),
+        })
+
+    def add_tidal_host(self, particle_index):
+        # Tides between this particle (e.g., the secondary star of a binary) and every other orbiting body,
+        # it uses the same tidal model it has as orbiting body of the central body
+        if particle_index not in self._tidal_hosts:
+            self._tidal_hosts.append(particle_index)
+
+    def tidal_host_pairs(self):
+        tidal_pairs = list(self._data['tidal_pairs'])
+        for host_index in self._tidal_hosts:
+            if host_index >= self._data['n_particles'] or "OrbitingBody" not in self._data['particles'][host_index]['tides']['effect']:
+                raise Exception("Particle {} cannot be a tidal host because it has no tidal effect (orbiting body)".format(host_index))
+            for i, particle in enumerate(self._data['particles'][:self._data['n_particles']]):
+                if i == host_index or "OrbitingBody" not in particle['tides']['effect']:
+                    continue
+                if any(set((tidal_pair['host'], tidal_pair['orbiting'])) == set((host_index, i)) for tidal_pair in tidal_pairs):
+                    continue
+                # Moons already interact with the particle they orbit
+                if particle['reference'] == {"Particle": host_index} or self._data['particles'][host_index]['reference'] == {"Particle": i}:
+                    continue
+                tidal_pairs.append({
+                    "host": host_index,
+                    "orbiting": i,
+                    "host_tidal_model": self._data['particles'][host_index]['tides']['effect']["OrbitingBody"],
+                    "orbiting_tidal_model": particle['tides']['effect']["OrbitingBody"],
+                })
+        return tidal_pairs
+
+    def set_interior_structure(self, particle_index, layers):
+        # Layers (posidonius.effects.rheology.Layer) from the center to the surface of a particle with the
+        # Kaula tidal model, its Love number tables are computed when the simulation starts
+        tides_effect = self._data['particles'][particle_index]['tides']['effect'] if particle_index < self._data['n_particles'] else "Disabled"
+        if not isinstance(tides_effect, dict) or not any("Kaula" in tidal_model for tidal_model in tides_effect.values()):
+            raise Exception("Particle {} cannot have an interior structure because it does not use the Kaula tidal model".format(particle_index))
+        if len(layers) == 0:
+            raise Exception("The interior structure of particle {} has no layers".format(particle_index))
+        self._data['interior_structures'] = [interior_structure for interior_structure in self._data['interior_structures'] if interior_structure['particle'] != particle_index]
+        self._data['interior_structures'].append({
+            "particle": particle_index,
+            "layers": [layer.get() for layer in layers],
+        })
+
+    def set_love_number_table(self, particle_index, filename=None, excitation_frequency=None, real_part=None, imaginary_part=None, interpolation="Linear"):
+        # Love numbers of a particle with the Kaula tidal model with any number of points, either read
+        # from a file or embedded in the case:
+        # - filename: text file with three columns (excitation frequency, imaginary part, real part) and
+        #   comments starting with '#', or binary file with the magic number "POSLOVE1", the number of points
+        #   (unsigned 64-bit integer) and the points (64-bit floats), all in little-endian byte order.
+        #   The file is read when the simulation starts/resumes and it should not be modified.
+        # - excitation_frequency, real_part and imaginary_part: same columns as the files (sorted by frequency)
+        # - interpolation: "Linear", "LogLog" or "Spline" (monotone cubic)
+        tides_effect = self._data['particles'][particle_index]['tides']['effect'] if particle_index < self._data['n_particles'] else "Disabled"
+        if not isinstance(tides_effect, dict) or not any("Kaula" in tidal_model for tidal_model in tides_effect.values()):
+            raise Exception("Particle {} cannot have a Love number table because it does not use the Kaula tidal model".format(particle_index))
+        if interpolation not in ("Linear", "LogLog", "Spline"):
+            raise Exception("Unknown Love number interpolation '{}' (it should be Linear, LogLog or Spline)".format(interpolation))
+        if filename is not None:
+            filename = os.path.abspath(filename)
+            with open(filename, "rb") as f:
+                source = {"File": {"path": filename, "content_hash": _content_hash(f.read())}}
+        elif excitation_frequency is not None and real_part is not None and imaginary_part is not None:
+            if not len(excitation_frequency) == len(real_part) == len(imaginary_part):
+                raise Exception("The Love number table of particle {} has {} frequencies but {} real and {} imaginary parts".format(particle_index, len(excitation_frequency), len(real_part), len(imaginary_part)))
+            source = {"Embedded": {
+                "excitation_frequency": [float(v) for v in excitation_frequency],
+                "real_part": [float(v) for v in real_part],
+                "imaginary_part": [float(v) for v in imaginary_part],
+            }}
+        else:
+            raise Exception("The Love number table of particle {} needs a filename or the excitation frequency, real and imaginary parts".format(particle_index))
+        self._data['love_number_tables'] = [love_number_table for love_number_table in self._data['love_number_tables'] if love_number_table['particle'] != particle_index]
+        self._data['love_number_tables'].append({
+            "particle": particle_index,
+            "source": source,
+            "interpolation": interpolation,
+        })
+
+    def set_stopping_conditions_evaluation(self, evaluation):
+        # "EveryStep" or "HistoricSnapshot" (only when a historic snapshot is written, the wall time is always checked)
+        if evaluation not in EVALUATIONS:
+            raise Exception("Unknown stopping conditions evaluation '{}' (it should be one of {})".format(evaluation, ", ".join(EVALUATIONS)))
+        self._stopping_conditions_evaluation = evaluation
+
     def add_dummy_particle(self):
         self.add_particle(DummyParticle())
         self._data['n_particles'] -= 1 # Compensate the addition from the previous add_particle call
@@ -325,6 +455,7 @@ class Universe(object):
             self.add_dummy_particle()
 
         data = self._data.copy()
+        data['tidal_pairs'] = self.tidal_host_pairs()
 
         # Reset indices according to enabled effects
         if data["consider_effects"]["rotational_flattening"] and not data["consider_effects"]["tides"]:
@@ -350,15 +481,16 @@ class Universe(object):
     def write(self, filename, integrator="WHFast", whfast_alternative_coordinates="DemocraticHeliocentric"):
         if integrator.lower() == "whfast":
             universe_integrator = WHFast(whfast_alternative_coordinates, self._time_step, self._recovery_snapshot_period, self._historic_snapshot_period, self)
-            universe_integrator.write(filename)
         elif integrator.lower() == "ias15":
             universe_integrator = Ias15(self._time_step, self._recovery_snapshot_period, self._historic_snapshot_period, self)
-            universe_integrator.write(filename)
         elif integrator.lower() == "leapfrog":
             universe_integrator = LeapFrog(self._time_step, self._recovery_snapshot_period, self._historic_snapshot_period, self)
-            universe_integrator.write(filename)
         else:
             raise Exception("Unknown integtrator '{}'".format(integrator))
+        universe_integrator.set_output_streams(self._output_streams)
+        universe_integrator.set_event_timeline(self._events)
+        universe_integrator.set_stopping_conditions(self._stopping_conditions, self._stopping_conditions_evaluation)
+        universe_integrator.write(filename)
         base_filename = os.path.splitext(filename)[0]
         print("[INFO {} UTC] Start the simulation with:".format(datetime.datetime.now(datetime.UTC).strftime("%Y.%m.%d %H:%M:%S")))
         print("posidonius start {} {} {}".format(filename, base_filename+".bin", base_filename+"_history.bin"))
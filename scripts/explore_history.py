@@ -17,6 +17,9 @@ if __name__ == "__main__":
     args = parser.parse_args()
 
     universe_integrator_json = json.load(open(args.start_case_filename, "r"))
+    if "format_version" in universe_integrator_json:
+        # Recovery snapshot with envelope
+        universe_integrator_json = universe_integrator_json['integrator']
 
     filename = args.historic_snapshot_filename
     n_particles, data = posidonius.analysis.history.read(filename)
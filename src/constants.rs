@@ -5,6 +5,8 @@ pub const MAX_DISTANCE : f64 = 100.; // AU
 pub const MAX_DISTANCE_2 : f64 = MAX_DISTANCE*MAX_DISTANCE; // AU (use a negative value to disable)
 //
 pub const MIN_ORBITAL_PERIOD_TIME_STEP_RATIO : f64 = -1.0; // The orbital period should be 5.0 times greater than the time step to correctly integrate an orbit (use a negative value to disable)
+pub const RECOMMENDED_ORBITAL_PERIOD_TIME_STEP_RATIO : f64 = 20.0; // Validation warns when an orbital period is covered by fewer time steps
+pub const CREEP_COPLANAR_MAX_TILT : f64 = 1.0e-6; // Validation warns when the spin or the orbit of a body with a creep coplanar model is tilted more than this (rad) with respect to the z axis
 
 //// Constants for IAS15 integrator (to be ignored for others)
 pub const INTEGRATOR_FORCE_IS_VELOCITYDEPENDENT : bool = true;	// Turn this off to safe some time if the force is not velocity dependent (i.e. radiation forces, tides depend on vel.).
@@ -66,6 +68,10 @@ pub const K2 : f64 = G_MERCURY;
 pub const SUN_DYN_FREQ_2 : f64 = K2/(R_SUN*R_SUN*R_SUN); // Needed for MathisSolarLike
 pub const SMOOTHING_FACTOR_DYN_TIDE_COROTATION : f64 = 1.0e-5*86400.; // smoothing factor from Bolmont and Mathis 2016 (rho in Eq.11). Here rho = 1e-5 s-1.
 
+// Kaula tides
+pub const KAULA_ECCENTRICITY_FUNCTION_TOLERANCE : f64 = 1.0e-8; // eccentricity functions G_lpq smaller than this (relative to the largest one) are neglected when truncating q
+pub const KAULA_FUNCTIONS_CACHE_TOLERANCE : f64 = 1.0e-6; // Kaula functions are computed again when the eccentricity or the inclination (rad) of the orbit changed by more than this
+
 // Boltzmann constant and mass of an hydrogen atom from CODATA 2017
 // Table 3 of Newell et al. 2018 (https://iopscience.iop.org/article/10.1088/1681-7575/aa950a/pdf) 
 pub const BOLTZMANN_CONSTANT_SI : f64 = 1.380649e-23; // J.K-1
@@ -45,6 +45,175 @@ impl Clone for Evolver {
     }
 }
 
+/// Evolution track file required by an evolution type (the mass has to match one of the available tracks)
+pub fn evolution_track_filename(evolution: EvolutionType) -> Result<String, String> {
+    let filename = match evolution {
+        EvolutionType::GalletBolmont2017(mass) => {
+            if mass <= 0.301 && mass >= 0.299 {
+                String::from("input/Gallet_Bolmont_2017/M_03_Z_0134.dat")
+            } else if mass <= 0.401 && mass >= 0.399 {
+                String::from("input/Gallet_Bolmont_2017/M_04_Z_0134.dat")
+            } else if mass <= 0.601 && mass >= 0.599 {
+                String::from("input/Gallet_Bolmont_2017/M_06_Z_0134.dat")
+            } else if mass <= 0.701 && mass >= 0.699 {
+                String::from("input/Gallet_Bolmont_2017/M_07_Z_0134.dat")
+            } else if mass <= 0.801 && mass >= 0.799 {
+                String::from("input/Gallet_Bolmont_2017/M_08_Z_0134.dat")
+            } else if mass <= 0.901 && mass >= 0.899 {
+                String::from("input/Gallet_Bolmont_2017/M_09_Z_0134.dat")
+            } else if mass <= 1.001 && mass >= 0.999 {
+                String::from("input/Gallet_Bolmont_2017/M_10_Z_0134.dat")
+            } else if mass <= 1.101 && mass >= 1.099 {
+                String::from("input/Gallet_Bolmont_2017/M_11_Z_0134.dat")
+            } else if mass <= 1.201 && mass >= 1.199 {
+                String::from("input/Gallet_Bolmont_2017/M_12_Z_0134.dat")
+            } else if mass <= 1.401 && mass >= 1.399 {
+                String::from("input/Gallet_Bolmont_2017/M_14_Z_0134.dat")
+            } else {
+                return Err(format!("The evolution type Gallet_Bolmont_2017 does not support a mass of {} Msun!", mass));
+            }
+        },
+        EvolutionType::Baraffe2015(mass) => {
+            if mass <= 0.0101 && mass >= 0.0099 {
+                String::from("input/Baraffe_2015/0010_Msun.dat")
+            } else if mass <= 0.0151 && mass >= 0.0149 {
+                String::from("input/Baraffe_2015/0015_Msun.dat")
+            } else if mass <= 0.0201 && mass >= 0.0199 {
+                String::from("input/Baraffe_2015/0020_Msun.dat")
+            } else if mass <= 0.0301 && mass >= 0.0299 {
+                String::from("input/Baraffe_2015/0030_Msun.dat")
+            } else if mass <= 0.0401 && mass >= 0.0399 {
+                String::from("input/Baraffe_2015/0040_Msun.dat")
+            } else if mass <= 0.0501 && mass >= 0.0499 {
+                String::from("input/Baraffe_2015/0050_Msun.dat")
+            } else if mass <= 0.0601 && mass >= 0.0599 {
+                String::from("input/Baraffe_2015/0060_Msun.dat")
+            } else if mass <= 0.0701 && mass >= 0.0699 {
+                String::from("input/Baraffe_2015/0070_Msun.dat")
+            } else if mass <= 0.0721 && mass >= 0.0719 {
+                String::from("input/Baraffe_2015/0072_Msun.dat")
+            } else if mass <= 0.0751 && mass >= 0.0749 {
+                String::from("input/Baraffe_2015/0075_Msun.dat")
+            } else if mass <= 0.0801 && mass >= 0.0799 {
+                String::from("input/Baraffe_2015/0080_Msun.dat")
+            } else if (mass - 0.09).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0090_Msun.dat")
+            } else if (mass - 0.11).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0110_Msun.dat")
+            } else if (mass - 0.13).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0130_Msun.dat")
+            } else if (mass - 0.15).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0150_Msun.dat")
+            } else if (mass - 0.17).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0170_Msun.dat")
+            } else if (mass - 0.20).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0200_Msun.dat")
+            } else if (mass - 0.30).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0300_Msun.dat")
+            } else if (mass - 0.40).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0400_Msun.dat")
+            } else if (mass - 0.50).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0500_Msun.dat")
+            } else if (mass - 0.60).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0600_Msun.dat")
+            } else if (mass - 0.70).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0700_Msun.dat")
+            } else if (mass - 0.80).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0800_Msun.dat")
+            } else if (mass - 0.90).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/0900_Msun.dat")
+            } else if (mass - 1.00).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/1000_Msun.dat")
+            } else if (mass - 1.10).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/1100_Msun.dat")
+            } else if (mass - 1.20).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/1200_Msun.dat")
+            } else if (mass - 1.30).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/1300_Msun.dat")
+            } else if (mass - 1.40).abs() < 1e-7 {
+                String::from("input/Baraffe_2015/1400_Msun.dat")
+            } else {
+                return Err(format!("The evolution type Baraffe2015 does not support a mass of {} Msun!", mass));
+            }
+        },
+        EvolutionType::Leconte2011(mass) => {
+            if mass <= 0.0101 && mass >= 0.0099 {
+                String::from("input/Leconte_2011/mass_10.0000.dat")
+            } else if mass <= 0.0121 && mass >= 0.0119 {
+                String::from("input/Leconte_2011/mass_12.0000.dat")
+            } else if mass <= 0.0151 && mass >= 0.0149 {
+                String::from("input/Leconte_2011/mass_15.0000.dat")
+            } else if mass <= 0.0201 && mass >= 0.0199 {
+                String::from("input/Leconte_2011/mass_20.0000.dat")
+            } else if mass <= 0.0301 && mass >= 0.0299 {
+                String::from("input/Leconte_2011/mass_30.0000.dat")
+            } else if mass <= 0.0401 && mass >= 0.0399 {
+                String::from("input/Leconte_2011/mass_40.0000.dat")
+            } else if mass <= 0.0501 && mass >= 0.0499 {
+                String::from("input/Leconte_2011/mass_50.0000.dat")
+            } else if mass <= 0.0601 && mass >= 0.0599 {
+                String::from("input/Leconte_2011/mass_60.0000.dat")
+            } else if mass <= 0.0701 && mass >= 0.0699 {
+                String::from("input/Leconte_2011/mass_70.0000.dat")
+            } else if mass <= 0.0721 && mass >= 0.0719 {
+                String::from("input/Leconte_2011/mass_72.0000.dat")
+            } else if mass <= 0.0751 && mass >= 0.0749 {
+                String::from("input/Leconte_2011/mass_75.0000.dat")
+            } else if mass <= 0.0801 && mass >= 0.0799 {
+                String::from("input/Leconte_2011/mass_80.0000.dat")
+            } else {
+                return Err(format!("The evolution type Leconte2011 does not support a mass of {} Msun!", mass));
+            }
+        },
+        EvolutionType::Baraffe1998(mass) => {
+            if (mass - 0.10).abs() <= 1.0e-7 {
+                String::from("input/Baraffe_1998/01Msun.dat")
+            } else if (mass - 1.0).abs() <= 1.0e-7 {
+                String::from("input/Baraffe_1998/SRad_Spli_M-1_0000.dat")
+            } else {
+                return Err(format!("The evolution type Baraffe1998 does not support a mass of {} Msun!", mass));
+            }
+        },
+
+        EvolutionType::BolmontMathis2016(mass) => {
+            if mass <= 0.401 && mass >= 0.399 {
+                String::from("input/Bolmont_Mathis_2016/L04Z02r.dat")
+            } else if mass <= 0.501 && mass >= 0.499 {
+                String::from("input/Bolmont_Mathis_2016/L05Z02r.dat")
+            } else if mass <= 0.601 && mass >= 0.599 {
+                String::from("input/Bolmont_Mathis_2016/L06Z02r.dat")
+            } else if mass <= 0.701 && mass >= 0.699 {
+                String::from("input/Bolmont_Mathis_2016/L07Z02r.dat")
+            } else if mass <= 0.801 && mass >= 0.799 {
+                String::from("input/Bolmont_Mathis_2016/L08Z02r.dat")
+            } else if mass <= 0.901 && mass >= 0.899 {
+                String::from("input/Bolmont_Mathis_2016/L09Z02r.dat")
+            } else if mass <= 1.001 && mass >= 0.999 {
+                String::from("input/Bolmont_Mathis_2016/L10Z02r.dat")
+            } else if mass <= 1.101 && mass >= 1.099 {
+                String::from("input/Bolmont_Mathis_2016/L11Z02r.dat")
+            } else if mass <= 1.201 && mass >= 1.199 {
+                String::from("input/Bolmont_Mathis_2016/L12Z02r.dat")
+            } else if mass <= 1.301 && mass >= 1.299 {
+                String::from("input/Bolmont_Mathis_2016/L13Z02r.dat")
+            } else if mass <= 1.401 && mass >= 1.399 {
+                String::from("input/Bolmont_Mathis_2016/L14Z02r.dat")
+            } else if mass <= 1.501 && mass >= 1.499 {
+                String::from("input/Bolmont_Mathis_2016/L15Z02r.dat")
+            } else {
+                return Err(format!("The evolution type BolmontMathis2016 does not support a mass of {} Msun!", mass));
+            }
+        },
+        EvolutionType::LeconteChabrier2013(_) => {
+            String::from("input/Leconte_Chabrier_2013/Jupiter.dat")
+        },
+        EvolutionType::NonEvolving => {
+            String::from("input/empty.dat")
+        }
+    };
+    Ok(filename)
+}
+
 impl Evolver {
     //pub fn new_dummy() -> Evolver {
         //Evolver{
@@ -57,180 +226,22 @@ impl Evolver {
             //left_index: 0,
         //}
     //}
-    pub fn new(evolution: EvolutionType, initial_time: f64, time_limit: f64) -> Evolver {
+    /// Evolution track of the given type loaded for the simulation time range, an error is returned
+    /// if the track file cannot be read or it does not cover the initial time and the time limit
+    pub fn new(evolution: EvolutionType, initial_time: f64, time_limit: f64) -> Result<Evolver, String> {
         let mut time: Vec<f64> = Vec::new();
         let mut radius: Vec<f64> = Vec::new();
         let mut radius_of_gyration_2: Vec<f64> = Vec::new();
         let mut love_number: Vec<f64> = Vec::new();
         let mut inverse_tidal_q_factor: Vec<f64> = Vec::new();
 
-        let filename = match evolution {
-            EvolutionType::GalletBolmont2017(mass) => {
-                if mass <= 0.301 && mass >= 0.299 {
-                    String::from("input/Gallet_Bolmont_2017/M_03_Z_0134.dat")
-                } else if mass <= 0.401 && mass >= 0.399 {
-                    String::from("input/Gallet_Bolmont_2017/M_04_Z_0134.dat")
-                } else if mass <= 0.601 && mass >= 0.599 {
-                    String::from("input/Gallet_Bolmont_2017/M_06_Z_0134.dat")
-                } else if mass <= 0.701 && mass >= 0.699 {
-                    String::from("input/Gallet_Bolmont_2017/M_07_Z_0134.dat")
-                } else if mass <= 0.801 && mass >= 0.799 {
-                    String::from("input/Gallet_Bolmont_2017/M_08_Z_0134.dat")
-                } else if mass <= 0.901 && mass >= 0.899 {
-                    String::from("input/Gallet_Bolmont_2017/M_09_Z_0134.dat")
-                } else if mass <= 1.001 && mass >= 0.999 {
-                    String::from("input/Gallet_Bolmont_2017/M_10_Z_0134.dat")
-                } else if mass <= 1.101 && mass >= 1.099 {
-                    String::from("input/Gallet_Bolmont_2017/M_11_Z_0134.dat")
-                } else if mass <= 1.201 && mass >= 1.199 {
-                    String::from("input/Gallet_Bolmont_2017/M_12_Z_0134.dat")
-                } else if mass <= 1.401 && mass >= 1.399 {
-                    String::from("input/Gallet_Bolmont_2017/M_14_Z_0134.dat")
-                } else {
-                    panic!("The evolution type Gallet_Bolmont_2017 does not support a mass of {} Msun!", mass);
-                }
-            },
-            EvolutionType::Baraffe2015(mass) => {
-                if mass <= 0.0101 && mass >= 0.0099 {
-                    String::from("input/Baraffe_2015/0010_Msun.dat")
-                } else if mass <= 0.0151 && mass >= 0.0149 {
-                    String::from("input/Baraffe_2015/0015_Msun.dat")
-                } else if mass <= 0.0201 && mass >= 0.0199 {
-                    String::from("input/Baraffe_2015/0020_Msun.dat")
-                } else if mass <= 0.0301 && mass >= 0.0299 {
-                    String::from("input/Baraffe_2015/0030_Msun.dat")
-                } else if mass <= 0.0401 && mass >= 0.0399 {
-                    String::from("input/Baraffe_2015/0040_Msun.dat")
-                } else if mass <= 0.0501 && mass >= 0.0499 {
-                    String::from("input/Baraffe_2015/0050_Msun.dat")
-                } else if mass <= 0.0601 && mass >= 0.0599 {
-                    String::from("input/Baraffe_2015/0060_Msun.dat")
-                } else if mass <= 0.0701 && mass >= 0.0699 {
-                    String::from("input/Baraffe_2015/0070_Msun.dat")
-                } else if mass <= 0.0721 && mass >= 0.0719 {
-                    String::from("input/Baraffe_2015/0072_Msun.dat")
-                } else if mass <= 0.0751 && mass >= 0.0749 {
-                    String::from("input/Baraffe_2015/0075_Msun.dat")
-                } else if mass <= 0.0801 && mass >= 0.0799 {
-                    String::from("input/Baraffe_2015/0080_Msun.dat")
-                } else if (mass - 0.09).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0090_Msun.dat")
-                } else if (mass - 0.11).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0110_Msun.dat")
-                } else if (mass - 0.13).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0130_Msun.dat")
-                } else if (mass - 0.15).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0150_Msun.dat")
-                } else if (mass - 0.17).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0170_Msun.dat")
-                } else if (mass - 0.20).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0200_Msun.dat")
-                } else if (mass - 0.30).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0300_Msun.dat")
-                } else if (mass - 0.40).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0400_Msun.dat")
-                } else if (mass - 0.50).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0500_Msun.dat")
-                } else if (mass - 0.60).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0600_Msun.dat")
-                } else if (mass - 0.70).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0700_Msun.dat")
-                } else if (mass - 0.80).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0800_Msun.dat")
-                } else if (mass - 0.90).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/0900_Msun.dat")
-                } else if (mass - 1.00).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/1000_Msun.dat")
-                } else if (mass - 1.10).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/1100_Msun.dat")
-                } else if (mass - 1.20).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/1200_Msun.dat")
-                } else if (mass - 1.30).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/1300_Msun.dat")
-                } else if (mass - 1.40).abs() < 1e-7 {
-                    String::from("input/Baraffe_2015/1400_Msun.dat")
-                } else {
-                    panic!("The evolution type Baraffe2015 does not support a mass of {} Msun!", mass);
-                }
-            },
-            EvolutionType::Leconte2011(mass) => {
-                if mass <= 0.0101 && mass >= 0.0099 {
-                    String::from("input/Leconte_2011/mass_10.0000.dat")
-                } else if mass <= 0.0121 && mass >= 0.0119 {
-                    String::from("input/Leconte_2011/mass_12.0000.dat")
-                } else if mass <= 0.0151 && mass >= 0.0149 {
-                    String::from("input/Leconte_2011/mass_15.0000.dat")
-                } else if mass <= 0.0201 && mass >= 0.0199 {
-                    String::from("input/Leconte_2011/mass_20.0000.dat")
-                } else if mass <= 0.0301 && mass >= 0.0299 {
-                    String::from("input/Leconte_2011/mass_30.0000.dat")
-                } else if mass <= 0.0401 && mass >= 0.0399 {
-                    String::from("input/Leconte_2011/mass_40.0000.dat")
-                } else if mass <= 0.0501 && mass >= 0.0499 {
-                    String::from("input/Leconte_2011/mass_50.0000.dat")
-                } else if mass <= 0.0601 && mass >= 0.0599 {
-                    String::from("input/Leconte_2011/mass_60.0000.dat")
-                } else if mass <= 0.0701 && mass >= 0.0699 {
-                    String::from("input/Leconte_2011/mass_70.0000.dat")
-                } else if mass <= 0.0721 && mass >= 0.0719 {
-                    String::from("input/Leconte_2011/mass_72.0000.dat")
-                } else if mass <= 0.0751 && mass >= 0.0749 {
-                    String::from("input/Leconte_2011/mass_75.0000.dat")
-                } else if mass <= 0.0801 && mass >= 0.0799 {
-                    String::from("input/Leconte_2011/mass_80.0000.dat")
-                } else {
-                    panic!("The evolution type Leconte2011 does not support a mass of {} Msun!", mass);
-                }
-            },
-            EvolutionType::Baraffe1998(mass) => {
-                if (mass - 0.10).abs() <= 1.0e-7 {
-                    String::from("input/Baraffe_1998/01Msun.dat")
-                } else if (mass - 1.0).abs() <= 1.0e-7 {
-                    String::from("input/Baraffe_1998/SRad_Spli_M-1_0000.dat")
-                } else {
-                    panic!("The evolution type Baraffe1998 does not support a mass of {} Msun!", mass);
-                }
-            },
-
-            EvolutionType::BolmontMathis2016(mass) => {
-                if mass <= 0.401 && mass >= 0.399 {
-                    String::from("input/Bolmont_Mathis_2016/L04Z02r.dat")
-                } else if mass <= 0.501 && mass >= 0.499 {
-                    String::from("input/Bolmont_Mathis_2016/L05Z02r.dat")
-                } else if mass <= 0.601 && mass >= 0.599 {
-                    String::from("input/Bolmont_Mathis_2016/L06Z02r.dat")
-                } else if mass <= 0.701 && mass >= 0.699 {
-                    String::from("input/Bolmont_Mathis_2016/L07Z02r.dat")
-                } else if mass <= 0.801 && mass >= 0.799 {
-                    String::from("input/Bolmont_Mathis_2016/L08Z02r.dat")
-                } else if mass <= 0.901 && mass >= 0.899 {
-                    String::from("input/Bolmont_Mathis_2016/L09Z02r.dat")
-                } else if mass <= 1.001 && mass >= 0.999 {
-                    String::from("input/Bolmont_Mathis_2016/L10Z02r.dat")
-                } else if mass <= 1.101 && mass >= 1.099 {
-                    String::from("input/Bolmont_Mathis_2016/L11Z02r.dat")
-                } else if mass <= 1.201 && mass >= 1.199 {
-                    String::from("input/Bolmont_Mathis_2016/L12Z02r.dat")
-                } else if mass <= 1.301 && mass >= 1.299 {
-                    String::from("input/Bolmont_Mathis_2016/L13Z02r.dat")
-                } else if mass <= 1.401 && mass >= 1.399 {
-                    String::from("input/Bolmont_Mathis_2016/L14Z02r.dat")
-                } else if mass <= 1.501 && mass >= 1.499 {
-                    String::from("input/Bolmont_Mathis_2016/L15Z02r.dat")
-                } else {
-                    panic!("The evolution type BolmontMathis2016 does not support a mass of {} Msun!", mass);
-                }
-            },
-            EvolutionType::LeconteChabrier2013(_) => {
-                String::from("input/Leconte_Chabrier_2013/Jupiter.dat")
-            },
-            EvolutionType::NonEvolving => {
-                String::from("input/empty.dat")
-            }
-        };
+        let filename = evolution_track_filename(evolution)?;
         //println!("Filename {}", filename);
         
-        let mut rdr = csv::ReaderBuilder::new().has_headers(false).delimiter(b' ').flexible(true).from_path(&filename).unwrap();
+        let mut rdr = match csv::ReaderBuilder::new().has_headers(false).delimiter(b' ').flexible(true).from_path(&filename) {
+            Ok(rdr) => rdr,
+            Err(why) => return Err(format!("Impossible to read the evolution track '{}': {}", filename, why)),
+        };
         for (i, row) in rdr.records().map(|r| r.unwrap()).enumerate() {
             let raw_time = row[0].parse::<f64>().unwrap_or(-1.);
             if i == 0 && raw_time < 0. {
@@ -393,10 +404,14 @@ impl Evolver {
                 } else if mass <= 0.0801 && mass >= 0.0799 {
                     12
                 } else {
-                    panic!("The evolution type Leconte2011 does not support a mass of {} Msun!", mass);
+                    return Err(format!("The evolution type Leconte2011 does not support a mass of {} Msun!", mass));
                 }
             };
-            let mut rdr = csv::ReaderBuilder::new().has_headers(false).delimiter(b' ').flexible(true).from_path("input/Leconte_2011/rg2BD.dat").unwrap();
+            let aux_filename = "input/Leconte_2011/rg2BD.dat";
+            let mut rdr = match csv::ReaderBuilder::new().has_headers(false).delimiter(b' ').flexible(true).from_path(aux_filename) {
+                Ok(rdr) => rdr,
+                Err(why) => return Err(format!("Impossible to read the evolution track '{}': {}", aux_filename, why)),
+            };
             for row in rdr.records().map(|r| r.unwrap()) {
                 let raw_time = row[0].parse::<f64>().unwrap();
                 let raw_radius_of_gyration_2 = row[aux_column].parse::<f64>().unwrap();
@@ -427,20 +442,20 @@ impl Evolver {
         };
 
         if time.len() > 0 && time[0] > 0. {
-            panic!("Your initial time ({} days) is smaller than the minimum allowed age of the star ({} days)", initial_time, time[0]+initial_time);
+            return Err(format!("Your initial time ({} days) is smaller than the minimum allowed age of the star ({} days)", initial_time, time[0]+initial_time));
         }
         if time.len() > 0 && time[time.len()-1] < time_limit {
-            panic!("Your time limit ({} days) is greater than the maximum allowed age of the star ({} days)", time_limit, time[time.len()-1]);
+            return Err(format!("Your time limit ({} days) is greater than the maximum allowed age of the star ({} days)", time_limit, time[time.len()-1]));
         }
 
-        Evolver { evolution:evolution, 
+        Ok(Evolver { evolution:evolution, 
                 time:time,
                 radius:radius,
                 radius_of_gyration_2:radius_of_gyration_2,
                 love_number:love_number,
                 inverse_tidal_q_factor:inverse_tidal_q_factor,
                 left_index:0,
-        }
+        })
     }
 
     // OPTIMIZATION: Skip first N elements which belong to the past
@@ -531,9 +546,9 @@ pub fn calculate_particles_non_spin_dependent_evolving_quantities(current_time:
         ////////////////////////////////////////////////////////////////////
         // Love number
         ////////////////////////////////////////////////////////////////////
-        match &particle.tides.effect {
+        match &mut particle.tides.effect {
             TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model) => {
-                if let TidalModel::ConstantTimeLag(mut params) = tidal_model {
+                if let TidalModel::ConstantTimeLag(params) = tidal_model {
                     params.love_number = evolver.love_number(current_time, params.love_number);
                 }
             },
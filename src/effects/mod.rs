@@ -8,14 +8,32 @@ pub mod general_relativity;
 pub use self::tides::Tides;
 pub use self::tides::TidesEffect;
 pub use self::tides::TidalModel;
+pub use self::tides::TidalResponse;
+pub use self::tides::TidalContext;
+pub use self::tides::Tide;
 pub use self::tides::ConstantTimeLagParameters;
+pub use self::tides::ConstantPhaseLagParameters;
+pub use self::tides::CreepParameters;
 pub use self::tides::CreepCoplanarParameters;
 pub use self::tides::KaulaParameters;
-pub use self::tides::Polynomials;
+pub use self::tides::KaulaFunctions;
+pub use self::tides::KaulaFunctionsCache;
+pub use self::tides::inclination_function;
+pub use self::tides::eccentricity_function;
+pub use self::tides::hansen_coefficients;
+pub use self::tides::LoveNumberTable;
+pub use self::tides::LoveNumberTableSource;
+pub use self::tides::LoveNumberData;
+pub use self::tides::LoveNumberInterpolation;
+pub use self::tides::TidalPair;
+pub use self::tides::Rheology;
+pub use self::tides::RheologicalLayer;
+pub use self::tides::InteriorStructure;
 pub use self::rotational_flattening::RotationalFlattening;
 pub use self::rotational_flattening::RotationalFlatteningEffect;
 pub use self::rotational_flattening::RotationalFlatteningModel;
 pub use self::rotational_flattening::OblateSpheroidParameters;
+pub use self::rotational_flattening::RotationalFlatteningPair;
 pub use self::general_relativity::GeneralRelativity;
 pub use self::general_relativity::GeneralRelativityEffect;
 pub use self::general_relativity::GeneralRelativityImplementation;
@@ -3,7 +3,9 @@ use super::super::super::{Particle};
 use super::super::super::{Axes};
 use super::oblate_spheroid;
 use super::creep_coplanar;
+use super::creep;
 use super::super::tides::creep_coplanar::CreepCoplanarParameters;
+use super::super::tides::creep::CreepParameters;
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RotationalFlatteningParticleInternalParameters {
@@ -45,6 +47,7 @@ pub struct RotationalFlatteningParticleCoordinates {
 pub enum RotationalFlatteningModel {
     OblateSpheroid(oblate_spheroid::OblateSpheroidParameters),
     CreepCoplanar(CreepCoplanarParameters),
+    Creep(CreepParameters),
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
@@ -169,8 +172,9 @@ pub fn calculate_dangular_momentum_dt_induced_by_rotational_flattening(rotationa
     for particle in particles.iter_mut().chain(more_particles.iter_mut()) {
         if let RotationalFlatteningEffect::OrbitingBody(rotational_flattening_model) = particle.rotational_flattening.effect {
             let torque_induced_by_rotational_flattening = match rotational_flattening_model {
-                RotationalFlatteningModel::OblateSpheroid(_) => oblate_spheroid::calculate_torque_induced_by_rotational_flattening(&rotational_flattening_host_particle, &particle, central_body),
-                RotationalFlatteningModel::CreepCoplanar(_) => creep_coplanar::calculate_torque_induced_by_rotational_flattening(&rotational_flattening_host_particle, &particle, central_body),
+                RotationalFlatteningModel::OblateSpheroid(_) => oblate_spheroid::calculate_torque_induced_by_rotational_flattening(rotational_flattening_host_particle, particle, central_body),
+                RotationalFlatteningModel::CreepCoplanar(_) => creep_coplanar::calculate_torque_induced_by_rotational_flattening(rotational_flattening_host_particle, particle, central_body),
+                RotationalFlatteningModel::Creep(_) => creep::calculate_torque_induced_by_rotational_flattening(rotational_flattening_host_particle, particle, central_body),
             };
             // - Equation 25 from Bolmont et al. 2015
             // Integration of the spin (total torque rot):
@@ -185,8 +189,9 @@ pub fn calculate_dangular_momentum_dt_induced_by_rotational_flattening(rotationa
     for particle in particles.iter_mut().chain(more_particles.iter_mut()) {
         if let RotationalFlatteningEffect::CentralBody(rotational_flattening_model) = rotational_flattening_host_particle.rotational_flattening.effect {
             let torque_induced_by_rotational_flattening = match rotational_flattening_model {
-                RotationalFlatteningModel::OblateSpheroid(_) => oblate_spheroid::calculate_torque_induced_by_rotational_flattening(&rotational_flattening_host_particle, &particle, central_body),
-                RotationalFlatteningModel::CreepCoplanar(_) => creep_coplanar::calculate_torque_induced_by_rotational_flattening(&rotational_flattening_host_particle, &particle, central_body),
+                RotationalFlatteningModel::OblateSpheroid(_) => oblate_spheroid::calculate_torque_induced_by_rotational_flattening(rotational_flattening_host_particle, particle, central_body),
+                RotationalFlatteningModel::CreepCoplanar(_) => creep_coplanar::calculate_torque_induced_by_rotational_flattening(rotational_flattening_host_particle, particle, central_body),
+                RotationalFlatteningModel::Creep(_) => creep::calculate_torque_induced_by_rotational_flattening(rotational_flattening_host_particle, particle, central_body),
             };
             // Integration of the spin (total torque rot):
             dangular_momentum_dt.x += factor * torque_induced_by_rotational_flattening.x;
@@ -209,8 +214,9 @@ pub fn calculate_acceleration_induced_by_rotational_flattering(rotational_flatte
     for particle in particles.iter_mut().chain(more_particles.iter_mut()) {
         if let RotationalFlatteningEffect::OrbitingBody(rotational_flattening_model) = particle.rotational_flattening.effect {
             let force_induced_by_rotation = match rotational_flattening_model {
-                RotationalFlatteningModel::OblateSpheroid(_) => oblate_spheroid::calculate_acceleration_induced_by_rotational_flattering(&rotational_flattening_host_particle, &particle),
-                RotationalFlatteningModel::CreepCoplanar(_) => creep_coplanar::calculate_acceleration_induced_by_rotational_flattering(&rotational_flattening_host_particle, &particle),
+                RotationalFlatteningModel::OblateSpheroid(_) => oblate_spheroid::calculate_acceleration_induced_by_rotational_flattering(rotational_flattening_host_particle, particle),
+                RotationalFlatteningModel::CreepCoplanar(_) => creep_coplanar::calculate_acceleration_induced_by_rotational_flattering(rotational_flattening_host_particle, particle),
+                RotationalFlatteningModel::Creep(_) => creep::calculate_acceleration_induced_by_rotational_flattering(rotational_flattening_host_particle, particle),
             };
             sum_force_induced_by_rotation.x += force_induced_by_rotation.x;
             sum_force_induced_by_rotation.y += force_induced_by_rotation.y;
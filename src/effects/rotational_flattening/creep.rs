@@ -0,0 +1,45 @@
+// Rotational flattening of the creep tide theory for spins not aligned with the orbit normal (see
+// tides/creep.rs). For a constant spin, the rotational figure is the equilibrium one, which is
+// symmetric around the spin axis.
+use super::super::super::{Particle};
+use super::super::super::{Axes};
+use super::super::tides::creep::{calculate_rotational_figure, calculate_force_and_torque_due_to_figure};
+use super::super::rotational_flattening::RotationalFlatteningEffect;
+use super::super::rotational_flattening::RotationalFlatteningModel;
+
+pub fn calculate_torque_induced_by_rotational_flattening(rotational_flattening_host_particle: &Particle, particle: &Particle, central_body:bool) -> Axes {
+    let (deformed_particle, companion_mass) = match central_body {
+        true => (rotational_flattening_host_particle, particle.mass),
+        false => (particle, rotational_flattening_host_particle.mass),
+    };
+    if uniform_viscosity_coefficient(deformed_particle) == 0. {
+        return Axes{x: 0., y: 0., z: 0.};
+    }
+    let figure = calculate_rotational_figure(deformed_particle);
+    let (_, torque) = calculate_force_and_torque_due_to_figure(&figure, deformed_particle.radius, companion_mass, particle.rotational_flattening.coordinates.position);
+    // Torque acting on the orbit
+    Axes{x: -torque.x, y: -torque.y, z: -torque.z}
+}
+
+pub fn calculate_acceleration_induced_by_rotational_flattering(rotational_flattening_host_particle: &Particle, particle: &Particle) -> Axes {
+    let mut force_induced_by_rotational_flattening = Axes{x: 0., y: 0., z: 0.};
+    // Flattening of the orbiting body and of the host (if it also uses the creep model)
+    for (deformed_particle, companion_mass) in [(particle, rotational_flattening_host_particle.mass), (rotational_flattening_host_particle, particle.mass)].iter() {
+        if uniform_viscosity_coefficient(deformed_particle) == 0. {
+            continue;
+        }
+        let figure = calculate_rotational_figure(deformed_particle);
+        let (force, _) = calculate_force_and_torque_due_to_figure(&figure, deformed_particle.radius, *companion_mass, particle.rotational_flattening.coordinates.position);
+        force_induced_by_rotational_flattening.x += force.x;
+        force_induced_by_rotational_flattening.y += force.y;
+        force_induced_by_rotational_flattening.z += force.z;
+    }
+    force_induced_by_rotational_flattening
+}
+
+fn uniform_viscosity_coefficient(particle: &Particle) -> f64 {
+    match particle.rotational_flattening.effect {
+        RotationalFlatteningEffect::CentralBody(RotationalFlatteningModel::Creep(params)) | RotationalFlatteningEffect::OrbitingBody(RotationalFlatteningModel::Creep(params)) => params.uniform_viscosity_coefficient,
+        _ => 0.,
+    }
+}
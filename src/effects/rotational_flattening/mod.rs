@@ -1,6 +1,8 @@
 pub mod common;
 pub mod oblate_spheroid;
 pub mod creep_coplanar;
+pub mod creep;
+pub mod pairwise;
 
 pub use self::common::RotationalFlattening;
 pub use self::common::RotationalFlatteningEffect;
@@ -15,3 +17,6 @@ pub use self::oblate_spheroid::calculate_radial_component_of_the_force_induced_b
 pub use self::common::calculate_dangular_momentum_dt_induced_by_rotational_flattening;
 pub use self::common::calculate_acceleration_induced_by_rotational_flattering;
 pub use self::creep_coplanar::calculate_creep_coplanar_shapes;
+pub use self::pairwise::RotationalFlatteningPair;
+pub use self::pairwise::satellite_rotational_flattening_pairs;
+pub use self::pairwise::calculate_rotational_flattening_pairs;
@@ -0,0 +1,89 @@
+use super::super::super::{Particle, Reference};
+use super::common::{RotationalFlatteningEffect, RotationalFlatteningModel};
+use super::common;
+use super::oblate_spheroid;
+use super::creep_coplanar;
+
+// Rotational flattening between two particles that do not involve the central host (e.g., the J2
+// of a planet acting on its moons). As for tides (see `tides::pairwise`), each pair is computed
+// with the same functions used for the central host, but using a copy of both particles where
+// "host" takes the role of the central body and coordinates are relative to it. The resulting
+// accelerations and torques are added to the ones of the central host.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RotationalFlatteningPair {
+    pub host: usize, // Particle index
+    pub orbiting: usize, // Particle index
+    pub host_rotational_flattening_model: RotationalFlatteningModel,
+    pub orbiting_rotational_flattening_model: RotationalFlatteningModel,
+}
+
+/// Rotational flattening between every satellite (i.e., a particle that uses as reference another
+/// particle that is not the central host) and the particle it orbits, which plays the role of the
+/// central body with the same model it uses as orbiting body of the central host
+pub fn satellite_rotational_flattening_pairs(particles: &[Particle], rotational_flattening_host_index: usize) -> Vec<RotationalFlatteningPair> {
+    particles.iter().enumerate().filter_map(|(index, particle)| {
+        match (particle.reference, particle.rotational_flattening.effect) {
+            (Reference::Particle(host), RotationalFlatteningEffect::OrbitingBody(orbiting_rotational_flattening_model)) if host != index && host != rotational_flattening_host_index && host < particles.len() => {
+                match particles[host].rotational_flattening.effect {
+                    RotationalFlatteningEffect::OrbitingBody(host_rotational_flattening_model) => Some(RotationalFlatteningPair {
+                        host,
+                        orbiting: index,
+                        host_rotational_flattening_model,
+                        orbiting_rotational_flattening_model,
+                    }),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }).collect()
+}
+
+/// Add the accelerations and/or torques induced by rotational flattening of every pair to the ones
+/// already computed for the central host
+pub fn calculate_rotational_flattening_pairs(particles: &mut [Particle], rotational_flattening_pairs: &[RotationalFlatteningPair], dangular_momentum_dt: bool, accelerations: bool) {
+    for rotational_flattening_pair in rotational_flattening_pairs.iter() {
+        let (host_particle, orbiting_particle) = calculate_rotational_flattening_pair(particles, rotational_flattening_pair, dangular_momentum_dt, accelerations);
+        for (index, pair_particle) in [(rotational_flattening_pair.host, host_particle), (rotational_flattening_pair.orbiting, orbiting_particle)].iter() {
+            let output = &mut particles[*index].rotational_flattening.parameters.output;
+            if accelerations {
+                output.acceleration.x += pair_particle.rotational_flattening.parameters.output.acceleration.x;
+                output.acceleration.y += pair_particle.rotational_flattening.parameters.output.acceleration.y;
+                output.acceleration.z += pair_particle.rotational_flattening.parameters.output.acceleration.z;
+            }
+            if dangular_momentum_dt {
+                output.dangular_momentum_dt.x += pair_particle.rotational_flattening.parameters.output.dangular_momentum_dt.x;
+                output.dangular_momentum_dt.y += pair_particle.rotational_flattening.parameters.output.dangular_momentum_dt.y;
+                output.dangular_momentum_dt.z += pair_particle.rotational_flattening.parameters.output.dangular_momentum_dt.z;
+            }
+        }
+    }
+}
+
+fn calculate_rotational_flattening_pair(particles: &[Particle], rotational_flattening_pair: &RotationalFlatteningPair, dangular_momentum_dt: bool, accelerations: bool) -> (Particle, Particle) {
+    let mut host_particle = particles[rotational_flattening_pair.host];
+    let mut orbiting_particle = particles[rotational_flattening_pair.orbiting];
+    host_particle.rotational_flattening.effect = RotationalFlatteningEffect::CentralBody(rotational_flattening_pair.host_rotational_flattening_model);
+    orbiting_particle.rotational_flattening.effect = RotationalFlatteningEffect::OrbitingBody(rotational_flattening_pair.orbiting_rotational_flattening_model);
+    {
+        let orbiting_particles = std::slice::from_mut(&mut orbiting_particle);
+        common::inertial_to_heliocentric_coordinates(&mut host_particle, orbiting_particles, &mut []);
+        // Creep coplanar shapes are computed from the tides coordinates
+        orbiting_particles[0].tides.coordinates.position = orbiting_particles[0].rotational_flattening.coordinates.position;
+        orbiting_particles[0].tides.coordinates.velocity = orbiting_particles[0].rotational_flattening.coordinates.velocity;
+        orbiting_particles[0].tides.parameters.internal.distance = orbiting_particles[0].rotational_flattening.parameters.internal.distance;
+        common::initialize(&mut host_particle, orbiting_particles, &mut []);
+
+        oblate_spheroid::calculate_orthogonal_component_of_the_force_induced_by_rotational_flattening(&mut host_particle, orbiting_particles, &mut []);
+        creep_coplanar::calculate_creep_coplanar_shapes(&mut host_particle, orbiting_particles, &mut []);
+        if dangular_momentum_dt {
+            common::calculate_dangular_momentum_dt_induced_by_rotational_flattening(&mut host_particle, orbiting_particles, &mut []);
+        }
+        if accelerations {
+            oblate_spheroid::calculate_radial_component_of_the_force_induced_by_rotational_flattening(&mut host_particle, orbiting_particles, &mut []);
+            common::calculate_acceleration_induced_by_rotational_flattering(&mut host_particle, orbiting_particles, &mut []);
+        }
+    }
+    (host_particle, orbiting_particle)
+}
@@ -0,0 +1,53 @@
+use serde::{Serialize, Deserialize};
+use super::super::super::{Particle};
+use super::super::super::{Axes};
+use super::kaula::{self, KaulaParameters, KaulaLoveNumbers};
+use super::model::{TidalResponse, TidalContext, Tide, calculate_tidal_power_from_force_and_torque};
+
+// Constant phase lag (constant Q) model (Goldreich & Soter 1966, Efroimsky & Lainey 2007): the
+// tidal bulge lags by a phase 1/Q at every tidal frequency. The tidal potential is expanded over
+// the tidal modes (m, p, q) of degree 2 as in the Kaula model (see kaula.rs), where each mode has
+// the same Love number k2 and the phase lag sign(w_2mpq)/Q, i.e., the bulge lags behind the
+// perturber for positive frequencies and leads it for negative ones. The phase lag is therefore
+// frequency independent except for its sign, which changes at each commensurability
+// (w_2mpq = 0, where the mode does not dissipate).
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConstantPhaseLagParameters {
+    pub quality_factor: f64, // Q (dimensionless), inverse of the phase lag
+    pub love_number: f64,   // Love number of degree 2 (i.e., k2)
+}
+
+impl TidalResponse for ConstantPhaseLagParameters {
+    fn name(&self) -> &'static str {
+        "ConstantPhaseLag"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.quality_factor <= 0. || !self.quality_factor.is_finite() {
+            return Err(format!("The quality factor of the constant phase lag model must be positive ({})", self.quality_factor));
+        }
+        if self.love_number < 0. || !self.love_number.is_finite() {
+            return Err(format!("The love number of the constant phase lag model cannot be negative ({})", self.love_number));
+        }
+        Ok(())
+    }
+
+    /// Tidal excitation frequencies of the modes of degree 2 (same as the Kaula model)
+    fn excitation_frequencies(&self, tidal_host_particle: &Particle, particle: &Particle, tide: Tide) -> Vec<f64> {
+        KaulaParameters{}.excitation_frequencies(tidal_host_particle, particle, tide)
+    }
+
+    fn calculate_tidal_force_and_torque(&mut self, tidal_host_particle: &mut Particle, particle: &mut Particle, tide: Tide, context: &mut TidalContext) -> (Axes, Axes) {
+        let love_numbers = KaulaLoveNumbers::ConstantPhaseLag { love_number: self.love_number, quality_factor: self.quality_factor };
+        kaula::calculate_tidal_force_and_torque(tidal_host_particle, particle, tide, love_numbers, context.kaula_functions_cache)
+    }
+
+    fn dissipated_power(&self, tidal_host_particle: &Particle, particle: &Particle, context: &mut TidalContext) -> f64 {
+        calculate_tidal_power_from_force_and_torque(self, tidal_host_particle, particle, context)
+    }
+
+    fn love_number(&self) -> f64 {
+        self.love_number
+    }
+}
@@ -0,0 +1,299 @@
+// Creep tide theory for non-coplanar orbits and spins not aligned with the orbit normal, which
+// allows to follow the evolution of the obliquity and the inclination with a creep rheology
+// (Ferraz-Mello 2013, 2015; Folonier et al. 2018). It generalises the coplanar implementation
+// (see creep_coplanar.rs) to three dimensions.
+//
+// The figure of the tidally deformed body is described by a traceless symmetric tensor B, such that
+// the potential generated by the deformed body is -k_f K2 R^5 x.B.x / (2 |x|^5), with k_f = 3/2 the
+// fluid Love number of a homogeneous body. The equilibrium (fluid) figures are
+//   - Tide raised by a companion of mass M at r: B_eq = M / r^3 (3 r r / r^2 - I)
+//   - Rotational flattening due to the spin w: B_eq = (|w|^2 I / 3 - w w) / K2
+// The creep equation makes the figure relax towards the equilibrium one in the frame that rotates
+// with the body (dB/dt = gamma (B_eq - B) with gamma = 3 K2 m^2 / (8 pi R^4 eta)). For a Keplerian
+// orbit and a constant spin, its forced solution is obtained by expanding B_eq in harmonics of the
+// mean anomaly M (the Hansen coefficients X^{-3,0}_k and X^{-3,2}_k of (a/r)^3 and (a/r)^3 exp(2 i f))
+// and in orders m about the spin axis: each term exp(i k M) of order m is multiplied by
+//   gamma / (gamma + i (k n - m |w|))
+// thus the figure lags behind the equilibrium one for every tidal frequency (including the ones that
+// only appear with non-zero obliquities or inclinations). The rotational figure does not vary for a
+// constant spin and matches the equilibrium one.
+use serde::{Serialize, Deserialize};
+use super::super::super::constants::{K2, PI};
+use super::super::super::{Particle};
+use super::super::super::{Axes};
+use super::kaula_functions::KaulaFunctionsCache;
+use super::model::{TidalResponse, TidalContext, Tide, calculate_tidal_power_from_force_and_torque};
+use super::rheology::Complex;
+
+const FLUID_LOVE_NUMBER: f64 = 1.5; // Homogeneous fluid body
+
+pub type Figure = [[f64; 3]; 3];
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreepParameters {
+    pub uniform_viscosity_coefficient: f64,
+}
+
+impl TidalResponse for CreepParameters {
+    fn name(&self) -> &'static str {
+        "Creep"
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.uniform_viscosity_coefficient < 0. {
+            return Err(format!("The uniform viscosity coefficient of the creep tidal model cannot be negative ({})", self.uniform_viscosity_coefficient));
+        }
+        Ok(())
+    }
+
+    /// Semi-diurnal frequency and the diurnal frequencies that appear when the spin is not aligned
+    /// with the orbit normal (using the norm of the spin)
+    fn excitation_frequencies(&self, tidal_host_particle: &Particle, particle: &Particle, tide: Tide) -> Vec<f64> {
+        let deformed_particle = match tide {
+            Tide::Stellar => tidal_host_particle,
+            Tide::Planetary => particle,
+        };
+        let gm = tidal_host_particle.mass_g + particle.mass_g;
+        let spin = norm(to_vector(deformed_particle.spin));
+        let mean_motion = match Orbit::new(gm, particle.tides.coordinates.position, particle.tides.coordinates.velocity) {
+            Some(orbit) => orbit.mean_motion,
+            None => 0.,
+        };
+        vec![2. * (spin - mean_motion).abs(), (spin - 2. * mean_motion).abs(), spin]
+    }
+
+    fn calculate_tidal_force_and_torque(&mut self, tidal_host_particle: &mut Particle, particle: &mut Particle, tide: Tide, context: &mut TidalContext) -> (Axes, Axes) {
+        if self.uniform_viscosity_coefficient == 0. {
+            return (Axes{x: 0., y: 0., z: 0.}, Axes{x: 0., y: 0., z: 0.});
+        }
+        let (deformed_particle, companion) = match tide {
+            Tide::Stellar => (&*tidal_host_particle, &*particle),
+            Tide::Planetary => (&*particle, &*tidal_host_particle),
+        };
+        let gm = tidal_host_particle.mass_g + particle.mass_g;
+        let figure = calculate_tidal_figure(deformed_particle, companion, gm, particle.tides.coordinates.position, particle.tides.coordinates.velocity, self.uniform_viscosity_coefficient, context.kaula_functions_cache);
+        calculate_force_and_torque_due_to_figure(&figure, deformed_particle.radius, companion.mass, particle.tides.coordinates.position)
+    }
+
+    fn dissipated_power(&self, tidal_host_particle: &Particle, particle: &Particle, context: &mut TidalContext) -> f64 {
+        calculate_tidal_power_from_force_and_torque(self, tidal_host_particle, particle, context)
+    }
+}
+
+/// Figure of the deformed particle due to the tide raised by a companion at the given position and
+/// velocity (relative to the host), gm being K2 times the sum of the masses
+pub fn calculate_tidal_figure(deformed_particle: &Particle, companion: &Particle, gm: f64, position: Axes, velocity: Axes, uniform_viscosity_coefficient: f64, kaula_functions_cache: &mut KaulaFunctionsCache) -> Figure {
+    let position = to_vector(position);
+    let distance = norm(position);
+    let orbit = match Orbit::new(gm, to_axes(position), velocity) {
+        Some(orbit) => orbit,
+        // Unbound or radial trajectories: the figure is assumed to be the equilibrium one
+        None => return scale(&equilibrium_tidal_figure(position), companion.mass / distance.powi(3)),
+    };
+    let relaxation_factor = 3.0 * K2 * deformed_particle.mass * deformed_particle.mass
+        / (8.0 * PI * deformed_particle.radius.powi(4) * uniform_viscosity_coefficient);
+
+    // Frame of the deformed particle with the z-axis along the spin
+    let spin_vector = to_vector(deformed_particle.spin);
+    let spin = norm(spin_vector);
+    let z_axis = if spin > 0. { scale_vector(spin_vector, 1. / spin) } else { orbit.z_axis };
+    let node = cross(orbit.z_axis, z_axis);
+    let x_axis = if norm(node) > 1.0e-12 {
+        scale_vector(node, 1. / norm(node))
+    } else {
+        let projection = sub_vector(orbit.x_axis, scale_vector(z_axis, dot(orbit.x_axis, z_axis)));
+        scale_vector(projection, 1. / norm(projection))
+    };
+    let body_frame = [x_axis, cross(z_axis, x_axis), z_axis];
+    let orbital_frame = [orbit.x_axis, orbit.y_axis, orbit.z_axis];
+
+    // Constant tensors of the expansion in the orbital frame (x-axis towards the pericentre)
+    //   (a/r)^3 (3 r r / r^2 - I) = (a/r)^3 diag(1/2, 1/2, -1) + 3/2 (a/r)^3 [cos(2f) C + sin(2f) S]
+    // expressed in the frame of the deformed particle
+    let d = change_frame(&[[0.5, 0., 0.], [0., 0.5, 0.], [0., 0., -1.]], &orbital_frame, &body_frame);
+    let c = change_frame(&[[1.5, 0., 0.], [0., -1.5, 0.], [0., 0., 0.]], &orbital_frame, &body_frame);
+    let s = change_frame(&[[0., 1.5, 0.], [1.5, 0., 0.], [0., 0., 0.]], &orbital_frame, &body_frame);
+
+    // Hansen coefficients X^{-3,0}_k = G_21(k) and X^{-3,2}_k = G_20(k-2)
+    let kaula_functions = kaula_functions_cache.kaula_functions(deformed_particle.id, companion.id, 2, orbit.eccentricity, 0.);
+    let q_max = kaula_functions.q_max as i32;
+    let i_unit = Complex::new(0., 1.);
+    let mut figure = [[0.; 3]; 3];
+    for k in -q_max..=q_max + 2 {
+        let x_0 = kaula_functions.eccentricity_function(1, k);
+        let x_2 = kaula_functions.eccentricity_function(0, k - 2);
+        if x_0 == 0. && x_2 == 0. {
+            continue;
+        }
+        // Equilibrium figure term exp(i k M) (x_0 D + x_2 (C - i S)), decomposed in orders m about the spin axis
+        let component = |i: usize, j: usize| Complex::new(x_0 * d[i][j] + x_2 * c[i][j], -x_2 * s[i][j]);
+        let frequency = k as f64 * orbit.mean_motion;
+        let transfer = |m: f64| Complex::real(relaxation_factor) / Complex::new(relaxation_factor, frequency - m * spin);
+        let order_0 = (component(0, 0) + component(1, 1)) * 0.5 * transfer(0.);
+        let polar = component(2, 2) * transfer(0.);
+        let order_1 = (component(0, 2) + i_unit * component(1, 2)) * transfer(1.);
+        let order_minus_1 = (component(0, 2) - i_unit * component(1, 2)) * transfer(-1.);
+        let order_2 = ((component(0, 0) - component(1, 1)) * 0.5 + i_unit * component(0, 1)) * transfer(2.);
+        let order_minus_2 = ((component(0, 0) - component(1, 1)) * 0.5 - i_unit * component(0, 1)) * transfer(-2.);
+        let xz = (order_1 + order_minus_1) * 0.5;
+        let yz = (order_1 - order_minus_1) / (i_unit * 2.);
+        let prolateness = (order_2 + order_minus_2) * 0.5;
+        let xy = (order_2 - order_minus_2) / (i_unit * 2.);
+        let phase = Complex::new((k as f64 * orbit.mean_anomaly).cos(), (k as f64 * orbit.mean_anomaly).sin());
+        let lagged = [
+            [order_0 + prolateness, xy, xz],
+            [xy, order_0 - prolateness, yz],
+            [xz, yz, polar],
+        ];
+        for i in 0..3 {
+            for j in 0..3 {
+                figure[i][j] += (phase * lagged[i][j]).re;
+            }
+        }
+    }
+    // Back to the inertial frame
+    let inertial_frame = [[1., 0., 0.], [0., 1., 0.], [0., 0., 1.]];
+    scale(&change_frame(&figure, &body_frame, &inertial_frame), companion.mass / orbit.semimajor_axis.powi(3))
+}
+
+/// Figure of the deformed particle due to its rotation
+pub fn calculate_rotational_figure(deformed_particle: &Particle) -> Figure {
+    let spin = to_vector(deformed_particle.spin);
+    let spin_2 = dot(spin, spin);
+    let mut figure = [[0.; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            figure[i][j] = -spin[i] * spin[j] / K2;
+        }
+        figure[i][i] += spin_2 / (3. * K2);
+    }
+    figure
+}
+
+/// Force acting on a companion at the given position (relative to the deformed particle, or
+/// relative to the companion, the force being an odd function of the position) and torque acting
+/// on the deformed particle
+pub fn calculate_force_and_torque_due_to_figure(figure: &Figure, radius: f64, companion_mass: f64, position: Axes) -> (Axes, Axes) {
+    let position = to_vector(position);
+    let distance = norm(position);
+    let figure_position = multiply(figure, position);
+    let factor = FLUID_LOVE_NUMBER * K2 * companion_mass * radius.powi(5) / 2.;
+    let force = sub_vector(scale_vector(figure_position, 2. * factor / distance.powi(5)), scale_vector(position, 5. * factor * dot(position, figure_position) / distance.powi(7)));
+    let torque = cross(force, position);
+    (to_axes(force), to_axes(torque))
+}
+
+/// Equilibrium figure of the tide (without the mass and distance factors)
+fn equilibrium_tidal_figure(position: [f64; 3]) -> Figure {
+    let distance_2 = dot(position, position);
+    let mut figure = [[0.; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            figure[i][j] = 3. * position[i] * position[j] / distance_2;
+        }
+        figure[i][i] -= 1.;
+    }
+    figure
+}
+
+/// Keplerian orbit with the orbital frame (x-axis towards the pericentre, z-axis along the orbital
+/// angular momentum)
+struct Orbit {
+    semimajor_axis: f64,
+    eccentricity: f64,
+    mean_anomaly: f64,
+    mean_motion: f64,
+    x_axis: [f64; 3],
+    y_axis: [f64; 3],
+    z_axis: [f64; 3],
+}
+
+impl Orbit {
+    fn new(gm: f64, position: Axes, velocity: Axes) -> Option<Orbit> {
+        let position = to_vector(position);
+        let velocity = to_vector(velocity);
+        let distance = norm(position);
+        let angular_momentum = cross(position, velocity);
+        let semimajor_axis = 1. / (2. / distance - dot(velocity, velocity) / gm);
+        if norm(angular_momentum) == 0. || semimajor_axis <= 0. {
+            return None;
+        }
+        let z_axis = scale_vector(angular_momentum, 1. / norm(angular_momentum));
+        let eccentricity_vector = sub_vector(scale_vector(cross(velocity, angular_momentum), 1. / gm), scale_vector(position, 1. / distance));
+        let eccentricity = norm(eccentricity_vector);
+        if eccentricity >= 1. {
+            return None;
+        }
+        // The pericentre is not defined for circular orbits, the current position is used instead
+        let x_axis = if eccentricity > 1.0e-10 { scale_vector(eccentricity_vector, 1. / eccentricity) } else { scale_vector(position, 1. / distance) };
+        let y_axis = cross(z_axis, x_axis);
+        let true_anomaly = dot(position, y_axis).atan2(dot(position, x_axis));
+        let eccentric_anomaly = 2. * (((1. - eccentricity) / (1. + eccentricity)).sqrt() * (true_anomaly / 2.).sin()).atan2((true_anomaly / 2.).cos());
+        let mean_anomaly = eccentric_anomaly - eccentricity * eccentric_anomaly.sin();
+        let mean_motion = (gm / semimajor_axis.powi(3)).sqrt();
+        Some(Orbit { semimajor_axis, eccentricity, mean_anomaly, mean_motion, x_axis, y_axis, z_axis })
+    }
+}
+
+/// Tensor given in the frame `from` (rows are its axes) expressed in the frame `to`
+fn change_frame(tensor: &Figure, from: &[[f64; 3]; 3], to: &[[f64; 3]; 3]) -> Figure {
+    let mut rotation = [[0.; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            rotation[i][j] = dot(to[i], from[j]);
+        }
+    }
+    let mut result = [[0.; 3]; 3];
+    for i in 0..3 {
+        for j in 0..3 {
+            for k in 0..3 {
+                for l in 0..3 {
+                    result[i][j] += tensor[k][l] * rotation[i][k] * rotation[j][l];
+                }
+            }
+        }
+    }
+    result
+}
+
+fn scale(figure: &Figure, factor: f64) -> Figure {
+    let mut result = *figure;
+    for row in result.iter_mut() {
+        for value in row.iter_mut() {
+            *value *= factor;
+        }
+    }
+    result
+}
+
+fn multiply(figure: &Figure, vector: [f64; 3]) -> [f64; 3] {
+    [dot(figure[0], vector), dot(figure[1], vector), dot(figure[2], vector)]
+}
+
+fn to_vector(axes: Axes) -> [f64; 3] {
+    [axes.x, axes.y, axes.z]
+}
+
+fn to_axes(vector: [f64; 3]) -> Axes {
+    Axes{x: vector[0], y: vector[1], z: vector[2]}
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+fn scale_vector(a: [f64; 3], factor: f64) -> [f64; 3] {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn sub_vector(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
@@ -6,22 +6,63 @@ use super::super::super::tools;
 use super::super::super::{Particle};
 use super::super::super::{Axes};
 use super::common::TidesEffect;
-use super::common::TidalModel;
+use super::model::{TidalModel, TidalResponse, TidalContext, Tide, calculate_tidal_power_from_force_and_torque};
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub struct CreepCoplanarParameters {
     pub uniform_viscosity_coefficient: f64,
 }
 
-pub fn calculate_torque_due_to_tides(tidal_host_particle: &Particle, particle: &Particle, central_body:bool) -> Axes {
+impl TidalResponse for CreepCoplanarParameters {
+    fn name(&self) -> &'static str {
+        "CreepCoplanar"
+    }
+
+    /// Semi-diurnal frequency of the coplanar problem (using mean motion and the spin along the z-axis)
+    fn excitation_frequencies(&self, tidal_host_particle: &Particle, particle: &Particle, tide: Tide) -> Vec<f64> {
+        let deformed_particle = match tide {
+            Tide::Stellar => tidal_host_particle,
+            Tide::Planetary => particle,
+        };
+        let gm = tidal_host_particle.mass_g+particle.mass_g;
+        let (perihelion_distance, eccentricity) = tools::calculate_perihelion_distance_and_eccentricity(gm, particle.tides.coordinates.position, particle.tides.coordinates.velocity);
+        let mean_motion = gm.sqrt() * (perihelion_distance/(1.0 - eccentricity)).powf(-1.5);
+        vec![2. * (deformed_particle.spin.z - mean_motion).abs()]
+    }
+
+    fn calculate_tidal_force_and_torque(&mut self, tidal_host_particle: &mut Particle, particle: &mut Particle, tide: Tide, _context: &mut TidalContext) -> (Axes, Axes) {
+        let tidal_force = match tide {
+            Tide::Planetary => {
+                // Planet shape is host dependent, the host shape is computed when the torque is needed
+                if self.uniform_viscosity_coefficient != 0. {
+                    let consider_tides = true;
+                    let consider_rotational_flattening = false;
+                    let central_body = false;
+                    particle.tides.parameters.internal.shape = calculate_creep_coplanar_shape(tidal_host_particle, particle, consider_tides, consider_rotational_flattening, central_body);
+                }
+                calculate_tidal_force(tidal_host_particle, particle)
+            },
+            // The force due to the host shape is not considered
+            Tide::Stellar => Axes{x: 0., y: 0., z: 0.},
+        };
+        let torque_due_to_tides = calculate_torque_due_to_tides(tidal_host_particle, particle, tide);
+        (tidal_force, Axes{x: -1.0 * torque_due_to_tides.x, y: -1.0 * torque_due_to_tides.y, z: -1.0 * torque_due_to_tides.z})
+    }
+
+    fn dissipated_power(&self, tidal_host_particle: &Particle, particle: &Particle, context: &mut TidalContext) -> f64 {
+        calculate_tidal_power_from_force_and_torque(self, tidal_host_particle, particle, context)
+    }
+}
+
+pub fn calculate_torque_due_to_tides(tidal_host_particle: &Particle, particle: &Particle, tide: Tide) -> Axes {
     let torque_due_to_tides_x: f64 = 0.;
     let torque_due_to_tides_y: f64 = 0.;
     //let torque_due_to_tides_z: f64 = 0.0;
 
     // Torque expression for studying creep tide tidal despinning (use torque = 0 as above to study stat. rotation, makes code run faster)
 
-    let torque_due_to_tides_z = match central_body {
-        true => {
+    let torque_due_to_tides_z = match tide {
+        Tide::Stellar => {
             // Host shape is planet dependent, it needs to be computed for each.
             // For (mainly) co-orbital and circumbinary systems, planet shape cannot be linearly added.
             // The resulting shape is a combination of the shapes caused by each planet, but the
@@ -30,6 +71,7 @@ pub fn calculate_torque_due_to_tides(tidal_host_particle: &Particle, particle: &
             // See more discussions in https://arxiv.org/abs/2105.02336
             let consider_tides = true;
             let consider_rotational_flattening = false;
+            let central_body = true;
             let tidal_host_shape = calculate_creep_coplanar_shape(&tidal_host_particle, &particle, consider_tides, consider_rotational_flattening, central_body);
             3.0 / 5.0
             * K2
@@ -40,7 +82,7 @@ pub fn calculate_torque_due_to_tides(tidal_host_particle: &Particle, particle: &
             * tidal_host_shape.y
             / particle.tides.parameters.internal.distance.powi(3)
         },
-        false => {
+        Tide::Planetary => {
             // Planet shape is host dependent and it was already computed
             3.0 / 5.0
             * K2
@@ -122,31 +164,6 @@ pub fn calculate_tidal_force(tidal_host_particle: &Particle, particle: &Particle
 ////////////////////////////////////////////////////////////////////////////////
 // Creep coplanar tools
 ////////////////////////////////////////////////////////////////////////////////
-pub fn calculate_creep_coplanar_shapes(tidal_host_particle: &mut Particle, particles: &mut [Particle], more_particles: &mut [Particle]) {
-    // It does not compute the host shape since it is planet dependent
-    // and it will be computed later when it is needed
-    for particle in particles.iter_mut().chain(more_particles.iter_mut()) {
-        let particle_uniform_viscosity_coefficient = match &particle.tides.effect {
-            TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model) => {
-                if let TidalModel::CreepCoplanar(params) = tidal_model {
-                    params.uniform_viscosity_coefficient
-                } else {
-                    0.
-                }
-            },
-            _ => 0.,
-        };
-        if particle_uniform_viscosity_coefficient == 0. {
-            continue
-        }
-        let consider_tides = true;
-        let consider_rotational_flattening = false;
-        let central_body = false;
-        let shape = calculate_creep_coplanar_shape(&tidal_host_particle, &particle, consider_tides, consider_rotational_flattening, central_body);
-        particle.tides.parameters.internal.shape = shape;
-    }
-}
-
 pub fn calculate_creep_coplanar_shape(tidal_host_particle: &Particle, particle: &Particle, consider_tides: bool, consider_rotational_flattening: bool, central_body: bool) -> Axes {
     let gm = tidal_host_particle.mass_g + particle.mass_g;
     let (
@@ -4,44 +4,104 @@ use super::super::super::constants::{DAY, G, TWO_PI};
 use super::super::super::tools;
 use super::super::super::Axes;
 use super::super::super::Particle;
-use super::common::TidesEffect;
-use super::common::TidalModel;
-
-use serde_big_array::BigArray;
-
-// Structure declare the intput parameters needed for the Kaula model calculation.
-#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
-pub struct KaulaParameters {
-    // The tidal Love numbers function of the excitation frequency
-    #[serde(with = "BigArray")]
-    pub love_number_excitation_frequency: [f64; 32 * 32], // The excitation frequency
-    #[serde(with = "BigArray")]
-    pub real_part_love_number: [f64; 32 * 32], // The real part of the complex love number
-    #[serde(with = "BigArray")]
-    pub imaginary_part_love_number: [f64; 32 * 32], // The imaginary part of the complex love number
-    pub num_datapoints: f64, // The number of point stored in the 32 by 32 array
-    #[serde(default)]
-    pub polynomials: Polynomials,
-    pub kaula_tidal_force: Axes,
+use super::model::{TidalResponse, TidalContext, Tide, calculate_tidal_power_from_force_and_torque};
+use super::love_number_table::LoveNumberTable;
+use super::kaula_functions::{KaulaFunctions, KaulaFunctionsCache};
+
+// The Kaula model does not have parameters, the Love numbers of the tidally deformed body come
+// from its Love number table (see love_number_table.rs), which is either given by the user or
+// computed from its interior structure (see rheology.rs)
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KaulaParameters {}
+
+impl TidalResponse for KaulaParameters {
+    fn name(&self) -> &'static str {
+        "Kaula"
+    }
+
+    /// Tidal excitation frequencies of the modes (m, p, q) of degree 2, with p in [0, 2] and q in [-2, 2]
+    fn excitation_frequencies(&self, tidal_host_particle: &Particle, particle: &Particle, tide: Tide) -> Vec<f64> {
+        let deformed_particle = match tide {
+            Tide::Stellar => tidal_host_particle,
+            Tide::Planetary => particle,
+        };
+        let gm = G * (tidal_host_particle.mass + particle.mass);
+        let orbital_period = tools::calculate_keplerian_orbital_elements(gm, particle.heliocentric_position, particle.heliocentric_velocity).7;
+        let orbital_frequency = TWO_PI / (orbital_period * DAY); // The orbital mean motion in [rad.s^-1]
+        let spin = deformed_particle.norm_spin_vector_2.sqrt() / DAY; // The stellar/planetary spin in [rad.s^-1]
+        let mut frequencies = Vec::with_capacity(3 * 3 * 5);
+        for m in 0..=2 {
+            for p in 0..=2 {
+                for q in -2..=2 {
+                    frequencies.push(calculate_tidal_excitation_frequency_mode_sigma_2mpq(m as f64, p as f64, q as f64, spin, orbital_frequency) * DAY);
+                }
+            }
+        }
+        frequencies
+    }
+
+    fn calculate_tidal_force_and_torque(&mut self, tidal_host_particle: &mut Particle, particle: &mut Particle, tide: Tide, context: &mut TidalContext) -> (Axes, Axes) {
+        // The Love numbers of the tidally deformed body can come from a variable-size table
+        let deformed_particle_id = match tide {
+            Tide::Stellar => tidal_host_particle.id,
+            Tide::Planetary => particle.id,
+        };
+        let love_number_table = context.love_number_tables.iter().find(|love_number_table| love_number_table.particle == deformed_particle_id);
+        calculate_tidal_force_and_torque(tidal_host_particle, particle, tide, KaulaLoveNumbers::Table(love_number_table), context.kaula_functions_cache)
+    }
+
+    fn dissipated_power(&self, tidal_host_particle: &Particle, particle: &Particle, context: &mut TidalContext) -> f64 {
+        calculate_tidal_power_from_force_and_torque(self, tidal_host_particle, particle, context)
+    }
+}
+
+/// Love numbers of degree 2 of the tidally deformed body at each tidal excitation frequency,
+/// with the sign convention of the Love number tables (see `calculate_kaula_numbers`)
+#[derive(Debug, Copy, Clone)]
+pub enum KaulaLoveNumbers<'a> {
+    Table(Option<&'a LoveNumberTable>), // Bodies without a table are not deformed (see the validation of the Love number tables)
+    ConstantPhaseLag { love_number: f64, quality_factor: f64 }, // Same Love number and phase lag sign(w)/Q at every frequency (see constant_phase_lag.rs)
 }
 
-pub fn calculate_tidal_force(tidal_host_particle: &mut Particle, particle: &mut Particle) -> Axes {
-    // Planetary tide component
-    let central_body = false;
-    let tidal_force_due_to_planetary_tide = calculate_tidal_force_component(tidal_host_particle, particle, central_body);
-    let mut tidal_force = tidal_force_due_to_planetary_tide;
-    if matches!(tidal_host_particle.tides.effect, TidesEffect::CentralBody(TidalModel::Kaula(_))) {
-        // Stellar tide component
-        let central_body = true;
-        let tidal_force_due_to_stellar_tide = calculate_tidal_force_component(particle, tidal_host_particle, central_body);
-        tidal_force.x -= tidal_force_due_to_stellar_tide.x;
-        tidal_force.y -= tidal_force_due_to_stellar_tide.y;
-        tidal_force.z -= tidal_force_due_to_stellar_tide.z;
+impl<'a> KaulaLoveNumbers<'a> {
+    fn love_number(&self, frequency: f64) -> (f64, f64) {
+        match self {
+            KaulaLoveNumbers::Table(Some(love_number_table)) => love_number_table.love_number(frequency),
+            KaulaLoveNumbers::Table(None) => (0., 0.),
+            KaulaLoveNumbers::ConstantPhaseLag { love_number, quality_factor } => {
+                // The tables store the opposite of the Love number, whose imaginary part is negative for positive frequencies
+                let sign = if frequency > 0. { 1. } else if frequency < 0. { -1. } else { 0. };
+                (-love_number, sign * love_number / quality_factor)
+            },
+        }
     }
-    tidal_force
 }
 
-fn calculate_tidal_force_component(tidal_host_particle: &mut Particle, particle: &mut Particle, central_body: bool) -> Axes {
+/// Tidal force acting on the orbiting body (the host feels the opposite force) and secular torque
+/// acting on the tidally deformed body, summed over the tidal modes of degree 2
+pub fn calculate_tidal_force_and_torque(tidal_host_particle: &mut Particle, particle: &mut Particle, tide: Tide, love_numbers: KaulaLoveNumbers, kaula_functions_cache: &mut KaulaFunctionsCache) -> (Axes, Axes) {
+    match tide {
+        Tide::Planetary => {
+            let (tidal_force, secular_tidal_force) = calculate_tidal_force_component(tidal_host_particle, particle, tide, love_numbers, kaula_functions_cache);
+            let torque_due_to_tides = calculate_torque_due_to_tides(particle.tides.coordinates.position, secular_tidal_force);
+            (tidal_force, Axes{x: -torque_due_to_tides.x, y: -torque_due_to_tides.y, z: -torque_due_to_tides.z})
+        },
+        Tide::Stellar => {
+            // The roles are swapped: the orbiting body perturbs the host, and the computed force
+            // acts on the host
+            let (tidal_force, secular_tidal_force) = calculate_tidal_force_component(particle, tidal_host_particle, tide, love_numbers, kaula_functions_cache);
+            // The position vector goes from the perturber (orbiting body) to the perturbed body (host)
+            let position = Axes{x: -particle.tides.coordinates.position.x, y: -particle.tides.coordinates.position.y, z: -particle.tides.coordinates.position.z};
+            let torque_due_to_tides = calculate_torque_due_to_tides(position, secular_tidal_force);
+            (Axes{x: -tidal_force.x, y: -tidal_force.y, z: -tidal_force.z},
+             Axes{x: -torque_due_to_tides.x, y: -torque_due_to_tides.y, z: -torque_due_to_tides.z})
+        },
+    }
+}
+
+/// Tidal force acting on the perturber (i.e., tidal_host_particle) and its secular part due to the
+/// tide raised on the tidally deformed body (i.e., particle)
+fn calculate_tidal_force_component(tidal_host_particle: &mut Particle, particle: &mut Particle, tide: Tide, love_numbers: KaulaLoveNumbers, kaula_functions_cache: &mut KaulaFunctionsCache) -> (Axes, Axes) {
     // --- The spherical coordinate --- //
     // The following elements correspond to the coordinate in the spherical coordinate
     // The coplanar distance is the radial distance projected in the x-y plane
@@ -58,9 +118,9 @@ fn calculate_tidal_force_component(tidal_host_particle: &mut Particle, particle:
     // --- The Keplerian orbital elements --- //
     let gm = G * (tidal_host_particle.mass + particle.mass);
     let keplerian_elements: (f64, f64, f64, f64, f64, f64, f64, f64);
-    // !central_body ==> planetary tide ==> particle is the planet
-    // central_body ==> stellar tide ==> tidal_host_particle is the planet
-    if !central_body {
+    // Planetary tide ==> particle is the planet
+    // Stellar tide ==> tidal_host_particle is the planet
+    if tide == Tide::Planetary {
         keplerian_elements = tools::calculate_keplerian_orbital_elements(gm, particle.heliocentric_position, particle.heliocentric_velocity);
         radial_distance = particle.tides.parameters.internal.distance;
         coplanar_distance = (particle.tides.coordinates.position.x.powi(2) + particle.tides.coordinates.position.y.powi(2)).sqrt();
@@ -77,21 +137,29 @@ fn calculate_tidal_force_component(tidal_host_particle: &mut Particle, particle:
     }
     sin_theta = coplanar_distance / radial_distance;
 
+    // --- The inclination and eccentricity functions of degree 2 (and 3 for the 3D case) --- //
+    // The range of q grows with the eccentricity, they are thus computed once for all the components
+    // and kept until the orbit changes (see KaulaFunctionsCache)
+    let obliquity = if tide == Tide::Planetary {
+        tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin)
+    } else {
+        tools::calculate_inclination_orbital_equatorial_plane(tidal_host_particle.heliocentric_position, tidal_host_particle.heliocentric_velocity, particle.spin)
+    };
+    let kaula_functions_2 = kaula_functions_cache.kaula_functions(particle.id, tidal_host_particle.id, 2, keplerian_elements.2, obliquity).clone();
+    let kaula_functions_3 = if obliquity > 1.0e-8 {
+        Some(kaula_functions_cache.kaula_functions(particle.id, tidal_host_particle.id, 3, keplerian_elements.2, obliquity))
+    } else {
+        None
+    };
+
     // --- The spherical component of the tidal force --- //
     // The radial component is the force applicated through the radial axis
     // The normal component act on the co longitude axis
     // The orthogonal component act on the co latitude axis
     // ---
-    let (radial_component_of_the_tidal_force, radial_component_of_the_tidal_force_secular) = calculate_radial_component_of_the_tidal_force(tidal_host_particle, particle, keplerian_elements, central_body);
-    let (normal_component_of_the_tidal_force, normal_component_of_the_tidal_force_secular) = calculate_normal_component_of_the_tidal_force(tidal_host_particle, particle, keplerian_elements, central_body);
-    let (orthogonal_component_of_the_tidal_force, orthogonal_component_of_the_tidal_force_secular) = calculate_orthogonal_component_of_the_tidal_force(tidal_host_particle, particle, keplerian_elements, central_body);
-
-    // Required for denergy_dt calculation:
-    if !central_body {
-        particle.tides.parameters.internal.orthogonal_component_of_the_tidal_force_due_to_planetary_tide = orthogonal_component_of_the_tidal_force;
-    } else {
-        tidal_host_particle.tides.parameters.internal.orthogonal_component_of_the_tidal_force_due_to_stellar_tide = orthogonal_component_of_the_tidal_force;
-    }
+    let (radial_component_of_the_tidal_force, radial_component_of_the_tidal_force_secular) = calculate_radial_component_of_the_tidal_force(&kaula_functions_2, tidal_host_particle, particle, keplerian_elements, tide, love_numbers);
+    let (normal_component_of_the_tidal_force, normal_component_of_the_tidal_force_secular) = calculate_normal_component_of_the_tidal_force(&kaula_functions_2, tidal_host_particle, particle, keplerian_elements, tide, love_numbers);
+    let (orthogonal_component_of_the_tidal_force, orthogonal_component_of_the_tidal_force_secular) = calculate_orthogonal_component_of_the_tidal_force(&kaula_functions_2, kaula_functions_3, tidal_host_particle, particle, keplerian_elements, tide, love_numbers);
 
     // --- The cartesian tidal force --- // computed by projection of the spherical coordinates
     let tidal_force_x = radial_component_of_the_tidal_force * sin_theta * cos_phi + normal_component_of_the_tidal_force * cos_theta * cos_phi - orthogonal_component_of_the_tidal_force * sin_phi;
@@ -103,36 +171,31 @@ fn calculate_tidal_force_component(tidal_host_particle: &mut Particle, particle:
     //let tidal_force_y = radial_component_of_the_tidal_force_secular * sin_theta * sin_phi + normal_component_of_the_tidal_force_secular * cos_theta * sin_phi + orthogonal_component_of_the_tidal_force_secular * cos_phi;
     //let tidal_force_z = radial_component_of_the_tidal_force_secular * cos_theta - normal_component_of_the_tidal_force_secular * sin_theta;
     // --- The tidal torque --- // return the secular part of the tidal torque (simplified from the rapid varying phases)
-    if let Some(params) = match &mut particle.tides.effect {
-        TidesEffect::CentralBody(TidalModel::Kaula(ref mut params)) => Some(params),
-        TidesEffect::OrbitingBody(TidalModel::Kaula(ref mut params)) => Some(params),
-        _ => None,
-    } {
-        params.kaula_tidal_force.x = radial_component_of_the_tidal_force_secular * sin_theta * cos_phi + normal_component_of_the_tidal_force_secular * cos_theta * cos_phi - orthogonal_component_of_the_tidal_force_secular * sin_phi;
-        params.kaula_tidal_force.y = radial_component_of_the_tidal_force_secular * sin_theta * sin_phi + normal_component_of_the_tidal_force_secular * cos_theta * sin_phi + orthogonal_component_of_the_tidal_force_secular * cos_phi;
-        params.kaula_tidal_force.z = radial_component_of_the_tidal_force_secular * cos_theta - normal_component_of_the_tidal_force_secular * sin_theta;
-    } else {
-        unreachable!();
-    }
+    let secular_tidal_force = Axes {
+        x: radial_component_of_the_tidal_force_secular * sin_theta * cos_phi + normal_component_of_the_tidal_force_secular * cos_theta * cos_phi - orthogonal_component_of_the_tidal_force_secular * sin_phi,
+        y: radial_component_of_the_tidal_force_secular * sin_theta * sin_phi + normal_component_of_the_tidal_force_secular * cos_theta * sin_phi + orthogonal_component_of_the_tidal_force_secular * cos_phi,
+        z: radial_component_of_the_tidal_force_secular * cos_theta - normal_component_of_the_tidal_force_secular * sin_theta,
+    };
 
-    Axes {
+    (Axes {
         x: tidal_force_x,
         y: tidal_force_y,
         z: tidal_force_z,
-    }
+    }, secular_tidal_force)
 }
 
-
 // ------------------------------------- //
 // --- Calculate tidal force modules --- //
 // Function which compute the components of the tidal torque
 
 // --- The radial (e_{r}) component of tidal force
 fn calculate_radial_component_of_the_tidal_force(
+    kaula_functions_2: &KaulaFunctions,
     tidal_host_particle: &Particle,
     particle: &mut Particle,
     keplerian_elements: (f64, f64, f64, f64, f64, f64, f64, f64),
-    central_body: bool,
+    tide: Tide,
+    love_numbers: KaulaLoveNumbers,
 ) -> (f64, f64) {
     // --- The keplerian elements
     // ---
@@ -151,7 +214,7 @@ fn calculate_radial_component_of_the_tidal_force(
     let argument_perihelion: f64;
     let orbital_frequency: f64;
 
-    if !central_body {
+    if tide == Tide::Planetary {
         tem_radius = particle.heliocentric_distance;
         obliquity = tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin);
     } else {
@@ -162,12 +225,6 @@ fn calculate_radial_component_of_the_tidal_force(
     orbital_frequency = TWO_PI / (orbital_period * DAY); // The orbital mean motion in [rad.s^-1]
     let spin: f64 = particle.norm_spin_vector_2.sqrt() / DAY; // The stellar/planetary spin in [rad.s^-1]
 
-    let kaula_param = match particle.tides.effect {
-        TidesEffect::CentralBody(TidalModel::Kaula(ref mut params)) => params,
-        TidesEffect::OrbitingBody(TidalModel::Kaula(ref mut params)) => params,
-        _ => unreachable!(),
-    };
-
     // ---
     // --- local quantities needed --- //
     let cste: f64 = -(G * tidal_host_particle.mass.powi(2) * particle.radius.powi(5)) / (semi_major_axis.powi(6) * tem_radius);
@@ -183,36 +240,20 @@ fn calculate_radial_component_of_the_tidal_force(
             // --- If circular coplanar orbit
             let frequ_2010 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(0., 1., 0., spin, orbital_frequency);
             let frequ_2200 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., 0., 0., spin, orbital_frequency);
-            let (rek2_2010, _imk2_2010) = calculate_kaula_numbers(frequ_2010, kaula_param, central_body);
-            let (_rek2_2200, imk2_2200) = calculate_kaula_numbers(frequ_2200, kaula_param, central_body);
+            let (rek2_2010, _imk2_2010) = calculate_kaula_numbers(frequ_2010, love_numbers, tide);
+            let (_rek2_2200, imk2_2200) = calculate_kaula_numbers(frequ_2200, love_numbers, tide);
             radial_force = cste * ((3_f64 / 4_f64) * rek2_2010 + (9_f64 / 4_f64) * imk2_2200);
             radial_force_secular = radial_force;
         } else {
             //  --- If eccentric orbit
-            kaula_param.polynomials.calculate_eccentricity_function_g_20q(eccentricity);
-            kaula_param.polynomials.calculate_eccentricity_function_g_21q(eccentricity);
             let mut sum_over_q: f64 = 0.;
             let mut sum_over_q_secular: f64 = 0.;
 
-            let order = match () {
-                // Select the order of the summation q over the eccentricity function G_lpq
-                _ if (eccentricity > 0.3) => 7, // if ecc > 0.20 take q btw -7 and 7
-                _ if (eccentricity > 0.25) => 6, // if ecc > 0.20 take q btw -6 and 6
-                _ if (eccentricity > 0.2) => 5, // if ecc > 0.20 take q btw -5 and 5
-                _ if (eccentricity > 0.15) => 4, // if ecc > 0.15 take q btw -4 and 4
-                _ if (eccentricity > 0.1) => 3, // if ecc > 0.10 take q btw -3 and 3
-                _ if (eccentricity <= 0.1) => 2, // if ecc > 0.10 take q btw -2 and 2
-                _ => 0,
-            };
-            // let q_max = [7-order, 2*order+1];
-            let q_min = 7 - order;
-            let q_max = 2 * order + 1;
-
-            for (q, g_201q) in kaula_param.polynomials.eccentricity_function_g_20q.iter().zip(kaula_param.polynomials.eccentricity_function_g_21q.iter()).skip(q_min).take(q_max).enumerate() {
-                let frequ_201q = calculate_tidal_excitation_frequency_mode_sigma_2mpq(0., 1., q as f64 - order as f64, spin, orbital_frequency);
-                let frequ_220q = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., 0., q as f64 - order as f64, spin, orbital_frequency);
-                let (rek2_201q, imk2_201q) = calculate_kaula_numbers(frequ_201q, kaula_param, central_body);
-                let (rek2_220q, imk2_220q) = calculate_kaula_numbers(frequ_220q, kaula_param, central_body);
+            for q in kaula_functions_2.q_range() {
+                let frequ_201q = calculate_tidal_excitation_frequency_mode_sigma_2mpq(0., 1., q as f64, spin, orbital_frequency);
+                let frequ_220q = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., 0., q as f64, spin, orbital_frequency);
+                let (rek2_201q, imk2_201q) = calculate_kaula_numbers(frequ_201q, love_numbers, tide);
+                let (rek2_220q, imk2_220q) = calculate_kaula_numbers(frequ_220q, love_numbers, tide);
 
                 let mut sum_over_j_1: f64 = 0.;
                 let mut sum_over_j_3: f64 = 0.;
@@ -220,9 +261,9 @@ fn calculate_radial_component_of_the_tidal_force(
                 let mut sum_over_j_1_secular: f64 = 0.;
                 let mut sum_over_j_3_secular: f64 = 0.;
 
-                for (j, g_201j) in kaula_param.polynomials.eccentricity_function_g_20q.iter().zip(kaula_param.polynomials.eccentricity_function_g_21q.iter()).skip(q_min).take(q_max).enumerate() {
-                    let alpha_qj = alpha_pqkj(0., q as f64 - order as f64, 0., j as f64 - order as f64, mean_anomaly, argument_perihelion);
-                    let (g_20j, g_21j) = g_201j;
+                for j in kaula_functions_2.q_range() {
+                    let alpha_qj = alpha_pqkj(0., q as f64, 0., j as f64, mean_anomaly, argument_perihelion);
+                    let (g_20j, g_21j) = (kaula_functions_2.eccentricity_function(0, j), kaula_functions_2.eccentricity_function(1, j));
 
                     sum_over_j_1 += g_21j * (alpha_qj.cos() * rek2_201q - alpha_qj.sin() * imk2_201q);
                     sum_over_j_3 += g_20j * (alpha_qj.cos() * rek2_220q - alpha_qj.sin() * imk2_220q);
@@ -232,7 +273,7 @@ fn calculate_radial_component_of_the_tidal_force(
                     }
                 } // end loop over j
 
-                let (g_20q, g_21q) = g_201q;
+                let (g_20q, g_21q) = (kaula_functions_2.eccentricity_function(0, q), kaula_functions_2.eccentricity_function(1, q));
                 sum_over_q += (3_f64 / 4_f64) * g_21q * sum_over_j_1 + (9_f64 / 4_f64) * g_20q * sum_over_j_3;
                 sum_over_q_secular += (3_f64 / 4_f64) * g_21q * sum_over_j_1_secular + (9_f64 / 4_f64) * g_20q * sum_over_j_3_secular;
             }
@@ -248,7 +289,7 @@ fn calculate_radial_component_of_the_tidal_force(
         let particle_star_2: f64;
         let particle_radius_5: f64;
 
-        if !central_body {
+        if tide == Tide::Planetary {
             // inclination = tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin);
             heliocentric_r = particle.heliocentric_distance;
             particle_star_2 = tidal_host_particle.mass.powi(2);
@@ -263,23 +304,6 @@ fn calculate_radial_component_of_the_tidal_force(
         let _cot_theta: f64 = 0.;
         let semi_major_axis_6: f64 = semi_major_axis.powi(6);
 
-        kaula_param.polynomials.calculate_inclination_function_f_20p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_21p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_22p(obliquity);
-        kaula_param.polynomials.calculate_eccentricity_function_g_2pq(eccentricity);
-
-        let order = match () {
-            // Select the order of the summation q over the eccentricity function G_lpq
-            _ if (eccentricity > 0.3) => 7, // if ecc > 0.20 take q btw -7 and 7
-            _ if (eccentricity > 0.25) => 6, // if ecc > 0.20 take q btw -6 and 6
-            _ if (eccentricity > 0.2) => 5, // if ecc > 0.20 take q btw -5 and 5
-            _ if (eccentricity > 0.15) => 4, // if ecc > 0.15 take q btw -4 and 4
-            _ if (eccentricity > 0.1) => 3, // if ecc > 0.10 take q btw -3 and 3
-            _ if (eccentricity <= 0.1) => 2, // if ecc > 0.10 take q btw -2 and 2
-            _ => 0,
-        };
-        let q_min = 7 - order;
-        let q_max = 2 * order + 1;
 
         let mut sum_over_p_m0: f64 = 0.;
         let mut sum_over_p_m1: f64 = 0.;
@@ -287,8 +311,8 @@ fn calculate_radial_component_of_the_tidal_force(
         let mut sum_over_p_m0_s: f64 = 0.;
         let mut sum_over_p_m1_s: f64 = 0.;
         let mut sum_over_p_m2_s: f64 = 0.;
-        for (p, f_2mp) in kaula_param.polynomials.inclination_function_f_20p.iter().zip( kaula_param.polynomials.inclination_function_f_21p.iter().zip(kaula_param.polynomials.inclination_function_f_22p.iter()),).enumerate() {
-            let (f_20p, (f_21p, f_22p)) = f_2mp;
+        for p in 0..=2 {
+            let (f_20p, f_21p, f_22p) = (kaula_functions_2.inclination_function(0, p), kaula_functions_2.inclination_function(1, p), kaula_functions_2.inclination_function(2, p));
 
             let tmp_p: f64 = p as f64;
             let f_20p_2 = f_20p.powi(2);
@@ -302,34 +326,33 @@ fn calculate_radial_component_of_the_tidal_force(
             let mut sum_over_q_m1_s: f64 = 0.;
             let mut sum_over_q_m2_s: f64 = 0.;
 
-            let sum_g_2pq = kaula_param.polynomials.eccentricity_function_g_2pq[p];
-
-            for (q, g_2pq) in sum_g_2pq.iter().skip(q_min).take(q_max).enumerate() {
-                let tmp_q: f64 = q as f64 - order as f64;
+            for q in kaula_functions_2.q_range() {
+                let g_2pq = kaula_functions_2.eccentricity_function(p, q);
+                let tmp_q: f64 = q as f64;
                 let g_2pq_2 = g_2pq.powi(2);
 
                 let frequ_20pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(0., tmp_p, tmp_q, spin, orbital_frequency);
                 let frequ_21pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(1., tmp_p, tmp_q, spin, orbital_frequency);
                 let frequ_22pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., tmp_p, tmp_q, spin, orbital_frequency);
-                let (rek2_20pq, imk2_20pq) = calculate_kaula_numbers(frequ_20pq, kaula_param, central_body);
-                let (rek2_21pq, imk2_21pq) = calculate_kaula_numbers(frequ_21pq, kaula_param, central_body);
-                let (rek2_22pq, imk2_22pq) = calculate_kaula_numbers(frequ_22pq, kaula_param, central_body);
+                let (rek2_20pq, imk2_20pq) = calculate_kaula_numbers(frequ_20pq, love_numbers, tide);
+                let (rek2_21pq, imk2_21pq) = calculate_kaula_numbers(frequ_21pq, love_numbers, tide);
+                let (rek2_22pq, imk2_22pq) = calculate_kaula_numbers(frequ_22pq, love_numbers, tide);
 
                 let mut sum_over_k_m0: f64 = 0.;
                 let mut sum_over_k_m1: f64 = 0.;
                 let mut sum_over_k_m2: f64 = 0.;
 
-                for (k, f_2mk) in kaula_param.polynomials.inclination_function_f_20p.iter().zip( kaula_param.polynomials.inclination_function_f_21p.iter().zip(kaula_param.polynomials.inclination_function_f_22p.iter()),).enumerate() {
-                    let (f_20k, (f_21k, f_22k)) = f_2mk;
+                for k in 0..=2 {
+                    let (f_20k, f_21k, f_22k) = (kaula_functions_2.inclination_function(0, k), kaula_functions_2.inclination_function(1, k), kaula_functions_2.inclination_function(2, k));
 
                     let tmp_k: f64 = k as f64;
                     let mut sum_over_j_m0: f64 = 0.;
                     let mut sum_over_j_m1: f64 = 0.;
                     let mut sum_over_j_m2: f64 = 0.;
-                    let sum_g_2kj = kaula_param.polynomials.eccentricity_function_g_2pq[k];
 
-                    for (j, g_2kj) in sum_g_2kj.iter().skip(q_min).take(q_max).enumerate() {
-                        let tmp_j: f64 = j as f64 - order as f64;
+                    for j in kaula_functions_2.q_range() {
+                        let g_2kj = kaula_functions_2.eccentricity_function(k, j);
+                        let tmp_j: f64 = j as f64;
                         let phase_alpha = alpha_pqkj(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, argument_perihelion);
 
                         let cos_alpha: f64 = phase_alpha.cos();
@@ -367,23 +390,19 @@ fn calculate_radial_component_of_the_tidal_force(
 
 // --- The Normal (e_{\theta}) component of tidal force
 fn calculate_normal_component_of_the_tidal_force(
+    kaula_functions_2: &KaulaFunctions,
     tidal_host_particle: &Particle,
     particle: &mut Particle,
     keplerian_elements: (f64, f64, f64, f64, f64, f64, f64, f64),
-    central_body: bool,
+    tide: Tide,
+    love_numbers: KaulaLoveNumbers,
 ) -> (f64, f64) {
-    let kaula_param = match particle.tides.effect {
-        TidesEffect::CentralBody(TidalModel::Kaula(ref mut params)) => params,
-        TidesEffect::OrbitingBody(TidalModel::Kaula(ref mut params)) => params,
-        _ => unreachable!(),
-    };
-
     // --- The keplerian elements
     // ---
     let (
         semi_major_axis,
         _perihelion_distance,
-        eccentricity,
+        _eccentricity,
         _inclination,
         longitude_perihelion,
         longitude_of_ascending_node,
@@ -394,7 +413,7 @@ fn calculate_normal_component_of_the_tidal_force(
     let orbital_frequency = TWO_PI / (orbital_period * DAY); // The orbital mean motion in [rad.s^-1]
 
     let obliquity: f64;
-    if !central_body {
+    if tide == Tide::Planetary {
         obliquity = tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin);
     } else {
         obliquity = tools::calculate_inclination_orbital_equatorial_plane(tidal_host_particle.heliocentric_position, tidal_host_particle.heliocentric_velocity, particle.spin);
@@ -413,7 +432,7 @@ fn calculate_normal_component_of_the_tidal_force(
         let particle_star_2: f64;
         let particle_radius_5: f64;
 
-        if !central_body {
+        if tide == Tide::Planetary {
             // inclination = tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin);
             heliocentric_r = particle.heliocentric_distance;
             particle_star_2 = tidal_host_particle.mass.powi(2);
@@ -429,56 +448,40 @@ fn calculate_normal_component_of_the_tidal_force(
         let semi_major_axis_6: f64 = semi_major_axis.powi(6);
         let _cot_theta: f64 = 0.;
 
-        kaula_param.polynomials.calculate_inclination_function_f_20p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_21p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_22p(obliquity);
-        kaula_param.polynomials.calculate_eccentricity_function_g_2pq(eccentricity);
-
-        let order = match () {
-            // Select the order of the summation q over the eccentricity function G_lpq
-            _ if (eccentricity > 0.3) => 7, // if ecc > 0.20 take q btw -7 and 7
-            _ if (eccentricity > 0.25) => 6, // if ecc > 0.20 take q btw -6 and 6
-            _ if (eccentricity > 0.2) => 5, // if ecc > 0.20 take q btw -5 and 5
-            _ if (eccentricity > 0.15) => 4, // if ecc > 0.15 take q btw -4 and 4
-            _ if (eccentricity > 0.1) => 3, // if ecc > 0.10 take q btw -3 and 3
-            _ if (eccentricity <= 0.1) => 2, // if ecc > 0.10 take q btw -2 and 2
-            _ => 0,
-        };
-        let q_min = 7 - order;
-        let q_max = 2 * order + 1;
 
         // term m = 0
         let mut sum_over_p: f64 = 0.;
         let mut sum_over_p_s: f64 = 0.;
-        for (p, f_20p) in kaula_param.polynomials.inclination_function_f_20p.iter().enumerate() {
+        for p in 0..=2 {
+            let f_20p = kaula_functions_2.inclination_function(0, p);
             // println!("|\t p = {:?}", p);
             let tmp_p: f64 = p as f64;
             let mut sum_over_q: f64 = 0.;
             let mut sum_over_q_s: f64 = 0.;
-            let sum_g_2pq = kaula_param.polynomials.eccentricity_function_g_2pq[p];
 
-            for (q, g_2pq) in sum_g_2pq.iter().skip(q_min).take(q_max).enumerate() {
-                let tmp_q: f64 = q as f64 - order as f64;
+            for q in kaula_functions_2.q_range() {
+                let g_2pq = kaula_functions_2.eccentricity_function(p, q);
+                let tmp_q: f64 = q as f64;
                 // println!("|\t \t q = {:?} {:?}", q, tmp_q);
                 let frequ_20pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(0., tmp_p, tmp_q, spin, orbital_frequency);
+                let (rek2_20pq, imk2_20pq) = calculate_kaula_numbers(frequ_20pq, love_numbers, tide);
                 let mut sum_over_k: f64 = 0.;
                 let mut sum_over_k_s: f64 = 0.;
 
-                for (k, f_21k) in kaula_param.polynomials.inclination_function_f_21p.iter().enumerate() {
+                for k in 0..=2 {
+                    let f_21k = kaula_functions_2.inclination_function(1, k);
                     // println!("|\t \t \t k = {:?}", k);
                     let tmp_k: f64 = k as f64;
                     let mut sum_over_j: f64 = 0.;
                     let mut sum_over_j_s: f64 = 0.;
-                    let sum_g_2kj = kaula_param.polynomials.eccentricity_function_g_2pq[k];
 
-                    for (j, g_2kj) in sum_g_2kj.iter().skip(q_min).take(q_max).enumerate() {
-                        let tmp_j: f64 = j as f64 - order as f64;
+                    for j in kaula_functions_2.q_range() {
+                        let g_2kj = kaula_functions_2.eccentricity_function(k, j);
+                        let tmp_j: f64 = j as f64;
                         // println!("|\t \t \t \t j = {:?} {:?}", j, tmp_j);
                         let phase_beta: f64 = compute_phase_beta(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
                         let phase_alpha_1: f64 = compute_phase_alpha_1(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
 
-                        let (rek2_20pq, imk2_20pq) = calculate_kaula_numbers(frequ_20pq, kaula_param, central_body);
-
                         let cos_alpha_1: f64 = phase_alpha_1.cos();
                         let sin_alpha_1: f64 = phase_alpha_1.sin();
                         let cos_beta: f64 = phase_beta.cos();
@@ -502,40 +505,40 @@ fn calculate_normal_component_of_the_tidal_force(
         // term m = 1
         sum_over_p = 0.;
         sum_over_p_s = 0.;
-        for (p, f_21p) in kaula_param.polynomials.inclination_function_f_21p.iter().enumerate() {
+        for p in 0..=2 {
+            let f_21p = kaula_functions_2.inclination_function(1, p);
             let tmp_p: f64 = p as f64;
             let mut sum_over_q: f64 = 0.;
             let mut sum_over_q_s: f64 = 0.;
-            let sum_g_2pq = kaula_param.polynomials.eccentricity_function_g_2pq[p];
 
-            for (q, g_2pq) in sum_g_2pq.iter().skip(q_min).take(q_max).enumerate() {
-                let tmp_q: f64 = q as f64 - order as f64;
+            for q in kaula_functions_2.q_range() {
+                let g_2pq = kaula_functions_2.eccentricity_function(p, q);
+                let tmp_q: f64 = q as f64;
                 let frequ_21pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(1., tmp_p, tmp_q, spin, orbital_frequency);
+                let (rek2_21pq, imk2_21pq) = calculate_kaula_numbers(frequ_21pq, love_numbers, tide);
 
                 let mut sum_over_k_m2: f64 = 0.;
                 let mut sum_over_k_m0: f64 = 0.;
                 let mut sum_over_k_m2_s: f64 = 0.;
                 let mut sum_over_k_m0_s: f64 = 0.;
 
-                for (k, f_22_20_k) in kaula_param.polynomials.inclination_function_f_22p.iter().zip(kaula_param.polynomials.inclination_function_f_20p.iter()).enumerate() {
-                    let (f_22k, f_20k) = f_22_20_k; // is sum over p for each m
+                for k in 0..=2 {
+                    let (f_22k, f_20k) = (kaula_functions_2.inclination_function(2, k), kaula_functions_2.inclination_function(0, k)); // is sum over p for each m
 
                     let tmp_k: f64 = k as f64;
                     let mut sum_over_j_p2: f64 = 0.;
                     let mut sum_over_j_p0: f64 = 0.;
                     let mut sum_over_j_p2_s: f64 = 0.;
                     let mut sum_over_j_p0_s: f64 = 0.;
-                    let sum_g_2kj = kaula_param.polynomials.eccentricity_function_g_2pq[k];
 
-                    for (j, g_2kj) in sum_g_2kj.iter().skip(q_min).take(q_max).enumerate() {
-                        let tmp_j: f64 = j as f64 - order as f64;
+                    for j in kaula_functions_2.q_range() {
+                        let g_2kj = kaula_functions_2.eccentricity_function(k, j);
+                        let tmp_j: f64 = j as f64;
 
                         // let phase_beta:f64 =compute_phase_beta(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_ascending_node);
                         let phase_alpha_1: f64 = compute_phase_alpha_1(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
                         let phase_alpha_2: f64 = compute_phase_alpha_2(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
 
-                        let (rek2_21pq, imk2_21pq) = calculate_kaula_numbers(frequ_21pq, kaula_param, central_body);
-
                         let cos_alpha_1: f64 = phase_alpha_1.cos();
                         let sin_alpha_1: f64 = phase_alpha_1.sin();
                         let cos_alpha_2: f64 = phase_alpha_2.cos();
@@ -563,34 +566,34 @@ fn calculate_normal_component_of_the_tidal_force(
         // term m = 2
         sum_over_p = 0.;
         sum_over_p_s = 0.;
-        for (p, f_22p) in kaula_param.polynomials.inclination_function_f_22p.iter().enumerate() {
+        for p in 0..=2 {
+            let f_22p = kaula_functions_2.inclination_function(2, p);
             let tmp_p: f64 = p as f64;
             let mut sum_over_q: f64 = 0.;
             let mut sum_over_q_s: f64 = 0.;
-            let sum_g_2pq = kaula_param.polynomials.eccentricity_function_g_2pq[p];
 
-            for (q, g_2pq) in sum_g_2pq.iter().skip(q_min).take(q_max).enumerate() {
-                let tmp_q: f64 = q as f64 - order as f64;
+            for q in kaula_functions_2.q_range() {
+                let g_2pq = kaula_functions_2.eccentricity_function(p, q);
+                let tmp_q: f64 = q as f64;
                 let frequ_22pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., tmp_p, tmp_q, spin, orbital_frequency);
+                let (rek2_22pq, imk2_22pq) = calculate_kaula_numbers(frequ_22pq, love_numbers, tide);
 
                 let mut sum_over_k: f64 = 0.;
                 let mut sum_over_k_s: f64 = 0.;
 
-                for (k, f_21k) in kaula_param.polynomials.inclination_function_f_21p.iter().enumerate() {
+                for k in 0..=2 {
+                    let f_21k = kaula_functions_2.inclination_function(1, k);
                     let tmp_k: f64 = k as f64;
                     let mut sum_over_j: f64 = 0.;
                     let mut sum_over_j_s: f64 = 0.;
 
-                    let sum_g_2kj = kaula_param.polynomials.eccentricity_function_g_2pq[k];
-
-                    for (j, g_2kj) in sum_g_2kj.iter().skip(q_min).take(q_max).enumerate() {
-                        let tmp_j: f64 = j as f64 - order as f64;
+                    for j in kaula_functions_2.q_range() {
+                        let g_2kj = kaula_functions_2.eccentricity_function(k, j);
+                        let tmp_j: f64 = j as f64;
 
                         // let phase_beta:f64 =compute_phase_beta(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_ascending_node);
                         let phase_alpha_2: f64 = compute_phase_alpha_2(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
 
-                        let (rek2_22pq, imk2_22pq) = calculate_kaula_numbers(frequ_22pq, kaula_param, central_body);
-
                         let cos_alpha_2: f64 = phase_alpha_2.cos();
                         let sin_alpha_2: f64 = phase_alpha_2.sin();
 
@@ -619,17 +622,14 @@ fn calculate_normal_component_of_the_tidal_force(
 
 // --- The Ortho-radial (the e_{\varphi}) component of tidal force
 fn calculate_orthogonal_component_of_the_tidal_force(
+    kaula_functions_2: &KaulaFunctions,
+    kaula_functions_3: Option<&KaulaFunctions>,
     tidal_host_particle: &Particle, 
     particle: &mut Particle, 
     keplerian_elements: (f64, f64, f64, f64, f64, f64, f64, f64), 
-    central_body: bool,
+    tide: Tide,
+    love_numbers: KaulaLoveNumbers,
 ) -> (f64, f64) {
-    let kaula_param = match particle.tides.effect {
-        TidesEffect::CentralBody(TidalModel::Kaula(ref mut params)) => params,
-        TidesEffect::OrbitingBody(TidalModel::Kaula(ref mut params)) => params,
-        _ => unreachable!(),
-    };
-
     // --- The keplerian elements
     // ---
     let (
@@ -652,7 +652,7 @@ fn calculate_orthogonal_component_of_the_tidal_force(
     let sin_theta: f64;
     let cste_2d: f64;
 
-    if !central_body {
+    if tide == Tide::Planetary {
         tem_radius = particle.heliocentric_distance;
         // inclination = tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin);
         obliquity = tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin);
@@ -688,37 +688,23 @@ fn calculate_orthogonal_component_of_the_tidal_force(
     if obliquity <= 1.0e-8 {
         if eccentricity == 0. {
             let frequ_2200 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., 0., 0., spin, orbital_frequency);
-            let (_rek2_2200, imk2_2200) = calculate_kaula_numbers(frequ_2200, kaula_param, central_body);
+            let (_rek2_2200, imk2_2200) = calculate_kaula_numbers(frequ_2200, love_numbers, tide);
             orthogonal_force = cste_2d * (3_f64 / 2_f64) * imk2_2200;
             orthogonal_force_secular = orthogonal_force;
         } else {
-            kaula_param.polynomials.calculate_eccentricity_function_g_20q(eccentricity);
             let mut sum_over_q: f64 = 0.;
             let mut sum_over_q_secular: f64 = 0.;
 
-            let order = match () {
-                // Select the order of the summation q over the eccentricity function G_lpq
-                _ if (eccentricity > 0.3) => 7, // if ecc > 0.20 take q btw -7 and 7
-                _ if (eccentricity > 0.25) => 6, // if ecc > 0.20 take q btw -6 and 6
-                _ if (eccentricity > 0.2) => 5, // if ecc > 0.20 take q btw -5 and 5
-                _ if (eccentricity > 0.15) => 4, // if ecc > 0.15 take q btw -4 and 4
-                _ if (eccentricity > 0.1) => 3, // if ecc > 0.10 take q btw -3 and 3
-                _ if (eccentricity <= 0.1) => 2, // if ecc > 0.10 take q btw -2 and 2
-                _ => 0,
-            };
-            // let q_max = [7-order, 2*order+1];
-            let q_min = 7 - order;
-            let q_max = 2 * order + 1;
-
-            // for q in -7..8{
-            for (q, g_20q) in kaula_param.polynomials.eccentricity_function_g_20q.iter().skip(q_min).take(q_max).enumerate() {
-                let frequ_220q = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., 0., q as f64 - order as f64, spin, orbital_frequency);
-                let (rek2_220q, imk2_220q) = calculate_kaula_numbers(frequ_220q, kaula_param, central_body);
+            for q in kaula_functions_2.q_range() {
+                let g_20q = kaula_functions_2.eccentricity_function(0, q);
+                let frequ_220q = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., 0., q as f64, spin, orbital_frequency);
+                let (rek2_220q, imk2_220q) = calculate_kaula_numbers(frequ_220q, love_numbers, tide);
 
                 let mut sum_over_j_2: f64 = 0.;
                 let mut sum_over_j_2_secular: f64 = 0.;
-                for (j, g_20j) in kaula_param.polynomials.eccentricity_function_g_20q.iter().skip(q_min).take(q_max).enumerate() {
-                    let alpha_qj = alpha_pqkj(0., q as f64 - order as f64, 0., j as f64 - order as f64, mean_anomaly, argument_perihelion);
+                for j in kaula_functions_2.q_range() {
+                    let g_20j = kaula_functions_2.eccentricity_function(0, j);
+                    let alpha_qj = alpha_pqkj(0., q as f64, 0., j as f64, mean_anomaly, argument_perihelion);
 
                     sum_over_j_2 += g_20j * (alpha_qj.sin() * rek2_220q + alpha_qj.cos() * imk2_220q);
                     if q == j {
@@ -738,27 +724,7 @@ fn calculate_orthogonal_component_of_the_tidal_force(
         let spin_angle: f64 = 0.;
         let _cot_theta: f64 = 0.;
 
-        kaula_param.polynomials.calculate_inclination_function_f_21p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_22p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_30p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_31p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_32p(obliquity);
-        kaula_param.polynomials.calculate_inclination_function_f_33p(obliquity);
-        kaula_param.polynomials.calculate_eccentricity_function_g_2pq(eccentricity);
-        kaula_param.polynomials.calculate_eccentricity_function_g_3pq(eccentricity);
-
-        let order = match () {
-            // Select the order of the summation q over the eccentricity function G_lpq
-            _ if (eccentricity > 0.3) => 7, // if ecc > 0.20 take q btw -7 and 7
-            _ if (eccentricity > 0.25) => 6, // if ecc > 0.20 take q btw -6 and 6
-            _ if (eccentricity > 0.2) => 5, // if ecc > 0.20 take q btw -5 and 5
-            _ if (eccentricity > 0.15) => 4, // if ecc > 0.15 take q btw -4 and 4
-            _ if (eccentricity > 0.1) => 3, // if ecc > 0.10 take q btw -3 and 3
-            _ if (eccentricity <= 0.1) => 2, // if ecc > 0.10 take q btw -2 and 2
-            _ => 0,
-        };
-        let q_min = 7 - order;
-        let q_max = 2 * order + 1;
+        let kaula_functions_3 = kaula_functions_3.expect("Kaula functions of degree 3 are computed for inclined orbits");
 
         let mut sum_over_p: f64;
         let mut sum_over_p_s: f64;
@@ -768,41 +734,40 @@ fn calculate_orthogonal_component_of_the_tidal_force(
         sum_over_p_s = 0.;
         let c1 = 1_f64 / 6_f64;
         // let c2 = -(4_f64) / (15_f64).sqrt();
-        for (p, f_21p) in kaula_param.polynomials.inclination_function_f_21p.iter().enumerate() {
+        for p in 0..=2 {
+            let f_21p = kaula_functions_2.inclination_function(1, p);
             let tmp_p: f64 = p as f64;
             let mut sum_over_q: f64 = 0.;
             let mut sum_over_q_s: f64 = 0.;
-            let sum_g_2pq = kaula_param.polynomials.eccentricity_function_g_2pq[p];
 
-            for (q, g_2pq) in sum_g_2pq.iter().skip(q_min).take(q_max).enumerate() {
-                let tmp_q: f64 = q as f64 - order as f64;
+            for q in kaula_functions_2.q_range() {
+                let g_2pq = kaula_functions_2.eccentricity_function(p, q);
+                let tmp_q: f64 = q as f64;
                 let frequ_21pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(1., tmp_p, tmp_q, spin, orbital_frequency);
+                let (rek2_21pq, imk2_21pq) = calculate_kaula_numbers(frequ_21pq, love_numbers, tide);
 
                 let mut sum_over_k_term1: f64 = 0.;
                 let mut sum_over_k_term2: f64 = 0.;
                 let mut sum_over_k_term1_s: f64 = 0.;
                 let mut sum_over_k_term2_s: f64 = 0.;
 
-                for (k, f_30_32_k) in kaula_param.polynomials.inclination_function_f_30p.iter().zip(kaula_param.polynomials.inclination_function_f_32p.iter()).enumerate() {
-                    // println!("|\t integer k {:?}", k);
-                    let (f_30k, f_32k) = f_30_32_k; // is sum over p for each m
+                for k in 0..=3 {
+                    let (f_30k, f_32k) = (kaula_functions_3.inclination_function(0, k), kaula_functions_3.inclination_function(2, k)); // is sum over p for each m
 
                     let tmp_k: f64 = k as f64;
                     let mut sum_over_j_term1: f64 = 0.;
                     let mut sum_over_j_term2: f64 = 0.;
                     let mut sum_over_j_term1_s: f64 = 0.;
                     let mut sum_over_j_term2_s: f64 = 0.;
-                    let sum_g_3kj = kaula_param.polynomials.eccentricity_function_g_3pq[k];
 
-                    for (j, g_3kj) in sum_g_3kj.iter().skip(q_min).take(q_max).enumerate() {
-                        let tmp_j: f64 = j as f64 - order as f64;
+                    for j in kaula_functions_3.q_range() {
+                        let g_3kj = kaula_functions_3.eccentricity_function(k, j);
+                        let tmp_j: f64 = j as f64;
 
                         // let phase_beta:f64 =compute_phase_beta(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_ascending_node);
                         let phase_alpha_3: f64 = compute_phase_alpha_3(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
                         let phase_alpha_4: f64 = compute_phase_alpha_4(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
 
-                        let (rek2_21pq, imk2_21pq) = calculate_kaula_numbers(frequ_21pq, kaula_param, central_body);
-
                         let cos_alpha_1: f64 = (phase_alpha_3).cos();
                         let sin_alpha_1: f64 = (phase_alpha_3).sin();
                         let cos_alpha_2: f64 = (phase_alpha_4).cos();
@@ -832,40 +797,40 @@ fn calculate_orthogonal_component_of_the_tidal_force(
         sum_over_p_s = 0.;
         // let c1 = -(5_f64) / (48_f64*6_f64.sqrt());
         // let c3 = 2_f64;
-        for (p, f_21p) in kaula_param.polynomials.inclination_function_f_22p.iter().enumerate() {
+        for p in 0..=2 {
+            let f_21p = kaula_functions_2.inclination_function(2, p);
             let tmp_p: f64 = p as f64;
             let mut sum_over_q: f64 = 0.;
             let mut sum_over_q_s: f64 = 0.;
-            let sum_g_2pq = kaula_param.polynomials.eccentricity_function_g_2pq[p];
 
-            for (q, g_2pq) in sum_g_2pq.iter().skip(q_min).take(q_max).enumerate() {
-                let tmp_q: f64 = q as f64 - order as f64;
+            for q in kaula_functions_2.q_range() {
+                let g_2pq = kaula_functions_2.eccentricity_function(p, q);
+                let tmp_q: f64 = q as f64;
                 let frequ_22pq: f64 = calculate_tidal_excitation_frequency_mode_sigma_2mpq(2., tmp_p, tmp_q, spin, orbital_frequency);
+                let (rek2_22pq, imk2_22pq) = calculate_kaula_numbers(frequ_22pq, love_numbers, tide);
 
                 let mut sum_over_k_term1: f64 = 0.;
                 let mut sum_over_k_term2: f64 = 0.;
                 let mut sum_over_k_term1_s: f64 = 0.;
                 let mut sum_over_k_term2_s: f64 = 0.;
 
-                for (k, f_31_33_k) in kaula_param.polynomials.inclination_function_f_31p.iter().zip(kaula_param.polynomials.inclination_function_f_33p.iter()).enumerate() {
-                    let (f_31k, f_33k) = f_31_33_k; // is sum over p for each m
+                for k in 0..=3 {
+                    let (f_31k, f_33k) = (kaula_functions_3.inclination_function(1, k), kaula_functions_3.inclination_function(3, k)); // is sum over p for each m
 
                     let tmp_k: f64 = k as f64;
                     let mut sum_over_j_term1: f64 = 0.;
                     let mut sum_over_j_term2: f64 = 0.;
                     let mut sum_over_j_term1_s: f64 = 0.;
                     let mut sum_over_j_term2_s: f64 = 0.;
-                    let sum_g_3kj = kaula_param.polynomials.eccentricity_function_g_3pq[k];
 
-                    for (j, g_3kj) in sum_g_3kj.iter().skip(q_min).take(q_max).enumerate() {
-                        let tmp_j: f64 = j as f64 - order as f64;
+                    for j in kaula_functions_3.q_range() {
+                        let g_3kj = kaula_functions_3.eccentricity_function(k, j);
+                        let tmp_j: f64 = j as f64;
 
                         // let phase_beta:f64 =compute_phase_beta(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_ascending_node);
                         let phase_alpha_3: f64 = compute_phase_alpha_3(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
                         let phase_alpha_4: f64 = compute_phase_alpha_4(tmp_p, tmp_q, tmp_k, tmp_j, mean_anomaly, spin_angle, argument_perihelion, longitude_of_ascending_node, heliocentric_varphi);
 
-                        let (rek2_22pq, imk2_22pq) = calculate_kaula_numbers(frequ_22pq, kaula_param, central_body);
-
                         let cos_alpha_1: f64 = (phase_alpha_3).cos();
                         let sin_alpha_1: f64 = (phase_alpha_3).sin();
                         let cos_alpha_2: f64 = (phase_alpha_4).cos();
@@ -890,7 +855,7 @@ fn calculate_orthogonal_component_of_the_tidal_force(
         let _term_m2: f64 = -(5_f64) / (48_f64 * 6_f64.sqrt()) * sum_over_p;
         let _term_m2_s: f64 = -(5_f64) / (48_f64 * 6_f64.sqrt()) * sum_over_p_s;
 
-        let cste_3d = if !central_body {
+        let cste_3d = if tide == Tide::Planetary {
             // inclination = tools::calculate_inclination_orbital_equatorial_plane(particle.heliocentric_position, particle.heliocentric_velocity, particle.spin);
             -(G * tidal_host_particle.mass.powi(2) * particle.radius.powi(5)) / (semi_major_axis.powi(7))
         } else {
@@ -906,55 +871,11 @@ fn calculate_orthogonal_component_of_the_tidal_force(
 
 // -------------------------------------------------- //
 // --- Calculate tidal torque due to tidal forces --- //
-pub fn calculate_torque_due_to_tides(tidal_host_particle: &Particle, particle: &Particle, central_body: bool) -> Axes {
-    let position_x: f64;
-    let position_y: f64;
-    let position_z: f64;
-    let tidal_force_x: f64;
-    let tidal_force_y: f64;
-    let tidal_force_z: f64;
-
-    if !central_body {
-        let kaula_tidal_force = match &particle.tides.effect {
-            TidesEffect::OrbitingBody(tidal_model) => {
-                match tidal_model {
-                    TidalModel::Kaula(params) => params.kaula_tidal_force,
-                    _ => unreachable!(),
-                }
-            },
-            _ => unreachable!(),
-        };
-        tidal_force_x = kaula_tidal_force.x;
-        tidal_force_y = kaula_tidal_force.y;
-        tidal_force_z = kaula_tidal_force.z;
-        position_x = particle.tides.coordinates.position.x;
-        position_y = particle.tides.coordinates.position.y;
-        position_z = particle.tides.coordinates.position.z;
-    } else {
-        let kaula_tidal_force = match &tidal_host_particle.tides.effect {
-            TidesEffect::CentralBody(tidal_model) => {
-                match tidal_model {
-                    TidalModel::Kaula(params) => params.kaula_tidal_force,
-                    _ => unreachable!(),
-                }
-            },
-            _ => unreachable!(),
-        };
-        tidal_force_x = kaula_tidal_force.x;
-        tidal_force_y = kaula_tidal_force.y;
-        tidal_force_z = kaula_tidal_force.z;
-        position_x = -particle.tides.coordinates.position.x;
-        position_y = -particle.tides.coordinates.position.y;
-        position_z = -particle.tides.coordinates.position.z;
-    }
-    // The negative sign of the position vector for stellar tide: Torque = r cross F
-    // The r here should be the vector point from thr primary (perturber) to the secondary (perturbed)
-    // Thus we added a minus sign here as particle.tides.coordinates.position.xyx is always heliocentric.
-
+fn calculate_torque_due_to_tides(position: Axes, tidal_force: Axes) -> Axes {
     // Let the torque be the cross product of the radial distance vector and the tidal force vector
-    let torque_due_to_tides_x = position_y * tidal_force_z - position_z * tidal_force_y;
-    let torque_due_to_tides_y = position_z * tidal_force_x - position_x * tidal_force_z;
-    let torque_due_to_tides_z = position_x * tidal_force_y - position_y * tidal_force_x;
+    let torque_due_to_tides_x = position.y * tidal_force.z - position.z * tidal_force.y;
+    let torque_due_to_tides_y = position.z * tidal_force.x - position.x * tidal_force.z;
+    let torque_due_to_tides_z = position.x * tidal_force.y - position.y * tidal_force.x;
     Axes {
         x: torque_due_to_tides_x,
         y: torque_due_to_tides_y,
@@ -1011,48 +932,14 @@ fn compute_phase_alpha_4(p: f64, q: f64, k: f64, j: f64, mean_anomaly: f64, spin
 }
 
 // --- Find the real part and the imaginary part of the Love number associated to the excitation frequenccy wk2
-fn calculate_kaula_numbers(mut wk2: f64, kaula_param: &KaulaParameters, central_body: bool) -> (f64, f64) {
+fn calculate_kaula_numbers(mut wk2: f64, love_numbers: KaulaLoveNumbers, tide: Tide) -> (f64, f64) {
     // Planetary tide: planets have symmetric tidal response. stars DO NOT have symmetric tidal response
-    let parity = !central_body & (wk2 < 0.0);
+    let parity = tide == Tide::Planetary && wk2 < 0.0;
     if parity {
         wk2 = wk2.abs();
     }
 
-    let im_k2;
-    let re_k2;
-    // Find the index of the closest match to use for the interpolation
-    // TODO will panic if wk2 < love_number[0]
-
-    // assert!(wk2 >love_number_excitation_frequency[0]);
-    // assert!(wk2 < love_number_excitation_frequency[love_number_excitation_frequency.len() - 1]);
-
-    if wk2 <= kaula_param.love_number_excitation_frequency[0] {
-        // If wk2 is less than or equal to the first element, take the first value
-        im_k2 = kaula_param.imaginary_part_love_number[0];
-        re_k2 = kaula_param.real_part_love_number[0];
-    } else if wk2 >= kaula_param.love_number_excitation_frequency[kaula_param.love_number_excitation_frequency.len() - 1] {
-        // If wk2 is greater than or equal to the last element, take the last value
-        im_k2 = kaula_param.imaginary_part_love_number[kaula_param.love_number_excitation_frequency.len() - 1];
-        re_k2 = kaula_param.real_part_love_number[kaula_param.love_number_excitation_frequency.len() - 1];
-    } else {
-        // Find the index of the closest match to use for the interpolation
-        match kaula_param.love_number_excitation_frequency.binary_search_by(|val| val.total_cmp(&wk2))
-        {
-            Ok(i) => {
-                // Exact match found: love_number[i] == wk2
-                im_k2 = kaula_param.imaginary_part_love_number[i];
-                re_k2 = kaula_param.real_part_love_number[i];
-            }
-            Err(i) => {
-                // wk2 is between love_number[i - 1] and love_number[i]
-                let prev_freq = kaula_param.love_number_excitation_frequency[i - 1];
-                let next_freq = kaula_param.love_number_excitation_frequency[i];
-                let delta = (wk2 - prev_freq) / (next_freq - prev_freq);
-                im_k2 = (1.0 - delta) * kaula_param.imaginary_part_love_number[i - 1] + delta * kaula_param.imaginary_part_love_number[i];
-                re_k2 = (1.0 - delta) * kaula_param.real_part_love_number[i - 1] + delta * kaula_param.real_part_love_number[i];
-            }
-        };
-    }
+    let (re_k2, im_k2) = love_numbers.love_number(wk2);
 
     if parity {
         (-re_k2, -im_k2)
@@ -1060,496 +947,3 @@ fn calculate_kaula_numbers(mut wk2: f64, kaula_param: &KaulaParameters, central_
         (-re_k2, im_k2)
     }
 }
-
-#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Default)]
-pub struct Polynomials {
-    eccentricity_function_g_2pq: [[f64; 15]; 3],
-    eccentricity_function_g_20q: [f64; 15],
-    eccentricity_function_g_21q: [f64; 15],
-    eccentricity_function_g_3pq: [[f64; 15]; 4],
-
-    inclination_function_f_20p: [f64; 3],
-    inclination_function_f_21p: [f64; 3],
-    inclination_function_f_22p: [f64; 3],
-    inclination_function_f_30p: [f64; 4],
-    inclination_function_f_31p: [f64; 4],
-    inclination_function_f_32p: [f64; 4],
-    inclination_function_f_33p: [f64; 4],
-}
-
-impl Polynomials {
-    // Constructor function that creates a Polynomials instance with all values set to 0.0
-    pub fn new() -> Self {
-        Polynomials {
-            eccentricity_function_g_2pq: [[0.0; 15]; 3],
-            eccentricity_function_g_20q: [0.0; 15],
-            eccentricity_function_g_21q: [0.0; 15],
-            eccentricity_function_g_3pq: [[0.0; 15]; 4],
-
-            inclination_function_f_20p: [0.0; 3],
-            inclination_function_f_21p: [0.0; 3],
-            inclination_function_f_22p: [0.0; 3],
-            inclination_function_f_30p: [0.0; 4],
-            inclination_function_f_31p: [0.0; 4],
-            inclination_function_f_32p: [0.0; 4],
-            inclination_function_f_33p: [0.0; 4],
-        }
-    }
-
-    #[allow(dead_code)]
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_2mp(inclination: f64) -> [[f64; 3]; 3] {
-        let mut inclination_function = [[0.; 3]; 3];
-        // in this notation: 0 is q=-2, 1 is q=-1, 2 is q=0, 3 is q=1, 4 is q=2 ... cf Kaula 64 Table 3
-        let sin_incl = inclination.sin();
-        let sin_incl_2 = sin_incl * sin_incl;
-        let cos_incl = inclination.cos();
-
-        inclination_function[0][0] = -(3. / 8.) * sin_incl_2;
-        inclination_function[0][1] = (3. / 4.) * sin_incl_2 - (1. / 2.);
-        inclination_function[0][2] = -(3. / 8.) * sin_incl_2;
-
-        inclination_function[1][0] = (3. / 4.) * sin_incl * (1. + cos_incl);
-        inclination_function[1][1] = -(3. / 2.) * sin_incl * cos_incl;
-        inclination_function[1][2] = (3. / 4.) * sin_incl * (cos_incl - 1.);
-
-        inclination_function[2][0] = (3. / 4.) * (1. + cos_incl).powi(2);
-        inclination_function[2][1] = (3. / 2.) * sin_incl.powi(2);
-        inclination_function[2][2] = (3. / 4.) * (1. - cos_incl).powi(2);
-
-        inclination_function
-    }
-
-    // F2mp
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_20p(&mut self, inclination: f64) {
-        // in this notation: 0 is q=-2, 1 is q=-1, 2 is q=0, 3 is q=1, 4 is q=2 ... cf Kaula 64 Table 3
-        let sin_incl = inclination.sin();
-        let sin_incl_2 = sin_incl * sin_incl;
-
-        self.inclination_function_f_20p[0] = -(3. / 8.) * sin_incl_2;
-        self.inclination_function_f_20p[1] = (3. / 4.) * sin_incl_2 - (1. / 2.);
-        self.inclination_function_f_20p[2] = -(3. / 8.) * sin_incl_2;
-    }
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_21p(&mut self, inclination: f64) {
-        let sin_incl = inclination.sin();
-        let cos_incl = inclination.cos();
-
-        self.inclination_function_f_21p[0] = (3. / 4.) * sin_incl * (1. + cos_incl);
-        self.inclination_function_f_21p[1] = -(3. / 2.) * sin_incl * cos_incl;
-        self.inclination_function_f_21p[2] = (3. / 4.) * sin_incl * (cos_incl - 1.);
-    }
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_22p(&mut self, inclination: f64) {
-        let sin_incl = inclination.sin();
-        let cos_incl = inclination.cos();
-
-        self.inclination_function_f_22p[0] = (3. / 4.) * (1. + cos_incl).powi(2);
-        self.inclination_function_f_22p[1] = (3. / 2.) * sin_incl.powi(2);
-        self.inclination_function_f_22p[2] = (3. / 4.) * (1. - cos_incl).powi(2);
-    }
-    // F3pmp
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_30p(&mut self, inclination: f64) {
-        let sin_incl = inclination.sin();
-        let sin_3_factor_1 = (5. / 16.) * sin_incl.powi(3);
-        let sin_factor_2 = (3. / 4.) * sin_incl;
-
-        self.inclination_function_f_30p[0] = -sin_3_factor_1;
-        self.inclination_function_f_30p[1] = sin_3_factor_1 - sin_factor_2;
-        self.inclination_function_f_30p[2] = -sin_3_factor_1 + sin_factor_2;
-        self.inclination_function_f_30p[3] = -self.inclination_function_f_30p[0];
-    }
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_31p(&mut self, inclination: f64) {
-        let sin_incl = inclination.sin();
-        let sin_2_factor_1 = (15. / 16.) * sin_incl.powi(2);
-        let sin_2_factor_43 = sin_2_factor_1 - (3. / 4.);
-        let cos_incl = inclination.cos();
-        let cos_incl_factor_1 = 1.0 + 3.0 * cos_incl;
-        let cos_incl_factor_2 = 1.0 - 3.0 * cos_incl;
-
-        self.inclination_function_f_31p[0] = -sin_2_factor_1 * cos_incl_factor_1;
-        self.inclination_function_f_31p[1] = sin_2_factor_43 * cos_incl_factor_1;
-        self.inclination_function_f_31p[2] = sin_2_factor_43 * cos_incl_factor_2;
-        self.inclination_function_f_31p[3] = -sin_2_factor_1 * cos_incl_factor_2;
-    }
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_32p(&mut self, inclination: f64) {
-        let sin_incl = inclination.sin();
-        let sin_2_factor_1 = (15. / 8.) * sin_incl.powi(2);
-        let cos_incl = inclination.cos();
-        let cos_incl_2 = cos_incl.powi(2);
-
-        self.inclination_function_f_32p[0] = sin_2_factor_1 * (1.0 + cos_incl).powi(2);
-        self.inclination_function_f_32p[1] = sin_2_factor_1 * (1.0 - 2.0 * cos_incl - 3.0 * cos_incl_2);
-        self.inclination_function_f_32p[2] = -sin_2_factor_1 * (1.0 + 2.0 * cos_incl - 3.0 * cos_incl_2);
-        self.inclination_function_f_32p[3] = -sin_2_factor_1 * (1.0 - cos_incl).powi(2);
-    }
-    // --- The inclination function of the Kaula 1961 developpement Flmp(i)
-    fn calculate_inclination_function_f_33p(&mut self, inclination: f64) {
-        let sin_incl = inclination.sin();
-        let sin_2_factor_1 = (48. / 8.) * sin_incl.powi(2);
-        let cos_incl = inclination.cos();
-        let cos_incl_factor_1 = 1.0 + cos_incl;
-        let cos_incl_factor_2 = 1.0 - cos_incl;
-
-        self.inclination_function_f_33p[0] = (15. / 8.) * cos_incl_factor_1.powi(3);
-        self.inclination_function_f_33p[1] = sin_2_factor_1 * cos_incl_factor_1;
-        self.inclination_function_f_33p[2] = sin_2_factor_1 * cos_incl_factor_2;
-        self.inclination_function_f_33p[3] = (15. / 8.) * cos_incl_factor_2.powi(3);
-    }
-
-    // --- The eccentricity function of the Kaula 1961 developpement Glpq(e) from the Hensen coeff [?] (see Cayley table [?])
-    fn calculate_eccentricity_function_g_2pq(&mut self, eccentricity: f64) {
-        // in this notation:
-        // [0] is -7
-        // [1] is -6
-        // [2] is -5
-        // [3] is -4
-        // [4] is -3
-        // [5] is -2
-        // [6] is -1
-        // [7] is 0
-        // [8] is 1
-        // [9] is 2
-        // [10] is 3
-        // [11] is 4
-        // [12] is 5
-        // [13] is 6
-        // [14] is 7
-
-        let ecc: f64 = eccentricity;
-        let ecc_2: f64 = ecc * ecc;
-        let ecc_3: f64 = ecc_2 * ecc;
-        let ecc_4: f64 = ecc_3 * ecc;
-        let ecc_5: f64 = ecc_4 * ecc;
-        let ecc_6: f64 = ecc_5 * ecc;
-        let ecc_7: f64 = ecc_6 * ecc;
-
-        self.eccentricity_function_g_2pq[0][0] = (15625. / 129024.) * ecc_7;
-        self.eccentricity_function_g_2pq[0][1] = (4. / 45.) * ecc_6;
-        self.eccentricity_function_g_2pq[0][2] = (81. / 1280.) * ecc_5 + (81. / 2048.) * ecc_7;
-        self.eccentricity_function_g_2pq[0][3] = (1. / 24.) * ecc_4 + (7. / 240.) * ecc_6;
-        self.eccentricity_function_g_2pq[0][4] = (1. / 48.) * ecc_3 + (11. / 768.) * ecc_5 + (313. / 30720.) * ecc_7;
-        self.eccentricity_function_g_2pq[0][5] = 0.;
-        self.eccentricity_function_g_2pq[0][6] = -0.5 * ecc + (1. / 16.) * ecc_3 - (5. / 384.) * ecc_5 - (143. / 18432.) * ecc_7;
-        self.eccentricity_function_g_2pq[0][7] = 1. - (5. / 2.) * ecc_2 + (13. / 16.) * ecc_4 - (35. / 288.) * ecc_6;
-        self.eccentricity_function_g_2pq[0][8] = (7. / 2.) * ecc - (123. / 16.) * ecc_3 + (489. / 128.) * ecc_5 - (1763. / 2048.) * ecc_7;
-        self.eccentricity_function_g_2pq[0][9] = (17. / 2.) * ecc_2 - (115. / 16.) * ecc_4 + (601. / 48.) * ecc_6;
-        self.eccentricity_function_g_2pq[0][10] = (845. / 48.) * ecc_3 - (32525. / 768.) * ecc_5 + (208225. / 6144.) * ecc_7;
-        self.eccentricity_function_g_2pq[0][11] = (533. / 16.) * ecc_4 - (13827. / 160.) * ecc_6;
-        self.eccentricity_function_g_2pq[0][12] = (228347. / 3840.) * ecc_5 - (3071075. / 18432.) * ecc_7;
-        self.eccentricity_function_g_2pq[0][13] = (73369. / 720.) * ecc_6;
-        self.eccentricity_function_g_2pq[0][14] = (12144273. / 71680.) * ecc_7;
-
-        self.eccentricity_function_g_2pq[1][0] = (432091. / 30720.) * ecc_7;
-        self.eccentricity_function_g_2pq[1][1] = (3167. / 320.) * ecc_6;
-        self.eccentricity_function_g_2pq[1][2] = (1773. / 256.) * ecc_5 - (4987. / 6144.) * ecc_7;
-        self.eccentricity_function_g_2pq[1][3] = (77. / 16.) * ecc_4 + (129. / 160.) * ecc_6;
-        self.eccentricity_function_g_2pq[1][4] = (53. / 16.) * ecc_3 + (393. / 256.) * ecc_5 + (24753. / 10240.) * ecc_7;
-        self.eccentricity_function_g_2pq[1][5] = (9. / 4.) * ecc_2 + (7. / 4.) * ecc_4 + (141. / 64.) * ecc_6;
-        self.eccentricity_function_g_2pq[1][6] = (3. / 2.) * ecc + (27. / 16.) * ecc_3 + (261. / 128.) * ecc_5 + (14309. / 6144.) * ecc_7;
-        self.eccentricity_function_g_2pq[1][7] = (1. - ecc_2).powf(-3. / 2.);
-        self.eccentricity_function_g_2pq[1][8] = self.eccentricity_function_g_2pq[1][6];
-        self.eccentricity_function_g_2pq[1][9] = self.eccentricity_function_g_2pq[1][5];
-        self.eccentricity_function_g_2pq[1][10] = self.eccentricity_function_g_2pq[1][4];
-        self.eccentricity_function_g_2pq[1][11] = self.eccentricity_function_g_2pq[1][3];
-        self.eccentricity_function_g_2pq[1][12] = self.eccentricity_function_g_2pq[1][2];
-        self.eccentricity_function_g_2pq[1][13] = self.eccentricity_function_g_2pq[1][1];
-        self.eccentricity_function_g_2pq[1][14] = self.eccentricity_function_g_2pq[1][0];
-
-        self.eccentricity_function_g_2pq[2][0] = self.eccentricity_function_g_2pq[0][14];
-        self.eccentricity_function_g_2pq[2][1] = self.eccentricity_function_g_2pq[0][13];
-        self.eccentricity_function_g_2pq[2][2] = self.eccentricity_function_g_2pq[0][12];
-        self.eccentricity_function_g_2pq[2][3] = self.eccentricity_function_g_2pq[0][11];
-        self.eccentricity_function_g_2pq[2][4] = self.eccentricity_function_g_2pq[0][10];
-        self.eccentricity_function_g_2pq[2][5] = self.eccentricity_function_g_2pq[0][9];
-        self.eccentricity_function_g_2pq[2][6] = self.eccentricity_function_g_2pq[0][8];
-        self.eccentricity_function_g_2pq[2][7] = self.eccentricity_function_g_2pq[0][7];
-        self.eccentricity_function_g_2pq[2][8] = self.eccentricity_function_g_2pq[0][6];
-        self.eccentricity_function_g_2pq[2][9] = self.eccentricity_function_g_2pq[0][5];
-        self.eccentricity_function_g_2pq[2][10] = self.eccentricity_function_g_2pq[0][4];
-        self.eccentricity_function_g_2pq[2][11] = self.eccentricity_function_g_2pq[0][3];
-        self.eccentricity_function_g_2pq[2][12] = self.eccentricity_function_g_2pq[0][2];
-        self.eccentricity_function_g_2pq[2][13] = self.eccentricity_function_g_2pq[0][1];
-        self.eccentricity_function_g_2pq[2][14] = self.eccentricity_function_g_2pq[0][0];
-    }
-
-    // --- The eccentricity function of the Kaula 1961 developpement G20q(e) from the Hensen coeff [?] (see Cayley table [?])
-    fn calculate_eccentricity_function_g_20q(&mut self, eccentricity: f64) {
-        // in this notation:
-        // [0] is -7
-        // [1] is -6
-        // [2] is -5
-        // [3] is -4
-        // [4] is -3
-        // [5] is -2
-        // [6] is -1
-        // [7] is 0
-        // [8] is 1
-        // [9] is 2
-        // [10] is 3
-        // [11] is 4
-        // [12] is 5
-        // [13] is 6
-        // [14] is 7
-
-        let ecc: f64 = eccentricity;
-        let ecc_2: f64 = ecc * ecc;
-        let ecc_3: f64 = ecc_2 * ecc;
-        let ecc_4: f64 = ecc_3 * ecc;
-        let ecc_5: f64 = ecc_4 * ecc;
-        let ecc_6: f64 = ecc_5 * ecc;
-        let ecc_7: f64 = ecc_6 * ecc;
-
-        self.eccentricity_function_g_20q[0] = (15625. / 129024.) * ecc_7;
-        self.eccentricity_function_g_20q[1] = (4. / 45.) * ecc_6;
-        self.eccentricity_function_g_20q[2] = (81. / 1280.) * ecc_5 + (81. / 2048.) * ecc_7;
-        self.eccentricity_function_g_20q[3] = (1. / 24.) * ecc_4 + (7. / 240.) * ecc_6;
-        self.eccentricity_function_g_20q[4] = (1. / 48.) * ecc_3 + (11. / 768.) * ecc_5 + (313. / 30720.) * ecc_7;
-        self.eccentricity_function_g_20q[5] = 0.;
-        self.eccentricity_function_g_20q[6] = -0.5 * ecc + (1. / 16.) * ecc_3 - (5. / 384.) * ecc_5 - (143. / 18432.) * ecc_7;
-        self.eccentricity_function_g_20q[7] = 1. - (5. / 2.) * ecc_2 + (13. / 16.) * ecc_4 - (35. / 288.) * ecc_6;
-        self.eccentricity_function_g_20q[8] = (7. / 2.) * ecc - (123. / 16.) * ecc_3 + (489. / 128.) * ecc_5 - (1763. / 2048.) * ecc_7;
-        self.eccentricity_function_g_20q[9] = (17. / 2.) * ecc_2 - (115. / 16.) * ecc_4 + (601. / 48.) * ecc_6;
-        self.eccentricity_function_g_20q[10] = (845. / 48.) * ecc_3 - (32525. / 768.) * ecc_5 + (208225. / 6144.) * ecc_7;
-        self.eccentricity_function_g_20q[11] = (533. / 16.) * ecc_4 - (13827. / 160.) * ecc_6;
-        self.eccentricity_function_g_20q[12] = (228347. / 3840.) * ecc_5 - (3071075. / 18432.) * ecc_7;
-        self.eccentricity_function_g_20q[13] = (73369. / 720.) * ecc_6;
-        self.eccentricity_function_g_20q[14] = (12144273. / 71680.) * ecc_7;
-    }
-
-    // --- The eccentricity function of the Kaula 1961 developpement G21q(e) from the Hensen coeff [?] (see Cayley table [?])
-    fn calculate_eccentricity_function_g_21q(&mut self, eccentricity: f64) {
-        // in this notation:
-        // [0] is -7
-        // [1] is -6
-        // [2] is -5
-        // [3] is -4
-        // [4] is -3
-        // [5] is -2
-        // [6] is -1
-        // [7] is 0
-        // [8] is 1
-        // [9] is 2
-        // [10] is 3
-        // [11] is 4
-        // [12] is 5
-        // [13] is 6
-        // [14] is 7
-
-        let ecc: f64 = eccentricity;
-        let ecc_2: f64 = ecc * ecc;
-        let ecc_3: f64 = ecc_2 * ecc;
-        let ecc_4: f64 = ecc_3 * ecc;
-        let ecc_5: f64 = ecc_4 * ecc;
-        let ecc_6: f64 = ecc_5 * ecc;
-        let ecc_7: f64 = ecc_6 * ecc;
-
-        self.eccentricity_function_g_21q[0] = (432091. / 30720.) * ecc_7;
-        self.eccentricity_function_g_21q[1] = (3167. / 320.) * ecc_6;
-        self.eccentricity_function_g_21q[2] = (1773. / 256.) * ecc_5 - (4987. / 6144.) * ecc_7;
-        self.eccentricity_function_g_21q[3] = (77. / 16.) * ecc_4 + (129. / 160.) * ecc_6;
-        self.eccentricity_function_g_21q[4] = (53. / 16.) * ecc_3 + (393. / 256.) * ecc_5 + (24753. / 10240.) * ecc_7;
-        self.eccentricity_function_g_21q[5] = (9. / 4.) * ecc_2 + (7. / 4.) * ecc_4 + (141. / 64.) * ecc_6;
-        self.eccentricity_function_g_21q[6] = (3. / 2.) * ecc + (27. / 16.) * ecc_3 + (261. / 128.) * ecc_5 + (14309. / 6144.) * ecc_7;
-        self.eccentricity_function_g_21q[7] = (1. - ecc_2).powf(-3. / 2.);
-        self.eccentricity_function_g_21q[8] = self.eccentricity_function_g_21q[6];
-        self.eccentricity_function_g_21q[9] = self.eccentricity_function_g_21q[5];
-        self.eccentricity_function_g_21q[10] = self.eccentricity_function_g_21q[4];
-        self.eccentricity_function_g_21q[11] = self.eccentricity_function_g_21q[3];
-        self.eccentricity_function_g_21q[12] = self.eccentricity_function_g_21q[2];
-        self.eccentricity_function_g_21q[13] = self.eccentricity_function_g_21q[1];
-        self.eccentricity_function_g_21q[14] = self.eccentricity_function_g_21q[0];
-    }
-
-    // --- The eccentricity function of the Kaula 1961 developpement Glpq(e) from the Hensen coeff [?] (see Cayley table [?])
-    fn calculate_eccentricity_function_g_3pq(&mut self, eccentricity: f64) {
-        // in this notation:
-        // [0] is -7
-        // [1] is -6
-        // [2] is -5
-        // [3] is -4
-        // [4] is -3
-        // [5] is -2
-        // [6] is -1
-        // [7] is 0
-        // [8] is 1
-        // [9] is 2
-        // [10] is 3
-        // [11] is 4
-        // [12] is 5
-        // [13] is 6
-        // [14] is 7
-
-        let ecc: f64 = eccentricity;
-        let ecc_2: f64 = ecc.powi(2);
-        let ecc_3: f64 = ecc.powi(3);
-        let ecc_4: f64 = ecc.powi(4);
-        let ecc_5: f64 = ecc.powi(5);
-        let ecc_6: f64 = ecc.powi(6);
-        let ecc_7: f64 = ecc.powi(7);
-
-        self.eccentricity_function_g_3pq[0][0] = (8. / 315.) * ecc_7;
-        self.eccentricity_function_g_3pq[0][1] = (81. / 5120.) * ecc_6;
-        self.eccentricity_function_g_3pq[0][2] = (1. / 120.) * ecc_5 + (13. / 1440.) * ecc_7;
-        self.eccentricity_function_g_3pq[0][3] = (1. / 384.) * ecc_4 + (1. / 384.) * ecc_6;
-        self.eccentricity_function_g_3pq[0][4] = 0.;
-        self.eccentricity_function_g_3pq[0][5] = (1. / 8.) * ecc_2 + (1. / 48.) * ecc_4 + (55. / 3072.) * ecc_6;
-        self.eccentricity_function_g_3pq[0][6] = -ecc + (5. / 4.) * ecc_3 - (7. / 48.) * ecc_5 + (23. / 288.) * ecc_7;
-        self.eccentricity_function_g_3pq[0][7] = 1. - 6. * ecc_2 + (423. / 64.) * ecc_4 - (125. / 64.) * ecc_6;
-        self.eccentricity_function_g_3pq[0][8] = 5. * ecc - 22. * ecc_3 + (607. / 24.) * ecc_5 - (98. / 9.) * ecc_7;
-        self.eccentricity_function_g_3pq[0][9] = (127. / 8.) * ecc_2 - (3065. / 48.) * ecc_4 + (243805. / 3072.) * ecc_6;
-        self.eccentricity_function_g_3pq[0][10] = (163. / 4.) * ecc_3 - (2577. / 16.) * ecc_5 + (1089. / 5.) * ecc_7;
-        self.eccentricity_function_g_3pq[0][11] = (35413. / 384.) * ecc_4 - (709471. / 1920.) * ecc_6;
-        self.eccentricity_function_g_3pq[0][12] = (23029. / 120.) * ecc_5 - (35614. / 45.) * ecc_7;
-        self.eccentricity_function_g_3pq[0][13] = (385095. / 1024.) * ecc_6;
-        self.eccentricity_function_g_3pq[0][14] = (44377. / 63.) * ecc_7;
-
-        self.eccentricity_function_g_3pq[1][0] = (16337. / 2240.) * ecc_7;
-        self.eccentricity_function_g_3pq[1][1] = (48203. / 9240.) * ecc_6;
-        self.eccentricity_function_g_3pq[1][2] = (899. / 240.) * ecc_5 + (2441. / 480.) * ecc_7;
-        self.eccentricity_function_g_3pq[1][3] = (343. / 128.) * ecc_4 + (2819. / 640.) * ecc_6;
-        self.eccentricity_function_g_3pq[1][4] = (23. / 12.) * ecc_3 + (89. / 24.) * ecc_5 + (5663. / 960.) * ecc_7;
-        self.eccentricity_function_g_3pq[1][5] = (11. / 8.) * ecc_2 + (49. / 16.) * ecc_4 + (15665. / 3072.) * ecc_6;
-        self.eccentricity_function_g_3pq[1][6] = ecc * (1. - ecc_2).powf(-5. / 2.);
-        self.eccentricity_function_g_3pq[1][7] = 1. + (1. / 2.) * ecc_2 + (239. / 64.) * ecc_4 - (3323. / 576.) * ecc_6;
-        self.eccentricity_function_g_3pq[1][8] = 3. * ecc + (11. / 4.) * ecc_3 + (245. / 48.) * ecc_5 + (463. / 64.) * ecc_7;
-        self.eccentricity_function_g_3pq[1][9] = (53. / 8.) * ecc_2 + (39. / 16.) * ecc_4 + (7041. / 1024.) * ecc_6;
-        self.eccentricity_function_g_3pq[1][10] = (163. / 4.) * ecc_3 - (2577. / 16.) * ecc_5 + (1089. / 5.) * ecc_7;
-        self.eccentricity_function_g_3pq[1][11] = (35413. / 384.) * ecc_4 - (709471. / 1920.) * ecc_6;
-        self.eccentricity_function_g_3pq[1][12] = (23029. / 120.) * ecc_5 - (35614. / 45.) * ecc_7;
-        self.eccentricity_function_g_3pq[1][13] = (385095. / 1024.) * ecc_6;
-        self.eccentricity_function_g_3pq[1][14] = (44377. / 63.) * ecc_7;
-
-        self.eccentricity_function_g_3pq[2][0] = self.eccentricity_function_g_3pq[1][14];
-        self.eccentricity_function_g_3pq[2][1] = self.eccentricity_function_g_3pq[1][13];
-        self.eccentricity_function_g_3pq[2][2] = self.eccentricity_function_g_3pq[1][12];
-        self.eccentricity_function_g_3pq[2][3] = self.eccentricity_function_g_3pq[1][11];
-        self.eccentricity_function_g_3pq[2][4] = self.eccentricity_function_g_3pq[1][10];
-        self.eccentricity_function_g_3pq[2][5] = self.eccentricity_function_g_3pq[1][9];
-        self.eccentricity_function_g_3pq[2][6] = self.eccentricity_function_g_3pq[1][8];
-        self.eccentricity_function_g_3pq[2][7] = self.eccentricity_function_g_3pq[1][7];
-        self.eccentricity_function_g_3pq[2][8] = self.eccentricity_function_g_3pq[1][6];
-        self.eccentricity_function_g_3pq[2][9] = self.eccentricity_function_g_3pq[1][5];
-        self.eccentricity_function_g_3pq[2][10] = self.eccentricity_function_g_3pq[1][4];
-        self.eccentricity_function_g_3pq[2][11] = self.eccentricity_function_g_3pq[1][3];
-        self.eccentricity_function_g_3pq[2][12] = self.eccentricity_function_g_3pq[1][2];
-        self.eccentricity_function_g_3pq[2][13] = self.eccentricity_function_g_3pq[1][1];
-        self.eccentricity_function_g_3pq[2][14] = self.eccentricity_function_g_3pq[1][0];
-
-        self.eccentricity_function_g_3pq[3][0] = self.eccentricity_function_g_3pq[0][14];
-        self.eccentricity_function_g_3pq[3][1] = self.eccentricity_function_g_3pq[0][13];
-        self.eccentricity_function_g_3pq[3][2] = self.eccentricity_function_g_3pq[0][12];
-        self.eccentricity_function_g_3pq[3][3] = self.eccentricity_function_g_3pq[0][11];
-        self.eccentricity_function_g_3pq[3][4] = self.eccentricity_function_g_3pq[0][10];
-        self.eccentricity_function_g_3pq[3][5] = self.eccentricity_function_g_3pq[0][9];
-        self.eccentricity_function_g_3pq[3][6] = self.eccentricity_function_g_3pq[0][8];
-        self.eccentricity_function_g_3pq[3][7] = self.eccentricity_function_g_3pq[0][7];
-        self.eccentricity_function_g_3pq[3][8] = self.eccentricity_function_g_3pq[0][6];
-        self.eccentricity_function_g_3pq[3][9] = self.eccentricity_function_g_3pq[0][5];
-        self.eccentricity_function_g_3pq[3][10] = self.eccentricity_function_g_3pq[0][4];
-        self.eccentricity_function_g_3pq[3][11] = self.eccentricity_function_g_3pq[0][3];
-        self.eccentricity_function_g_3pq[3][12] = self.eccentricity_function_g_3pq[0][2];
-        self.eccentricity_function_g_3pq[3][13] = self.eccentricity_function_g_3pq[0][1];
-        self.eccentricity_function_g_3pq[3][14] = self.eccentricity_function_g_3pq[0][0];
-    }
-
-    #[allow(dead_code)]
-    // --- The eccentricity function of the Kaula 1961 developpement G30q(e) from the Hensen coeff [?] (see Cayley table [?])
-    fn calculate_eccentricity_function_g_30q(eccentricity: f64) -> [f64; 15] {
-        let mut eccentricity_function = [0.0; 15];
-        // in this notation:
-        // [0] is -7
-        // [1] is -6
-        // [2] is -5
-        // [3] is -4
-        // [4] is -3
-        // [5] is -2
-        // [6] is -1
-        // [7] is 0
-        // [8] is 1
-        // [9] is 2
-        // [10] is 3
-        // [11] is 4
-        // [12] is 5
-        // [13] is 6
-        // [14] is 7
-
-        let ecc: f64 = eccentricity;
-        let ecc_2: f64 = ecc.powi(2);
-        let ecc_3: f64 = ecc.powi(3);
-        let ecc_4: f64 = ecc.powi(4);
-        let ecc_5: f64 = ecc.powi(5);
-        let ecc_6: f64 = ecc.powi(6);
-        let ecc_7: f64 = ecc.powi(7);
-
-        eccentricity_function[0] = (8. / 315.) * ecc_7;
-        eccentricity_function[1] = (81. / 5120.) * ecc_6;
-        eccentricity_function[2] = (1. / 120.) * ecc_5 + (13. / 1440.) * ecc_7;
-        eccentricity_function[3] = (1. / 384.) * ecc_4 + (1. / 384.) * ecc_6;
-        eccentricity_function[4] = 0.;
-        eccentricity_function[5] = (1. / 8.) * ecc_2 + (1. / 48.) * ecc_4 + (55. / 3072.) * ecc_6;
-        eccentricity_function[6] = -ecc + (5. / 4.) * ecc_3 - (7. / 48.) * ecc_5 + (23. / 288.) * ecc_7;
-        eccentricity_function[7] = 1. - 6. * ecc_2 + (423. / 64.) * ecc_4 - (125. / 64.) * ecc_6;
-        eccentricity_function[8] = 5. * ecc - 22. * ecc_3 + (607. / 24.) * ecc_5 - (98. / 9.) * ecc_7;
-        eccentricity_function[9] = (127. / 8.) * ecc_2 - (3065. / 48.) * ecc_4 + (243805. / 3072.) * ecc_6;
-        eccentricity_function[10] = (163. / 4.) * ecc_3 - (2577. / 16.) * ecc_5 + (1089. / 5.) * ecc_7;
-        eccentricity_function[11] = (35413. / 384.) * ecc_4 - (709471. / 1920.) * ecc_6;
-        eccentricity_function[12] = (23029. / 120.) * ecc_5 - (35614. / 45.) * ecc_7;
-        eccentricity_function[13] = (385095. / 1024.) * ecc_6;
-        eccentricity_function[14] = (44377. / 63.) * ecc_7;
-
-        eccentricity_function
-    }
-
-    #[allow(dead_code)]
-    // --- The eccentricity function of the Kaula 1961 developpement G31q(e) from the Hensen coeff [?] (see Cayley table [?])
-    fn calculate_eccentricity_function_g_31q(eccentricity: f64) -> [f64; 15] {
-        let mut eccentricity_function = [0.0; 15];
-        // in this notation:
-        // [0] is -7
-        // [1] is -6
-        // [2] is -5
-        // [3] is -4
-        // [4] is -3
-        // [5] is -2
-        // [6] is -1
-        // [7] is 0
-        // [8] is 1
-        // [9] is 2
-        // [10] is 3
-        // [11] is 4
-        // [12] is 5
-        // [13] is 6
-        // [14] is 7
-
-        let ecc: f64 = eccentricity;
-        let ecc_2: f64 = ecc.powi(2);
-        let ecc_3: f64 = ecc.powi(3);
-        let ecc_4: f64 = ecc.powi(4);
-        let ecc_5: f64 = ecc.powi(5);
-        let ecc_6: f64 = ecc.powi(6);
-        let ecc_7: f64 = ecc.powi(7);
-
-        eccentricity_function[0] = (16337. / 2240.) * ecc_7;
-        eccentricity_function[1] = (48203. / 9240.) * ecc_6;
-        eccentricity_function[2] = (899. / 240.) * ecc_5 + (2441. / 480.) * ecc_7;
-        eccentricity_function[3] = (343. / 128.) * ecc_4 + (2819. / 640.) * ecc_6;
-        eccentricity_function[4] = (23. / 12.) * ecc_3 + (89. / 24.) * ecc_5 + (5663. / 960.) * ecc_7;
-        eccentricity_function[5] = (11. / 8.) * ecc_2 + (49. / 16.) * ecc_4 + (15665. / 3072.) * ecc_6;
-        eccentricity_function[6] = ecc * (1. - ecc_2).powf(-5. / 2.);
-        eccentricity_function[7] = 1. + (1. / 2.) * ecc_2 + (239. / 64.) * ecc_4 - (3323. / 576.) * ecc_6;
-        eccentricity_function[8] = 3. * ecc + (11. / 4.) * ecc_3 + (245. / 48.) * ecc_5 + (463. / 64.) * ecc_7;
-        eccentricity_function[9] = (53. / 8.) * ecc_2 + (39. / 16.) * ecc_4 + (7041. / 1024.) * ecc_6;
-        eccentricity_function[10] = (163. / 4.) * ecc_3 - (2577. / 16.) * ecc_5 + (1089. / 5.) * ecc_7;
-        eccentricity_function[11] = (35413. / 384.) * ecc_4 - (709471. / 1920.) * ecc_6;
-        eccentricity_function[12] = (23029. / 120.) * ecc_5 - (35614. / 45.) * ecc_7;
-        eccentricity_function[13] = (385095. / 1024.) * ecc_6;
-        eccentricity_function[14] = (44377. / 63.) * ecc_7;
-
-        eccentricity_function
-    }
-}
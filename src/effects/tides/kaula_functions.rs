@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use super::super::super::constants::{TWO_PI, KAULA_ECCENTRICITY_FUNCTION_TOLERANCE, KAULA_FUNCTIONS_CACHE_TOLERANCE};
+
+// Inclination and eccentricity functions of the Kaula (1966) expansion of the tidal potential of
+// degree l, used by the Kaula model (see kaula.rs):
+//  - Inclination functions F_lmp(i) are generated from their closed form (Kaula 1966, Eq. 3.62)
+//  - Eccentricity functions G_lpq(e) are the Hansen coefficients X^{-(l+1), l-2p}_{l-2p+q}(e),
+//    computed numerically for any eccentricity below 1
+// The range of q (i.e., -q_max <= q <= q_max) is truncated when the eccentricity functions of every p
+// become smaller than a tolerance relative to the largest one, thus it grows with the eccentricity.
+// Computing them is expensive, the functions of each orbit are kept in a cache and only computed again
+// when its eccentricity or inclination changed enough (see KaulaFunctionsCache).
+
+const MAX_Q: usize = 200; // Limit of the truncation of q (reached only for eccentricities close to 1)
+const MIN_SAMPLES: usize = 64;
+const MAX_SAMPLES: usize = 1 << 16;
+const QUADRATURE_TOLERANCE: f64 = 1.0e-12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KaulaFunctions {
+    pub degree: usize,
+    pub q_max: usize,
+    pub eccentricity: f64,
+    pub inclination: f64,
+    inclination_functions: Vec<f64>, // F_lmp for m and p in [0, l]
+    eccentricity_functions: Vec<f64>, // G_lpq for p in [0, l] and q in [-q_max, q_max]
+}
+
+impl KaulaFunctions {
+    /// Functions of degree l with the range of q truncated using the given relative tolerance
+    pub fn new(degree: usize, eccentricity: f64, inclination: f64, tolerance: f64) -> KaulaFunctions {
+        let mut q_max = 8;
+        loop {
+            let kaula_functions = KaulaFunctions::with_q_max(degree, eccentricity, inclination, q_max);
+            let truncated_q_max = kaula_functions.truncated_q_max(tolerance);
+            if truncated_q_max < q_max || q_max == MAX_Q {
+                return kaula_functions.truncate(truncated_q_max);
+            }
+            q_max = (2 * q_max).min(MAX_Q);
+        }
+    }
+
+    /// Functions of degree l for q in [-q_max, q_max]
+    pub fn with_q_max(degree: usize, eccentricity: f64, inclination: f64, q_max: usize) -> KaulaFunctions {
+        let mut inclination_functions = Vec::with_capacity((degree + 1) * (degree + 1));
+        for m in 0..=degree {
+            for p in 0..=degree {
+                inclination_functions.push(inclination_function(degree, m, p, inclination));
+            }
+        }
+        let mut eccentricity_functions = Vec::with_capacity((degree + 1) * (2 * q_max + 1));
+        for p in 0..=degree {
+            let l_2p = degree as i32 - 2 * p as i32;
+            let first_k = l_2p - q_max as i32;
+            eccentricity_functions.extend(hansen_coefficients(-(degree as i32 + 1), l_2p, first_k..=first_k + 2 * q_max as i32, eccentricity));
+        }
+        KaulaFunctions { degree, q_max, eccentricity, inclination, inclination_functions, eccentricity_functions }
+    }
+
+    pub fn q_range(&self) -> RangeInclusive<i32> {
+        -(self.q_max as i32)..=self.q_max as i32
+    }
+
+    /// F_lmp(i)
+    pub fn inclination_function(&self, m: usize, p: usize) -> f64 {
+        self.inclination_functions[m * (self.degree + 1) + p]
+    }
+
+    /// G_lpq(e), zero outside of the truncated range of q
+    pub fn eccentricity_function(&self, p: usize, q: i32) -> f64 {
+        if q.unsigned_abs() as usize > self.q_max {
+            return 0.;
+        }
+        self.eccentricity_functions[p * (2 * self.q_max + 1) + (q + self.q_max as i32) as usize]
+    }
+
+    /// Largest |q| with an eccentricity function above the tolerance (relative to the largest one)
+    fn truncated_q_max(&self, tolerance: f64) -> usize {
+        let largest = self.eccentricity_functions.iter().fold(0_f64, |largest, value| largest.max(value.abs()));
+        (0..=self.q_max).rev().find(|q| {
+            let q = *q as i32;
+            (0..=self.degree).any(|p| self.eccentricity_function(p, q).abs() > tolerance * largest || self.eccentricity_function(p, -q).abs() > tolerance * largest)
+        }).unwrap_or(0)
+    }
+
+    fn truncate(&self, q_max: usize) -> KaulaFunctions {
+        let mut eccentricity_functions = Vec::with_capacity((self.degree + 1) * (2 * q_max + 1));
+        for p in 0..=self.degree {
+            eccentricity_functions.extend(self.q_range().filter(|q| q.unsigned_abs() as usize <= q_max).map(|q| self.eccentricity_function(p, q)));
+        }
+        KaulaFunctions { degree: self.degree, q_max, eccentricity: self.eccentricity, inclination: self.inclination, inclination_functions: self.inclination_functions.clone(), eccentricity_functions }
+    }
+}
+
+/// Kaula functions of the orbit of each perturber around each tidally deformed body, they are
+/// computed again only when the eccentricity or the inclination changed by more than
+/// KAULA_FUNCTIONS_CACHE_TOLERANCE. The cache is not part of the state of the simulation.
+#[derive(Clone, Default)]
+pub struct KaulaFunctionsCache {
+    kaula_functions: HashMap<(usize, usize, usize), KaulaFunctions>, // (deformed particle id, perturber id, degree)
+}
+
+impl KaulaFunctionsCache {
+    pub fn kaula_functions(&mut self, deformed_particle_id: usize, perturber_id: usize, degree: usize, eccentricity: f64, inclination: f64) -> &KaulaFunctions {
+        let kaula_functions = self.kaula_functions.entry((deformed_particle_id, perturber_id, degree))
+            .or_insert_with(|| KaulaFunctions::new(degree, eccentricity, inclination, KAULA_ECCENTRICITY_FUNCTION_TOLERANCE));
+        if (kaula_functions.eccentricity - eccentricity).abs() > KAULA_FUNCTIONS_CACHE_TOLERANCE
+            || (kaula_functions.inclination - inclination).abs() > KAULA_FUNCTIONS_CACHE_TOLERANCE {
+            *kaula_functions = KaulaFunctions::new(degree, eccentricity, inclination, KAULA_ECCENTRICITY_FUNCTION_TOLERANCE);
+        }
+        kaula_functions
+    }
+
+    pub fn len(&self) -> usize {
+        self.kaula_functions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kaula_functions.is_empty()
+    }
+}
+
+// Cached values are derived from the particles, they do not make two simulations different
+impl PartialEq for KaulaFunctionsCache {
+    fn eq(&self, _other: &KaulaFunctionsCache) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for KaulaFunctionsCache {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "KaulaFunctionsCache")
+    }
+}
+
+/// Inclination function F_lmp(i) (Kaula 1966, Eq. 3.62)
+pub fn inclination_function(l: usize, m: usize, p: usize, inclination: f64) -> f64 {
+    if m > l || p > l {
+        return 0.;
+    }
+    let sin_inclination = inclination.sin();
+    let cos_inclination = inclination.cos();
+    let k = (l - m) / 2;
+    let mut sum_over_t = 0.;
+    for t in 0..=p.min(k) {
+        let factor_t = factorial(2 * l - 2 * t) / (factorial(t) * factorial(l - t) * factorial(l - m - 2 * t) * 2_f64.powi((2 * l - 2 * t) as i32));
+        let mut sum_over_s = 0.;
+        for s in 0..=m {
+            let mut sum_over_c = 0.;
+            for c in 0..=(l - m - 2 * t + s) {
+                if c > p - t || p - t - c > m - s {
+                    continue;
+                }
+                let sign = if (c as i64 - k as i64).rem_euclid(2) == 0 { 1. } else { -1. };
+                sum_over_c += binomial(l - m - 2 * t + s, c) * binomial(m - s, p - t - c) * sign;
+            }
+            sum_over_s += binomial(m, s) * cos_inclination.powi(s as i32) * sum_over_c;
+        }
+        sum_over_t += factor_t * sin_inclination.powi((l - m - 2 * t) as i32) * sum_over_s;
+    }
+    sum_over_t
+}
+
+/// Eccentricity function G_lpq(e)
+pub fn eccentricity_function(l: usize, p: usize, q: i32, eccentricity: f64) -> f64 {
+    let l_2p = l as i32 - 2 * p as i32;
+    hansen_coefficients(-(l as i32 + 1), l_2p, l_2p + q..=l_2p + q, eccentricity)[0]
+}
+
+/// Hansen coefficients X^{n,m}_k(e) for a range of k, defined by
+///   (r/a)^n exp(i m f) = sum_k X^{n,m}_k(e) exp(i k M)
+/// They are computed by integrating over the true anomaly f (trapezoidal rule, which converges
+/// exponentially for periodic functions), doubling the number of samples until convergence:
+///   X^{n,m}_k(e) = (1 - e^2)^(n + 3/2) / (2 pi) int_0^(2 pi) (1 + e cos f)^(-n - 2) cos(m f - k M) df
+pub fn hansen_coefficients(n: i32, m: i32, k: RangeInclusive<i32>, eccentricity: f64) -> Vec<f64> {
+    if eccentricity == 0. {
+        return k.map(|k| if k == m { 1. } else { 0. }).collect();
+    }
+    let largest_k = k.start().abs().max(k.end().abs()) as usize;
+    let mut samples = MIN_SAMPLES;
+    while samples < 4 * (largest_k + m.unsigned_abs() as usize + n.unsigned_abs() as usize) {
+        samples *= 2;
+    }
+    let factor = (1. - eccentricity * eccentricity).powf(n as f64 + 1.5);
+    let sqrt_ratio = ((1. - eccentricity) / (1. + eccentricity)).sqrt();
+    loop {
+        // exp(i (m f - k M)) is obtained for consecutive k by rotating a phasor of angle -M
+        let mut phasors = Vec::with_capacity(samples);
+        let mut rotations = Vec::with_capacity(samples);
+        for i in 0..samples {
+            let true_anomaly = TWO_PI * i as f64 / samples as f64;
+            let eccentric_anomaly = 2. * (sqrt_ratio * (true_anomaly / 2.).sin()).atan2((true_anomaly / 2.).cos());
+            let mean_anomaly = eccentric_anomaly - eccentricity * eccentric_anomaly.sin();
+            let weight = factor * (1. + eccentricity * true_anomaly.cos()).powi(-n - 2);
+            let phase = m as f64 * true_anomaly - *k.start() as f64 * mean_anomaly;
+            phasors.push((weight * phase.cos(), weight * phase.sin()));
+            rotations.push((mean_anomaly.cos(), -mean_anomaly.sin()));
+        }
+        let mut converged = true;
+        let mut coefficients = Vec::with_capacity(k.clone().count());
+        for _ in k.clone() {
+            // Trapezoidal rule with all the samples and with half of them (every other sample)
+            let mut sum = 0.;
+            let mut half_sum = 0.;
+            for (i, (phasor, rotation)) in phasors.iter_mut().zip(rotations.iter()).enumerate() {
+                sum += phasor.0;
+                if i % 2 == 0 {
+                    half_sum += phasor.0;
+                }
+                *phasor = (phasor.0 * rotation.0 - phasor.1 * rotation.1, phasor.0 * rotation.1 + phasor.1 * rotation.0);
+            }
+            let coefficient = sum / samples as f64;
+            let half_coefficient = 2. * half_sum / samples as f64;
+            if (coefficient - half_coefficient).abs() > QUADRATURE_TOLERANCE * (1. + coefficient.abs()) {
+                converged = false;
+            }
+            coefficients.push(coefficient);
+        }
+        if converged || samples >= MAX_SAMPLES {
+            return coefficients;
+        }
+        samples *= 2;
+    }
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..=n).fold(1., |factorial, i| factorial * i as f64)
+}
+
+fn binomial(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.;
+    }
+    factorial(n) / (factorial(k) * factorial(n - k))
+}
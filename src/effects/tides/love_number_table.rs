@@ -0,0 +1,244 @@
+use std::fs;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use super::super::super::integrator::content_hash;
+
+// Love number tables of arbitrary size for the Kaula model (see kaula.rs), one per tidally
+// deformed body (stored in the universe, not in the tidal models which are copied around).
+//
+// Excitation frequencies are in rad/s sorted in increasing order, together with the opposite of the
+// real and imaginary parts of the complex Love number (i.e., -Re(k2) and -Im(k2)). Frequencies
+// outside the table are clamped to its limits.
+//
+// Tables can be embedded in the snapshots, computed from the interior structure of the body (see
+// rheology.rs) or read from a file referenced by path, together with the content hash of the file
+// to detect if it changed after the simulation was created. Files can be:
+//  - Text: three whitespace separated columns (excitation frequency, imaginary part, real part),
+//          lines starting with '#' are comments (same format as the tables used by the Kaula tests).
+//  - Binary: the 8 bytes magic number "POSLOVE1", the number of points (unsigned 64-bit integer) and
+//            for each point the excitation frequency, imaginary part and real part (64-bit floats),
+//            all of them in little-endian byte order.
+
+const BINARY_MAGIC_NUMBER: &[u8; 8] = b"POSLOVE1";
+
+/// Interpolation between the points of a Love number table
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LoveNumberInterpolation {
+    Linear,
+    // Linear in the logarithm of the frequency and the Love number (power laws between points),
+    // linear when the neighbouring points have different signs
+    LogLog,
+    // Monotone piecewise cubic (Fritsch & Carlson 1980), smooth without overshooting the tabulated points
+    Spline,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct LoveNumberData {
+    pub excitation_frequency: Vec<f64>, // rad/s
+    pub real_part: Vec<f64>, // Opposite of the real part of the complex Love number
+    pub imaginary_part: Vec<f64>, // Opposite of the imaginary part of the complex Love number
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum LoveNumberTableSource {
+    Embedded(LoveNumberData),
+    File { path: String, content_hash: u64 },
+    InteriorStructure(LoveNumberData), // Computed from the interior structure of the particle
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoveNumberTable {
+    pub particle: usize,
+    pub source: LoveNumberTableSource,
+    pub interpolation: LoveNumberInterpolation,
+    #[serde(skip)]
+    loaded_data: LoveNumberData, // Content of the file (not stored in snapshots, see `load`)
+}
+
+impl LoveNumberTable {
+    pub fn new(particle: usize, data: LoveNumberData, interpolation: LoveNumberInterpolation) -> LoveNumberTable {
+        LoveNumberTable { particle, source: LoveNumberTableSource::Embedded(data), interpolation, loaded_data: LoveNumberData::default() }
+    }
+
+    /// Table computed from the interior structure of the particle (see rheology.rs)
+    pub fn from_interior_structure(particle: usize, data: LoveNumberData) -> LoveNumberTable {
+        LoveNumberTable { particle, source: LoveNumberTableSource::InteriorStructure(data), interpolation: LoveNumberInterpolation::Linear, loaded_data: LoveNumberData::default() }
+    }
+
+    /// Table read from a (text or binary) file, its current content hash is stored to verify it
+    /// when the simulation is restored
+    pub fn from_file(particle: usize, path: &Path, interpolation: LoveNumberInterpolation) -> Result<LoveNumberTable, String> {
+        let bytes = fs::read(path).map_err(|why| format!("Couldn't read Love number table {}: {}", path.display(), why))?;
+        let source = LoveNumberTableSource::File { path: path.display().to_string(), content_hash: content_hash(&bytes) };
+        let mut love_number_table = LoveNumberTable { particle, source, interpolation, loaded_data: LoveNumberData::default() };
+        love_number_table.loaded_data = parse_love_number_file(path, &bytes)?;
+        Ok(love_number_table)
+    }
+
+    /// Read the file of the table (if any) and verify that it did not change since the table was created
+    pub fn load(&mut self) -> Result<(), String> {
+        if let LoveNumberTableSource::File { path, content_hash: expected_content_hash } = &self.source {
+            let path = Path::new(path);
+            let bytes = fs::read(path).map_err(|why| format!("Couldn't read Love number table {} of particle {}: {}", path.display(), self.particle, why))?;
+            if content_hash(&bytes) != *expected_content_hash {
+                return Err(format!("The Love number table {} of particle {} changed since the simulation was created (content hash {} instead of {})", path.display(), self.particle, content_hash(&bytes), expected_content_hash));
+            }
+            self.loaded_data = parse_love_number_file(path, &bytes)?;
+        }
+        Ok(())
+    }
+
+    pub fn data(&self) -> &LoveNumberData {
+        match &self.source {
+            LoveNumberTableSource::Embedded(data) | LoveNumberTableSource::InteriorStructure(data) => data,
+            LoveNumberTableSource::File { .. } => &self.loaded_data,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let data = self.data();
+        let n_datapoints = data.excitation_frequency.len();
+        if n_datapoints < 2 {
+            return Err(format!("The Love number table of particle {} has less than 2 points", self.particle));
+        }
+        if data.real_part.len() != n_datapoints || data.imaginary_part.len() != n_datapoints {
+            return Err(format!("The Love number table of particle {} has {} frequencies but {} real and {} imaginary parts", self.particle, n_datapoints, data.real_part.len(), data.imaginary_part.len()));
+        }
+        if data.excitation_frequency.iter().chain(data.real_part.iter()).chain(data.imaginary_part.iter()).any(|value| !value.is_finite()) {
+            return Err(format!("The Love number table of particle {} contains non-finite values", self.particle));
+        }
+        if !data.excitation_frequency.windows(2).all(|frequencies| frequencies[0] < frequencies[1]) {
+            return Err(format!("The excitation frequencies of the Love number table of particle {} must be sorted in strictly increasing order", self.particle));
+        }
+        Ok(())
+    }
+
+    /// Opposite of the real and imaginary parts of the Love number for a given excitation frequency
+    pub fn love_number(&self, frequency: f64) -> (f64, f64) {
+        let data = self.data();
+        (interpolate(&data.excitation_frequency, &data.real_part, frequency, self.interpolation),
+         interpolate(&data.excitation_frequency, &data.imaginary_part, frequency, self.interpolation))
+    }
+}
+
+impl LoveNumberData {
+    /// Points of the fixed-size tables (1024 points) of the Kaula parameters of snapshot format
+    /// versions 0 to 10, which were padded with zeros: only the leading points with strictly
+    /// increasing frequencies are kept (none if there are less than 2 of them)
+    pub fn from_fixed_size_tables(excitation_frequency: &[f64], real_part: &[f64], imaginary_part: &[f64]) -> Option<LoveNumberData> {
+        let n_available = excitation_frequency.len().min(real_part.len()).min(imaginary_part.len());
+        let n_datapoints = 1 + excitation_frequency[..n_available].windows(2).take_while(|frequencies| frequencies[0] < frequencies[1]).count();
+        if n_available < 2 || n_datapoints < 2 {
+            return None;
+        }
+        Some(LoveNumberData {
+            excitation_frequency: excitation_frequency[..n_datapoints].to_vec(),
+            real_part: real_part[..n_datapoints].to_vec(),
+            imaginary_part: imaginary_part[..n_datapoints].to_vec(),
+        })
+    }
+}
+
+fn parse_love_number_file(path: &Path, bytes: &[u8]) -> Result<LoveNumberData, String> {
+    let mut data = LoveNumberData::default();
+    let mut push = |values: [f64; 3]| {
+        data.excitation_frequency.push(values[0]);
+        data.imaginary_part.push(values[1]);
+        data.real_part.push(values[2]);
+    };
+    if bytes.starts_with(BINARY_MAGIC_NUMBER) {
+        let header_size = BINARY_MAGIC_NUMBER.len() + 8;
+        if bytes.len() < header_size {
+            return Err(format!("Love number table {} is truncated", path.display()));
+        }
+        let mut n_datapoints_bytes = [0_u8; 8];
+        n_datapoints_bytes.copy_from_slice(&bytes[BINARY_MAGIC_NUMBER.len()..header_size]);
+        let n_datapoints = u64::from_le_bytes(n_datapoints_bytes) as usize;
+        if bytes.len() != header_size + n_datapoints.saturating_mul(3 * 8) {
+            return Err(format!("Love number table {} should contain {} points but its size is {} bytes", path.display(), n_datapoints, bytes.len()));
+        }
+        for point in bytes[header_size..].chunks_exact(3 * 8) {
+            let mut values = [0.; 3];
+            for (value, value_bytes) in values.iter_mut().zip(point.chunks_exact(8)) {
+                let mut float_bytes = [0_u8; 8];
+                float_bytes.copy_from_slice(value_bytes);
+                *value = f64::from_le_bytes(float_bytes);
+            }
+            push(values);
+        }
+    } else {
+        let text = std::str::from_utf8(bytes).map_err(|why| format!("Love number table {} is neither a binary nor a text table: {}", path.display(), why))?;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() < 3 {
+                return Err(format!("Line {} of the Love number table {} has less than 3 columns", i + 1, path.display()));
+            }
+            let mut values = [0.; 3];
+            for (value, column) in values.iter_mut().zip(columns.iter()) {
+                *value = column.parse().map_err(|why| format!("Line {} of the Love number table {}: {}", i + 1, path.display(), why))?;
+            }
+            push(values);
+        }
+    }
+    Ok(data)
+}
+
+/// Interpolated value for `x` given a table sorted by increasing `xs`, values outside the table
+/// are clamped to the first/last value
+pub fn interpolate(xs: &[f64], ys: &[f64], x: f64, interpolation: LoveNumberInterpolation) -> f64 {
+    let last = xs.len() - 1;
+    if x <= xs[0] {
+        return ys[0];
+    } else if x >= xs[last] {
+        return ys[last];
+    }
+    let i = match xs.binary_search_by(|val| val.total_cmp(&x)) {
+        Ok(i) => return ys[i], // Exact match
+        Err(i) => i, // x is between xs[i - 1] and xs[i]
+    };
+    let delta = (x - xs[i - 1]) / (xs[i] - xs[i - 1]);
+    let linear = (1.0 - delta) * ys[i - 1] + delta * ys[i];
+    match interpolation {
+        LoveNumberInterpolation::Linear => linear,
+        LoveNumberInterpolation::LogLog => {
+            let same_sign = |a: f64, b: f64| (a > 0. && b > 0.) || (a < 0. && b < 0.);
+            if !same_sign(xs[i - 1], xs[i]) || !same_sign(ys[i - 1], ys[i]) {
+                return linear;
+            }
+            let log_delta = (x / xs[i - 1]).ln() / (xs[i] / xs[i - 1]).ln();
+            ys[i - 1] * (ys[i] / ys[i - 1]).powf(log_delta)
+        },
+        LoveNumberInterpolation::Spline => {
+            let h = xs[i] - xs[i - 1];
+            let slope_start = monotone_slope(xs, ys, i - 1);
+            let slope_end = monotone_slope(xs, ys, i);
+            // Cubic Hermite basis
+            let h00 = (1. + 2. * delta) * (1. - delta).powi(2);
+            let h10 = delta * (1. - delta).powi(2);
+            let h01 = delta.powi(2) * (3. - 2. * delta);
+            let h11 = delta.powi(2) * (delta - 1.);
+            h00 * ys[i - 1] + h10 * h * slope_start + h01 * ys[i] + h11 * h * slope_end
+        },
+    }
+}
+
+/// Derivative at the point `i` that preserves the monotonicity of the data (Fritsch & Butland 1984)
+fn monotone_slope(xs: &[f64], ys: &[f64], i: usize) -> f64 {
+    let secant = |j: usize| (ys[j + 1] - ys[j]) / (xs[j + 1] - xs[j]);
+    if i == 0 {
+        return secant(0);
+    } else if i == xs.len() - 1 {
+        return secant(i - 1);
+    }
+    let (previous_secant, next_secant) = (secant(i - 1), secant(i));
+    if previous_secant * next_secant <= 0. {
+        // Local extremum
+        return 0.;
+    }
+    let (previous_h, next_h) = (xs[i] - xs[i - 1], xs[i + 1] - xs[i]);
+    3. * (previous_h + next_h) / ((2. * next_h + previous_h) / previous_secant + (next_h + 2. * previous_h) / next_secant)
+}
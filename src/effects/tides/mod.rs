@@ -1,22 +1,44 @@
 pub mod common;
 pub mod constant_time_lag;
+pub mod constant_phase_lag;
+pub mod creep;
 pub mod creep_coplanar;
 pub mod kaula;
+pub mod kaula_functions;
+pub mod love_number_table;
+pub mod model;
+pub mod pairwise;
+pub mod rheology;
 
 pub use self::common::Tides;
 pub use self::common::TidesEffect;
-pub use self::common::TidalModel;
 pub use self::common::initialize;
 pub use self::common::inertial_to_heliocentric_coordinates;
 pub use self::common::copy_heliocentric_coordinates;
-pub use self::common::calculate_dangular_momentum_dt_due_to_tides;
+pub use self::common::calculate_tides;
 pub use self::common::calculate_denergy_dt;
-pub use self::common::calculate_tidal_acceleration;
-pub use self::constant_time_lag::calculate_pair_dependent_scaled_dissipation_factors;
 pub use self::constant_time_lag::ConstantTimeLagParameters;
-pub use self::constant_time_lag::calculate_radial_component_of_the_tidal_force;
-pub use self::constant_time_lag::calculate_orthogonal_component_of_the_tidal_force;
+pub use self::constant_phase_lag::ConstantPhaseLagParameters;
+pub use self::creep::CreepParameters;
 pub use self::creep_coplanar::CreepCoplanarParameters;
-pub use self::creep_coplanar::calculate_creep_coplanar_shapes;
 pub use self::kaula::KaulaParameters;
-pub use self::kaula::Polynomials;
+pub use self::kaula_functions::KaulaFunctions;
+pub use self::kaula_functions::KaulaFunctionsCache;
+pub use self::kaula_functions::inclination_function;
+pub use self::kaula_functions::eccentricity_function;
+pub use self::kaula_functions::hansen_coefficients;
+pub use self::love_number_table::LoveNumberTable;
+pub use self::love_number_table::LoveNumberTableSource;
+pub use self::love_number_table::LoveNumberData;
+pub use self::love_number_table::LoveNumberInterpolation;
+pub use self::model::TidalModel;
+pub use self::model::TidalResponse;
+pub use self::model::TidalContext;
+pub use self::model::Tide;
+pub use self::pairwise::TidalPair;
+pub use self::pairwise::satellite_tidal_pairs;
+pub use self::pairwise::calculate_tidal_pairs;
+pub use self::pairwise::calculate_tidal_pairs_denergy_dt;
+pub use self::rheology::Rheology;
+pub use self::rheology::RheologicalLayer;
+pub use self::rheology::InteriorStructure;
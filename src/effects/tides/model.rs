@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use super::super::super::{Particle};
+use super::super::super::{Axes};
+use super::constant_time_lag;
+use super::constant_phase_lag;
+use super::creep;
+use super::creep_coplanar;
+use super::kaula;
+use super::love_number_table::LoveNumberTable;
+use super::kaula_functions::KaulaFunctionsCache;
+
+// Every tidal model implements the TidalResponse trait, which describes the tide raised on a body
+// that uses the model (i.e., the tidally deformed body) by its companion. The tides raised on the
+// host (stellar tide) and on the orbiting body (planetary tide) are computed independently with
+// the model of each body (see common::calculate_tides), thus adding a new tidal model only requires
+// to implement the trait and to add it to the TidalModel enum below.
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TidalModel {
+    ConstantTimeLag(constant_time_lag::ConstantTimeLagParameters),
+    CreepCoplanar(creep_coplanar::CreepCoplanarParameters),
+    Kaula(kaula::KaulaParameters),
+    ConstantPhaseLag(constant_phase_lag::ConstantPhaseLagParameters),
+    Creep(creep::CreepParameters),
+}
+
+impl TidalModel {
+    pub fn response(&self) -> &dyn TidalResponse {
+        match self {
+            TidalModel::ConstantTimeLag(params) => params,
+            TidalModel::ConstantPhaseLag(params) => params,
+            TidalModel::CreepCoplanar(params) => params,
+            TidalModel::Kaula(params) => params,
+            TidalModel::Creep(params) => params,
+        }
+    }
+
+    pub fn response_mut(&mut self) -> &mut dyn TidalResponse {
+        match self {
+            TidalModel::ConstantTimeLag(params) => params,
+            TidalModel::ConstantPhaseLag(params) => params,
+            TidalModel::CreepCoplanar(params) => params,
+            TidalModel::Kaula(params) => params,
+            TidalModel::Creep(params) => params,
+        }
+    }
+}
+
+/// Tidally deformed body of a host/orbiting body pair
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Tide {
+    Stellar, // Tide raised on the host (tidal_host_particle) by the orbiting body
+    Planetary, // Tide raised on the orbiting body (particle) by the host
+}
+
+/// State shared by all the pairs (and models) of the simulation
+pub struct TidalContext<'a> {
+    pub pair_dependent_scaled_dissipation_factor: &'a mut HashMap<usize, f64>, // Constant time lag specific
+    pub love_number_tables: &'a [LoveNumberTable], // Kaula specific
+    pub kaula_functions_cache: &'a mut KaulaFunctionsCache, // Kaula, constant phase lag and creep specific
+}
+
+pub trait TidalResponse {
+    fn name(&self) -> &'static str;
+
+    fn validate(&self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Main tidal excitation frequencies (rad/day) of the tide, which is raised on the host or the
+    /// orbiting body depending on `tide`
+    fn excitation_frequencies(&self, tidal_host_particle: &Particle, particle: &Particle, tide: Tide) -> Vec<f64>;
+
+    /// Tidal force acting on the orbiting body (the host feels the opposite force) and torque
+    /// acting on the tidally deformed body (i.e., its contribution to dangular_momentum_dt). The
+    /// coordinates of the orbiting body are relative to the host (see common::inertial_to_heliocentric_coordinates).
+    fn calculate_tidal_force_and_torque(&mut self, tidal_host_particle: &mut Particle, particle: &mut Particle, tide: Tide, context: &mut TidalContext) -> (Axes, Axes);
+
+    /// Energy dissipated per unit of time (Msun.AU^2.day^-3) in the orbiting body by the tide raised
+    /// on it (planetary tide), once the tidal forces and torques have been computed. Models without
+    /// a dissipative-only expression return the tidal power (see calculate_tidal_power_from_force_and_torque),
+    /// which equals the dissipated power on average over an orbit
+    fn dissipated_power(&self, tidal_host_particle: &Particle, particle: &Particle, context: &mut TidalContext) -> f64;
+
+    // Output fields
+
+    /// Love number of degree 2 (history output)
+    fn love_number(&self) -> f64 {
+        0.
+    }
+
+    /// Scaled dissipation factor (sigma) used until the model updates it (history output)
+    fn scaled_dissipation_factor(&self) -> f64 {
+        0.
+    }
+}
+
+/// Power (Msun.AU^2.day^-3) of the force and torque of the planetary tide (-F.v - T.w), which can
+/// be computed for any tidal model. It is not a dissipative-only expression: the instantaneous
+/// value also includes the energy exchanged with the conservative part of the tidal force, thus it
+/// only equals the dissipated power on average over an orbit (and it can be negative).
+pub fn calculate_tidal_power_from_force_and_torque<R: TidalResponse + Copy>(tidal_response: &R, tidal_host_particle: &Particle, particle: &Particle, context: &mut TidalContext) -> f64 {
+    // The models can update the particles and their own state, work on copies
+    let mut tidal_response = *tidal_response;
+    let mut tidal_host_particle = *tidal_host_particle;
+    let mut particle = *particle;
+    let (tidal_force, torque) = tidal_response.calculate_tidal_force_and_torque(&mut tidal_host_particle, &mut particle, Tide::Planetary, context);
+    let velocity = particle.tides.coordinates.velocity;
+    -(tidal_force.x*velocity.x + tidal_force.y*velocity.y + tidal_force.z*velocity.z)
+        - (torque.x*particle.spin.x + torque.y*particle.spin.y + torque.z*particle.spin.z)
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use super::super::super::{Particle, Reference};
+use super::super::super::{Axes};
+use super::common::TidesEffect;
+use super::common;
+use super::model::{TidalModel, TidalContext};
+use super::love_number_table::LoveNumberTable;
+use super::kaula_functions::KaulaFunctionsCache;
+
+// Tides between two particles that do not involve the central host (e.g., the second star of
+// a circumbinary system, planet-planet or planet-moon tides). Each pair is computed with the
+// same functions used for the central host, but using a copy of both particles where:
+//  - "host" takes the role of the central body (CentralBody(host_tidal_model))
+//  - "orbiting" takes the role of the orbiting body (OrbitingBody(orbiting_tidal_model))
+//  - Coordinates are relative to the host (tides coordinates and also the heliocentric ones,
+//    which are used by the Kaula model)
+// The resulting accelerations and torques are added to the ones of the central host.
+// Satellites (e.g., moons) are pairs built from the particle of reference instead of being
+// explicitly defined (see `satellite_tidal_pairs`).
+//
+// As for the central host, the tides raised on the host and on the orbiting body are computed
+// with the tidal model of each of them (e.g., a constant time lag host with zero love number and
+// dissipation factor behaves as a point mass).
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TidalPair {
+    pub host: usize, // Particle index
+    pub orbiting: usize, // Particle index
+    pub host_tidal_model: TidalModel, // Tides raised on the host by the orbiting body
+    pub orbiting_tidal_model: TidalModel, // Tides raised on the orbiting body by the host
+}
+
+impl TidalPair {
+    pub fn new(host: usize, orbiting: usize, host_tidal_model: TidalModel, orbiting_tidal_model: TidalModel) -> TidalPair {
+        TidalPair {
+            host,
+            orbiting,
+            host_tidal_model,
+            orbiting_tidal_model,
+        }
+    }
+
+    pub fn involves(&self, index: usize) -> bool {
+        self.host == index || self.orbiting == index
+    }
+}
+
+/// Tides between every satellite (i.e., a particle that uses as reference another particle that
+/// is not the central host) and the particle it orbits, which plays the role of the central body
+/// with the same tidal model it uses as orbiting body of the central host
+pub fn satellite_tidal_pairs(particles: &[Particle], tidal_host_index: usize) -> Vec<TidalPair> {
+    particles.iter().enumerate().filter_map(|(index, particle)| {
+        match (particle.reference, particle.tides.effect) {
+            (Reference::Particle(host), TidesEffect::OrbitingBody(orbiting_tidal_model)) if host != index && host != tidal_host_index && host < particles.len() => {
+                match particles[host].tides.effect {
+                    TidesEffect::OrbitingBody(host_tidal_model) => Some(TidalPair::new(host, index, host_tidal_model, orbiting_tidal_model)),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }).collect()
+}
+
+/// Add the tidal accelerations and/or torques of every pair to the ones already computed for
+/// the central host (particles without tides effect start from zero)
+pub fn calculate_tidal_pairs(particles: &mut [Particle], tidal_pairs: &mut [TidalPair], pair_dependent_scaled_dissipation_factor: &mut HashMap<usize, f64>, love_number_tables: &[LoveNumberTable], kaula_functions_cache: &mut KaulaFunctionsCache, dangular_momentum_dt: bool, accelerations: bool) {
+    initialize(particles, tidal_pairs);
+    for tidal_pair in tidal_pairs.iter_mut() {
+        let (host_particle, orbiting_particle) = calculate_tidal_pair(particles, tidal_pair, pair_dependent_scaled_dissipation_factor, love_number_tables, kaula_functions_cache, dangular_momentum_dt, accelerations);
+        for (index, pair_particle) in [(tidal_pair.host, host_particle), (tidal_pair.orbiting, orbiting_particle)].iter() {
+            let output = &mut particles[*index].tides.parameters.output;
+            if accelerations {
+                output.acceleration.x += pair_particle.tides.parameters.output.acceleration.x;
+                output.acceleration.y += pair_particle.tides.parameters.output.acceleration.y;
+                output.acceleration.z += pair_particle.tides.parameters.output.acceleration.z;
+            }
+            if dangular_momentum_dt {
+                output.dangular_momentum_dt.x += pair_particle.tides.parameters.output.dangular_momentum_dt.x;
+                output.dangular_momentum_dt.y += pair_particle.tides.parameters.output.dangular_momentum_dt.y;
+                output.dangular_momentum_dt.z += pair_particle.tides.parameters.output.dangular_momentum_dt.z;
+            }
+        }
+    }
+}
+
+/// Add the energy dissipated by every pair to the orbiting body (only for history output)
+pub fn calculate_tidal_pairs_denergy_dt(particles: &mut [Particle], tidal_pairs: &mut [TidalPair], pair_dependent_scaled_dissipation_factor: &mut HashMap<usize, f64>, love_number_tables: &[LoveNumberTable], kaula_functions_cache: &mut KaulaFunctionsCache) {
+    for tidal_pair in tidal_pairs.iter() {
+        for index in [tidal_pair.host, tidal_pair.orbiting].iter() {
+            if particles[*index].tides.effect == TidesEffect::Disabled {
+                particles[*index].tides.parameters.internal.denergy_dt = 0.;
+            }
+        }
+    }
+    for tidal_pair in tidal_pairs.iter_mut() {
+        let dangular_momentum_dt = true;
+        let accelerations = true;
+        let (host_particle, mut orbiting_particle) = calculate_tidal_pair(particles, tidal_pair, pair_dependent_scaled_dissipation_factor, love_number_tables, kaula_functions_cache, dangular_momentum_dt, accelerations);
+        let mut context = TidalContext {
+            pair_dependent_scaled_dissipation_factor,
+            love_number_tables,
+            kaula_functions_cache,
+        };
+        common::calculate_denergy_dt(&host_particle, std::slice::from_mut(&mut orbiting_particle), &mut [], &mut context);
+        particles[tidal_pair.orbiting].tides.parameters.internal.denergy_dt += orbiting_particle.tides.parameters.internal.denergy_dt;
+    }
+}
+
+fn initialize(particles: &mut [Particle], tidal_pairs: &[TidalPair]) {
+    // Particles with tides effect were already initialized for the central host
+    for tidal_pair in tidal_pairs.iter() {
+        for index in [tidal_pair.host, tidal_pair.orbiting].iter() {
+            let particle = &mut particles[*index];
+            if particle.tides.effect == TidesEffect::Disabled {
+                particle.tides.parameters.output.acceleration = Axes{x: 0., y: 0., z: 0.};
+                particle.tides.parameters.output.dangular_momentum_dt = Axes{x: 0., y: 0., z: 0.};
+            }
+        }
+    }
+}
+
+fn calculate_tidal_pair(particles: &[Particle], tidal_pair: &mut TidalPair, pair_dependent_scaled_dissipation_factor: &mut HashMap<usize, f64>, love_number_tables: &[LoveNumberTable], kaula_functions_cache: &mut KaulaFunctionsCache, dangular_momentum_dt: bool, accelerations: bool) -> (Particle, Particle) {
+    let mut host_particle = particles[tidal_pair.host];
+    let mut orbiting_particle = particles[tidal_pair.orbiting];
+    set_pair_tidal_model(&mut host_particle, TidesEffect::CentralBody(tidal_pair.host_tidal_model));
+    set_pair_tidal_model(&mut orbiting_particle, TidesEffect::OrbitingBody(tidal_pair.orbiting_tidal_model));
+    // Heliocentric coordinates are used by the Kaula model
+    host_particle.heliocentric_position = Axes{x: 0., y: 0., z: 0.};
+    host_particle.heliocentric_velocity = Axes{x: 0., y: 0., z: 0.};
+    host_particle.heliocentric_distance = 0.;
+    host_particle.heliocentric_radial_velocity = 0.;
+    host_particle.heliocentric_norm_velocity_vector_2 = 0.;
+    host_particle.heliocentric_norm_velocity_vector = 0.;
+    {
+        let orbiting_particles = std::slice::from_mut(&mut orbiting_particle);
+        common::inertial_to_heliocentric_coordinates(&mut host_particle, orbiting_particles, &mut []);
+        orbiting_particles[0].heliocentric_position = orbiting_particles[0].tides.coordinates.position;
+        orbiting_particles[0].heliocentric_velocity = orbiting_particles[0].tides.coordinates.velocity;
+        orbiting_particles[0].heliocentric_distance = orbiting_particles[0].tides.parameters.internal.distance;
+        orbiting_particles[0].heliocentric_radial_velocity = orbiting_particles[0].tides.parameters.internal.radial_velocity;
+        orbiting_particles[0].heliocentric_norm_velocity_vector_2 = orbiting_particles[0].tides.coordinates.velocity.x.powi(2)
+                                                                    + orbiting_particles[0].tides.coordinates.velocity.y.powi(2)
+                                                                    + orbiting_particles[0].tides.coordinates.velocity.z.powi(2);
+        orbiting_particles[0].heliocentric_norm_velocity_vector = orbiting_particles[0].heliocentric_norm_velocity_vector_2.sqrt();
+        common::initialize(&mut host_particle, orbiting_particles, &mut []);
+
+        let mut context = TidalContext {
+            pair_dependent_scaled_dissipation_factor,
+            love_number_tables,
+            kaula_functions_cache,
+        };
+        common::calculate_tides(&mut host_particle, orbiting_particles, &mut [], &mut context, dangular_momentum_dt, accelerations);
+    }
+    // Models can keep intermediate results
+    if let TidesEffect::CentralBody(tidal_model) = host_particle.tides.effect {
+        tidal_pair.host_tidal_model = tidal_model;
+    }
+    if let TidesEffect::OrbitingBody(tidal_model) = orbiting_particle.tides.effect {
+        tidal_pair.orbiting_tidal_model = tidal_model;
+    }
+    (host_particle, orbiting_particle)
+}
+
+fn set_pair_tidal_model(particle: &mut Particle, effect: TidesEffect) {
+    particle.tides.parameters.internal.scaled_dissipation_factor = match effect {
+        TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model) => tidal_model.response().scaled_dissipation_factor(),
+        _ => 0.,
+    };
+    particle.tides.effect = effect;
+}
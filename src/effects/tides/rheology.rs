@@ -0,0 +1,507 @@
+use std::ops::{Add, Sub, Mul, Div};
+use serde::{Serialize, Deserialize};
+use super::super::super::constants::{G_SI, PI};
+use super::love_number_table::{LoveNumberTable, LoveNumberData};
+
+// Complex Love numbers k_n(w) of a body computed from the rheology of its interior, used to fill
+// the tables of the Kaula model (see kaula.rs) instead of precomputing them externally.
+//
+// Each layer is described by a complex compliance J(w) (Efroimsky 2012, Renaud & Henning 2018),
+// using the convention exp(i*w*t) with w > 0 (tidal frequency in rad/s):
+//  - Maxwell:          J(w) = 1/rigidity - i/(viscosity*w)
+//  - Burgers:          Maxwell + J_T/(1 + i*w*tau_T)     with J_T = 1/transient_rigidity and tau_T = transient_viscosity/transient_rigidity
+//  - Andrade:          Maxwell + beta*Gamma(1+alpha)*(i*w)^-alpha     with beta = tau_A^-alpha/rigidity
+//  - Sundberg-Cooper:  Andrade + Burgers transient term
+// The complex rigidity is the inverse of the compliance (correspondence principle), and its
+// imaginary part is positive, thus Im(k_n) < 0 for w > 0 (the bulge lags behind the perturber).
+// Negative frequencies are the complex conjugate of the positive ones.
+//
+// Homogeneous incompressible bodies use the analytic expression:
+//
+//      k_n = 3/(2*(n-1)) / (1 + (2n^2+4n+3)/n * rigidity/(density*g*R))
+//
+// Otherwise, the static spheroidal deformation equations (Takeuchi & Saito 1972, Sabadini &
+// Vermeersen 2004) are integrated from the center (or the surface of a fluid core) to the
+// surface with the variables y1 (radial displacement), y2 (radial stress), y3 (tangential
+// displacement), y4 (tangential stress), y5 (potential) and y6 = y5' + 4*pi*G*density*y1
+// (continuous across density jumps). Incompressible layers (zero bulk modulus) are
+// approximated with a bulk modulus much larger than the rigidities and gravitational stress,
+// and the rigidity of almost fluid layers (e.g., low viscosity at low frequencies) is bounded.
+//
+// All the quantities of the interior structure are in S.I. units (m, kg/m^3, Pa, Pa.s, s).
+
+// Ratio between the bulk modulus used to approximate incompressible layers and the largest
+// rigidity or gravitational stress (density*g*R) of the body
+const QUASI_INCOMPRESSIBLE_BULK_MODULUS_RATIO: f64 = 1.0e4;
+// Minimum magnitude of the complex rigidity with respect to the gravitational stress of the body,
+// the deformation equations cannot be integrated for almost fluid layers (the Love numbers
+// are already in the fluid limit)
+const MIN_RELATIVE_RIGIDITY: f64 = 1.0e-6;
+// Integration steps for each layer
+const INTEGRATION_STEPS_PER_LAYER: usize = 200;
+// Relative radius where the integration starts when the center is solid
+const CENTER_RELATIVE_RADIUS: f64 = 1.0e-3;
+// Tidal frequencies of the Kaula tables (rad/s), half of the table for negative frequencies
+const LOVE_NUMBER_TABLE_MIN_FREQUENCY: f64 = 1.0e-14;
+const LOVE_NUMBER_TABLE_MAX_FREQUENCY: f64 = 1.0e-2;
+const LOVE_NUMBER_TABLE_SIZE: usize = 32 * 32;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Rheology {
+    Fluid, // Only allowed for the innermost layer (i.e., liquid core)
+    Maxwell,
+    Burgers {
+        transient_rigidity: f64, // Pa
+        transient_viscosity: f64, // Pa.s
+    },
+    Andrade {
+        alpha: f64, // Andrade exponent (between 0 and 1, typically 0.2-0.4)
+        andrade_timescale: f64, // s (equal to the Maxwell time if the anelastic and viscous responses are coupled)
+    },
+    SundbergCooper {
+        alpha: f64,
+        andrade_timescale: f64, // s
+        transient_rigidity: f64, // Pa
+        transient_viscosity: f64, // Pa.s
+    },
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RheologicalLayer {
+    pub outer_radius: f64, // m
+    pub density: f64, // kg/m^3
+    pub rigidity: f64, // Pa (unrelaxed shear modulus)
+    pub viscosity: f64, // Pa.s
+    pub bulk_modulus: f64, // Pa (zero for incompressible layers)
+    pub rheology: Rheology,
+}
+
+/// Layers of a particle (from the center to the surface) used to compute the Love numbers of its
+/// Kaula tidal model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InteriorStructure {
+    pub particle: usize, // Particle index
+    pub layers: Vec<RheologicalLayer>,
+}
+
+impl InteriorStructure {
+    pub fn new(particle: usize, layers: Vec<RheologicalLayer>) -> InteriorStructure {
+        InteriorStructure {
+            particle,
+            layers,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.layers.is_empty() {
+            return Err(format!("The interior structure of particle {} has no layers", self.particle));
+        }
+        let mut inner_radius = 0.;
+        for (i, layer) in self.layers.iter().enumerate() {
+            let error = |message: String| Err(format!("Layer {} of the interior structure of particle {}: {}", i, self.particle, message));
+            if layer.outer_radius <= inner_radius || !layer.outer_radius.is_finite() {
+                return error(format!("the outer radius ({} m) must be greater than the one of the previous layer ({} m)", layer.outer_radius, inner_radius));
+            }
+            inner_radius = layer.outer_radius;
+            if layer.density <= 0. || !layer.density.is_finite() {
+                return error(format!("the density must be positive ({} kg/m^3)", layer.density));
+            }
+            if let Rheology::Fluid = layer.rheology {
+                if i != 0 || self.layers.len() == 1 {
+                    return error("only the innermost layer of a body with more layers can be fluid".to_string());
+                }
+                continue;
+            }
+            if layer.rigidity <= 0. || !layer.rigidity.is_finite() {
+                return error(format!("the rigidity must be positive ({} Pa)", layer.rigidity));
+            }
+            if layer.viscosity <= 0. || layer.viscosity.is_nan() {
+                return error(format!("the viscosity must be positive ({} Pa.s)", layer.viscosity));
+            }
+            if layer.bulk_modulus < 0. || !layer.bulk_modulus.is_finite() {
+                return error(format!("the bulk modulus cannot be negative ({} Pa)", layer.bulk_modulus));
+            }
+            match layer.rheology {
+                Rheology::Andrade { alpha, andrade_timescale } | Rheology::SundbergCooper { alpha, andrade_timescale, .. } => {
+                    if alpha <= 0. || alpha >= 1. || alpha.is_nan() {
+                        return error(format!("the Andrade exponent must be between 0 and 1 ({})", alpha));
+                    }
+                    if andrade_timescale <= 0. || !andrade_timescale.is_finite() {
+                        return error(format!("the Andrade timescale must be positive ({} s)", andrade_timescale));
+                    }
+                },
+                _ => {},
+            }
+            if let Rheology::Burgers { transient_rigidity, transient_viscosity } | Rheology::SundbergCooper { transient_rigidity, transient_viscosity, .. } = layer.rheology {
+                if transient_rigidity <= 0. || !transient_rigidity.is_finite() || transient_viscosity <= 0. || !transient_viscosity.is_finite() {
+                    return error(format!("the transient rigidity ({} Pa) and viscosity ({} Pa.s) must be positive", transient_rigidity, transient_viscosity));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.layers.last().map(|layer| layer.outer_radius).unwrap_or(0.)
+    }
+
+    /// Complex Love number of degree n (>= 2) at the given tidal frequency (rad/s)
+    pub fn love_number(&self, degree: usize, frequency: f64) -> (f64, f64) {
+        let love_number = if frequency < 0. {
+            self.calculate_love_number(degree, -frequency).conj()
+        } else {
+            self.calculate_love_number(degree, frequency)
+        };
+        (love_number.re, love_number.im)
+    }
+
+    /// Table of the degree 2 Love numbers used by the Kaula model (see `calculate_kaula_numbers` in
+    /// kaula.rs, which expects the opposite of the real and imaginary parts)
+    pub fn love_number_table(&self) -> LoveNumberTable {
+        let half_size = LOVE_NUMBER_TABLE_SIZE / 2;
+        let log_min_frequency = LOVE_NUMBER_TABLE_MIN_FREQUENCY.log10();
+        let log_step = (LOVE_NUMBER_TABLE_MAX_FREQUENCY.log10() - log_min_frequency) / (half_size - 1) as f64;
+        let mut data = LoveNumberData {
+            excitation_frequency: vec![0.; LOVE_NUMBER_TABLE_SIZE],
+            real_part: vec![0.; LOVE_NUMBER_TABLE_SIZE],
+            imaginary_part: vec![0.; LOVE_NUMBER_TABLE_SIZE],
+        };
+        for i in 0..half_size {
+            let frequency = 10_f64.powf(log_min_frequency + log_step * i as f64);
+            let love_number = self.calculate_love_number(2, frequency);
+            // Positive frequencies in the second half and negative (conjugate) in the first one (mirrored)
+            let positive_index = half_size + i;
+            let negative_index = half_size - 1 - i;
+            data.excitation_frequency[positive_index] = frequency;
+            data.real_part[positive_index] = -love_number.re;
+            data.imaginary_part[positive_index] = -love_number.im;
+            data.excitation_frequency[negative_index] = -frequency;
+            data.real_part[negative_index] = -love_number.re;
+            data.imaginary_part[negative_index] = love_number.im;
+        }
+        LoveNumberTable::from_interior_structure(self.particle, data)
+    }
+
+    /// Mean density times surface gravity and radius (Pa)
+    fn gravitational_stress(&self) -> f64 {
+        let mut mass = 0.;
+        let mut inner_radius: f64 = 0.;
+        for layer in self.layers.iter() {
+            mass += 4. / 3. * PI * layer.density * (layer.outer_radius.powi(3) - inner_radius.powi(3));
+            inner_radius = layer.outer_radius;
+        }
+        let radius = self.radius();
+        mass / (4. / 3. * PI * radius.powi(3)) * G_SI * mass / radius
+    }
+
+    fn calculate_love_number(&self, degree: usize, frequency: f64) -> Complex {
+        let rigidities: Vec<Complex> = self.layers.iter().map(|layer| complex_rigidity(layer, frequency)).collect();
+        if self.layers.len() == 1 && self.layers[0].bulk_modulus == 0. {
+            let layer = &self.layers[0];
+            let surface_gravity = G_SI * 4. / 3. * PI * layer.density * layer.outer_radius;
+            let n = degree as f64;
+            let factor = (2. * n * n + 4. * n + 3.) / (n * layer.density * surface_gravity * layer.outer_radius);
+            return Complex::real(1.5 / (n - 1.)) / (Complex::real(1.) + rigidities[0] * factor);
+        }
+        self.integrate_deformation_equations(degree, &rigidities)
+    }
+
+    fn integrate_deformation_equations(&self, degree: usize, rigidities: &[Complex]) -> Complex {
+        let n = degree as f64;
+        let four_pi_g = 4. * PI * G_SI;
+        let gravitational_stress = self.gravitational_stress();
+        let incompressible_bulk_modulus = QUASI_INCOMPRESSIBLE_BULK_MODULUS_RATIO * gravitational_stress.max(self.layers.iter().fold(0., |max, layer| layer.rigidity.max(max)));
+        let min_rigidity = MIN_RELATIVE_RIGIDITY * gravitational_stress;
+        let rigidities: Vec<Complex> = rigidities.iter().map(|mu| {
+            let norm = mu.norm();
+            if norm > 0. && norm < min_rigidity { *mu * (min_rigidity / norm) } else { *mu }
+        }).collect();
+        let (mut solutions, first_solid_layer, mut radius, mut mass) = match self.layers[0].rheology {
+            Rheology::Fluid => {
+                let core = &self.layers[0];
+                let r = core.outer_radius;
+                let mass = 4. / 3. * PI * core.density * r.powi(3);
+                let gravity = G_SI * mass / (r * r);
+                let mut solutions = [[Complex::default(); 6]; 3];
+                // Potential perturbation of the core with its hydrostatic pressure
+                solutions[0][1] = Complex::real(core.density * r.powf(n));
+                solutions[0][4] = Complex::real(r.powf(n));
+                solutions[0][5] = Complex::real(n * r.powf(n - 1.));
+                // Free radial displacement of the core boundary
+                solutions[1][0] = Complex::real(1.);
+                solutions[1][1] = Complex::real(core.density * gravity);
+                solutions[1][5] = Complex::real(four_pi_g * core.density);
+                // Free slip of the solid layer over the core
+                solutions[2][2] = Complex::real(1.);
+                (solutions, 1, r, mass)
+            },
+            _ => {
+                let layer = &self.layers[0];
+                let r = CENTER_RELATIVE_RADIUS * layer.outer_radius;
+                let mu = rigidities[0];
+                let lambda = lame_parameter(layer, mu, incompressible_bulk_modulus);
+                let beta = lambda + mu * 2.;
+                let mut solutions = [[Complex::default(); 6]; 3];
+                // Regular solutions of a homogeneous sphere close to the center
+                solutions[0][4] = Complex::real(r.powf(n));
+                solutions[0][5] = Complex::real(n * r.powf(n - 1.));
+                solutions[1][0] = Complex::real(n * r.powf(n - 1.));
+                solutions[1][1] = mu * (2. * n * (n - 1.) * r.powf(n - 2.));
+                solutions[1][2] = Complex::real(r.powf(n - 1.));
+                solutions[1][3] = mu * (2. * (n - 1.) * r.powf(n - 2.));
+                solutions[1][5] = Complex::real(four_pi_g * layer.density * n * r.powf(n - 1.));
+                solutions[2][0] = Complex::real(r.powf(n + 1.));
+                solutions[2][1] = (beta * (n + 1.) + lambda * 2.) * r.powf(n);
+                solutions[2][3] = mu * r.powf(n);
+                solutions[2][5] = Complex::real(four_pi_g * layer.density * r.powf(n + 1.));
+                let mass = 4. / 3. * PI * layer.density * r.powi(3);
+                (solutions, 0, r, mass)
+            },
+        };
+
+        for (layer, mu) in self.layers.iter().zip(rigidities.iter()).skip(first_solid_layer) {
+            let inner_radius = radius;
+            let inner_mass = mass;
+            let step = (layer.outer_radius - inner_radius) / INTEGRATION_STEPS_PER_LAYER as f64;
+            let medium = Medium::new(layer, *mu, incompressible_bulk_modulus, n, inner_radius, inner_mass);
+            for solution in solutions.iter_mut() {
+                let mut r = inner_radius;
+                for _ in 0..INTEGRATION_STEPS_PER_LAYER {
+                    *solution = medium.runge_kutta_step(solution, r, step);
+                    r += step;
+                }
+            }
+            radius = layer.outer_radius;
+            mass = inner_mass + 4. / 3. * PI * layer.density * (radius.powi(3) - inner_radius.powi(3));
+        }
+
+        // Free surface (no radial and tangential stress) and continuity of the potential
+        // with the external tidal potential (r/R)^n plus the response k_n*(R/r)^(n+1)
+        let mut matrix = [[Complex::default(); 3]; 3];
+        for (j, solution) in solutions.iter().enumerate() {
+            matrix[0][j] = solution[1];
+            matrix[1][j] = solution[3];
+            matrix[2][j] = solution[5] + solution[4] * ((n + 1.) / radius);
+        }
+        let constants = solve_linear_system(matrix, [Complex::default(), Complex::default(), Complex::real((2. * n + 1.) / radius)]);
+        let mut potential = Complex::default();
+        for (constant, solution) in constants.iter().zip(solutions.iter()) {
+            potential = potential + *constant * solution[4];
+        }
+        potential - Complex::real(1.)
+    }
+}
+
+/// Inverse of the complex compliance of the layer (see the header of this file)
+fn complex_rigidity(layer: &RheologicalLayer, frequency: f64) -> Complex {
+    let compliance = 1. / layer.rigidity;
+    let maxwell = Complex::new(compliance, -1. / (layer.viscosity * frequency));
+    let andrade = |alpha: f64, andrade_timescale: f64| {
+        let magnitude = compliance * (andrade_timescale * frequency).powf(-alpha) * gamma(1. + alpha);
+        Complex::new(magnitude * (alpha * PI / 2.).cos(), -magnitude * (alpha * PI / 2.).sin())
+    };
+    let transient = |transient_rigidity: f64, transient_viscosity: f64| {
+        Complex::real(1. / transient_rigidity) / Complex::new(1., frequency * transient_viscosity / transient_rigidity)
+    };
+    let complex_compliance = match layer.rheology {
+        Rheology::Fluid => return Complex::default(),
+        Rheology::Maxwell => maxwell,
+        Rheology::Burgers { transient_rigidity, transient_viscosity } => maxwell + transient(transient_rigidity, transient_viscosity),
+        Rheology::Andrade { alpha, andrade_timescale } => maxwell + andrade(alpha, andrade_timescale),
+        Rheology::SundbergCooper { alpha, andrade_timescale, transient_rigidity, transient_viscosity } => {
+            maxwell + andrade(alpha, andrade_timescale) + transient(transient_rigidity, transient_viscosity)
+        },
+    };
+    Complex::real(1.) / complex_compliance
+}
+
+/// First Lame parameter from the bulk modulus (elastic) and the complex rigidity
+fn lame_parameter(layer: &RheologicalLayer, mu: Complex, incompressible_bulk_modulus: f64) -> Complex {
+    let bulk_modulus = if layer.bulk_modulus == 0. {
+        incompressible_bulk_modulus
+    } else {
+        layer.bulk_modulus
+    };
+    Complex::real(bulk_modulus) - mu * (2. / 3.)
+}
+
+/// Coefficients of the deformation equations that are constant in a layer
+struct Medium {
+    density: f64,
+    mu: Complex,
+    lambda: Complex,
+    beta: Complex, // lambda + 2*mu
+    gamma: Complex, // mu*(3*lambda + 2*mu)/beta
+    degree: f64,
+    inner_radius: f64,
+    inner_mass: f64,
+}
+
+impl Medium {
+    fn new(layer: &RheologicalLayer, mu: Complex, incompressible_bulk_modulus: f64, degree: f64, inner_radius: f64, inner_mass: f64) -> Medium {
+        let lambda = lame_parameter(layer, mu, incompressible_bulk_modulus);
+        let beta = lambda + mu * 2.;
+        let gamma = mu * (lambda * 3. + mu * 2.) / beta;
+        Medium { density: layer.density, mu, lambda, beta, gamma, degree, inner_radius, inner_mass }
+    }
+
+    fn gravity(&self, r: f64) -> f64 {
+        let mass = self.inner_mass + 4. / 3. * PI * self.density * (r.powi(3) - self.inner_radius.powi(3));
+        G_SI * mass / (r * r)
+    }
+
+    fn derivatives(&self, y: &[Complex; 6], r: f64) -> [Complex; 6] {
+        let l = self.degree * (self.degree + 1.);
+        let rho = self.density;
+        let rho_g = rho * self.gravity(r);
+        let four_pi_g_rho = 4. * PI * G_SI * rho;
+        let (mu, lambda, beta, gamma) = (self.mu, self.lambda, self.beta, self.gamma);
+        let dy1 = (y[1] - lambda * (y[0] * 2. - y[2] * l) / r) / beta;
+        let dy2 = (gamma * (4. / (r * r)) - Complex::real(4. * rho_g / r)) * y[0]
+                    - mu / beta * (4. / r) * y[1]
+                    + (Complex::real(rho_g * l / r) - gamma * (2. * l / (r * r))) * y[2]
+                    + y[3] * (l / r)
+                    + y[5] * rho;
+        let dy3 = (y[2] - y[0]) / r + y[3] / mu;
+        let dy4 = (Complex::real(rho_g / r) - gamma * (2. / (r * r))) * y[0]
+                    - lambda / beta / r * y[1]
+                    + mu / beta * (2. / (r * r)) * (lambda * (2. * l - 1.) + mu * (2. * (l - 1.))) * y[2]
+                    - y[3] * (3. / r)
+                    + y[4] * (rho / r);
+        let dy5 = y[5] - y[0] * four_pi_g_rho;
+        let dy6 = y[2] * (four_pi_g_rho * l / r) + y[4] * (l / (r * r)) - y[5] * (2. / r);
+        [dy1, dy2, dy3, dy4, dy5, dy6]
+    }
+
+    fn runge_kutta_step(&self, y: &[Complex; 6], r: f64, step: f64) -> [Complex; 6] {
+        let shifted = |y: &[Complex; 6], k: &[Complex; 6], factor: f64| {
+            let mut shifted = *y;
+            for (value, derivative) in shifted.iter_mut().zip(k.iter()) {
+                *value = *value + *derivative * factor;
+            }
+            shifted
+        };
+        let k1 = self.derivatives(y, r);
+        let k2 = self.derivatives(&shifted(y, &k1, step / 2.), r + step / 2.);
+        let k3 = self.derivatives(&shifted(y, &k2, step / 2.), r + step / 2.);
+        let k4 = self.derivatives(&shifted(y, &k3, step), r + step);
+        let mut next = *y;
+        for i in 0..6 {
+            next[i] = next[i] + (k1[i] + k2[i] * 2. + k3[i] * 2. + k4[i]) * (step / 6.);
+        }
+        next
+    }
+}
+
+/// Gaussian elimination with partial pivoting
+fn solve_linear_system(mut matrix: [[Complex; 3]; 3], mut vector: [Complex; 3]) -> [Complex; 3] {
+    for column in 0..3 {
+        let pivot = (column..3).max_by(|a, b| matrix[*a][column].norm().total_cmp(&matrix[*b][column].norm())).unwrap();
+        matrix.swap(column, pivot);
+        vector.swap(column, pivot);
+        let pivot_row = matrix[column];
+        for row in column+1..3 {
+            let factor = matrix[row][column] / pivot_row[column];
+            for (value, pivot_value) in matrix[row].iter_mut().zip(pivot_row.iter()).skip(column) {
+                *value = *value - factor * *pivot_value;
+            }
+            vector[row] = vector[row] - factor * vector[column];
+        }
+    }
+    let mut solution = [Complex::default(); 3];
+    for row in (0..3).rev() {
+        let mut value = vector[row];
+        for k in row+1..3 {
+            value = value - matrix[row][k] * solution[k];
+        }
+        solution[row] = value / matrix[row][row];
+    }
+    solution
+}
+
+/// Gamma function (Lanczos approximation, g = 7)
+fn gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8,
+        771.323_428_777_653_1, -176.615_029_162_140_6, 12.507_343_278_686_905,
+        -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6, 1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        return PI / ((PI * x).sin() * gamma(1. - x));
+    }
+    let x = x - 1.;
+    let mut sum = COEFFICIENTS[0];
+    for (i, coefficient) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += coefficient / (x + i as f64);
+    }
+    let t = x + 7.5;
+    (2. * PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * sum
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub(crate) struct Complex {
+    pub(crate) re: f64,
+    pub(crate) im: f64,
+}
+
+impl Complex {
+    pub(crate) fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub(crate) fn real(re: f64) -> Complex {
+        Complex { re, im: 0. }
+    }
+
+    fn conj(self) -> Complex {
+        Complex { re: self.re, im: -self.im }
+    }
+
+    fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+}
+
+impl Add for Complex {
+    type Output = Complex;
+    fn add(self, other: Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl Sub for Complex {
+    type Output = Complex;
+    fn sub(self, other: Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+
+impl Mul for Complex {
+    type Output = Complex;
+    fn mul(self, other: Complex) -> Complex {
+        Complex::new(self.re * other.re - self.im * other.im, self.re * other.im + self.im * other.re)
+    }
+}
+
+impl Mul<f64> for Complex {
+    type Output = Complex;
+    fn mul(self, factor: f64) -> Complex {
+        Complex::new(self.re * factor, self.im * factor)
+    }
+}
+
+impl Div for Complex {
+    type Output = Complex;
+    fn div(self, other: Complex) -> Complex {
+        let denominator = other.re * other.re + other.im * other.im;
+        Complex::new((self.re * other.re + self.im * other.im) / denominator, (self.im * other.re - self.re * other.im) / denominator)
+    }
+}
+
+impl Div<f64> for Complex {
+    type Output = Complex;
+    fn div(self, divisor: f64) -> Complex {
+        Complex::new(self.re / divisor, self.im / divisor)
+    }
+}
@@ -0,0 +1,327 @@
+use serde::{Serialize, Deserialize};
+use super::Integrator;
+use super::super::particles::{Universe, Particle, Reference};
+use super::super::{Tides, TidesEffect, TidalModel};
+use super::particle_set::{OrbitalElements, add_particle, remove_particle};
+
+/// Simulation switches (see `ConsiderEffects`) that can be turned off by an event
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum EventEffect {
+    Tides,
+    RotationalFlattening,
+    GeneralRelativity,
+    Disk,
+    Wind,
+    Evolution,
+}
+
+/// Changes that can be scheduled during a simulation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum EventAction {
+    // E.g., turn off the disk effect at disk dispersal
+    DisableEffect {
+        effect: EventEffect,
+    },
+    // Constant time lag dissipation factor of a particle (index in the universe)
+    SetDissipationFactor {
+        particle: usize,
+        dissipation_factor: f64,
+    },
+    // Replace the tidal effect/model of a particle (index in the universe)
+    SetTidesEffect {
+        particle: usize,
+        effect: Box<TidesEffect>,
+    },
+    // Viscosity of a layer of the interior structure of a particle, its Kaula Love numbers are computed again
+    SetViscosity {
+        particle: usize,
+        layer: usize,
+        viscosity: f64, // Pa.s
+    },
+    SetTimeStep {
+        time_step: f64, // days
+    },
+    SetHistoricSnapshotPeriod {
+        historic_snapshot_period: f64, // days
+    },
+    // New particle appended at the end (heliocentric position/velocity unless orbital elements are given)
+    AddParticle {
+        particle: Box<Particle>,
+        orbital_elements: Option<OrbitalElements>,
+    },
+    // The following particles are shifted one position
+    RemoveParticle {
+        particle: usize,
+    },
+}
+
+impl EventAction {
+    // Index of the particle the action refers to (for a new particle, its particle of reference)
+    fn particle_mut(&mut self) -> Option<&mut usize> {
+        match self {
+            EventAction::SetDissipationFactor { particle, .. } | EventAction::SetTidesEffect { particle, .. }
+                | EventAction::SetViscosity { particle, .. } | EventAction::RemoveParticle { particle } => Some(particle),
+            EventAction::AddParticle { particle, .. } => match &mut particle.reference {
+                Reference::Particle(index) => Some(index),
+                Reference::MostMassiveParticle => None,
+            },
+            EventAction::DisableEffect { .. } | EventAction::SetTimeStep { .. } | EventAction::SetHistoricSnapshotPeriod { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduledEvent {
+    pub time: f64, // days (same reference as the simulation current time)
+    pub action: EventAction,
+}
+
+/// Events sorted by time, the integrators shorten the step that would go beyond
+/// the next event so that it is applied exactly at its scheduled time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct EventTimeline {
+    pub events: Vec<ScheduledEvent>,
+    next_index: usize, // Index of the next event to be applied
+}
+
+impl EventTimeline {
+    pub fn new(mut events: Vec<ScheduledEvent>) -> EventTimeline {
+        // Stable sort: events with the same time are applied in the given order
+        events.sort_by(|a, b| a.time.total_cmp(&b.time));
+        EventTimeline {
+            events,
+            next_index: 0,
+        }
+    }
+
+    pub fn next_time(&self) -> Option<f64> {
+        self.events.get(self.next_index).map(|event| event.time)
+    }
+
+    pub fn is_due(&self, current_time: f64) -> bool {
+        match self.next_time() {
+            Some(next_time) => next_time <= current_time,
+            None => false,
+        }
+    }
+
+    /// Events already applied (they are kept in the snapshots as a record)
+    pub fn applied_events(&self) -> &[ScheduledEvent] {
+        &self.events[..self.next_index]
+    }
+
+    pub fn take_due_events(&mut self, current_time: f64) -> Vec<ScheduledEvent> {
+        let mut due_events = Vec::new();
+        while self.is_due(current_time) {
+            due_events.push(self.events[self.next_index].clone());
+            self.next_index += 1;
+        }
+        due_events
+    }
+
+    /// Time of the next event if it happens before the end of the given time step
+    /// (integrators shorten that step to reach it exactly)
+    pub fn next_time_within(&self, current_time: f64, time_step: f64) -> Option<f64> {
+        self.next_time().filter(|next_time| *next_time > current_time && *next_time < current_time + time_step)
+    }
+
+    /// Pending events that refer to a removed particle are discarded and the ones that refer
+    /// to the following particles are updated (applied events are kept as they were)
+    pub fn remove_particle(&mut self, index: usize) {
+        let mut pending_events = self.events.split_off(self.next_index);
+        pending_events.retain(|event| {
+            let mut action = event.action.clone();
+            let refers_to_removed_particle = action.particle_mut().map(|particle| *particle == index).unwrap_or(false);
+            if refers_to_removed_particle {
+                log_warning!("Discarded event {:?} at {} days because particle {} was removed", event.action, event.time, index);
+            }
+            !refers_to_removed_particle
+        });
+        for event in pending_events.iter_mut() {
+            if let Some(particle) = event.action.particle_mut() {
+                if *particle > index {
+                    *particle -= 1;
+                }
+            }
+        }
+        self.events.extend(pending_events);
+    }
+}
+
+/// Errors that would make the application of an event fail (the particles and their tidal
+/// effects follow the pending events that add or remove particles and replace tidal effects)
+pub fn validate_events(event_timeline: &EventTimeline, universe: &Universe) -> Vec<String> {
+    let mut errors = Vec::new();
+    if event_timeline.events.windows(2).any(|events| events[0].time > events[1].time) {
+        errors.push("Scheduled events are not sorted by time".to_string());
+    }
+    let mut tides_effects: Vec<TidesEffect> = universe.particles[..universe.n_particles].iter().map(|particle| particle.tides.effect).collect();
+    for event in event_timeline.events[event_timeline.next_index..].iter() {
+        if let Err(e) = validate_event(event, universe, &tides_effects) {
+            errors.push(e);
+        }
+        match &event.action {
+            EventAction::SetTidesEffect { particle, effect } if *particle < tides_effects.len() => tides_effects[*particle] = **effect,
+            EventAction::AddParticle { particle, .. } => tides_effects.push(particle.tides.effect),
+            EventAction::RemoveParticle { particle } if *particle < tides_effects.len() => { tides_effects.remove(*particle); },
+            _ => {},
+        }
+    }
+    errors
+}
+
+/// Checks of an event given the tidal effects of the particles at the time it is applied
+fn validate_event(event: &ScheduledEvent, universe: &Universe, tides_effects: &[TidesEffect]) -> Result<(), String> {
+    let n_particles = tides_effects.len();
+    if event.time.is_nan() {
+        return Err(format!("Event {:?} has a NaN scheduled time", event.action));
+    }
+    if event.time < 0. || event.time > universe.time_limit {
+        return Err(format!("Event {:?} at {} days is out of the simulation time range", event.action, event.time));
+    }
+    match &event.action {
+        EventAction::SetDissipationFactor { particle, dissipation_factor } => {
+            if *particle >= n_particles {
+                return Err(format!("Event at {} days refers to particle {} but there are only {} particles", event.time, particle, n_particles));
+            }
+            if *dissipation_factor < 0. {
+                return Err(format!("Event at {} days sets a negative dissipation factor ({})", event.time, dissipation_factor));
+            }
+            if !matches!(tides_effects[*particle], TidesEffect::CentralBody(TidalModel::ConstantTimeLag(_)) | TidesEffect::OrbitingBody(TidalModel::ConstantTimeLag(_))) {
+                return Err(format!("Event at {} days sets the dissipation factor of particle {} but it does not use the constant time lag tidal model", event.time, particle));
+            }
+        },
+        EventAction::SetTidesEffect { particle, .. } => {
+            if *particle >= n_particles {
+                return Err(format!("Event at {} days refers to particle {} but there are only {} particles", event.time, particle, n_particles));
+            }
+        },
+        EventAction::SetViscosity { particle, viscosity, .. } => {
+            if *particle >= n_particles {
+                return Err(format!("Event at {} days refers to particle {} but there are only {} particles", event.time, particle, n_particles));
+            }
+            if *viscosity <= 0. || viscosity.is_nan() {
+                return Err(format!("Event at {} days sets a non-positive viscosity ({})", event.time, viscosity));
+            }
+        },
+        EventAction::SetTimeStep { time_step } => {
+            if *time_step <= 0. {
+                return Err(format!("Event at {} days sets a non-positive time step ({})", event.time, time_step));
+            }
+        },
+        EventAction::SetHistoricSnapshotPeriod { historic_snapshot_period } => {
+            if *historic_snapshot_period <= 0. {
+                return Err(format!("Event at {} days sets a non-positive historic snapshot period ({})", event.time, historic_snapshot_period));
+            }
+        },
+        EventAction::AddParticle { particle, .. } => {
+            if let Reference::Particle(index) = particle.reference {
+                if index >= n_particles {
+                    return Err(format!("Event at {} days adds a particle that refers to particle {} but there are only {} particles", event.time, index, n_particles));
+                }
+            }
+            if particle.mass <= 0. {
+                return Err(format!("Event at {} days adds a particle with a non-positive mass ({})", event.time, particle.mass));
+            }
+        },
+        EventAction::RemoveParticle { particle } => {
+            if *particle >= n_particles {
+                return Err(format!("Event at {} days refers to particle {} but there are only {} particles", event.time, particle, n_particles));
+            }
+        },
+        EventAction::DisableEffect { .. } => {},
+    }
+    Ok(())
+}
+
+pub fn apply_event(universe_integrator: &mut dyn Integrator, event: &ScheduledEvent) {
+    let universe = universe_integrator.get_universe();
+    let tides_effects: Vec<TidesEffect> = universe.particles[..universe.n_particles].iter().map(|particle| particle.tides.effect).collect();
+    if let Err(e) = validate_event(event, universe, &tides_effects) {
+        fatal!("{}", e);
+    }
+    match &event.action {
+        // The full particle description is too verbose for the log
+        EventAction::AddParticle { particle, orbital_elements } => log_info!("Applying event AddParticle {{ mass: {}, orbital_elements: {:?} }} at {} days", particle.mass, orbital_elements, event.time),
+        action => log_info!("Applying event {:?} at {} days", action, event.time),
+    }
+    match &event.action {
+        EventAction::DisableEffect { effect } => {
+            let universe = universe_integrator.get_universe_mut();
+            match effect {
+                EventEffect::Tides => universe.consider_effects.tides = false,
+                EventEffect::RotationalFlattening => universe.consider_effects.rotational_flattening = false,
+                EventEffect::GeneralRelativity => universe.consider_effects.general_relativity = false,
+                EventEffect::Disk => universe.consider_effects.disk = false,
+                EventEffect::Wind => universe.consider_effects.wind = false,
+                EventEffect::Evolution => universe.consider_effects.evolution = false,
+            }
+            universe.check_consistency();
+        },
+        EventAction::SetDissipationFactor { particle, dissipation_factor } => {
+            let universe = universe_integrator.get_universe_mut();
+            let tides = &mut universe.particles[*particle].tides;
+            match &mut tides.effect {
+                TidesEffect::CentralBody(TidalModel::ConstantTimeLag(params)) | TidesEffect::OrbitingBody(TidalModel::ConstantTimeLag(params)) => {
+                    params.dissipation_factor = *dissipation_factor;
+                    tides.parameters.internal.scaled_dissipation_factor = params.dissipation_factor_scale * params.dissipation_factor;
+                },
+                _ => unreachable!(), // Rejected by validate_event
+            }
+        },
+        EventAction::SetTidesEffect { particle, effect } => {
+            let universe = universe_integrator.get_universe_mut();
+            universe.particles[*particle].tides = Tides::new(**effect);
+            if let Err(e) = universe.particles[*particle].validate_uniform_viscosity_coefficient() {
+                fatal!("{}", e);
+            }
+            universe.calculate_love_number_tables(*particle); // New Kaula model of a particle with interior structure
+            universe.check_consistency();
+        },
+        EventAction::SetViscosity { particle, layer, viscosity } => {
+            if let Err(e) = universe_integrator.get_universe_mut().set_layer_viscosity(*particle, *layer, *viscosity) {
+                fatal!("{}", e);
+            }
+        },
+        EventAction::SetTimeStep { time_step } => {
+            universe_integrator.set_time_step(*time_step);
+        },
+        EventAction::SetHistoricSnapshotPeriod { historic_snapshot_period } => {
+            universe_integrator.set_snapshot_periods(*historic_snapshot_period, -1.);
+        },
+        EventAction::AddParticle { particle, orbital_elements } => {
+            if let Err(e) = add_particle(universe_integrator, **particle, *orbital_elements) {
+                fatal!("{}", e);
+            }
+        },
+        EventAction::RemoveParticle { particle } => {
+            if let Err(e) = remove_particle(universe_integrator, *particle) {
+                fatal!("{}", e);
+            }
+        },
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_sorted_and_reached_exactly() {
+        let mut event_timeline = EventTimeline::new(vec![
+            ScheduledEvent { time: 25., action: EventAction::SetTimeStep { time_step: 0.5 } },
+            ScheduledEvent { time: 10., action: EventAction::DisableEffect { effect: EventEffect::Disk } },
+            ScheduledEvent { time: 10., action: EventAction::SetHistoricSnapshotPeriod { historic_snapshot_period: 1. } },
+        ]);
+        assert_eq!(event_timeline.next_time(), Some(10.));
+        assert_eq!(event_timeline.next_time_within(8., 4.), Some(10.));
+        assert_eq!(event_timeline.next_time_within(4., 4.), None);
+        assert!(!event_timeline.is_due(9.));
+        let due_events = event_timeline.take_due_events(10.);
+        assert_eq!(due_events.len(), 2);
+        assert_eq!(due_events[0].action, EventAction::DisableEffect { effect: EventEffect::Disk });
+        assert_eq!(event_timeline.applied_events().len(), 2);
+        assert_eq!(event_timeline.next_time(), Some(25.));
+    }
+}
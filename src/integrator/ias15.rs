@@ -1,4 +1,3 @@
-extern crate time;
 use std;
 use std::io::{Write, BufWriter};
 use std::fs::File;
@@ -10,11 +9,15 @@ use super::super::particles::Universe;
 use super::super::particles::IgnoreGravityTerms;
 use super::super::effects::GeneralRelativityImplementation;
 use super::super::effects::EvolutionType;
-use super::output::{write_recovery_snapshot, write_historic_snapshot};
-use time::{OffsetDateTime, format_description};
+use super::output::{write_recovery_snapshot, backup_recovery_snapshot, sync_writer, write_historic_snapshot, is_accumulating_output_field, sample_output_streams, accumulate_output_streams, write_output_streams};
+use super::stream::{OutputStream, OutputField};
+use super::progress::{IntegratorStatistics, ConservationReference};
+use super::reconfigure::HistoryCounters;
+use super::events::{EventTimeline, apply_event};
+use super::stopping::StoppingConditions;
+use super::particle_set::ParticleSetChange;
+use super::snapshot::{IntegratorType, TaggedSnapshot};
 use std::path::Path;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
 use std::any::Any;
 
 
@@ -37,10 +40,11 @@ pub struct Ias15 {
     last_recovery_snapshot_time: f64,
     last_historic_snapshot_time: f64,
     pub n_historic_snapshots: usize,
-    pub hash: u64,
     //// Integrator IAS15 data:
     n_particles: usize,
     integrator_iterations_max_exceeded : i32,  // Count how many times the iteration did not converge
+    #[serde(skip)]
+    n_rejected_steps: u64, // Count how many steps were repeated with a smaller time step (since start/resume)
     time_step_last_success: f64,			// Last accepted timestep (corresponding to br and er)
     //#[serde(with = "BigArray")]
     //b: [[f64; 3*MAX_PARTICLES]; 7], // Coefficient b: acceleration dimension
@@ -225,15 +229,16 @@ pub struct Ias15 {
     #[serde(with = "BigArray")]
     css : [f64; 3*MAX_PARTICLES], // spin
     s: [f64; 9], // Summation coefficients
-}
-
-impl Hash for Ias15 {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        // Hash only works with certain types (for instance, it does not work with f64 values)
-        // thus we convert the whole integrator to a string thanks to the debug trait
-        // and we hash that value
-        format!("{:?}", self).hash(state); 
-    }
+    #[serde(default)]
+    pub event_timeline: EventTimeline, // Scheduled events (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub stopping_conditions: StoppingConditions, // Conditions to end the simulation early (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub particle_set_changes: Vec<ParticleSetChange>, // Particles added/removed during the simulation (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub output_streams: Vec<OutputStream>, // Additional historic outputs with their own schedule, fields and file (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub conservation_reference: Option<ConservationReference>, // Total energy and angular momentum at the start (kept last, see snapshot format version 1)
 }
 
 impl Ias15 {
@@ -246,12 +251,17 @@ impl Ias15 {
                     last_recovery_snapshot_time: -1.,
                     last_historic_snapshot_time: -1.,
                     n_historic_snapshots:0,
-                    hash: 0,
+                    event_timeline: EventTimeline::default(),
+                    stopping_conditions: StoppingConditions::default(),
+                    particle_set_changes: Vec::new(),
+                    output_streams: Vec::new(),
+                    conservation_reference: None,
                     universe:universe,
                     current_time:0.,
                     current_iteration:0,
                     n_particles:n_particles,
                     integrator_iterations_max_exceeded:0,
+                    n_rejected_steps:0,
                     time_step_last_success:0.,
                     //b :   [[0.; 3*MAX_PARTICLES]; 7],
                     b_0 :   [0.; 3*MAX_PARTICLES],
@@ -368,6 +378,82 @@ impl Integrator for Ias15 {
         self.current_time
     }
 
+    fn get_last_historic_snapshot_time(&self) -> f64 {
+        self.last_historic_snapshot_time
+    }
+
+    fn get_output_streams(&self) -> &[OutputStream] {
+        &self.output_streams
+    }
+
+    fn get_event_timeline(&self) -> &EventTimeline {
+        &self.event_timeline
+    }
+
+    fn get_stopping_conditions(&self) -> &StoppingConditions {
+        &self.stopping_conditions
+    }
+
+    fn get_particle_set_changes(&self) -> &[ParticleSetChange] {
+        &self.particle_set_changes
+    }
+
+    fn get_integrator_type(&self) -> IntegratorType {
+        self.integrator_type()
+    }
+
+    fn get_time_step(&self) -> f64 {
+        self.time_step
+    }
+
+    fn get_snapshot_periods(&self) -> (f64, f64) {
+        (self.historic_snapshot_period, self.recovery_snapshot_period)
+    }
+
+    fn get_statistics(&self) -> IntegratorStatistics {
+        IntegratorStatistics {
+            n_steps: self.current_iteration as u64,
+            time_step: self.time_step,
+            n_rejected_steps: self.n_rejected_steps,
+            n_iterations_max_exceeded: self.integrator_iterations_max_exceeded as u64,
+        }
+    }
+
+    fn get_conservation_reference(&self) -> Option<ConservationReference> {
+        self.conservation_reference
+    }
+
+    fn get_history_counters(&self) -> HistoryCounters {
+        HistoryCounters {
+            current_time: self.current_time,
+            current_iteration: self.current_iteration as u64,
+            last_recovery_snapshot_time: self.last_recovery_snapshot_time,
+            last_historic_snapshot_time: self.last_historic_snapshot_time,
+            n_historic_snapshots: self.n_historic_snapshots,
+            output_streams: self.output_streams.clone(),
+            event_timeline: self.event_timeline.clone(),
+            stopping_conditions: self.stopping_conditions.clone(),
+            particle_set_changes: self.particle_set_changes.clone(),
+            conservation_reference: self.conservation_reference,
+        }
+    }
+
+    fn get_universe(&self) -> &Universe {
+        &self.universe
+    }
+
+    fn get_universe_mut(&mut self) -> &mut Universe {
+        &mut self.universe
+    }
+
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn write_snapshot(&self, snapshot_path: &Path) -> Result<(), String> {
+        write_recovery_snapshot(snapshot_path, self)
+    }
+
     fn set_time_limit(&mut self, time_limit: f64) {
         if time_limit > 0. && self.universe.time_limit != time_limit {
             if time_limit > self.universe.time_limit && self.universe.consider_effects.evolution {
@@ -386,27 +472,52 @@ impl Integrator for Ias15 {
                     panic!("Your new time limit ({} days) is smaller than the current time ({} days)", time_limit, self.current_time);
                 }
             }
-            println!("[INFO {} UTC] The time limit changed from {} to {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.universe.time_limit, time_limit);
+            log_info!("The time limit changed from {} to {} days", self.universe.time_limit, time_limit);
             self.universe.time_limit = time_limit;
         }
     }
 
     fn set_snapshot_periods(&mut self, historic_snapshot_period: f64, recovery_snapshot_period: f64) {
         if historic_snapshot_period > 0. && self.historic_snapshot_period != historic_snapshot_period {
-            println!("[INFO {} UTC] The historic snapshot period changed from {} to {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.historic_snapshot_period, historic_snapshot_period);
+            log_info!("The historic snapshot period changed from {} to {} days", self.historic_snapshot_period, historic_snapshot_period);
             self.historic_snapshot_period = historic_snapshot_period;
         } else {
-            println!("[INFO {} UTC] A historic snapshot will be saved every {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.historic_snapshot_period);
+            log_info!("A historic snapshot will be saved every {} days", self.historic_snapshot_period);
         }
         
         if recovery_snapshot_period > 0. && self.recovery_snapshot_period != recovery_snapshot_period {
-            println!("[INFO {} UTC] The recovery snapshot period changed from {} to {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.recovery_snapshot_period, recovery_snapshot_period);
+            log_info!("The recovery snapshot period changed from {} to {} days", self.recovery_snapshot_period, recovery_snapshot_period);
             self.recovery_snapshot_period = recovery_snapshot_period;
         } else {
-            println!("[INFO {} UTC] A recovery snapshot will be saved every {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.recovery_snapshot_period);
+            log_info!("A recovery snapshot will be saved every {} days", self.recovery_snapshot_period);
         }
     }
 
+    fn set_time_step(&mut self, time_step: f64) {
+        // The adaptive time step will change it again if the required precision is not reached
+        if time_step > 0. && self.time_step != time_step {
+            log_info!("The time step changed from {} to {} days", self.time_step, time_step);
+            self.time_step = time_step;
+        }
+    }
+
+    fn set_conservation_reference(&mut self, conservation_reference: ConservationReference) {
+        self.conservation_reference = Some(conservation_reference);
+    }
+
+    fn set_history_counters(&mut self, history_counters: HistoryCounters) {
+        self.current_time = history_counters.current_time;
+        self.current_iteration = history_counters.current_iteration as u32;
+        self.last_recovery_snapshot_time = history_counters.last_recovery_snapshot_time;
+        self.last_historic_snapshot_time = history_counters.last_historic_snapshot_time;
+        self.n_historic_snapshots = history_counters.n_historic_snapshots;
+        self.output_streams = history_counters.output_streams;
+        self.event_timeline = history_counters.event_timeline;
+        self.stopping_conditions = history_counters.stopping_conditions;
+        self.particle_set_changes = history_counters.particle_set_changes;
+        self.conservation_reference = history_counters.conservation_reference;
+    }
+
     fn initialize_physical_values(&mut self) {
         if self.current_time != 0. {
             panic!("Physical values cannot be initialized on a resumed simulation");
@@ -414,28 +525,75 @@ impl Integrator for Ias15 {
         let evolution = true;
         self.universe.calculate_spin_and_evolving_quantities(self.current_time, evolution); // Make sure we start with the good initial values
         self.universe.calculate_roche_radiuses(); // Needed for collision detection
+        self.universe.calculate_all_love_number_tables(); // Kaula tables of the particles defined by their interior structure
     }
 
 
-    fn iterate(&mut self, universe_history_writer: &mut BufWriter<File>, silent_mode: bool) -> Result<bool, String> {
+    fn reset_internal_state(&mut self) {
+        // Predictor-corrector coefficients (b, g, e...) of the previous particle set are
+        // meaningless, start again from scratch as in a new simulation (but keeping the
+        // universe and the history counters). The universe clone downgrades the evolvers,
+        // but it is replaced by the original one.
+        let mut universe_integrator = Ias15::new(self.time_step, self.recovery_snapshot_period, self.historic_snapshot_period, self.universe.clone());
+        std::mem::swap(&mut universe_integrator.universe, &mut self.universe);
+        universe_integrator.set_history_counters(self.get_history_counters());
+        universe_integrator.integrator_iterations_max_exceeded = self.integrator_iterations_max_exceeded;
+        universe_integrator.n_rejected_steps = self.n_rejected_steps;
+        *self = universe_integrator;
+    }
+
+    fn iterate(&mut self, universe_history_writer: &mut BufWriter<File>, output_stream_writers: &mut [BufWriter<File>], silent_mode: bool) -> Result<bool, String> {
+        // Stopped simulations do not advance (see StoppingConditions::clear_stop_reason)
+        if let Some(stop_reason) = self.stopping_conditions.stop_reason {
+            return Err(stop_reason.to_string());
+        }
+        // Scheduled events (applied before the output to record their effect in the history)
+        let due_events = self.event_timeline.take_due_events(self.current_time);
+        for event in due_events.iter() {
+            apply_event(self, event);
+        }
+        let event_trigger = !due_events.is_empty();
+
         // Output
         let first_snapshot_trigger = self.last_historic_snapshot_time < 0.;
         let historic_snapshot_time_trigger = self.last_historic_snapshot_time + self.historic_snapshot_period <= self.current_time;
         let recovery_snapshot_time_trigger = self.last_recovery_snapshot_time + self.recovery_snapshot_period <= self.current_time;
-        if first_snapshot_trigger || historic_snapshot_time_trigger {
+        let output_stream_time_trigger = self.output_streams.iter().any(|output_stream| output_stream.is_due(self.current_time));
+        let output_stream_averaging_trigger = self.output_streams.iter().any(|output_stream| output_stream.is_accumulating(self.current_time));
+        let output_snapshot_trigger = first_snapshot_trigger || historic_snapshot_time_trigger || event_trigger || output_stream_time_trigger;
+        if output_snapshot_trigger {
             self.universe.inertial_to_heliocentric();
             let evolution = true;
             self.universe.calculate_spin_and_evolving_quantities(self.current_time, evolution);
             if self.universe.consider_effects.tides {
                 self.universe.calculate_denergy_dt();
             }
-            write_historic_snapshot(universe_history_writer, &self.universe, self.current_time, self.time_step);
-            self.last_historic_snapshot_time = self.current_time;
-            self.n_historic_snapshots += 1;
-            let current_time_years = self.current_time/365.25;
-            if ! silent_mode {
-                print!("Year: {:0.0} ({:0.1e}) | Time step: {:0.3} days                    \r", current_time_years, current_time_years, self.time_step);
-                let _ = std::io::stdout().flush();
+        }
+        let step_start_time = self.current_time;
+        let mut output_stream_samples = Vec::new();
+        if output_stream_averaging_trigger {
+            // Averaged streams sample every time step (the sample taken when a snapshot is written belongs to the
+            // next interval) from the integrated state, only the dissipated power has to be computed when it is not synchronized
+            if !output_snapshot_trigger && self.universe.consider_effects.tides && is_accumulating_output_field(&self.output_streams, &OutputField::DenergyDt, self.current_time) {
+                self.universe.calculate_denergy_dt();
+            }
+            output_stream_samples = sample_output_streams(&self.output_streams, &self.universe, self.current_time);
+        }
+        if output_snapshot_trigger {
+            if first_snapshot_trigger || historic_snapshot_time_trigger || event_trigger {
+                write_historic_snapshot(universe_history_writer, &self.universe, self.current_time, self.time_step);
+                if first_snapshot_trigger || historic_snapshot_time_trigger {
+                    self.last_historic_snapshot_time = self.current_time;
+                }
+                self.n_historic_snapshots += 1;
+                let current_time_years = self.current_time/365.25;
+                if ! silent_mode {
+                    print!("Year: {:0.0} ({:0.1e}) | Time step: {:0.3} days                    \r", current_time_years, current_time_years, self.time_step);
+                    let _ = std::io::stdout().flush();
+                }
+            }
+            if output_stream_time_trigger {
+                write_output_streams(&mut self.output_streams, output_stream_writers, &self.universe, self.current_time, self.time_step);
             }
         }
 
@@ -451,26 +609,44 @@ impl Integrator for Ias15 {
         self.universe.calculate_additional_effects(self.current_time, evolution, dangular_momentum_dt, accelerations, ignored_gravity_terms);
         self.universe.apply_acceleration_corrections();
 
+        // Shorten the step to reach the next scheduled event exactly
+        let full_time_step = self.time_step;
+        let next_event_time = self.event_timeline.next_time_within(self.current_time, self.time_step);
+        let time_step_to_next_event = next_event_time.map(|next_event_time| next_event_time - self.current_time);
+        if let Some(time_step_to_next_event) = time_step_to_next_event {
+            self.time_step = time_step_to_next_event;
+        }
         self.integrator();
+        if let (Some(next_event_time), Some(time_step_to_next_event)) = (next_event_time, time_step_to_next_event) {
+            // A rejected step ends before the event, it will be reached in the next iterations
+            if self.time_step_last_success == time_step_to_next_event {
+                self.current_time = next_event_time; // Avoid round-off deviations
+                self.time_step = full_time_step;
+            }
+        }
+        accumulate_output_streams(&mut self.output_streams, output_stream_samples, self.current_time - step_start_time);
         self.current_iteration += 1;
 
         // Return
-        if self.current_time+self.time_step > self.universe.time_limit {
+        let historic_snapshot_written = first_snapshot_trigger || historic_snapshot_time_trigger || event_trigger;
+        if let Some(stop_reason) = self.stopping_conditions.evaluate_iteration(&self.universe, self.current_time, historic_snapshot_written) {
+            Err(stop_reason.to_string())
+        } else if self.current_time+self.time_step > self.universe.time_limit {
             Err("Simulation completed".to_string())
         } else {
             Ok(first_snapshot_trigger || recovery_snapshot_time_trigger)
         }
     }
 
-    fn write_recovery_snapshot(&mut self, snapshot_path: &Path, universe_history_writer: &mut BufWriter<File>) {
+    fn write_recovery_snapshot(&mut self, snapshot_path: &Path, universe_history_writer: &mut BufWriter<File>, output_stream_writers: &mut [BufWriter<File>]) -> Result<(), String> {
         self.last_recovery_snapshot_time = self.current_time;
-        universe_history_writer.flush().unwrap();
-        // Compute hash for this universe at this moment of time
-        let mut s = DefaultHasher::new();
-        self.hash = 0;
-        self.hash(&mut s);
-        self.hash = s.finish();
-        write_recovery_snapshot(&snapshot_path, &self);
+        // The history must be on disk before the recovery snapshot that refers to it
+        sync_writer(universe_history_writer)?;
+        for output_stream_writer in output_stream_writers.iter_mut() {
+            sync_writer(output_stream_writer)?;
+        }
+        write_recovery_snapshot(snapshot_path, &*self)?;
+        backup_recovery_snapshot(snapshot_path, self.current_time)
     }
 }
 
@@ -602,7 +778,7 @@ impl Ias15 {
                     self.integrator_iterations_max_exceeded += 1;
                     const INTEGRATOR_ITERATIONS_WARNING: i32 = 10;
                     if self.integrator_iterations_max_exceeded == INTEGRATOR_ITERATIONS_WARNING {
-                        println!("[WARNING {} UTC] At least {} predictor corrector loops in integrator IAS15 did not converge. This is typically an indication of the timestep being too large.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), INTEGRATOR_ITERATIONS_WARNING);
+                        log_warning!("At least {} predictor corrector loops in integrator IAS15 did not converge. This is typically an indication of the timestep being too large.", INTEGRATOR_ITERATIONS_WARNING);
                     }
                     break;								// Quit predictor corrector loop
                 }
@@ -1052,7 +1228,7 @@ impl Ias15 {
                                     predictor_corrector_error = self.max(maxb6ktmp, maxb6kstmp)/self.max(maxak, max_dangular_momentum_dtk);
                                 }
                             },
-                        _ => { println!("[WARNING {} UTC] This should not happen because the loop stops at 7!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap()); }
+                        _ => { log_warning!("This should not happen because the loop stops at 7!"); }
 
                     } // end match
                 } // end loop over interval using Gauss-Radau spacings
@@ -1215,6 +1391,7 @@ impl Ias15 {
                     }
                     
                     //println!("Step rejected, repeating with new timestep {}", self.time_step);
+                    self.n_rejected_steps += 1;
                     continue; // Step rejected. Do again. 
                 }
                 if (dt_new/dt_done).abs() > 1.0 {	// New timestep is larger.
@@ -0,0 +1,230 @@
+use std::fmt::Write;
+use serde_json::Value;
+use super::super::Integrator;
+use super::super::particles::Universe;
+use super::super::Particle;
+use super::super::constants::{R_SUN, PI, MAX_PARTICLES};
+use super::super::{TidesEffect, RotationalFlatteningEffect, RotationalFlatteningModel};
+use super::super::{GeneralRelativityEffect, DiskEffect, WindEffect, EvolutionType};
+use super::output::{calculate_keplerian_orbital_elements_around_reference, reference_particle_index};
+use super::snapshot::deserialize_integrator_from_json_value;
+use super::whfast::WHFast;
+use super::particle_set::{OrbitalElements, add_particle, remove_particle};
+
+////////////////////////////////////////////////////////////////////////////////
+//- Inspect
+////////////////////////////////////////////////////////////////////////////////
+
+/// Human readable summary of a snapshot: integrator, times, effects and particles
+pub fn inspect_snapshot(universe_integrator: &dyn Integrator) -> String {
+    let universe = universe_integrator.get_universe();
+    let (historic_snapshot_period, recovery_snapshot_period) = universe_integrator.get_snapshot_periods();
+    let mut summary = String::new();
+    writeln!(summary, "Integrator: {:?}", universe_integrator.get_integrator_type()).unwrap();
+    if let Some(whfast) = universe_integrator.as_any().downcast_ref::<WHFast>() {
+        writeln!(summary, "WHFast coordinates: {:?}", whfast.get_alternative_coordinates_type()).unwrap();
+    }
+    writeln!(summary, "Current time: {:.6e} days ({:.6e} years)", universe_integrator.get_current_time(), universe_integrator.get_current_time()/365.25).unwrap();
+    writeln!(summary, "Initial time: {:.6e} days ({:.6e} years)", universe.initial_time, universe.initial_time/365.25).unwrap();
+    writeln!(summary, "Time limit: {:.6e} days ({:.6e} years)", universe.time_limit, universe.time_limit/365.25).unwrap();
+    writeln!(summary, "Time step: {:.6e} days", universe_integrator.get_time_step()).unwrap();
+    writeln!(summary, "Historic snapshot period: {:.6e} days", historic_snapshot_period).unwrap();
+    writeln!(summary, "Recovery snapshot period: {:.6e} days", recovery_snapshot_period).unwrap();
+    writeln!(summary, "Historic snapshots written: {}", universe_integrator.get_n_historic_snapshots()).unwrap();
+    writeln!(summary, "Output streams: {}", universe_integrator.get_output_streams().iter().map(|output_stream| output_stream.name.clone()).collect::<Vec<String>>().join(", ")).unwrap();
+    let event_timeline = universe_integrator.get_event_timeline();
+    if !event_timeline.events.is_empty() {
+        writeln!(summary, "Scheduled events: {} ({} applied)", event_timeline.events.len(), event_timeline.applied_events().len()).unwrap();
+        for event in event_timeline.events.iter() {
+            writeln!(summary, "  {:.6e} days: {:?}", event.time, event.action).unwrap();
+        }
+    }
+    let stopping_conditions = universe_integrator.get_stopping_conditions();
+    if !stopping_conditions.conditions.is_empty() {
+        writeln!(summary, "Stopping conditions: {} (evaluated {:?}, wall time {:.1} seconds)", stopping_conditions.conditions.len(), stopping_conditions.evaluation, stopping_conditions.elapsed_wall_time).unwrap();
+        for condition in stopping_conditions.conditions.iter() {
+            writeln!(summary, "  {:?}", condition).unwrap();
+        }
+        if let Some(stop_reason) = stopping_conditions.stop_reason {
+            writeln!(summary, "Stopped: {} at {:.6e} days (value {:.6e}, exit code {})", stop_reason.condition.reason_code(), stop_reason.time, stop_reason.value, stop_reason.condition.exit_code()).unwrap();
+        }
+    }
+    let particle_set_changes = universe_integrator.get_particle_set_changes();
+    if !particle_set_changes.is_empty() {
+        writeln!(summary, "Particle set changes: {}", particle_set_changes.len()).unwrap();
+        for change in particle_set_changes.iter() {
+            writeln!(summary, "  {:.6e} days: {:?} ({} -> {} particles, from historic snapshot {})", change.time, change.action, change.previous_n_particles, change.n_particles, change.n_historic_snapshots).unwrap();
+        }
+    }
+    writeln!(summary, "Effects: tides={} rotational_flattening={} general_relativity={} ({:?}) disk={} wind={} evolution={}",
+             universe.consider_effects.tides, universe.consider_effects.rotational_flattening,
+             universe.consider_effects.general_relativity, universe.general_relativity_implementation,
+             universe.consider_effects.disk, universe.consider_effects.wind, universe.consider_effects.evolution).unwrap();
+    writeln!(summary, "Hosts: most_massive={} tides={} rotational_flattening={} general_relativity={} disk={}",
+             universe.hosts.index.most_massive, universe.hosts.index.tides, universe.hosts.index.rotational_flattening,
+             universe.hosts.index.general_relativity, universe.hosts.index.disk).unwrap();
+    if !universe.tidal_pairs.is_empty() {
+        writeln!(summary, "Tidal pairs: {}", universe.tidal_pairs.iter().map(|tidal_pair| format!("{}-{}", tidal_pair.host, tidal_pair.orbiting)).collect::<Vec<String>>().join(", ")).unwrap();
+    }
+    if !universe.interior_structures.is_empty() {
+        writeln!(summary, "Interior structures: {}", universe.interior_structures.iter().map(|interior_structure| format!("{} ({} layers)", interior_structure.particle, interior_structure.layers.len())).collect::<Vec<String>>().join(", ")).unwrap();
+    }
+    if !universe.love_number_tables.is_empty() {
+        writeln!(summary, "Love number tables: {}", universe.love_number_tables.iter().map(|love_number_table| format!("{} ({} points, {:?})", love_number_table.particle, love_number_table.data().excitation_frequency.len(), love_number_table.interpolation)).collect::<Vec<String>>().join(", ")).unwrap();
+    }
+    writeln!(summary, "Particles: {}", universe.n_particles).unwrap();
+    writeln!(summary, "{:>4} {:>12} {:>12} {:>12} {:>10} {:>10} {:>12}  Effects",
+             "id", "mass [Msun]", "radius [Rsun]", "a [AU]", "e", "i [deg]", "spin P [d]").unwrap();
+    for particle in universe.particles[..universe.n_particles].iter() {
+        let (a, e, i) = calculate_orbit_around_reference(universe, particle);
+        let norm_spin = particle.norm_spin_vector_2.sqrt();
+        let spin_period = if norm_spin > 0. { 2.*PI/norm_spin } else { f64::INFINITY };
+        writeln!(summary, "{:>4} {:>12.6e} {:>13.6e} {:>12.6e} {:>10.6} {:>10.4} {:>12.6e}  {}",
+                 particle.id, particle.mass, particle.radius/R_SUN, a, e, i.to_degrees(), spin_period, particle_effects(particle)).unwrap();
+    }
+    summary
+}
+
+fn calculate_orbit_around_reference(universe: &Universe, particle: &Particle) -> (f64, f64, f64) {
+    if reference_particle_index(universe, particle) == particle.id {
+        (0., 0., 0.)
+    } else {
+        let (a, _, e, i, _, _, _, _) = calculate_keplerian_orbital_elements_around_reference(universe, particle);
+        (a, e, i)
+    }
+}
+
+fn particle_effects(particle: &Particle) -> String {
+    let mut effects = Vec::new();
+    match particle.tides.effect {
+        TidesEffect::CentralBody(tidal_model) => effects.push(format!("tides=central({})", tidal_model.response().name())),
+        TidesEffect::OrbitingBody(tidal_model) => effects.push(format!("tides=orbiting({})", tidal_model.response().name())),
+        TidesEffect::Disabled => {},
+    }
+    match particle.rotational_flattening.effect {
+        RotationalFlatteningEffect::CentralBody(model) => effects.push(format!("rotational_flattening=central({})", rotational_flattening_model_name(&model))),
+        RotationalFlatteningEffect::OrbitingBody(model) => effects.push(format!("rotational_flattening=orbiting({})", rotational_flattening_model_name(&model))),
+        RotationalFlatteningEffect::Disabled => {},
+    }
+    match particle.general_relativity.effect {
+        GeneralRelativityEffect::CentralBody(implementation) => effects.push(format!("general_relativity=central({:?})", implementation)),
+        GeneralRelativityEffect::OrbitingBody => effects.push("general_relativity=orbiting".to_string()),
+        GeneralRelativityEffect::Disabled => {},
+    }
+    match particle.disk.effect {
+        DiskEffect::CentralBody(_) => effects.push("disk=central".to_string()),
+        DiskEffect::OrbitingBody => effects.push("disk=orbiting".to_string()),
+        DiskEffect::Disabled => {},
+    }
+    if let WindEffect::Interaction = particle.wind.effect {
+        effects.push("wind".to_string());
+    }
+    match particle.evolution {
+        EvolutionType::NonEvolving => {},
+        evolution => effects.push(format!("evolution={:?}", evolution)),
+    }
+    if effects.is_empty() {
+        "-".to_string()
+    } else {
+        effects.join(" ")
+    }
+}
+
+fn rotational_flattening_model_name(model: &RotationalFlatteningModel) -> &'static str {
+    match model {
+        RotationalFlatteningModel::OblateSpheroid(_) => "OblateSpheroid",
+        RotationalFlatteningModel::CreepCoplanar(_) => "CreepCoplanar",
+        RotationalFlatteningModel::Creep(_) => "Creep",
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//- Edit
+////////////////////////////////////////////////////////////////////////////////
+
+/// JSON Merge Patch (RFC 7386): objects are merged recursively, null removes
+/// the key and any other value replaces the target
+pub fn apply_json_merge_patch(target: &mut Value, patch: &Value) {
+    match patch {
+        Value::Object(patch_map) => {
+            if !target.is_object() {
+                *target = Value::Object(serde_json::Map::new());
+            }
+            let target_map = target.as_object_mut().unwrap();
+            for (key, value) in patch_map.iter() {
+                if value.is_null() {
+                    target_map.remove(key);
+                } else {
+                    apply_json_merge_patch(target_map.entry(key.clone()).or_insert(Value::Null), value);
+                }
+            }
+        },
+        _ => *target = patch.clone(),
+    }
+}
+
+/// Apply a JSON merge patch to the serialized integrator and rebuild it, the
+/// universe is validated and its consistency checks are executed again (e.g.,
+/// hosts are re-computed)
+pub fn edit_snapshot(universe_integrator: &dyn Integrator, patch: &Value) -> Result<Box<dyn Integrator>, String> {
+    let mut value = universe_integrator.to_json_value();
+    apply_json_merge_patch(&mut value, patch);
+    // The time step is changed through the integrator to update the values derived from it (e.g., half time step)
+    let time_step = value.get("time_step").cloned().unwrap_or(Value::Null);
+    let time_step = time_step.as_f64().ok_or(format!("Invalid time step: {}", time_step))?;
+    if time_step <= 0. {
+        return Err(format!("Invalid time step: {} (it must be positive)", time_step));
+    }
+    value["time_step"] = Value::from(universe_integrator.get_time_step());
+    let mut edited_universe_integrator = deserialize_integrator_from_json_value(universe_integrator.get_integrator_type(), value)?;
+    let n_particles = edited_universe_integrator.get_universe().n_particles;
+    if n_particles > MAX_PARTICLES {
+        return Err(format!("Invalid number of particles: {} (only {} bodies are allowed)", n_particles, MAX_PARTICLES));
+    }
+    edited_universe_integrator.set_time_step(time_step);
+    let report = edited_universe_integrator.get_universe_mut().validate_and_check_consistency(time_step);
+    for warning in report.warnings.iter() {
+        log_warning!("{}", warning);
+    }
+    if !report.is_valid() {
+        return Err(format!("Invalid edited snapshot: {}", report.errors.join("; ")));
+    }
+    Ok(edited_universe_integrator)
+}
+
+/// Add a particle to a snapshot, the description contains the particle and optionally
+/// its orbital elements (e.g., '{"particle": {...}, "orbital_elements": {...}}')
+pub fn add_particle_to_snapshot(universe_integrator: &dyn Integrator, description: &Value) -> Result<Box<dyn Integrator>, String> {
+    let particle: Particle = match description.get("particle") {
+        Some(particle) => serde_json::from_value(particle.clone()).map_err(|e| format!("Invalid particle: {}", e))?,
+        None => return Err("Missing particle".to_string()),
+    };
+    let orbital_elements: Option<OrbitalElements> = match description.get("orbital_elements") {
+        Some(orbital_elements) => serde_json::from_value(orbital_elements.clone()).map_err(|e| format!("Invalid orbital elements: {}", e))?,
+        None => None,
+    };
+    let mut edited_universe_integrator = deserialize_integrator_from_json_value(universe_integrator.get_integrator_type(), universe_integrator.to_json_value())?;
+    add_particle(edited_universe_integrator.as_mut(), particle, orbital_elements)?;
+    Ok(edited_universe_integrator)
+}
+
+pub fn remove_particle_from_snapshot(universe_integrator: &dyn Integrator, index: usize) -> Result<Box<dyn Integrator>, String> {
+    let mut edited_universe_integrator = deserialize_integrator_from_json_value(universe_integrator.get_integrator_type(), universe_integrator.to_json_value())?;
+    remove_particle(edited_universe_integrator.as_mut(), index)?;
+    Ok(edited_universe_integrator)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_merge_patch() {
+        let mut target: Value = serde_json::from_str(r#"{"a": "b", "c": {"d": "e", "f": "g"}, "h": [1, 2]}"#).unwrap();
+        let patch: Value = serde_json::from_str(r#"{"a": "z", "c": {"f": null}, "h": [3]}"#).unwrap();
+        apply_json_merge_patch(&mut target, &patch);
+        let expected: Value = serde_json::from_str(r#"{"a": "z", "c": {"d": "e"}, "h": [3]}"#).unwrap();
+        assert_eq!(target, expected);
+    }
+}
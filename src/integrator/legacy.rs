@@ -0,0 +1,639 @@
+// Frozen layout of the integrators stored in binary recovery snapshots of format version 0 (no
+// envelope). Binary snapshots are not self-describing, thus they can only be read with the exact
+// definitions that were used to write them (even a new enum variant in the middle of an enum
+// changes them). These definitions must never be modified: version 0 snapshots are deserialized
+// with them, upgraded piece by piece and serialized again with the layout of the current version,
+// which is then read with the current integrator definitions (hence every value is preserved bit
+// by bit, including non-finite values that JSON cannot represent).
+//
+// Both layouts share the same structures, the pieces that changed are generic parameters:
+//  - P: particles (the Love number tables of the Kaula model were moved to the universe)
+//  - X: fields appended at the end of the universe (unit, i.e. zero bytes, in version 0)
+//  - T: fields appended at the end of the integrator (unit in version 0)
+//  - H: hash of the debug representation of the integrator stored with the snapshot counters (only
+//       in version 0, it was never verified)
+// The current layout is also expressed with these structures (see `Ias15Current` and the others).
+use std::collections::HashMap;
+use serde::{Serialize, Deserialize};
+use serde::de::DeserializeOwned;
+use serde_big_array::BigArray;
+use super::Integrator;
+use super::snapshot::{IntegratorType, deserialize_integrator_from_bin};
+use super::stream::OutputStream;
+use super::events::EventTimeline;
+use super::stopping::StoppingConditions;
+use super::particle_set::ParticleSetChange;
+use super::progress::ConservationReference;
+use super::whfast::{AlternativeCoordinates, CoordinatesType};
+use super::super::constants::MAX_PARTICLES;
+use super::super::particles::{Particle, Axes, Reference, ConsiderEffects};
+use super::super::particles::universe::Hosts;
+use super::super::effects::{Evolver, EvolutionType, GeneralRelativityImplementation, InteriorStructure, LoveNumberTable, LoveNumberData, LoveNumberInterpolation};
+use super::super::effects::{Tides, TidesEffect, TidalModel, TidalPair, RotationalFlattening, GeneralRelativity, Wind, Disk};
+use super::super::effects::{ConstantTimeLagParameters, CreepCoplanarParameters, KaulaParameters};
+use super::super::effects::tides::common::{TidesParticleParameters, TidesParticleCoordinates};
+
+////////////////////////////////////////////////////////////////////////////////
+//- Tides
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum TidalModelV0 {
+    ConstantTimeLag(ConstantTimeLagParameters),
+    CreepCoplanar(CreepCoplanarParameters),
+    Kaula(Box<KaulaParametersV0>),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KaulaParametersV0 {
+    #[serde(with = "BigArray")]
+    love_number_excitation_frequency: [f64; 32 * 32],
+    #[serde(with = "BigArray")]
+    real_part_love_number: [f64; 32 * 32],
+    #[serde(with = "BigArray")]
+    imaginary_part_love_number: [f64; 32 * 32],
+    num_datapoints: f64,
+    polynomials: PolynomialsV0,
+    kaula_tidal_force: Axes,
+}
+
+// Not used since the Kaula functions are computed for the required degree and tolerance
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PolynomialsV0 {
+    eccentricity_function_g_2pq: [[f64; 15]; 3],
+    eccentricity_function_g_20q: [f64; 15],
+    eccentricity_function_g_21q: [f64; 15],
+    eccentricity_function_g_3pq: [[f64; 15]; 4],
+    inclination_function_f_20p: [f64; 3],
+    inclination_function_f_21p: [f64; 3],
+    inclination_function_f_22p: [f64; 3],
+    inclination_function_f_30p: [f64; 4],
+    inclination_function_f_31p: [f64; 4],
+    inclination_function_f_32p: [f64; 4],
+    inclination_function_f_33p: [f64; 4],
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum TidesEffectV0 {
+    CentralBody(TidalModelV0),
+    OrbitingBody(TidalModelV0),
+    Disabled,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TidesV0 {
+    effect: TidesEffectV0,
+    parameters: TidesParticleParameters,
+    coordinates: TidesParticleCoordinates,
+}
+
+impl TidalModelV0 {
+    fn love_number_data(&self) -> Option<LoveNumberData> {
+        match self {
+            TidalModelV0::Kaula(params) => LoveNumberData::from_fixed_size_tables(&params.love_number_excitation_frequency, &params.real_part_love_number, &params.imaginary_part_love_number),
+            _ => None,
+        }
+    }
+}
+
+impl From<TidalModelV0> for TidalModel {
+    fn from(tidal_model: TidalModelV0) -> TidalModel {
+        match tidal_model {
+            TidalModelV0::ConstantTimeLag(params) => TidalModel::ConstantTimeLag(params),
+            TidalModelV0::CreepCoplanar(params) => TidalModel::CreepCoplanar(params),
+            TidalModelV0::Kaula(_) => TidalModel::Kaula(KaulaParameters {}),
+        }
+    }
+}
+
+impl TidesEffectV0 {
+    fn love_number_data(&self) -> Option<LoveNumberData> {
+        match self {
+            TidesEffectV0::CentralBody(tidal_model) | TidesEffectV0::OrbitingBody(tidal_model) => tidal_model.love_number_data(),
+            TidesEffectV0::Disabled => None,
+        }
+    }
+}
+
+impl From<TidesEffectV0> for TidesEffect {
+    fn from(effect: TidesEffectV0) -> TidesEffect {
+        match effect {
+            TidesEffectV0::CentralBody(tidal_model) => TidesEffect::CentralBody(tidal_model.into()),
+            TidesEffectV0::OrbitingBody(tidal_model) => TidesEffect::OrbitingBody(tidal_model.into()),
+            TidesEffectV0::Disabled => TidesEffect::Disabled,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//- Universe
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParticleV0 {
+    id: usize,
+    mass: f64,
+    mass_g: f64,
+    radius: f64,
+    inertial_position: Axes,
+    inertial_velocity: Axes,
+    inertial_acceleration: Axes,
+    inertial_additional_acceleration: Axes,
+    heliocentric_position: Axes,
+    heliocentric_velocity: Axes,
+    heliocentric_distance: f64,
+    heliocentric_radial_velocity: f64,
+    heliocentric_norm_velocity_vector: f64,
+    heliocentric_norm_velocity_vector_2: f64,
+    spin: Axes,
+    norm_spin_vector_2: f64,
+    angular_momentum: Axes,
+    dangular_momentum_dt: Axes,
+    radius_of_gyration_2: f64,
+    moment_of_inertia: f64,
+    reference: Reference,
+    tides: TidesV0,
+    rotational_flattening: RotationalFlattening,
+    general_relativity: GeneralRelativity,
+    wind: Wind,
+    disk: Disk,
+    evolution: EvolutionType,
+}
+
+impl From<ParticleV0> for Particle {
+    fn from(particle: ParticleV0) -> Particle {
+        Particle {
+            id: particle.id,
+            mass: particle.mass,
+            mass_g: particle.mass_g,
+            radius: particle.radius,
+            inertial_position: particle.inertial_position,
+            inertial_velocity: particle.inertial_velocity,
+            inertial_acceleration: particle.inertial_acceleration,
+            inertial_additional_acceleration: particle.inertial_additional_acceleration,
+            heliocentric_position: particle.heliocentric_position,
+            heliocentric_velocity: particle.heliocentric_velocity,
+            heliocentric_distance: particle.heliocentric_distance,
+            heliocentric_radial_velocity: particle.heliocentric_radial_velocity,
+            heliocentric_norm_velocity_vector: particle.heliocentric_norm_velocity_vector,
+            heliocentric_norm_velocity_vector_2: particle.heliocentric_norm_velocity_vector_2,
+            spin: particle.spin,
+            norm_spin_vector_2: particle.norm_spin_vector_2,
+            angular_momentum: particle.angular_momentum,
+            dangular_momentum_dt: particle.dangular_momentum_dt,
+            radius_of_gyration_2: particle.radius_of_gyration_2,
+            moment_of_inertia: particle.moment_of_inertia,
+            reference: particle.reference,
+            tides: Tides {
+                effect: particle.tides.effect.into(),
+                parameters: particle.tides.parameters,
+                coordinates: particle.tides.coordinates,
+            },
+            rotational_flattening: particle.rotational_flattening,
+            general_relativity: particle.general_relativity,
+            wind: particle.wind,
+            disk: particle.disk,
+            evolution: particle.evolution,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(bound(serialize = "P: Serialize, X: Serialize", deserialize = "P: Deserialize<'de>, X: Deserialize<'de>"))]
+pub struct UniverseV0<P, X> {
+    initial_time: f64,
+    time_limit: f64,
+    #[serde(with = "BigArray")]
+    particles: [P; MAX_PARTICLES],
+    particles_evolvers: Vec<Evolver>,
+    n_particles: usize,
+    consider_effects: ConsiderEffects,
+    general_relativity_implementation: GeneralRelativityImplementation,
+    hosts: Hosts,
+    pair_dependent_scaled_dissipation_factor: HashMap<usize, f64>,
+    #[serde(with = "BigArray")]
+    roche_radiuses: [f64; MAX_PARTICLES * MAX_PARTICLES],
+    extensions: X,
+}
+
+/// Fields appended at the end of the universe since version 1
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UniverseExtensions {
+    tidal_pairs: Vec<TidalPair>,
+    interior_structures: Vec<InteriorStructure>,
+    love_number_tables: Vec<LoveNumberTable>,
+}
+
+/// The fixed-size Love number tables of the Kaula models (version 0) become the tables of the
+/// particles, given in order of precedence: the tables that the universe already has take
+/// precedence, and the tables of the particles over the ones of the tidal pairs (which are copies)
+pub fn add_migrated_love_number_tables(love_number_tables: &mut Vec<LoveNumberTable>, interior_structures: &[usize], love_number_data: Vec<(usize, LoveNumberData)>) {
+    for (index, love_number_data) in love_number_data {
+        if love_number_tables.iter().any(|love_number_table| love_number_table.particle == index) {
+            continue;
+        }
+        let love_number_table = if interior_structures.contains(&index) {
+            LoveNumberTable::from_interior_structure(index, love_number_data)
+        } else {
+            LoveNumberTable::new(index, love_number_data, LoveNumberInterpolation::Linear)
+        };
+        love_number_tables.push(love_number_table);
+    }
+}
+
+impl UniverseV0<ParticleV0, ()> {
+    fn upgrade(self) -> UniverseV0<Particle, UniverseExtensions> {
+        let love_number_data: Vec<(usize, LoveNumberData)> = self.particles[..self.n_particles].iter().enumerate()
+            .filter_map(|(index, particle)| particle.tides.effect.love_number_data().map(|love_number_data| (index, love_number_data)))
+            .collect();
+        let mut love_number_tables = Vec::new();
+        add_migrated_love_number_tables(&mut love_number_tables, &[], love_number_data);
+        UniverseV0 {
+            initial_time: self.initial_time,
+            time_limit: self.time_limit,
+            particles: self.particles.map(|particle| particle.into()),
+            particles_evolvers: self.particles_evolvers,
+            n_particles: self.n_particles,
+            consider_effects: self.consider_effects,
+            general_relativity_implementation: self.general_relativity_implementation,
+            hosts: self.hosts,
+            pair_dependent_scaled_dissipation_factor: self.pair_dependent_scaled_dissipation_factor,
+            roche_radiuses: self.roche_radiuses,
+            extensions: UniverseExtensions { tidal_pairs: Vec::new(), interior_structures: Vec::new(), love_number_tables },
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//- Integrators
+////////////////////////////////////////////////////////////////////////////////
+
+/// Fields appended at the end of the integrators since version 1
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Trailing {
+    event_timeline: EventTimeline,
+    stopping_conditions: StoppingConditions,
+    particle_set_changes: Vec<ParticleSetChange>,
+    output_streams: Vec<OutputStream>,
+    conservation_reference: Option<ConservationReference>,
+}
+
+/// Snapshot counters, which are the same for all the integrators (except for the type of the iteration counter)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CountersV0<I, H> {
+    current_time: f64,
+    current_iteration: I,
+    recovery_snapshot_period: f64,
+    historic_snapshot_period: f64,
+    last_recovery_snapshot_time: f64,
+    last_historic_snapshot_time: f64,
+    n_historic_snapshots: usize,
+    hash: H,
+}
+
+impl<I> CountersV0<I, u64> {
+    fn upgrade(self) -> CountersV0<I, ()> {
+        CountersV0 {
+            current_time: self.current_time,
+            current_iteration: self.current_iteration,
+            recovery_snapshot_period: self.recovery_snapshot_period,
+            historic_snapshot_period: self.historic_snapshot_period,
+            last_recovery_snapshot_time: self.last_recovery_snapshot_time,
+            last_historic_snapshot_time: self.last_historic_snapshot_time,
+            n_historic_snapshots: self.n_historic_snapshots,
+            hash: (),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WHFastV0<P, X, T, H> {
+    time_step: f64,
+    half_time_step: f64,
+    universe: UniverseV0<P, X>,
+    counters: CountersV0<usize, H>,
+    state: WHFastStateV0,
+    trailing: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WHFastStateV0 {
+    #[serde(with = "BigArray")]
+    particles_alternative_coordinates: [AlternativeCoordinates; MAX_PARTICLES],
+    alternative_coordinates_type: CoordinatesType,
+    timestep_warning: usize,
+    #[serde(with = "BigArray")]
+    inertial_velocity_errors: [Axes; MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    particle_angular_momentum_errors: [Axes; MAX_PARTICLES],
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeapFrogV0<P, X, T, H> {
+    time_step: f64,
+    half_time_step: f64,
+    universe: UniverseV0<P, X>,
+    counters: CountersV0<u32, H>,
+    trailing: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Ias15V0<P, X, T, H> {
+    time_step: f64,
+    universe: UniverseV0<P, X>,
+    counters: CountersV0<u32, H>,
+    state: Ias15StateV0,
+    trailing: T,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Ias15StateV0 {
+    n_particles: usize,
+    integrator_iterations_max_exceeded: i32,
+    time_step_last_success: f64,
+    #[serde(with = "BigArray")]
+    b_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    b_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    b_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    b_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    b_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    b_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    b_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    br_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    br_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    br_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    br_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    br_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    br_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    br_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    g_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    g_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    g_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    g_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    g_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    g_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    g_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    e_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    e_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    e_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    e_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    e_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    e_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    e_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    er_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    er_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    er_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    er_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    er_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    er_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    er_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    at: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    x0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    v0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    a0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sb_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sb_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sb_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sb_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sb_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sb_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sb_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sbr_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sbr_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sbr_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sbr_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sbr_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sbr_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sbr_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sg_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sg_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sg_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sg_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sg_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sg_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    sg_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    se_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    se_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    se_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    se_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    se_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    se_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    se_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    ser_0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    ser_1: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    ser_2: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    ser_3: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    ser_4: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    ser_5: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    ser_6: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    dangular_momentum_dtt: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    dangular_momentum_dt0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    angular_momentum0: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    csx: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    csv: [f64; 3*MAX_PARTICLES],
+    #[serde(with = "BigArray")]
+    css: [f64; 3*MAX_PARTICLES],
+    s: [f64; 9],
+}
+
+// Version 0 layouts
+type WHFastLegacy = WHFastV0<ParticleV0, (), (), u64>;
+type LeapFrogLegacy = LeapFrogV0<ParticleV0, (), (), u64>;
+type Ias15Legacy = Ias15V0<ParticleV0, (), (), u64>;
+
+// Current layouts
+type WHFastCurrent = WHFastV0<Particle, UniverseExtensions, Trailing, ()>;
+type LeapFrogCurrent = LeapFrogV0<Particle, UniverseExtensions, Trailing, ()>;
+type Ias15Current = Ias15V0<Particle, UniverseExtensions, Trailing, ()>;
+
+/// Integrators of version 0 that can be upgraded to the current layout
+pub trait LegacyIntegrator: DeserializeOwned {
+    type Current: Serialize;
+    fn upgrade(self) -> Self::Current;
+}
+
+impl LegacyIntegrator for WHFastLegacy {
+    type Current = WHFastCurrent;
+    fn upgrade(self) -> WHFastCurrent {
+        WHFastV0 {
+            time_step: self.time_step,
+            half_time_step: self.half_time_step,
+            universe: self.universe.upgrade(),
+            counters: self.counters.upgrade(),
+            state: self.state,
+            trailing: Trailing::default(),
+        }
+    }
+}
+
+impl LegacyIntegrator for LeapFrogLegacy {
+    type Current = LeapFrogCurrent;
+    fn upgrade(self) -> LeapFrogCurrent {
+        LeapFrogV0 {
+            time_step: self.time_step,
+            half_time_step: self.half_time_step,
+            universe: self.universe.upgrade(),
+            counters: self.counters.upgrade(),
+            trailing: Trailing::default(),
+        }
+    }
+}
+
+impl LegacyIntegrator for Ias15Legacy {
+    type Current = Ias15Current;
+    fn upgrade(self) -> Ias15Current {
+        Ias15V0 {
+            time_step: self.time_step,
+            universe: self.universe.upgrade(),
+            counters: self.counters.upgrade(),
+            state: self.state,
+            trailing: Trailing::default(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//- Migration
+////////////////////////////////////////////////////////////////////////////////
+
+/// Integrator of a binary snapshot of an older format version upgraded to the current version
+pub fn migrate_legacy_bin_integrator(format_version: u32, integrator_type: IntegratorType, bytes: &[u8]) -> Result<Box<dyn Integrator>, String> {
+    match (format_version, integrator_type) {
+        (0, IntegratorType::WHFast) => migrate::<WHFastLegacy>(integrator_type, bytes),
+        (0, IntegratorType::Ias15) => migrate::<Ias15Legacy>(integrator_type, bytes),
+        (0, IntegratorType::LeapFrog) => migrate::<LeapFrogLegacy>(integrator_type, bytes),
+        _ => Err(format!("No frozen layout available for snapshot format version {} ({:?} integrator)", format_version, integrator_type)),
+    }
+}
+
+/// Version 0 snapshots do not have an envelope, the integrator is the only one whose frozen
+/// layout reads the whole snapshot (trailing bytes are not accepted)
+pub fn guess_legacy_bin_integrator_type(bytes: &[u8]) -> Option<IntegratorType> {
+    [IntegratorType::WHFast, IntegratorType::Ias15, IntegratorType::LeapFrog].iter().copied().find(|integrator_type| {
+        match integrator_type {
+            IntegratorType::WHFast => deserialize_exact::<WHFastLegacy>(bytes).is_ok(),
+            IntegratorType::Ias15 => deserialize_exact::<Ias15Legacy>(bytes).is_ok(),
+            IntegratorType::LeapFrog => deserialize_exact::<LeapFrogLegacy>(bytes).is_ok(),
+        }
+    })
+}
+
+fn deserialize_exact<I: DeserializeOwned>(bytes: &[u8]) -> Result<I, bincode::Error> {
+    use bincode::Options;
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+        .deserialize(bytes)
+}
+
+fn migrate<I: LegacyIntegrator>(integrator_type: IntegratorType, bytes: &[u8]) -> Result<Box<dyn Integrator>, String> {
+    let integrator: I = deserialize_exact(bytes).map_err(|why| format!("Couldn't deserialize {:?} integrator: {}", integrator_type, why))?;
+    let upgraded_bytes = bincode::serialize(&integrator.upgrade()).unwrap();
+    deserialize_integrator_from_bin(integrator_type, &mut upgraded_bytes.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use super::*;
+    use super::super::{WHFast, Ias15, LeapFrog};
+    use super::super::output::restore_snapshot;
+
+    fn assert_current_layout<I: Serialize, C: Serialize + DeserializeOwned>(universe_integrator: &I) {
+        let bytes = bincode::serialize(universe_integrator).unwrap();
+        let current: C = deserialize_exact(&bytes).unwrap();
+        assert_eq!(bincode::serialize(&current).unwrap(), bytes);
+    }
+
+    #[test]
+    fn current_layouts_match_the_integrators() {
+        for case in ["ias15", "leapfrog", "whfast_jacobi"].iter() {
+            let universe_integrator = restore_snapshot(Path::new(&format!("tests/data/test_integrator-{}/case.json", case))).unwrap();
+            if let Some(universe_integrator) = universe_integrator.as_any().downcast_ref::<WHFast>() {
+                assert_current_layout::<WHFast, WHFastCurrent>(universe_integrator);
+            } else if let Some(universe_integrator) = universe_integrator.as_any().downcast_ref::<Ias15>() {
+                assert_current_layout::<Ias15, Ias15Current>(universe_integrator);
+            } else if let Some(universe_integrator) = universe_integrator.as_any().downcast_ref::<LeapFrog>() {
+                assert_current_layout::<LeapFrog, LeapFrogCurrent>(universe_integrator);
+            }
+        }
+    }
+}
@@ -1,16 +1,33 @@
 mod leapfrog;
 mod ias15;
+mod stream;
+mod snapshot;
+mod legacy;
 pub mod whfast;
 pub mod output;
+pub mod inspect;
+pub mod progress;
+pub mod reconfigure;
+pub mod events;
+pub mod stopping;
+pub mod particle_set;
 
 pub use self::leapfrog::*;
 pub use self::ias15::*;
 pub use self::whfast::WHFast;
+pub use self::stream::{OutputStream, OutputSchedule, OutputField, OutputAccumulator, validate_output_streams};
+pub use self::snapshot::{IntegratorType, TaggedSnapshot, SNAPSHOT_FORMAT_VERSION, content_hash};
+pub use self::progress::{IntegratorStatistics, ConservationReference, ProgressReporter};
+pub use self::reconfigure::{HistoryCounters, convert_integrator};
+pub use self::events::{EventTimeline, ScheduledEvent, EventAction, EventEffect};
+pub use self::stopping::{StoppingConditions, StoppingCondition, StoppingEvaluation, StopReason};
+pub use self::particle_set::{OrbitalElements, ParticleSetAction, ParticleSetChange};
 
 use std::io::{BufWriter};
 use std::fs::File;
 use std::path::Path;
 use std::any::Any;
+use super::particles::Universe;
 
 
 pub trait Integrator {
@@ -18,11 +35,29 @@ pub trait Integrator {
     fn get_n_historic_snapshots(&self) -> usize;
     fn get_n_particles(&self) -> usize;
     fn get_current_time(&self) -> f64;
+    fn get_last_historic_snapshot_time(&self) -> f64;
+    fn get_output_streams(&self) -> &[OutputStream];
+    fn get_event_timeline(&self) -> &EventTimeline;
+    fn get_stopping_conditions(&self) -> &StoppingConditions;
+    fn get_particle_set_changes(&self) -> &[ParticleSetChange];
+    fn get_integrator_type(&self) -> IntegratorType;
+    fn get_time_step(&self) -> f64;
+    fn get_snapshot_periods(&self) -> (f64, f64); // historic, recovery
+    fn get_statistics(&self) -> IntegratorStatistics;
+    fn get_conservation_reference(&self) -> Option<ConservationReference>;
+    fn get_history_counters(&self) -> HistoryCounters;
+    fn get_universe(&self) -> &Universe;
+    fn get_universe_mut(&mut self) -> &mut Universe;
+    fn to_json_value(&self) -> serde_json::Value;
+    fn write_snapshot(&self, snapshot_path: &Path) -> Result<(), String>; // Recovery snapshot without flushing the history
     fn set_time_limit(&mut self, time_limit: f64);
     fn set_snapshot_periods(&mut self, historic_snapshot_period: f64, recovery_snapshot_period: f64);
+    fn set_time_step(&mut self, time_step: f64);
+    fn set_history_counters(&mut self, history_counters: HistoryCounters);
+    fn set_conservation_reference(&mut self, conservation_reference: ConservationReference);
     fn initialize_physical_values(&mut self);
-    fn iterate(&mut self, universe_history_writer: &mut BufWriter<File>, silent_mode: bool) -> Result<bool, String>;
-    fn write_recovery_snapshot(&mut self, snapshot_path: &Path, universe_history_writer: &mut BufWriter<File>);
+    fn reset_internal_state(&mut self); // After adding/removing particles
+    fn iterate(&mut self, universe_history_writer: &mut BufWriter<File>, output_stream_writers: &mut [BufWriter<File>], silent_mode: bool) -> Result<bool, String>; // Err when the simulation ends (time limit or stopping condition)
+    fn write_recovery_snapshot(&mut self, snapshot_path: &Path, universe_history_writer: &mut BufWriter<File>, output_stream_writers: &mut [BufWriter<File>]) -> Result<(), String>;
 }
 
-
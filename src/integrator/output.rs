@@ -2,19 +2,26 @@ extern crate time;
 use std::fs::File;
 use std::fs::{OpenOptions};
 use std::io::{Write, BufWriter};
-use std::io::{Read, BufReader};
+use std::io::{Read, Seek, SeekFrom};
 use super::super::Integrator;
 use super::super::particles::Universe;
+use super::super::Particle;
 use super::super::particles::Reference;
-use super::super::{Axes, TidesEffect, TidalModel};
-use super::super::tools::calculate_keplerian_orbital_elements;
+use super::super::{Axes, TidesEffect};
+use super::super::tools::{calculate_keplerian_orbital_elements, calculate_inclination_orbital_equatorial_plane};
 use bincode;
-use time::{OffsetDateTime, format_description};
 use serde::{Serialize};
 use serde_json;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::fs;
 use super::super::constants::{MIN_ORBITAL_PERIOD_TIME_STEP_RATIO};
+use super::stream::{OutputStream, OutputField, validate_output_streams};
+use super::events::{ScheduledEvent, validate_events};
+use super::stopping::validate_stopping_conditions;
+use super::particle_set::{ParticleSetChange, expected_n_bytes, historic_snapshot_changes, output_stream_changes};
+use super::snapshot::{SNAPSHOT_FORMAT_VERSION, SNAPSHOT_MAGIC, IntegratorType, TaggedSnapshot, SnapshotHeader, JsonSnapshotEnvelope, content_hash, json_content_hash};
+use super::snapshot::{guess_json_integrator_type, guess_bin_integrator_type, migrate_json_snapshot, migrate_bin_snapshot, deserialize_integrator_from_json_value, serialize_integrator_to_bin};
+use serde_json::Value;
 
 pub use super::whfast::*;
 pub use super::ias15::*;
@@ -25,32 +32,138 @@ pub use super::leapfrog::*;
 //- Dump and restore functions
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn write_recovery_snapshot<I: Serialize>(snapshot_path: &Path, universe_integrator: &I) {
+/// Extension of a snapshot, which gives its format ('json' or binary otherwise)
+pub fn snapshot_extension(snapshot_path: &Path) -> Result<String, String> {
+    match snapshot_path.extension() {
+        Some(extension) => Ok(extension.to_string_lossy().to_string()),
+        None => Err(format!("Snapshot '{}' does not have an extension (e.g., '.json' or '.bin')", snapshot_path.display())),
+    }
+}
+
+pub fn write_recovery_snapshot<I: Serialize + TaggedSnapshot>(snapshot_path: &Path, universe_integrator: &I) -> Result<(), String> {
     // It can be excessively inefficient to work directly with something that implements Write. For
     // example, every call to write on File results in a system call. A BufWriter keeps an
     // in-memory buffer of data and writes it to an underlying writer in large, infrequent batches.
     //
     // The buffer will be written out when the writer is dropped.
 
-    if snapshot_path.exists() {
-        //// Backup (keep only 1 per hour thanks to filename collision)
-        //let new_extension = format!("{0}.bin", OffsetDateTime::now_utc().format(&format_description::parse("[year][month][day]T[hour]h)).unwrap());
-        // Backup (keep only 1 every 12 hours thanks to filename collision)
-        let new_extension = format!("{0}.bin", OffsetDateTime::now_utc().format(&format_description::parse("[year][month][day]T[period]").unwrap()).unwrap());
-        fs::rename(snapshot_path, snapshot_path.with_extension(new_extension)).unwrap();
-    }
+    // The snapshot is written to a temporary file that replaces the previous snapshot only once
+    // it is completely written to disk, thus a crash never leaves a partially written snapshot
+    let extension = snapshot_extension(snapshot_path)?;
+    let temporary_snapshot_path = snapshot_path.with_extension(format!("{}.tmp", extension));
 
     // 1.- Serialize the integrator to be able to resume if the simulation is interrupted
-    let mut writer = BufWriter::new(File::create(&snapshot_path).unwrap());
+    let temporary_snapshot_file = File::create(&temporary_snapshot_path).map_err(|e| format!("Couldn't create snapshot file '{}': {}", temporary_snapshot_path.display(), e))?;
+    let mut writer = BufWriter::new(temporary_snapshot_file);
 
-    if snapshot_path.extension().unwrap() == "json" {
-        let json_encoded = serde_json::to_string_pretty(&universe_integrator).unwrap();
-        writer.write(json_encoded.as_bytes()).unwrap();
+    // The envelope identifies the format version and the integrator to be able to migrate older snapshots
+    // and the content hash to detect corrupted snapshots
+    if extension == "json" {
+        let integrator = serde_json::to_value(universe_integrator).unwrap();
+        let envelope = JsonSnapshotEnvelope {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            integrator_type: universe_integrator.integrator_type(),
+            content_hash: json_content_hash(&integrator),
+            integrator: &integrator,
+        };
+        let json_encoded = serde_json::to_string_pretty(&envelope).unwrap();
+        writer.write_all(json_encoded.as_bytes()).map_err(|e| format!("Couldn't write snapshot file '{}': {}", temporary_snapshot_path.display(), e))?;
     } else {
         // Binary
-        bincode::serialize_into(&mut writer, &universe_integrator).unwrap(); // bin
+        let integrator = bincode::serialize(universe_integrator).unwrap();
+        bincode::serialize_into(&mut writer, &SnapshotHeader::new(universe_integrator.integrator_type())).map_err(|e| format!("Couldn't write snapshot file '{}': {}", temporary_snapshot_path.display(), e))?; // bin
+        writer.write_all(&integrator).map_err(|e| format!("Couldn't write snapshot file '{}': {}", temporary_snapshot_path.display(), e))?; // bin
+        bincode::serialize_into(&mut writer, &content_hash(&integrator)).map_err(|e| format!("Couldn't write snapshot file '{}': {}", temporary_snapshot_path.display(), e))?; // bin
     }
 
+    let snapshot_file = writer.into_inner().map_err(|e| format!("Couldn't write snapshot file '{}': {}", temporary_snapshot_path.display(), e.error()))?;
+    snapshot_file.sync_all().map_err(|e| format!("Couldn't sync snapshot file '{}': {}", temporary_snapshot_path.display(), e))?;
+    fs::rename(&temporary_snapshot_path, snapshot_path).map_err(|e| format!("Couldn't replace snapshot file '{}': {}", snapshot_path.display(), e))?;
+    sync_parent_directory(snapshot_path);
+    Ok(())
+}
+
+fn sync_parent_directory(path: &Path) {
+    // Make the rename durable (best effort, not supported by every platform/filesystem)
+    if let Some(dirname) = path.parent() {
+        let dirname = if dirname.as_os_str().is_empty() { Path::new(".") } else { dirname };
+        if let Ok(directory) = File::open(dirname) {
+            let _ = directory.sync_all();
+        }
+    }
+}
+
+/// Flush the buffer and force the data to be written to disk
+pub fn sync_writer(writer: &mut BufWriter<File>) -> Result<(), String> {
+    writer.flush().map_err(|e| format!("Couldn't write output file: {}", e))?;
+    writer.get_ref().sync_all().map_err(|e| format!("Couldn't sync output file: {}", e))
+}
+
+/// Which backups of the recovery snapshot are kept. Backups older than the
+/// last `keep_last` ones are removed, unless they are the first backup of their
+/// simulated interval of `keep_every` days. If `keep_last` is not defined (default),
+/// all the backups are kept.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct BackupRetention {
+    pub keep_last: Option<usize>,
+    pub keep_every: Option<f64>, // days
+}
+
+fn backup_snapshot_path(snapshot_path: &Path, current_time: f64) -> Result<PathBuf, String> {
+    // e.g., 'case.bin' -> 'case.backup-0000000036525.bin' (simulated time in days)
+    let extension = snapshot_extension(snapshot_path)?;
+    Ok(snapshot_path.with_extension(format!("backup-{:013}.{}", current_time.max(0.).floor() as u64, extension)))
+}
+
+/// Keep the recovery snapshot that was just written as backup. Snapshots are never modified but
+/// replaced (see `write_recovery_snapshot`), thus the backup is a hard link instead of a copy
+/// (unless the filesystem does not support them).
+pub fn backup_recovery_snapshot(snapshot_path: &Path, current_time: f64) -> Result<(), String> {
+    let backup_path = backup_snapshot_path(snapshot_path, current_time)?;
+    let extension = snapshot_extension(snapshot_path)?;
+    let temporary_backup_path = backup_path.with_extension(format!("{}.tmp", extension));
+    let _ = fs::remove_file(&temporary_backup_path); // Left by an interrupted run
+    if fs::hard_link(snapshot_path, &temporary_backup_path).is_err() {
+        fs::copy(snapshot_path, &temporary_backup_path).map_err(|e| format!("Couldn't copy snapshot file '{}' to '{}': {}", snapshot_path.display(), temporary_backup_path.display(), e))?;
+        File::open(&temporary_backup_path).and_then(|backup_file| backup_file.sync_all()).map_err(|e| format!("Couldn't sync backup file '{}': {}", temporary_backup_path.display(), e))?;
+    }
+    fs::rename(&temporary_backup_path, &backup_path).map_err(|e| format!("Couldn't rename backup file '{}': {}", temporary_backup_path.display(), e))?;
+    sync_parent_directory(&backup_path);
+    Ok(())
+}
+
+/// Select the backups to be removed given a list of (simulated time, path) sorted from the most recent to the oldest
+pub fn backups_to_remove(backups: &[(f64, PathBuf)], backup_retention: &BackupRetention) -> Vec<PathBuf> {
+    let keep_last = match backup_retention.keep_last {
+        Some(keep_last) => keep_last,
+        None => return Vec::new(),
+    };
+    let mut to_remove = Vec::new();
+    for (i, (current_time, backup_path)) in backups.iter().enumerate() {
+        if i < keep_last {
+            continue;
+        }
+        let first_of_interval = match backup_retention.keep_every {
+            Some(keep_every) if keep_every > 0. => {
+                let interval = (current_time / keep_every).floor();
+                // Backups are sorted from the most recent, an older one in the same interval means this is not the first
+                !backups[i+1..].iter().any(|(older_time, _)| (older_time / keep_every).floor() == interval)
+            },
+            _ => false,
+        };
+        if !first_of_interval {
+            to_remove.push(backup_path.clone());
+        }
+    }
+    to_remove
+}
+
+pub fn apply_backup_retention(snapshot_path: &Path, backup_retention: &BackupRetention) {
+    for backup_path in backups_to_remove(&find_timed_backup_snapshots(snapshot_path), backup_retention) {
+        if let Err(why) = fs::remove_file(&backup_path) {
+            log_warning!("Couldn't remove backup '{}': {}", backup_path.display(), why);
+        }
+    }
 }
 
 
@@ -71,13 +184,13 @@ pub fn get_universe_history_writer(universe_history_path: &Path, expected_n_byte
 
     let universe_history_file = match options_bin.open(&universe_history_path) {
         Ok(f) => f,
-        Err(e) => panic!("[PANIC {} UTC] File error: {}", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), e),
+        Err(e) => fatal!("File error: {}", e),
     };
 
     let metadata = universe_history_file.metadata().unwrap();
     let current_n_bytes = metadata.len();
     if current_n_bytes < expected_n_bytes {
-        panic!("[PANIC {} UTC] Historic snapshots do not contain all the expected history ({} bytes) as indicated by the recovery snapshot ({} bytes)", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), current_n_bytes, expected_n_bytes);
+        fatal!("Historic snapshots do not contain all the expected history ({} bytes) as indicated by the recovery snapshot ({} bytes)", current_n_bytes, expected_n_bytes);
     }
 
     // Keep only historic data that saved until the restored snapshot (if there it is the case)
@@ -88,6 +201,50 @@ pub fn get_universe_history_writer(universe_history_path: &Path, expected_n_byte
     universe_history_writer
 }
 
+/// Size of the historic snapshots file written so far, taking into account that the
+/// number of particles per snapshot can change (see `ParticleSetChange`)
+pub fn expected_universe_history_n_bytes(universe_integrator: &dyn Integrator) -> u64 {
+    expected_n_bytes(universe_integrator.get_n_historic_snapshots(), universe_integrator.get_n_particles(),
+                        n_bytes_per_particle_in_historic_snapshot(), &historic_snapshot_changes(universe_integrator.get_particle_set_changes()))
+}
+
+pub fn verify_universe_history(universe_history_path: &Path, universe_integrator: &dyn Integrator) -> Result<(), String> {
+    // The last complete historic snapshot must have been written between the last
+    // historic snapshot time and the current time of the recovery snapshot
+    let n_historic_snapshots = universe_integrator.get_n_historic_snapshots();
+    if n_historic_snapshots == 0 {
+        return Ok(());
+    }
+    let n_bytes_per_particle = n_bytes_per_particle_in_historic_snapshot();
+    let changes = historic_snapshot_changes(universe_integrator.get_particle_set_changes());
+    let last_snapshot_offset = expected_n_bytes(n_historic_snapshots - 1, universe_integrator.get_n_particles(), n_bytes_per_particle, &changes);
+    let expected_n_bytes = expected_universe_history_n_bytes(universe_integrator);
+    let n_particles = (expected_n_bytes - last_snapshot_offset) / n_bytes_per_particle; // Particles in the last snapshot
+    let mut universe_history_file = match File::open(universe_history_path) {
+        Ok(f) => f,
+        Err(why) => return Err(format!("Couldn't open {}: {}", universe_history_path.display(), why)),
+    };
+    let current_n_bytes = universe_history_file.metadata().unwrap().len();
+    if current_n_bytes < expected_n_bytes {
+        return Err(format!("Historic snapshots do not contain all the expected history ({} bytes) as indicated by the recovery snapshot ({} bytes)", current_n_bytes, expected_n_bytes));
+    }
+    let mut record_times = Vec::with_capacity(n_particles as usize);
+    for i in 0..n_particles {
+        universe_history_file.seek(SeekFrom::Start(last_snapshot_offset + i * n_bytes_per_particle)).unwrap();
+        let mut current_time_bytes = [0_u8; 8];
+        universe_history_file.read_exact(&mut current_time_bytes).unwrap();
+        record_times.push(bincode::deserialize::<f64>(&current_time_bytes).unwrap());
+    }
+    let last_historic_snapshot_time = universe_integrator.get_last_historic_snapshot_time();
+    let current_time = universe_integrator.get_current_time();
+    for record_time in record_times.iter() {
+        if *record_time != record_times[0] || *record_time < last_historic_snapshot_time || *record_time > current_time {
+            return Err(format!("The last historic snapshot (time {} days) does not match the recovery snapshot (last historic snapshot time {} days, current time {} days)", record_time, last_historic_snapshot_time, current_time));
+        }
+    }
+    Ok(())
+}
+
 pub fn write_historic_snapshot<T: Write>(universe_history_writer: &mut BufWriter<T>, universe: &Universe, current_time: f64, time_step: f64) {
     // It can be excessively inefficient to work directly with something that implements Write. For
     // example, every call to write on File results in a system call. A BufWriter keeps an
@@ -115,15 +272,7 @@ pub fn write_historic_snapshot<T: Write>(universe_history_writer: &mut BufWriter
                         particle.radius,                        // Rsun
                         particle.radius_of_gyration_2,
                     );
-        let love_number = match &particle.tides.effect {
-            TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model) => {
-                match tidal_model {
-                    TidalModel::ConstantTimeLag(params) => params.love_number,
-                    _ => 0.
-                }
-            },
-            _ => 0.
-        };
+        let love_number = love_number(particle);
         bincode::serialize_into(&mut *universe_history_writer, &output).unwrap();
         let output = (
                         love_number,
@@ -135,35 +284,14 @@ pub fn write_historic_snapshot<T: Write>(universe_history_writer: &mut BufWriter
         bincode::serialize_into(&mut *universe_history_writer, &output).unwrap();
 
         if MIN_ORBITAL_PERIOD_TIME_STEP_RATIO > 0. {
-            let reference_particle_index;
-            let (_semimajor_axis, _perihelion_distance, _eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, orbital_period) = match particle.reference {
-                Reference::MostMassiveParticle => {
-                    reference_particle_index = universe.hosts.index.most_massive;
-                    let reference_particle = &universe.particles[reference_particle_index];
-                    calculate_keplerian_orbital_elements(reference_particle.mass_g+particle.mass_g, particle.heliocentric_position, particle.heliocentric_velocity)
-                },
-                Reference::Particle(index) => {
-                    reference_particle_index = index;
-                    let reference_particle = &universe.particles[reference_particle_index];
-                    let position = Axes{
-                        x: particle.inertial_position.x - reference_particle.inertial_position.x,
-                        y: particle.inertial_position.y - reference_particle.inertial_position.y,
-                        z: particle.inertial_position.z - reference_particle.inertial_position.z,
-                    };
-                    let velocity = Axes{
-                        x: particle.inertial_velocity.x - reference_particle.inertial_velocity.x,
-                        y: particle.inertial_velocity.y - reference_particle.inertial_velocity.y,
-                        z: particle.inertial_velocity.z - reference_particle.inertial_velocity.z,
-                    };
-                    calculate_keplerian_orbital_elements(reference_particle.mass_g+particle.mass_g, position, velocity)
-                },
-            };
+            let reference_particle_index = reference_particle_index(universe, particle);
+            let (_semimajor_axis, _perihelion_distance, _eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, orbital_period) = calculate_keplerian_orbital_elements_around_reference(universe, particle);
 
             // Control once in a while (when historic point is written) that the
             // time step is small enough to correctly integrate an orbit
             if current_particle_index != reference_particle_index && orbital_period <= time_step*MIN_ORBITAL_PERIOD_TIME_STEP_RATIO {
                 println!("\n");
-                panic!("[PANIC {} UTC] Time step is too large! Particle {} has an orbital period around particle {} of {:0.3} days which is less than the recommended limit ({:0.3} days) based on the current time step ({:0.3} days).", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), current_particle_index, reference_particle_index, orbital_period, time_step*MIN_ORBITAL_PERIOD_TIME_STEP_RATIO, time_step);
+                fatal!("Time step is too large! Particle {} has an orbital period around particle {} of {:0.3} days which is less than the recommended limit ({:0.3} days) based on the current time step ({:0.3} days).", current_particle_index, reference_particle_index, orbital_period, time_step*MIN_ORBITAL_PERIOD_TIME_STEP_RATIO, time_step);
             }
         }
 
@@ -171,35 +299,353 @@ pub fn write_historic_snapshot<T: Write>(universe_history_writer: &mut BufWriter
     }
 }
 
+fn love_number(particle: &Particle) -> f64 {
+    match &particle.tides.effect {
+        TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model) => tidal_model.response().love_number(),
+        _ => 0.
+    }
+}
+
+pub fn reference_particle_index(universe: &Universe, particle: &Particle) -> usize {
+    match particle.reference {
+        Reference::MostMassiveParticle => universe.hosts.index.most_massive,
+        Reference::Particle(index) => index,
+    }
+}
+
+/// Computed from the inertial coordinates, which are always up to date (the heliocentric ones
+/// are only updated when needed)
+fn calculate_position_and_velocity_around_reference(universe: &Universe, particle: &Particle) -> (Axes, Axes) {
+    let reference_particle = &universe.particles[reference_particle_index(universe, particle)];
+    let position = Axes{
+        x: particle.inertial_position.x - reference_particle.inertial_position.x,
+        y: particle.inertial_position.y - reference_particle.inertial_position.y,
+        z: particle.inertial_position.z - reference_particle.inertial_position.z,
+    };
+    let velocity = Axes{
+        x: particle.inertial_velocity.x - reference_particle.inertial_velocity.x,
+        y: particle.inertial_velocity.y - reference_particle.inertial_velocity.y,
+        z: particle.inertial_velocity.z - reference_particle.inertial_velocity.z,
+    };
+    (position, velocity)
+}
+
+pub fn calculate_keplerian_orbital_elements_around_reference(universe: &Universe, particle: &Particle) -> (f64, f64, f64, f64, f64, f64, f64, f64) {
+    let reference_particle = &universe.particles[reference_particle_index(universe, particle)];
+    let (position, velocity) = calculate_position_and_velocity_around_reference(universe, particle);
+    calculate_keplerian_orbital_elements(reference_particle.mass_g+particle.mass_g, position, velocity)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//- Output streams
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn output_stream_path(universe_history_path: &Path, output_stream: &OutputStream) -> PathBuf {
+    // e.g., 'case_history.bin' -> 'case_history.dense.bin'
+    universe_history_path.with_extension(format!("{}.bin", output_stream.name))
+}
+
+pub fn get_output_stream_writers(universe_history_path: &Path, output_streams: &[OutputStream], n_particles: usize, particle_set_changes: &[ParticleSetChange]) -> Vec<BufWriter<File>> {
+    let mut output_stream_writers = Vec::with_capacity(output_streams.len());
+    for (i, output_stream) in output_streams.iter().enumerate() {
+        if output_streams[..i].iter().any(|previous_output_stream| previous_output_stream.name == output_stream.name) {
+            fatal!("Output stream name '{}' is used more than once", output_stream.name);
+        }
+        let expected_n_bytes = expected_n_bytes(output_stream.n_snapshots, n_particles, output_stream.n_bytes_per_particle(), &output_stream_changes(particle_set_changes, i));
+        output_stream_writers.push(get_universe_history_writer(&output_stream_path(universe_history_path, output_stream), expected_n_bytes));
+    }
+    output_stream_writers
+}
+
+pub fn particle_set_changes_path(universe_history_path: &Path) -> PathBuf {
+    // e.g., 'case_history.bin' -> 'case_history.particle_set_changes.json'
+    universe_history_path.with_extension("particle_set_changes.json")
+}
+
+/// Particles added/removed during the simulation, needed to read the historic snapshots
+/// and output streams because the number of records per snapshot changes
+pub fn write_particle_set_changes(universe_history_path: &Path, particle_set_changes: &[ParticleSetChange]) {
+    let particle_set_changes_path = particle_set_changes_path(universe_history_path);
+    if particle_set_changes.is_empty() && !particle_set_changes_path.exists() {
+        return;
+    }
+    if let Err(e) = fs::write(&particle_set_changes_path, serde_json::to_string_pretty(particle_set_changes).unwrap()) {
+        fatal!("File error: {}", e);
+    }
+}
+
+pub fn applied_events_path(universe_history_path: &Path) -> PathBuf {
+    // e.g., 'case_history.bin' -> 'case_history.events.json'
+    universe_history_path.with_extension("events.json")
+}
+
+/// Scheduled events applied during the simulation with their time, a historic snapshot is written
+/// at the time of each event to record the state when the change happened
+pub fn write_applied_events(universe_history_path: &Path, applied_events: &[ScheduledEvent]) {
+    let applied_events_path = applied_events_path(universe_history_path);
+    if applied_events.is_empty() && !applied_events_path.exists() {
+        return;
+    }
+    if let Err(e) = fs::write(&applied_events_path, serde_json::to_string_pretty(applied_events).unwrap()) {
+        fatal!("File error: {}", e);
+    }
+}
+
+pub fn is_accumulating_output_field(output_streams: &[OutputStream], field: &OutputField, current_time: f64) -> bool {
+    output_streams.iter().any(|output_stream| output_stream.is_accumulating(current_time) && output_stream.fields.contains(field))
+}
+
+/// Averaged streams sample every time step, thus the values are computed from the integrated
+/// state (inertial coordinates and angular momentum) which, unlike the heliocentric coordinates
+/// and spin, does not require synchronizing the universe
+pub fn sample_output_streams(output_streams: &[OutputStream], universe: &Universe, current_time: f64) -> Vec<Option<Vec<f64>>> {
+    output_streams.iter()
+        .map(|output_stream| if output_stream.is_accumulating(current_time) { Some(calculate_output_stream_values(output_stream, universe)) } else { None })
+        .collect()
+}
+
+/// The samples taken at the beginning of a step are accumulated once it is done, weighted by the
+/// time step actually taken (it can be shortened to reach an event or adapted by the integrator)
+pub fn accumulate_output_streams(output_streams: &mut [OutputStream], samples: Vec<Option<Vec<f64>>>, time_step: f64) {
+    for (output_stream, values) in output_streams.iter_mut().zip(samples) {
+        if let Some(values) = values {
+            output_stream.accumulator.add(&values, time_step);
+        }
+    }
+}
+
+pub fn write_output_streams<T: Write>(output_streams: &mut [OutputStream], output_stream_writers: &mut [BufWriter<T>], universe: &Universe, current_time: f64, time_step: f64) {
+    if output_streams.len() != output_stream_writers.len() {
+        fatal!("{} output streams were defined but {} writers were provided", output_streams.len(), output_stream_writers.len());
+    }
+    for (output_stream, output_stream_writer) in output_streams.iter_mut().zip(output_stream_writers.iter_mut()) {
+        if output_stream.is_due(current_time) {
+            write_output_stream_snapshot(output_stream_writer, output_stream, universe, current_time, time_step);
+            output_stream.n_snapshots += 1;
+            output_stream.schedule_next(current_time);
+            output_stream.accumulator.reset();
+        }
+    }
+}
+
+fn write_output_stream_snapshot<T: Write>(output_stream_writer: &mut BufWriter<T>, output_stream: &OutputStream, universe: &Universe, current_time: f64, time_step: f64) {
+    let n_values = output_stream.n_values();
+    let values = calculate_output_stream_values(output_stream, universe);
+    for (i, (particle, particle_values)) in universe.particles[..universe.n_particles].iter().zip(values.chunks(n_values)).enumerate() {
+        let output = (
+                        current_time,                           // days
+                        time_step,                              // days
+                        (particle.id as i32),
+                    );
+        bincode::serialize_into(&mut *output_stream_writer, &output).unwrap();
+        for (k, value) in particle_values.iter().enumerate() {
+            let j = i*n_values + k;
+            if output_stream.averaged {
+                if output_stream.accumulator.is_empty() {
+                    // Nothing was accumulated (e.g., the snapshot falls just at the start time)
+                    bincode::serialize_into(&mut *output_stream_writer, &(value, value, value)).unwrap();
+                } else {
+                    bincode::serialize_into(&mut *output_stream_writer, &(output_stream.accumulator.mean(j), output_stream.accumulator.min[j], output_stream.accumulator.max[j])).unwrap();
+                }
+            } else {
+                bincode::serialize_into(&mut *output_stream_writer, value).unwrap();
+            }
+        }
+    }
+}
+
+fn calculate_output_stream_values(output_stream: &OutputStream, universe: &Universe) -> Vec<f64> {
+    // All the values of the first particle, followed by all the values of the second particle, etc.
+    let mut values = Vec::with_capacity(output_stream.n_values()*universe.n_particles);
+    let host_particle = &universe.particles[universe.hosts.index.most_massive];
+    for particle in universe.particles[..universe.n_particles].iter() {
+        let orbits_reference = reference_particle_index(universe, particle) != particle.id;
+        // Same as Universe::calculate_spin_and_evolving_quantities (without evolution)
+        let spin = Axes{
+            x: particle.angular_momentum.x/particle.moment_of_inertia,
+            y: particle.angular_momentum.y/particle.moment_of_inertia,
+            z: particle.angular_momentum.z/particle.moment_of_inertia,
+        };
+        let norm_spin_vector_2 = spin.x.powi(2) + spin.y.powi(2) + spin.z.powi(2);
+        for field in output_stream.fields.iter() {
+            match field {
+                OutputField::Position => values.extend_from_slice(&[particle.inertial_position.x, particle.inertial_position.y, particle.inertial_position.z]),
+                OutputField::Velocity => values.extend_from_slice(&[particle.inertial_velocity.x, particle.inertial_velocity.y, particle.inertial_velocity.z]),
+                // Same as Universe::inertial_to_heliocentric
+                OutputField::HeliocentricPosition => values.extend_from_slice(&[particle.inertial_position.x - host_particle.inertial_position.x, particle.inertial_position.y - host_particle.inertial_position.y, particle.inertial_position.z - host_particle.inertial_position.z]),
+                OutputField::HeliocentricVelocity => values.extend_from_slice(&[particle.inertial_velocity.x - host_particle.inertial_velocity.x, particle.inertial_velocity.y - host_particle.inertial_velocity.y, particle.inertial_velocity.z - host_particle.inertial_velocity.z]),
+                OutputField::Spin => values.extend_from_slice(&[spin.x, spin.y, spin.z]),
+                OutputField::Mass => values.push(particle.mass),
+                OutputField::Radius => values.push(particle.radius),
+                OutputField::RadiusOfGyration2 => values.push(particle.radius_of_gyration_2),
+                OutputField::LoveNumber => values.push(love_number(particle)),
+                OutputField::ScaledDissipationFactor => values.push(particle.tides.parameters.internal.scaled_dissipation_factor),
+                OutputField::LagAngle => values.push(particle.tides.parameters.internal.lag_angle),
+                OutputField::DenergyDt => values.push(particle.tides.parameters.internal.denergy_dt),
+                OutputField::MigrationTimescale => values.push(particle.disk.parameters.internal.migration_timescale),
+                OutputField::SpinRate => values.push(norm_spin_vector_2.sqrt()),
+                OutputField::SemiMajorAxis | OutputField::Eccentricity | OutputField::Inclination => {
+                    let value = if orbits_reference {
+                        let (semimajor_axis, _perihelion_distance, eccentricity, inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, _orbital_period) = calculate_keplerian_orbital_elements_around_reference(universe, particle);
+                        match field {
+                            OutputField::SemiMajorAxis => semimajor_axis,
+                            OutputField::Eccentricity => eccentricity,
+                            _ => inclination,
+                        }
+                    } else {
+                        0. // The particle of reference does not orbit around itself
+                    };
+                    values.push(value);
+                },
+                OutputField::Obliquity => {
+                    let value = if orbits_reference && norm_spin_vector_2 > 0. {
+                        let (position, velocity) = calculate_position_and_velocity_around_reference(universe, particle);
+                        calculate_inclination_orbital_equatorial_plane(position, velocity, spin)
+                    } else {
+                        0.
+                    };
+                    values.push(value);
+                },
+            }
+        }
+    }
+    values
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 //- Restore functions
 ////////////////////////////////////////////////////////////////////////////////
 
 pub fn restore_snapshot(universe_integrator_snapshot_path: &Path) -> Result<Box<dyn Integrator>, String> {
-    let mut universe_integrator: Box<dyn Integrator>;
+    let mut universe_integrator = read_snapshot(universe_integrator_snapshot_path)?;
+    if universe_integrator.get_current_time() == 0. {
+        log_info!("Created new simulation based on '{}'.", universe_integrator_snapshot_path.display());
+        let event_errors = validate_events(universe_integrator.get_event_timeline(), universe_integrator.get_universe());
+        if !event_errors.is_empty() {
+            return Err(format!("Invalid scheduled events: {}", event_errors.join("; ")));
+        }
+        let stopping_condition_errors = validate_stopping_conditions(universe_integrator.get_stopping_conditions(), universe_integrator.get_universe());
+        if !stopping_condition_errors.is_empty() {
+            return Err(format!("Invalid stopping conditions: {}", stopping_condition_errors.join("; ")));
+        }
+        let output_stream_errors = validate_output_streams(universe_integrator.get_output_streams());
+        if !output_stream_errors.is_empty() {
+            return Err(format!("Invalid output streams: {}", output_stream_errors.join("; ")));
+        }
+        universe_integrator.initialize_physical_values();
+    } else {
+        log_info!("Restored previous simulation from '{}'.", universe_integrator_snapshot_path.display());
+        let current_time_years = universe_integrator.get_current_time()/365.25;
+        log_info!("Continuing from year {:0.0} ({:0.1e}).", current_time_years, current_time_years);
+    }
+    Ok(universe_integrator)
+}
+
+/// Read a snapshot as it is stored (i.e., without initializing the physical values of new simulations)
+pub fn read_snapshot(universe_integrator_snapshot_path: &Path) -> Result<Box<dyn Integrator>, String> {
     if universe_integrator_snapshot_path.exists() {
         // Open the path in read-only mode only to verify it exists, returns `io::Result<File>`
-        if let Err(why) = File::open(&universe_integrator_snapshot_path) {
+        if let Err(why) = File::open(universe_integrator_snapshot_path) {
             return Err(format!("Couldn't open {}: {}", universe_integrator_snapshot_path.display(), why))
         }
 
-        if universe_integrator_snapshot_path.extension().unwrap() == "json" { 
-            universe_integrator = deserialize_json_snapshot(&universe_integrator_snapshot_path).unwrap();
+        let mut universe_integrator = if snapshot_extension(universe_integrator_snapshot_path)? == "json" {
+            deserialize_json_snapshot(universe_integrator_snapshot_path)?
         } else {
-            universe_integrator = deserialize_bin_snapshot(&universe_integrator_snapshot_path).unwrap();
+            deserialize_bin_snapshot(universe_integrator_snapshot_path)?
+        };
+        // Love number tables stored in files are not part of the snapshot
+        universe_integrator.get_universe_mut().load_love_number_tables()?;
+        Ok(universe_integrator)
+    } else {
+        Err("File does not exist".to_string())
+    }
+}
+
+/// Convert a snapshot between JSON and binary formats (given by the file extensions). The
+/// converted snapshot is read back and must be bit-identical to the original integrator,
+/// otherwise it is removed and an error is returned.
+pub fn convert_snapshot(input_snapshot_path: &Path, output_snapshot_path: &Path) -> Result<(), String> {
+    let universe_integrator = read_snapshot(input_snapshot_path)?;
+    universe_integrator.write_snapshot(output_snapshot_path)?;
+    let converted_universe_integrator = match read_snapshot(output_snapshot_path) {
+        Ok(converted_universe_integrator) => converted_universe_integrator,
+        Err(why) => {
+            let _ = fs::remove_file(output_snapshot_path);
+            return Err(format!("Converted snapshot '{}' cannot be read back: {}", output_snapshot_path.display(), why));
         }
-        if universe_integrator.get_current_time() == 0. {
-            println!("[INFO {} UTC] Created new simulation based on '{}'.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), universe_integrator_snapshot_path.display());
-            universe_integrator.initialize_physical_values();
-        } else {
-            println!("[INFO {} UTC] Restored previous simulation from '{}'.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), universe_integrator_snapshot_path.display());
-            let current_time_years = universe_integrator.get_current_time()/365.25;
-            println!("[INFO {} UTC] Continuing from year {:0.0} ({:0.1e}).", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), current_time_years, current_time_years);
+    };
+    if serialize_integrator_to_bin(&*converted_universe_integrator) != serialize_integrator_to_bin(&*universe_integrator) {
+        let _ = fs::remove_file(output_snapshot_path);
+        return Err(format!("Converted snapshot '{}' differs from the original (e.g., non-finite values cannot be represented in JSON)", output_snapshot_path.display()));
+    }
+    Ok(())
+}
+
+fn list_snapshot_directory(snapshot_path: &Path) -> (String, String, PathBuf, Vec<String>) {
+    let stem = snapshot_path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let extension = snapshot_path.extension().map(|extension| extension.to_string_lossy().to_string()).unwrap_or_default();
+    let dirname = match snapshot_path.parent() {
+        Some(dirname) if dirname.as_os_str().is_empty() => PathBuf::from("."),
+        Some(dirname) => dirname.to_path_buf(),
+        None => PathBuf::from("."),
+    };
+    let filenames = match fs::read_dir(&dirname) {
+        Ok(entries) => entries.flatten().map(|entry| entry.file_name().to_string_lossy().to_string()).collect(),
+        Err(_) => Vec::new(),
+    };
+    (stem, extension, dirname, filenames)
+}
+
+/// Backups of a recovery snapshot with their simulated time (e.g., 'case.backup-0000000036525.bin' for 'case.bin')
+/// sorted from the most recent to the oldest
+pub fn find_timed_backup_snapshots(snapshot_path: &Path) -> Vec<(f64, PathBuf)> {
+    let (stem, extension, dirname, filenames) = list_snapshot_directory(snapshot_path);
+    let prefix = format!("{}.backup-", stem);
+    let suffix = format!(".{}", extension);
+    let mut backups: Vec<(f64, PathBuf)> = Vec::new();
+    for filename in filenames.iter() {
+        if let Some(current_time) = filename.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(&suffix)) {
+            if let Ok(current_time) = current_time.parse::<u64>() {
+                backups.push((current_time as f64, dirname.join(filename)));
+            }
         }
-        return Ok(universe_integrator);
-    } else {
-        return Err(format!("File does not exist"));
     }
+    backups.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+    backups
+}
+
+/// Backups of a recovery snapshot sorted from the most recent to the oldest, including
+/// the ones created by previous versions (e.g., 'case.20240101TAM.bin' for 'case.bin')
+pub fn find_backup_snapshots(snapshot_path: &Path) -> Vec<PathBuf> {
+    let mut backups: Vec<PathBuf> = find_timed_backup_snapshots(snapshot_path).into_iter().map(|(_, path)| path).collect();
+    let (stem, _extension, dirname, filenames) = list_snapshot_directory(snapshot_path);
+    let prefix = format!("{}.", stem);
+    let mut dated_backups: Vec<(String, PathBuf)> = Vec::new();
+    for filename in filenames.iter() {
+        if let Some(date) = filename.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(".bin")) {
+            // YYYYMMDDTAM or YYYYMMDDTPM
+            let is_backup = date.len() == 11 && date[..8].chars().all(|c| c.is_ascii_digit())
+                                && (&date[8..] == "TAM" || &date[8..] == "TPM");
+            if is_backup {
+                dated_backups.push((date.to_string(), dirname.join(filename)));
+            }
+        }
+    }
+    // Dates are sorted alphabetically and 'AM' goes before 'PM'
+    dated_backups.sort_by(|a, b| b.0.cmp(&a.0));
+    backups.extend(dated_backups.into_iter().map(|(_, path)| path));
+    backups
+}
+
+pub fn restore_most_recent_valid_backup(snapshot_path: &Path) -> Option<(PathBuf, Box<dyn Integrator>)> {
+    for backup_path in find_backup_snapshots(snapshot_path) {
+        match restore_snapshot(&backup_path) {
+            Ok(universe_integrator) => return Some((backup_path, universe_integrator)),
+            Err(why) => log_warning!("Invalid backup '{}': {}", backup_path.display(), why),
+        }
+    }
+    None
 }
 
 fn deserialize_json_snapshot(snapshot_path: &Path) -> Result<Box<dyn Integrator>, String> {
@@ -211,70 +657,78 @@ fn deserialize_json_snapshot(snapshot_path: &Path) -> Result<Box<dyn Integrator>
         Err(why) => return Err(format!("Couldn't read json snapshot file: {}", why)),
         Ok(_) => {}
     }
+    let mut value: Value = match serde_json::from_str(&json_encoded) {
+        Ok(value) => value,
+        Err(why) => return Err(format!("Couldn't parse json snapshot file: {}", why)),
+    };
 
-    let wrapped_universe_integrator: Result<WHFast, serde_json::Error> = serde_json::from_str(&json_encoded);
-    match wrapped_universe_integrator {
-        Ok(universe_integrator) => {
-            println!("[INFO {} UTC] WHFAST Integrator.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-            Ok(Box::new(universe_integrator))
+    let envelope = (value.get("format_version").and_then(|format_version| format_version.as_u64()),
+                    value.get("integrator_type").and_then(|integrator_type| serde_json::from_value::<IntegratorType>(integrator_type.clone()).ok()));
+    let (format_version, integrator_type, integrator) = match envelope {
+        (Some(format_version), Some(integrator_type)) => {
+            let integrator = match value.get_mut("integrator") {
+                Some(integrator) => integrator.take(),
+                None => return Err("Snapshot envelope without integrator".to_string()),
+            };
+            let expected_content_hash = value.get("content_hash").and_then(|content_hash| content_hash.as_u64());
+            if expected_content_hash != Some(json_content_hash(&integrator)) {
+                return Err(format!("Corrupted snapshot '{}' (content hash does not match)", snapshot_path.display()));
+            }
+            (format_version as u32, integrator_type, integrator)
         },
-        Err(_) => {
-            let wrapped_universe_integrator: Result<Ias15, serde_json::Error> = serde_json::from_str(&json_encoded);
-            match wrapped_universe_integrator {
-                Ok(universe_integrator) => {
-                    println!("[INFO {} UTC] IAS15 Integrator.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-                    Ok(Box::new(universe_integrator))
-                },
-                Err(_) => {
-                    let wrapped_universe_integrator: Result<LeapFrog, serde_json::Error> = serde_json::from_str(&json_encoded);
-                    match wrapped_universe_integrator {
-                        Ok(universe_integrator) => {
-                            println!("[INFO {} UTC] LeapFrog Integrator.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-                            Ok(Box::new(universe_integrator))
-                        },
-                        Err(_) => Err(format!("Unknown integrator!")),
-                    }
-                }
+        _ => {
+            // Version 0 (e.g., new cases generated by python)
+            match guess_json_integrator_type(&value) {
+                Some(integrator_type) => (0, integrator_type, value),
+                None => return Err("Unknown integrator!".to_string()),
             }
-        }
-    }
+        },
+    };
+    let integrator = migrate_json_snapshot(format_version, integrator_type, integrator)?;
+    print_integrator_type(integrator_type);
+    deserialize_integrator_from_json_value(integrator_type, integrator)
 }
 
 fn deserialize_bin_snapshot(snapshot_path: &Path) -> Result<Box<dyn Integrator>, String> {
     // Open the path in read-only mode, returns `io::Result<File>`
-    let snapshot_file = File::open(&snapshot_path).unwrap();
-    let mut reader = BufReader::new(&snapshot_file);
-    let wrapped_universe_integrator: Result<WHFast, bincode::Error> = bincode::deserialize_from(&mut reader);
-    match wrapped_universe_integrator {
-        Ok(universe_integrator) => {
-            println!("[INFO {} UTC] WHFAST Integrator.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-            Ok(Box::new(universe_integrator))
-        },
-        Err(_) => {
-            // Re-open file because the previous File/BufReader was already consumed
-            let snapshot_file = File::open(&snapshot_path).unwrap();
-            let mut reader = BufReader::new(&snapshot_file);
-            let wrapped_universe_integrator: Result<Ias15, bincode::Error> = bincode::deserialize_from(&mut reader);
-            match wrapped_universe_integrator {
-                Ok(universe_integrator) => {
-                    println!("[INFO {} UTC] IAS15 Integrator.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-                    Ok(Box::new(universe_integrator))
-                },
-                Err(_) => {
-                    // Re-open file because the previous File/BufReader was already consumed
-                    let snapshot_file = File::open(&snapshot_path).unwrap();
-                    let mut reader = BufReader::new(&snapshot_file);
-                    let wrapped_universe_integrator: Result<LeapFrog, bincode::Error> = bincode::deserialize_from(&mut reader);
-                    match wrapped_universe_integrator {
-                        Ok(universe_integrator) => {
-                            println!("[INFO {} UTC] LeapFrog Integrator.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-                            Ok(Box::new(universe_integrator))
-                        },
-                        Err(_) => Err(format!("Unknown integrator!")),
-                    }
-                }
-            }
-        }
+    let mut snapshot_file = File::open(snapshot_path).unwrap();
+    let mut bytes = Vec::new();
+    if let Err(why) = snapshot_file.read_to_end(&mut bytes) {
+        return Err(format!("Couldn't read bin snapshot file: {}", why));
     }
+
+    let (format_version, integrator_type, integrator_bytes) = if bytes.len() >= SNAPSHOT_MAGIC.len() && bytes[..SNAPSHOT_MAGIC.len()] == SNAPSHOT_MAGIC {
+        let header: SnapshotHeader = match bincode::deserialize(&bytes) {
+            Ok(header) => header,
+            Err(why) => return Err(format!("Couldn't read bin snapshot header: {}", why)),
+        };
+        let header_n_bytes = bincode::serialized_size(&header).unwrap() as usize;
+        let content_hash_n_bytes = bincode::serialized_size(&0_u64).unwrap() as usize;
+        if bytes.len() < header_n_bytes + content_hash_n_bytes {
+            return Err(format!("Corrupted snapshot '{}' (truncated file)", snapshot_path.display()));
+        }
+        let (integrator_bytes, content_hash_bytes) = bytes[header_n_bytes..].split_at(bytes.len() - header_n_bytes - content_hash_n_bytes);
+        let expected_content_hash: u64 = bincode::deserialize(content_hash_bytes).unwrap();
+        if expected_content_hash != content_hash(integrator_bytes) {
+            return Err(format!("Corrupted snapshot '{}' (content hash does not match)", snapshot_path.display()));
+        }
+        (header.format_version, header.integrator_type, integrator_bytes)
+    } else {
+        // Version 0
+        match guess_bin_integrator_type(&bytes) {
+            Some(integrator_type) => (0, integrator_type, &bytes[..]),
+            None => return Err("Unknown integrator!".to_string()),
+        }
+    };
+    print_integrator_type(integrator_type);
+    migrate_bin_snapshot(format_version, integrator_type, integrator_bytes)
 }
 
+fn print_integrator_type(integrator_type: IntegratorType) {
+    let integrator_name = match integrator_type {
+        IntegratorType::WHFast => "WHFAST",
+        IntegratorType::Ias15 => "IAS15",
+        IntegratorType::LeapFrog => "LeapFrog",
+    };
+    log_info!("{} Integrator.", integrator_name);
+}
@@ -0,0 +1,159 @@
+use serde::{Serialize, Deserialize};
+use super::Integrator;
+use super::super::particles::{Universe, Particle, Reference, Axes};
+use super::super::constants::G;
+use super::super::tools::calculate_cartesian_coordinates;
+
+/// Keplerian orbital elements of a new particle around its particle of reference
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OrbitalElements {
+    pub semi_major_axis: f64, // AU
+    pub eccentricity: f64,
+    pub inclination: f64, // rad
+    pub argument_of_perihelion: f64, // rad
+    pub longitude_of_ascending_node: f64, // rad
+    pub mean_anomaly: f64, // rad
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ParticleSetAction {
+    Added {
+        index: usize,
+    },
+    // The following particles were shifted one position
+    Removed {
+        index: usize,
+    },
+}
+
+/// Record of a change of the particle set: the historic snapshots (and output stream
+/// snapshots) written before the change contain `previous_n_particles` records each,
+/// the following ones `n_particles` records (particle identifiers are always the
+/// position in the particles array)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParticleSetChange {
+    pub time: f64, // days
+    pub action: ParticleSetAction,
+    pub previous_n_particles: usize,
+    pub n_particles: usize,
+    pub n_historic_snapshots: usize, // Historic snapshots written before the change
+    pub output_streams_n_snapshots: Vec<usize>, // Same for every output stream
+}
+
+/// Insert a particle during a simulation, its heliocentric position/velocity are used
+/// unless orbital elements around its particle of reference are provided. Returns the
+/// index of the new particle (always the last one).
+pub fn add_particle(universe_integrator: &mut dyn Integrator, mut particle: Particle, orbital_elements: Option<OrbitalElements>) -> Result<usize, String> {
+    let previous_n_particles = universe_integrator.get_n_particles();
+    let current_time = universe_integrator.get_current_time();
+    if let Some(orbital_elements) = orbital_elements {
+        set_heliocentric_state_from_orbital_elements(universe_integrator.get_universe(), &mut particle, &orbital_elements)?;
+    }
+    let index = universe_integrator.get_universe_mut().add_particle(particle, current_time)?;
+    record_particle_set_change(universe_integrator, ParticleSetAction::Added { index }, previous_n_particles);
+    log_info!("Added particle {} at {} days", index, current_time);
+    Ok(index)
+}
+
+/// Remove a particle during a simulation, pending events and stopping conditions that
+/// refer to it are discarded and the ones that refer to the following particles are
+/// updated. Returns the removed particle.
+pub fn remove_particle(universe_integrator: &mut dyn Integrator, index: usize) -> Result<Particle, String> {
+    let previous_n_particles = universe_integrator.get_n_particles();
+    let removed_particle = universe_integrator.get_universe_mut().remove_particle(index)?;
+    record_particle_set_change(universe_integrator, ParticleSetAction::Removed { index }, previous_n_particles);
+    log_info!("Removed particle {} at {} days", index, universe_integrator.get_current_time());
+    Ok(removed_particle)
+}
+
+fn record_particle_set_change(universe_integrator: &mut dyn Integrator, action: ParticleSetAction, previous_n_particles: usize) {
+    universe_integrator.reset_internal_state();
+    let mut history_counters = universe_integrator.get_history_counters();
+    history_counters.particle_set_changes.push(ParticleSetChange {
+        time: history_counters.current_time,
+        action,
+        previous_n_particles,
+        n_particles: universe_integrator.get_n_particles(),
+        n_historic_snapshots: history_counters.n_historic_snapshots,
+        output_streams_n_snapshots: history_counters.output_streams.iter().map(|output_stream| output_stream.n_snapshots).collect(),
+    });
+    if let ParticleSetAction::Removed { index } = action {
+        history_counters.event_timeline.remove_particle(index);
+        history_counters.stopping_conditions.remove_particle(index);
+    }
+    history_counters.stopping_conditions.reference_energy = None; // The total energy changes with the particle set
+    universe_integrator.set_history_counters(history_counters);
+}
+
+fn set_heliocentric_state_from_orbital_elements(universe: &Universe, particle: &mut Particle, orbital_elements: &OrbitalElements) -> Result<(), String> {
+    let reference_particle_index = match particle.reference {
+        Reference::MostMassiveParticle => universe.hosts.index.most_massive,
+        Reference::Particle(index) => index,
+    };
+    if reference_particle_index >= universe.n_particles {
+        return Err(format!("The new particle refers to particle {} but there are only {} particles", reference_particle_index, universe.n_particles));
+    }
+    if orbital_elements.semi_major_axis <= 0. || orbital_elements.eccentricity < 0. || orbital_elements.eccentricity >= 1. {
+        return Err(format!("Invalid orbital elements for the new particle: {:?}", orbital_elements));
+    }
+    let host_particle = &universe.particles[universe.hosts.index.most_massive];
+    let reference_particle = &universe.particles[reference_particle_index];
+    let gm = G*(reference_particle.mass + particle.mass);
+    let perihelion_distance = orbital_elements.semi_major_axis * (1. - orbital_elements.eccentricity);
+    let longitude_of_perihelion = orbital_elements.argument_of_perihelion + orbital_elements.longitude_of_ascending_node;
+    let (x, y, z, vx, vy, vz) = calculate_cartesian_coordinates(gm, perihelion_distance, orbital_elements.eccentricity, orbital_elements.inclination,
+                                                                longitude_of_perihelion, orbital_elements.longitude_of_ascending_node, orbital_elements.mean_anomaly);
+    // Heliocentric coordinates are always with respect to the most massive particle
+    particle.heliocentric_position = Axes{
+        x: reference_particle.inertial_position.x - host_particle.inertial_position.x + x,
+        y: reference_particle.inertial_position.y - host_particle.inertial_position.y + y,
+        z: reference_particle.inertial_position.z - host_particle.inertial_position.z + z,
+    };
+    particle.heliocentric_velocity = Axes{
+        x: reference_particle.inertial_velocity.x - host_particle.inertial_velocity.x + vx,
+        y: reference_particle.inertial_velocity.y - host_particle.inertial_velocity.y + vy,
+        z: reference_particle.inertial_velocity.z - host_particle.inertial_velocity.z + vz,
+    };
+    Ok(())
+}
+
+/// Number of bytes of a file with one record per particle and snapshot, where the
+/// number of particles changed after the given snapshot counts (paired with the
+/// number of particles before each change)
+pub fn expected_n_bytes(n_snapshots: usize, n_particles: usize, n_bytes_per_particle: u64, changes: &[(usize, usize)]) -> u64 {
+    let mut n_records = 0;
+    let mut first_snapshot = 0;
+    for (n_snapshots_before_change, previous_n_particles) in changes.iter() {
+        let last_snapshot = (*n_snapshots_before_change).min(n_snapshots).max(first_snapshot);
+        n_records += (last_snapshot - first_snapshot) * previous_n_particles;
+        first_snapshot = last_snapshot;
+    }
+    n_records += (n_snapshots - first_snapshot) * n_particles;
+    n_records as u64 * n_bytes_per_particle
+}
+
+/// Historic snapshot counts before every change (see `expected_n_bytes`)
+pub fn historic_snapshot_changes(particle_set_changes: &[ParticleSetChange]) -> Vec<(usize, usize)> {
+    particle_set_changes.iter().map(|change| (change.n_historic_snapshots, change.previous_n_particles)).collect()
+}
+
+/// Output stream snapshot counts before every change (see `expected_n_bytes`), streams
+/// defined after a change did not write anything before it
+pub fn output_stream_changes(particle_set_changes: &[ParticleSetChange], output_stream_index: usize) -> Vec<(usize, usize)> {
+    particle_set_changes.iter().map(|change| (change.output_streams_n_snapshots.get(output_stream_index).copied().unwrap_or(0), change.previous_n_particles)).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_n_bytes_with_particle_set_changes() {
+        assert_eq!(expected_n_bytes(4, 3, 10, &[]), 120);
+        // Two snapshots with 3 particles, one with 4 and one with 2
+        assert_eq!(expected_n_bytes(4, 2, 10, &[(2, 3), (3, 4)]), 60 + 40 + 20);
+        // Two changes between the same snapshots
+        assert_eq!(expected_n_bytes(3, 2, 10, &[(1, 3), (1, 4)]), 30 + 40);
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::Write;
+use std::time::{Duration, Instant};
+use serde::{Serialize, Deserialize};
+use super::Integrator;
+use super::super::logging::utc_timestamp;
+
+/// Counters of the integrator since the simulation was started/resumed
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntegratorStatistics {
+    pub n_steps: u64,
+    pub time_step: f64,
+    pub n_rejected_steps: u64, // IAS15: steps repeated with a smaller time step
+    pub n_iterations_max_exceeded: u64, // IAS15: predictor corrector loops that did not converge
+}
+
+/// Total energy and angular momentum at the start of the simulation, the conservation errors are
+/// relative to them (kept in the snapshots, thus resuming does not reset the errors)
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConservationReference {
+    pub energy: f64,
+    pub angular_momentum: f64,
+}
+
+/// One line of the JSON-lines progress channel
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProgressRecord {
+    pub utc: String,
+    pub status: String, // running, completed, time_limit, interrupted, stopped
+    pub current_time: f64, // days
+    pub time_limit: f64, // days
+    pub percentage: f64,
+    pub wall_time: f64, // seconds since start/resume
+    pub steps: u64,
+    pub steps_per_second: f64,
+    pub eta: Option<f64>, // seconds, unknown until the simulation advances
+    pub relative_energy_error: f64, // since start
+    pub relative_angular_momentum_error: f64, // since start
+    pub time_step: f64,
+    pub time_step_min: f64, // since the previous record
+    pub time_step_max: f64,
+    pub time_step_mean: f64,
+    pub rejected_steps: u64,
+    pub iterations_max_exceeded: u64,
+    pub stop_reason: Option<String>, // Reason code of the stopping condition that was met
+}
+
+/// Writes a progress record every `period` of execution time (plus a final one)
+pub struct ProgressReporter {
+    writer: Box<dyn Write>,
+    period: Duration,
+    start_instant: Instant,
+    last_report_instant: Instant,
+    start_time: f64,
+    start_n_steps: u64,
+    conservation_reference: ConservationReference,
+    time_step_min: f64,
+    time_step_max: f64,
+    time_step_sum: f64,
+    n_time_step_samples: u64,
+}
+
+impl ProgressReporter {
+    pub fn new(writer: Box<dyn Write>, period: Duration, universe_integrator: &mut dyn Integrator) -> ProgressReporter {
+        let conservation_reference = initialize_conservation_reference(universe_integrator);
+        ProgressReporter {
+            writer,
+            period,
+            start_instant: Instant::now(),
+            last_report_instant: Instant::now(),
+            start_time: universe_integrator.get_current_time(),
+            start_n_steps: universe_integrator.get_statistics().n_steps,
+            conservation_reference,
+            time_step_min: f64::INFINITY,
+            time_step_max: 0.,
+            time_step_sum: 0.,
+            n_time_step_samples: 0,
+        }
+    }
+
+    /// To be called after every iteration, it writes a record only when the period has elapsed
+    pub fn update(&mut self, universe_integrator: &dyn Integrator) {
+        let time_step = universe_integrator.get_time_step();
+        self.time_step_min = self.time_step_min.min(time_step);
+        self.time_step_max = self.time_step_max.max(time_step);
+        self.time_step_sum += time_step;
+        self.n_time_step_samples += 1;
+        if self.last_report_instant.elapsed() >= self.period {
+            self.report(universe_integrator, "running");
+        }
+    }
+
+    pub fn report(&mut self, universe_integrator: &dyn Integrator, status: &str) {
+        let record = self.record(universe_integrator, status);
+        let mut line = serde_json::to_string(&record).unwrap();
+        line.push('\n');
+        // Progress is informative, a broken channel must not stop the simulation
+        let _ = self.writer.write_all(line.as_bytes());
+        let _ = self.writer.flush();
+        self.last_report_instant = Instant::now();
+        self.time_step_min = f64::INFINITY;
+        self.time_step_max = 0.;
+        self.time_step_sum = 0.;
+        self.n_time_step_samples = 0;
+    }
+
+    fn record(&self, universe_integrator: &dyn Integrator, status: &str) -> ProgressRecord {
+        let statistics = universe_integrator.get_statistics();
+        let current_time = universe_integrator.get_current_time();
+        let time_limit = universe_integrator.get_universe().time_limit;
+        let wall_time = self.start_instant.elapsed().as_secs_f64();
+        let steps = statistics.n_steps.saturating_sub(self.start_n_steps);
+        let simulated_time = current_time - self.start_time;
+        let eta = if simulated_time > 0. && wall_time > 0. {
+            Some(((time_limit - current_time).max(0.) / simulated_time) * wall_time)
+        } else {
+            None
+        };
+        let (energy, angular_momentum) = calculate_energy_and_angular_momentum(universe_integrator);
+        let (time_step_min, time_step_max, time_step_mean) = if self.n_time_step_samples > 0 {
+            (self.time_step_min, self.time_step_max, self.time_step_sum / self.n_time_step_samples as f64)
+        } else {
+            (statistics.time_step, statistics.time_step, statistics.time_step)
+        };
+        ProgressRecord {
+            utc: utc_timestamp(),
+            status: status.to_string(),
+            current_time,
+            time_limit,
+            percentage: if time_limit > 0. { 100. * current_time / time_limit } else { 100. },
+            wall_time,
+            steps,
+            steps_per_second: if wall_time > 0. { steps as f64 / wall_time } else { 0. },
+            eta,
+            relative_energy_error: relative_error(energy, self.conservation_reference.energy),
+            relative_angular_momentum_error: relative_error(angular_momentum, self.conservation_reference.angular_momentum),
+            time_step: statistics.time_step,
+            time_step_min,
+            time_step_max,
+            time_step_mean,
+            rejected_steps: statistics.n_rejected_steps,
+            iterations_max_exceeded: statistics.n_iterations_max_exceeded,
+            stop_reason: universe_integrator.get_stopping_conditions().stop_reason.map(|stop_reason| stop_reason.condition.reason_code().to_string()),
+        }
+    }
+}
+
+/// Conservation reference of the simulation, which is computed from the current state and kept by
+/// the integrator if it does not have one yet (the one of resumed simulations comes from their snapshot)
+pub fn initialize_conservation_reference(universe_integrator: &mut dyn Integrator) -> ConservationReference {
+    match universe_integrator.get_conservation_reference() {
+        Some(conservation_reference) => conservation_reference,
+        None => {
+            let (energy, angular_momentum) = calculate_energy_and_angular_momentum(&*universe_integrator);
+            let conservation_reference = ConservationReference { energy, angular_momentum };
+            universe_integrator.set_conservation_reference(conservation_reference);
+            conservation_reference
+        },
+    }
+}
+
+fn relative_error(value: f64, initial_value: f64) -> f64 {
+    if initial_value != 0. {
+        ((value - initial_value) / initial_value).abs()
+    } else {
+        0.
+    }
+}
+
+fn calculate_energy_and_angular_momentum(universe_integrator: &dyn Integrator) -> (f64, f64) {
+    // Heliocentric coordinates are only updated when snapshots are written
+    let mut universe = universe_integrator.get_universe().clone();
+    universe.inertial_to_heliocentric();
+    (universe.compute_total_energy(), universe.compute_total_angular_momentum())
+}
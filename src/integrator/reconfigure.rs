@@ -0,0 +1,60 @@
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use super::Integrator;
+use super::whfast::{WHFast, CoordinatesType};
+use super::{Ias15, LeapFrog};
+use super::stream::OutputStream;
+use super::events::EventTimeline;
+use super::stopping::StoppingConditions;
+use super::particle_set::ParticleSetChange;
+use super::progress::ConservationReference;
+use super::snapshot::IntegratorType;
+
+/// State of a simulation that does not depend on the integrator and that
+/// must be carried over when the integrator is replaced (e.g., to continue
+/// appending to the same historic snapshot file)
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryCounters {
+    pub current_time: f64,
+    pub current_iteration: u64,
+    pub last_recovery_snapshot_time: f64,
+    pub last_historic_snapshot_time: f64,
+    pub n_historic_snapshots: usize,
+    pub output_streams: Vec<OutputStream>,
+    pub event_timeline: EventTimeline,
+    pub stopping_conditions: StoppingConditions,
+    pub particle_set_changes: Vec<ParticleSetChange>,
+    pub conservation_reference: Option<ConservationReference>,
+}
+
+/// Build a new integrator of the given type from the universe and history
+/// counters of an existing one (the integrator internal data structures are
+/// re-initialized). The WHFast coordinates type is only used if the
+/// new integrator is WHFast, converting a WHFast integrator into WHFast
+/// just switches its coordinates type.
+pub fn convert_integrator(universe_integrator: &dyn Integrator, integrator_type: IntegratorType, alternative_coordinates_type: CoordinatesType) -> Box<dyn Integrator> {
+    if integrator_type == IntegratorType::WHFast {
+        if let Some(whfast) = universe_integrator.as_any().downcast_ref::<WHFast>() {
+            let mut whfast = deep_copy(whfast);
+            whfast.set_alternative_coordinates_type(alternative_coordinates_type);
+            return Box::new(whfast);
+        }
+    }
+    let time_step = universe_integrator.get_time_step();
+    let (historic_snapshot_period, recovery_snapshot_period) = universe_integrator.get_snapshot_periods();
+    let universe = deep_copy(universe_integrator.get_universe());
+    let mut converted_universe_integrator: Box<dyn Integrator> = match integrator_type {
+        IntegratorType::WHFast => Box::new(WHFast::new(time_step, recovery_snapshot_period, historic_snapshot_period, universe, alternative_coordinates_type)),
+        IntegratorType::Ias15 => Box::new(Ias15::new(time_step, recovery_snapshot_period, historic_snapshot_period, universe)),
+        IntegratorType::LeapFrog => Box::new(LeapFrog::new(time_step, recovery_snapshot_period, historic_snapshot_period, universe)),
+    };
+    log_info!("The integrator changed from {:?} to {:?}", universe_integrator.get_integrator_type(), integrator_type);
+    converted_universe_integrator.set_history_counters(universe_integrator.get_history_counters());
+    converted_universe_integrator
+}
+
+// Cloned evolvers are downgraded to non-evolving (see `Evolver::clone`), thus
+// the universe is copied through its serialized representation
+fn deep_copy<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap()
+}
@@ -0,0 +1,236 @@
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use super::Integrator;
+use super::whfast::WHFast;
+use super::ias15::Ias15;
+use super::leapfrog::LeapFrog;
+use super::legacy::{migrate_legacy_bin_integrator, guess_legacy_bin_integrator_type, add_migrated_love_number_tables};
+use super::super::effects::{LoveNumberTable, LoveNumberData};
+
+/// Version of the recovery snapshot format. It must be increased every time
+/// a change in the integrators/universe makes older snapshots unreadable, and
+/// a migration step from the previous version must be added below.
+///
+/// - Version 0: integrator serialized without envelope (also used by the JSON cases generated by python)
+/// - Version 1: envelope with format version, integrator type and content hash of the serialized integrator
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+// Identifies binary snapshots with envelope (version 0 snapshots directly start with the integrator data)
+pub const SNAPSHOT_MAGIC: [u8; 8] = *b"POSIDNUS";
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum IntegratorType {
+    WHFast,
+    Ias15,
+    LeapFrog,
+}
+
+/// Integrators that can be stored in a recovery snapshot
+pub trait TaggedSnapshot {
+    fn integrator_type(&self) -> IntegratorType;
+}
+
+impl TaggedSnapshot for WHFast {
+    fn integrator_type(&self) -> IntegratorType {
+        IntegratorType::WHFast
+    }
+}
+
+impl TaggedSnapshot for Ias15 {
+    fn integrator_type(&self) -> IntegratorType {
+        IntegratorType::Ias15
+    }
+}
+
+impl TaggedSnapshot for LeapFrog {
+    fn integrator_type(&self) -> IntegratorType {
+        IntegratorType::LeapFrog
+    }
+}
+
+/// Binary snapshots: header followed by the bincode serialized integrator and its content hash
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotHeader {
+    pub magic: [u8; 8],
+    pub format_version: u32,
+    pub integrator_type: IntegratorType,
+}
+
+impl SnapshotHeader {
+    pub fn new(integrator_type: IntegratorType) -> SnapshotHeader {
+        SnapshotHeader {
+            magic: SNAPSHOT_MAGIC,
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            integrator_type,
+        }
+    }
+}
+
+/// JSON snapshots: {"format_version": 1, "integrator_type": "WHFast", "content_hash": 123, "integrator": {...}}
+#[derive(Debug, Serialize)]
+pub struct JsonSnapshotEnvelope<'a, I: Serialize> {
+    pub format_version: u32,
+    pub integrator_type: IntegratorType,
+    pub content_hash: u64,
+    pub integrator: &'a I,
+}
+
+/// 64-bit FNV-1a hash of the serialized integrator (fast enough to be computed
+/// for every recovery snapshot and sensitive to any corrupted byte)
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes.iter() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// JSON numbers can be written with different (but equivalent) representations,
+/// thus the hash is computed over the compact serialization of the parsed integrator
+pub fn json_content_hash(integrator: &Value) -> u64 {
+    content_hash(serde_json::to_string(integrator).unwrap().as_bytes())
+}
+
+pub fn deserialize_integrator_from_json_value(integrator_type: IntegratorType, value: Value) -> Result<Box<dyn Integrator>, String> {
+    match integrator_type {
+        IntegratorType::WHFast => serde_json::from_value::<WHFast>(value).map(|integrator| Box::new(integrator) as Box<dyn Integrator>),
+        IntegratorType::Ias15 => serde_json::from_value::<Ias15>(value).map(|integrator| Box::new(integrator) as Box<dyn Integrator>),
+        IntegratorType::LeapFrog => serde_json::from_value::<LeapFrog>(value).map(|integrator| Box::new(integrator) as Box<dyn Integrator>),
+    }.map_err(|why| format!("Couldn't deserialize {:?} integrator: {}", integrator_type, why))
+}
+
+pub fn deserialize_integrator_from_bin<R: std::io::Read>(integrator_type: IntegratorType, reader: &mut R) -> Result<Box<dyn Integrator>, String> {
+    match integrator_type {
+        IntegratorType::WHFast => bincode::deserialize_from::<_, WHFast>(reader).map(|integrator| Box::new(integrator) as Box<dyn Integrator>),
+        IntegratorType::Ias15 => bincode::deserialize_from::<_, Ias15>(reader).map(|integrator| Box::new(integrator) as Box<dyn Integrator>),
+        IntegratorType::LeapFrog => bincode::deserialize_from::<_, LeapFrog>(reader).map(|integrator| Box::new(integrator) as Box<dyn Integrator>),
+    }.map_err(|why| format!("Couldn't deserialize {:?} integrator: {}", integrator_type, why))
+}
+
+/// Exact binary representation of an integrator (e.g., to verify that a conversion
+/// did not alter a single bit of the simulation state)
+pub fn serialize_integrator_to_bin(universe_integrator: &dyn Integrator) -> Vec<u8> {
+    let any = universe_integrator.as_any();
+    match universe_integrator.get_integrator_type() {
+        IntegratorType::WHFast => bincode::serialize(any.downcast_ref::<WHFast>().unwrap()),
+        IntegratorType::Ias15 => bincode::serialize(any.downcast_ref::<Ias15>().unwrap()),
+        IntegratorType::LeapFrog => bincode::serialize(any.downcast_ref::<LeapFrog>().unwrap()),
+    }.unwrap()
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//- Migrations
+////////////////////////////////////////////////////////////////////////////////
+
+/// Integrator of a version 0 snapshot (no envelope) given by the fields that only it has
+pub fn guess_json_integrator_type(value: &Value) -> Option<IntegratorType> {
+    if value.get("particles_alternative_coordinates").is_some() {
+        Some(IntegratorType::WHFast)
+    } else if value.get("b_0").is_some() {
+        Some(IntegratorType::Ias15)
+    } else if value.get("half_time_step").is_some() {
+        Some(IntegratorType::LeapFrog)
+    } else {
+        None
+    }
+}
+
+/// Integrator of a version 0 snapshot (no envelope) given by the frozen layout that reads all its bytes
+pub fn guess_bin_integrator_type(bytes: &[u8]) -> Option<IntegratorType> {
+    guess_legacy_bin_integrator_type(bytes)
+}
+
+/// Upgrade the integrator data of a JSON snapshot from `format_version` to
+/// `SNAPSHOT_FORMAT_VERSION`, one version at a time.
+pub fn migrate_json_snapshot(format_version: u32, integrator_type: IntegratorType, mut integrator: Value) -> Result<Value, String> {
+    if format_version > SNAPSHOT_FORMAT_VERSION {
+        return Err(format!("Snapshot format version {} is newer than the supported version {}, please upgrade posidonius", format_version, SNAPSHOT_FORMAT_VERSION));
+    }
+    let mut version = format_version;
+    while version < SNAPSHOT_FORMAT_VERSION {
+        integrator = match version {
+            // Version 0 to 1: the fixed-size Love number tables of the Kaula parameters become Love number
+            // tables of the universe (the fields that were added since then are filled with their defaults
+            // and the ones that were removed are skipped)
+            0 => move_kaula_love_number_tables(integrator)?,
+            _ => return Err(format!("No migration available from snapshot format version {} ({:?} integrator)", version, integrator_type)),
+        };
+        version += 1;
+    }
+    Ok(integrator)
+}
+
+/// Love number tables of the Kaula models of the particles and tidal pairs moved to the universe
+/// (same rules as for binary snapshots, see `add_migrated_love_number_tables`)
+fn move_kaula_love_number_tables(mut integrator: Value) -> Result<Value, String> {
+    let universe = match integrator.get_mut("universe") {
+        Some(universe) => universe,
+        None => return Ok(integrator),
+    };
+    let kaula_love_number_data = |tidal_model: &Value| -> Option<LoveNumberData> {
+        let params = tidal_model.get("Kaula")?;
+        let column = |name: &str| params.get(name)?.as_array()?.iter().map(Value::as_f64).collect::<Option<Vec<f64>>>();
+        LoveNumberData::from_fixed_size_tables(&column("love_number_excitation_frequency")?, &column("real_part_love_number")?, &column("imaginary_part_love_number")?)
+    };
+    let n_particles = universe.get("n_particles").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let particles = universe.get("particles").and_then(Value::as_array).cloned().unwrap_or_default();
+    let tidal_pairs = universe.get("tidal_pairs").and_then(Value::as_array).cloned().unwrap_or_default();
+    let mut love_number_data = Vec::new();
+    for (index, particle) in particles.iter().take(n_particles).enumerate() {
+        let effect = particle.pointer("/tides/effect");
+        let tidal_model = effect.and_then(|effect| effect.get("CentralBody").or_else(|| effect.get("OrbitingBody")));
+        if let Some(data) = tidal_model.and_then(kaula_love_number_data) {
+            love_number_data.push((index, data));
+        }
+    }
+    for tidal_pair in tidal_pairs.iter() {
+        for (index_field, tidal_model_field) in [("host", "host_tidal_model"), ("orbiting", "orbiting_tidal_model")].iter() {
+            let index = tidal_pair.get(index_field).and_then(Value::as_u64);
+            let data = tidal_pair.get(tidal_model_field).and_then(kaula_love_number_data);
+            if let (Some(index), Some(data)) = (index, data) {
+                love_number_data.push((index as usize, data));
+            }
+        }
+    }
+    let mut love_number_tables: Vec<LoveNumberTable> = match universe.get("love_number_tables") {
+        Some(love_number_tables) => serde_json::from_value(love_number_tables.clone()).map_err(|why| format!("Couldn't read the Love number tables: {}", why))?,
+        None => Vec::new(),
+    };
+    let interior_structures: Vec<usize> = universe.get("interior_structures").and_then(Value::as_array).map(|interior_structures| {
+        interior_structures.iter().filter_map(|interior_structure| interior_structure.get("particle").and_then(Value::as_u64)).map(|index| index as usize).collect()
+    }).unwrap_or_default();
+    add_migrated_love_number_tables(&mut love_number_tables, &interior_structures, love_number_data);
+    universe["love_number_tables"] = serde_json::to_value(&love_number_tables).unwrap();
+    Ok(integrator)
+}
+
+/// Binary snapshots are not self-describing, older versions are read with the frozen layout
+/// of their own version and upgraded to the current one (see legacy.rs).
+pub fn migrate_bin_snapshot(format_version: u32, integrator_type: IntegratorType, bytes: &[u8]) -> Result<Box<dyn Integrator>, String> {
+    if format_version > SNAPSHOT_FORMAT_VERSION {
+        return Err(format!("Snapshot format version {} is newer than the supported version {}, please upgrade posidonius", format_version, SNAPSHOT_FORMAT_VERSION));
+    }
+    if format_version == SNAPSHOT_FORMAT_VERSION {
+        return deserialize_integrator_from_bin(integrator_type, &mut &bytes[..]);
+    }
+    let integrator = migrate_legacy_bin_integrator(format_version, integrator_type, bytes)?;
+    log_info!("Migrated snapshot from format version {} to {}.", format_version, SNAPSHOT_FORMAT_VERSION);
+    Ok(integrator)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_hash_detects_single_byte_changes() {
+        let bytes = b"posidonius".to_vec();
+        let mut corrupted_bytes = bytes.clone();
+        corrupted_bytes[3] ^= 0x01;
+        assert_eq!(content_hash(&[]), 0xcbf29ce484222325);
+        assert_eq!(content_hash(&bytes), content_hash(&bytes.clone()));
+        assert_ne!(content_hash(&bytes), content_hash(&corrupted_bytes));
+    }
+}
@@ -0,0 +1,311 @@
+use std::fmt;
+use std::time::Instant;
+use serde::{Serialize, Deserialize};
+use super::super::particles::Universe;
+use super::super::constants::TWO_PI;
+use super::output::{calculate_keplerian_orbital_elements_around_reference, reference_particle_index};
+
+/// Conditions that end a simulation before its time limit
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum StoppingCondition {
+    SemiMajorAxisBelow {
+        particle: usize, // index in the universe
+        semi_major_axis: f64, // AU
+    },
+    // Unbound orbits (eccentricity >= 1) are also considered above the threshold
+    SemiMajorAxisAbove {
+        particle: usize,
+        semi_major_axis: f64, // AU
+    },
+    EccentricityAbove {
+        particle: usize,
+        eccentricity: f64,
+    },
+    // Relative difference between the spin rate and the orbital mean motion
+    SpinSynchronization {
+        particle: usize,
+        tolerance: f64,
+    },
+    // Orbital period of the outer particle divided by the one of the inner particle
+    PeriodRatioWindow {
+        inner_particle: usize,
+        outer_particle: usize,
+        min_period_ratio: f64,
+        max_period_ratio: f64,
+    },
+    // Relative to the total energy at the first evaluation
+    EnergyError {
+        tolerance: f64,
+    },
+    // Execution time accumulated over all the runs (start and resumes)
+    WallTime {
+        seconds: f64,
+    },
+}
+
+impl StoppingCondition {
+    /// Short identifier reported in the logs, progress records and snapshots
+    pub fn reason_code(&self) -> &'static str {
+        match self {
+            StoppingCondition::SemiMajorAxisBelow { .. } => "semi_major_axis_below",
+            StoppingCondition::SemiMajorAxisAbove { .. } => "semi_major_axis_above",
+            StoppingCondition::EccentricityAbove { .. } => "eccentricity_above",
+            StoppingCondition::SpinSynchronization { .. } => "spin_synchronization",
+            StoppingCondition::PeriodRatioWindow { .. } => "period_ratio_window",
+            StoppingCondition::EnergyError { .. } => "energy_error",
+            StoppingCondition::WallTime { .. } => "wall_time",
+        }
+    }
+
+    /// Process exit code when the condition stops the simulation (e.g., for batch scripts
+    /// of parameter surveys), it does not overlap with the signal ones (128 + signal number)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            StoppingCondition::SemiMajorAxisBelow { .. } => 10,
+            StoppingCondition::SemiMajorAxisAbove { .. } => 11,
+            StoppingCondition::EccentricityAbove { .. } => 12,
+            StoppingCondition::SpinSynchronization { .. } => 13,
+            StoppingCondition::PeriodRatioWindow { .. } => 14,
+            StoppingCondition::EnergyError { .. } => 15,
+            StoppingCondition::WallTime { .. } => 16,
+        }
+    }
+
+    fn particles(&self) -> Vec<usize> {
+        match *self {
+            StoppingCondition::SemiMajorAxisBelow { particle, .. } | StoppingCondition::SemiMajorAxisAbove { particle, .. }
+                | StoppingCondition::EccentricityAbove { particle, .. } | StoppingCondition::SpinSynchronization { particle, .. } => vec![particle],
+            StoppingCondition::PeriodRatioWindow { inner_particle, outer_particle, .. } => vec![inner_particle, outer_particle],
+            StoppingCondition::EnergyError { .. } | StoppingCondition::WallTime { .. } => vec![],
+        }
+    }
+
+    fn particles_mut(&mut self) -> Vec<&mut usize> {
+        match self {
+            StoppingCondition::SemiMajorAxisBelow { particle, .. } | StoppingCondition::SemiMajorAxisAbove { particle, .. }
+                | StoppingCondition::EccentricityAbove { particle, .. } | StoppingCondition::SpinSynchronization { particle, .. } => vec![particle],
+            StoppingCondition::PeriodRatioWindow { inner_particle, outer_particle, .. } => vec![inner_particle, outer_particle],
+            StoppingCondition::EnergyError { .. } | StoppingCondition::WallTime { .. } => vec![],
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub enum StoppingEvaluation {
+    #[default]
+    EveryStep,
+    HistoricSnapshot, // Only when a historic snapshot has been written (cheaper)
+}
+
+/// Condition that stopped the simulation and the value that triggered it
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StopReason {
+    pub condition: StoppingCondition,
+    pub time: f64, // days
+    pub value: f64, // AU, eccentricity, relative difference, period ratio, relative error or seconds
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Stopping condition '{}' met at {} days (value {})", self.condition.reason_code(), self.time, self.value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct StoppingConditions {
+    pub conditions: Vec<StoppingCondition>,
+    pub evaluation: StoppingEvaluation,
+    pub reference_energy: Option<f64>,
+    pub elapsed_wall_time: f64, // seconds
+    pub stop_reason: Option<StopReason>,
+    #[serde(skip)]
+    last_evaluation: LastEvaluation, // Not stored in snapshots, each run measures its own wall time
+}
+
+/// Instant of the previous evaluation of the current run
+#[derive(Clone, Copy, Default)]
+struct LastEvaluation(Option<Instant>);
+
+// It does not make two simulations different
+impl PartialEq for LastEvaluation {
+    fn eq(&self, _other: &LastEvaluation) -> bool {
+        true
+    }
+}
+
+impl fmt::Debug for LastEvaluation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LastEvaluation")
+    }
+}
+
+impl StoppingConditions {
+    pub fn new(conditions: Vec<StoppingCondition>, evaluation: StoppingEvaluation) -> StoppingConditions {
+        StoppingConditions {
+            conditions,
+            evaluation,
+            reference_energy: None,
+            elapsed_wall_time: 0.,
+            stop_reason: None,
+            last_evaluation: LastEvaluation::default(),
+        }
+    }
+
+    /// To be called by the integrators after every iteration, the wall time increment is the
+    /// time since the previous iteration of the current run
+    pub fn evaluate_iteration(&mut self, universe: &Universe, current_time: f64, historic_snapshot_written: bool) -> Option<StopReason> {
+        if self.conditions.is_empty() {
+            return None;
+        }
+        let now = Instant::now();
+        let wall_time_increment = self.last_evaluation.0.map_or(0., |last_evaluation| now.duration_since(last_evaluation).as_secs_f64());
+        self.last_evaluation = LastEvaluation(Some(now));
+        self.evaluate(universe, current_time, wall_time_increment, historic_snapshot_written)
+    }
+
+    /// Resuming a stopped simulation evaluates the conditions again (e.g., after changing them)
+    pub fn clear_stop_reason(&mut self) {
+        self.stop_reason = None;
+    }
+
+    /// The wall time is always accumulated and checked but the rest of conditions depend on the
+    /// evaluation mode. The first condition that is met is recorded as the stop reason and returned.
+    /// Orbits are computed from the inertial coordinates, which are always up to date.
+    pub fn evaluate(&mut self, universe: &Universe, current_time: f64, wall_time_increment: f64, historic_snapshot_written: bool) -> Option<StopReason> {
+        if self.conditions.is_empty() {
+            return None;
+        }
+        if self.stop_reason.is_some() {
+            return self.stop_reason;
+        }
+        self.elapsed_wall_time += wall_time_increment;
+        let evaluate_orbits = self.evaluation == StoppingEvaluation::EveryStep || historic_snapshot_written;
+        for condition in self.conditions.iter() {
+            if let StoppingCondition::WallTime { seconds } = *condition {
+                if self.elapsed_wall_time >= seconds {
+                    self.stop_reason = Some(StopReason { condition: *condition, time: current_time, value: self.elapsed_wall_time });
+                    break;
+                }
+                continue;
+            }
+            if !evaluate_orbits {
+                continue;
+            }
+            let value = match *condition {
+                StoppingCondition::EnergyError { .. } => {
+                    let energy = compute_total_energy(universe);
+                    let reference_energy = *self.reference_energy.get_or_insert(energy);
+                    if reference_energy != 0. { ((energy - reference_energy) / reference_energy).abs() } else { 0. }
+                },
+                _ => evaluate_orbital_value(universe, condition),
+            };
+            let triggered = match *condition {
+                StoppingCondition::SemiMajorAxisBelow { semi_major_axis, .. } => value < semi_major_axis,
+                StoppingCondition::SemiMajorAxisAbove { particle, semi_major_axis } => value > semi_major_axis || eccentricity(universe, particle) >= 1.,
+                StoppingCondition::EccentricityAbove { eccentricity, .. } => value > eccentricity,
+                StoppingCondition::SpinSynchronization { tolerance, .. } => value <= tolerance,
+                StoppingCondition::PeriodRatioWindow { min_period_ratio, max_period_ratio, .. } => value >= min_period_ratio && value <= max_period_ratio,
+                StoppingCondition::EnergyError { tolerance } => value > tolerance,
+                StoppingCondition::WallTime { .. } => false,
+            };
+            if triggered {
+                self.stop_reason = Some(StopReason { condition: *condition, time: current_time, value });
+                break;
+            }
+        }
+        self.stop_reason
+    }
+
+    /// Conditions that refer to a removed particle are discarded and the ones that refer
+    /// to the following particles are updated
+    pub fn remove_particle(&mut self, index: usize) {
+        self.conditions.retain(|condition| {
+            let refers_to_removed_particle = condition.particles().contains(&index);
+            if refers_to_removed_particle {
+                log_warning!("Discarded stopping condition {:?} because particle {} was removed", condition, index);
+            }
+            !refers_to_removed_particle
+        });
+        for condition in self.conditions.iter_mut() {
+            for particle in condition.particles_mut() {
+                if *particle > index {
+                    *particle -= 1;
+                }
+            }
+        }
+    }
+}
+
+/// Same as `Universe::compute_total_energy` but with velocities relative to the most massive particle
+/// computed from the inertial ones (heliocentric coordinates are only updated when needed)
+fn compute_total_energy(universe: &Universe) -> f64 {
+    let particles = &universe.particles[..universe.n_particles];
+    let host_velocity = universe.particles[universe.hosts.index.most_massive].inertial_velocity;
+    let mut e_kin = 0.;
+    let mut e_pot = 0.;
+    for particle in particles.iter() {
+        e_kin += 0.5 * particle.mass * ((particle.inertial_velocity.x - host_velocity.x).powi(2) + (particle.inertial_velocity.y - host_velocity.y).powi(2) + (particle.inertial_velocity.z - host_velocity.z).powi(2));
+    }
+    for (i, particle_a) in particles.iter().enumerate() {
+        for particle_b in particles[i+1..].iter() {
+            let dx = particle_a.inertial_position.x - particle_b.inertial_position.x;
+            let dy = particle_a.inertial_position.y - particle_b.inertial_position.y;
+            let dz = particle_a.inertial_position.z - particle_b.inertial_position.z;
+            e_pot -= particle_b.mass_g*particle_a.mass/(dx.powi(2) + dy.powi(2) + dz.powi(2)).sqrt();
+        }
+    }
+    e_kin + e_pot
+}
+
+fn eccentricity(universe: &Universe, particle: usize) -> f64 {
+    let (_semimajor_axis, _perihelion_distance, eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, _orbital_period) = calculate_keplerian_orbital_elements_around_reference(universe, &universe.particles[particle]);
+    eccentricity
+}
+
+fn evaluate_orbital_value(universe: &Universe, condition: &StoppingCondition) -> f64 {
+    match *condition {
+        StoppingCondition::SemiMajorAxisBelow { particle, .. } | StoppingCondition::SemiMajorAxisAbove { particle, .. } => {
+            let (semimajor_axis, _perihelion_distance, _eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, _orbital_period) = calculate_keplerian_orbital_elements_around_reference(universe, &universe.particles[particle]);
+            semimajor_axis
+        },
+        StoppingCondition::EccentricityAbove { particle, .. } => eccentricity(universe, particle),
+        StoppingCondition::SpinSynchronization { particle, .. } => {
+            let (_semimajor_axis, _perihelion_distance, _eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, orbital_period) = calculate_keplerian_orbital_elements_around_reference(universe, &universe.particles[particle]);
+            let mean_motion = TWO_PI / orbital_period; // rad/day
+            let spin_rate = universe.particles[particle].norm_spin_vector_2.sqrt();
+            ((spin_rate - mean_motion) / mean_motion).abs()
+        },
+        StoppingCondition::PeriodRatioWindow { inner_particle, outer_particle, .. } => {
+            let (_semimajor_axis, _perihelion_distance, _eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, inner_orbital_period) = calculate_keplerian_orbital_elements_around_reference(universe, &universe.particles[inner_particle]);
+            let (_semimajor_axis, _perihelion_distance, _eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, outer_orbital_period) = calculate_keplerian_orbital_elements_around_reference(universe, &universe.particles[outer_particle]);
+            outer_orbital_period / inner_orbital_period
+        },
+        StoppingCondition::EnergyError { .. } | StoppingCondition::WallTime { .. } => 0.,
+    }
+}
+
+/// Errors that would make the evaluation of a condition meaningless
+pub fn validate_stopping_conditions(stopping_conditions: &StoppingConditions, universe: &Universe) -> Vec<String> {
+    let mut errors = Vec::new();
+    for condition in stopping_conditions.conditions.iter() {
+        for particle in condition.particles() {
+            if particle >= universe.n_particles {
+                errors.push(format!("Stopping condition {:?} refers to particle {} but there are only {} particles", condition, particle, universe.n_particles));
+            } else if reference_particle_index(universe, &universe.particles[particle]) == particle {
+                errors.push(format!("Stopping condition {:?} refers to particle {} which does not orbit any other particle", condition, particle));
+            }
+        }
+        let invalid_threshold = match *condition {
+            StoppingCondition::SemiMajorAxisBelow { semi_major_axis, .. } | StoppingCondition::SemiMajorAxisAbove { semi_major_axis, .. } => semi_major_axis <= 0.,
+            StoppingCondition::EccentricityAbove { eccentricity, .. } => eccentricity < 0.,
+            StoppingCondition::SpinSynchronization { tolerance, .. } | StoppingCondition::EnergyError { tolerance } => tolerance <= 0.,
+            StoppingCondition::PeriodRatioWindow { min_period_ratio, max_period_ratio, .. } => min_period_ratio <= 0. || min_period_ratio > max_period_ratio,
+            StoppingCondition::WallTime { seconds } => seconds <= 0.,
+        };
+        if invalid_threshold {
+            errors.push(format!("Stopping condition {:?} has an invalid threshold", condition));
+        }
+    }
+    errors
+}
+
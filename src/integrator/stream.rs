@@ -0,0 +1,330 @@
+use serde::{Serialize, Deserialize};
+
+/// Times (in days, same reference as the simulation current time) at which
+/// an output stream writes a snapshot.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum OutputSchedule {
+    // One snapshot every `period` days, starting at the stream start time
+    Linear {
+        period: f64,
+    },
+    // Snapshots at start time + first_time * 10^(k/snapshots_per_decade) for k = 0, 1, 2...
+    Logarithmic {
+        first_time: f64,
+        snapshots_per_decade: f64,
+    },
+    // User-defined (sorted) list of times
+    Explicit {
+        times: Vec<f64>,
+    },
+}
+
+/// Quantities that can be stored in an output stream. Vectors (e.g., position)
+/// are stored as three consecutive values (x, y, z).
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+pub enum OutputField {
+    Position,               // AU (inertial frame)
+    Velocity,               // AU/days (inertial frame)
+    HeliocentricPosition,   // AU
+    HeliocentricVelocity,   // AU/days
+    Spin,
+    Mass,                   // Msun
+    Radius,                 // Rsun
+    RadiusOfGyration2,
+    LoveNumber,
+    ScaledDissipationFactor,
+    LagAngle,
+    DenergyDt,              // Msun.AU^2.day^-3
+    MigrationTimescale,
+    SemiMajorAxis,          // AU (with respect to the particle of reference)
+    Eccentricity,
+    Inclination,            // rad
+    Obliquity,              // rad (angle between the spin and the orbital angular momentum)
+    SpinRate,               // rad/days
+}
+
+impl OutputField {
+    pub fn n_values(&self) -> usize {
+        match self {
+            OutputField::Position | OutputField::Velocity | OutputField::HeliocentricPosition
+                | OutputField::HeliocentricVelocity | OutputField::Spin => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// Time-weighted running mean, minimum and maximum of every stored value of
+/// every particle since the last written snapshot of an averaged stream.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct OutputAccumulator {
+    pub weight: f64, // days
+    pub sum: Vec<f64>,
+    pub min: Vec<f64>,
+    pub max: Vec<f64>,
+}
+
+impl OutputAccumulator {
+    pub fn add(&mut self, values: &[f64], weight: f64) {
+        if self.sum.len() != values.len() {
+            // First sample since the last reset (or the number of particles changed)
+            self.weight = 0.;
+            self.sum = vec![0.; values.len()];
+            self.min = vec![f64::INFINITY; values.len()];
+            self.max = vec![f64::NEG_INFINITY; values.len()];
+        }
+        for (i, value) in values.iter().enumerate() {
+            self.sum[i] += value * weight;
+            self.min[i] = self.min[i].min(*value);
+            self.max[i] = self.max[i].max(*value);
+        }
+        self.weight += weight;
+    }
+
+    pub fn mean(&self, index: usize) -> f64 {
+        if self.weight > 0. {
+            self.sum[index] / self.weight
+        } else {
+            0.
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sum.is_empty()
+    }
+
+    pub fn reset(&mut self) {
+        self.weight = 0.;
+        self.sum.clear();
+        self.min.clear();
+        self.max.clear();
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OutputStream {
+    pub name: String, // Used to build the stream filename from the historic snapshot filename
+    pub schedule: OutputSchedule,
+    pub fields: Vec<OutputField>,
+    pub start_time: f64, // days
+    pub stop_time: f64, // days (use a negative value to write until the end of the simulation)
+    next_index: usize, // Index of the next scheduled time
+    pub n_snapshots: usize,
+    #[serde(default)]
+    pub averaged: bool, // Write mean, minimum and maximum over each output interval instead of instantaneous values
+    #[serde(default)]
+    pub accumulator: OutputAccumulator,
+}
+
+impl OutputStream {
+    pub fn new(name: &str, schedule: OutputSchedule, fields: Vec<OutputField>, start_time: f64, stop_time: f64, averaged: bool) -> OutputStream {
+        let schedule = match schedule {
+            OutputSchedule::Explicit { mut times } => {
+                // NaN times are sorted last and rejected by the validation
+                times.sort_by(|a, b| a.total_cmp(b));
+                OutputSchedule::Explicit { times }
+            },
+            schedule => schedule,
+        };
+        let output_stream = OutputStream {
+            name: name.to_string(),
+            schedule,
+            fields,
+            start_time,
+            stop_time,
+            next_index: 0,
+            n_snapshots: 0,
+            averaged,
+            accumulator: OutputAccumulator::default(),
+        };
+        if let Err(why) = output_stream.validate() {
+            fatal!("{}", why);
+        }
+        output_stream
+    }
+
+    /// Streams of the cases generated by python are not built with `OutputStream::new`,
+    /// thus they are also validated when the case is loaded (see `validate_output_streams`)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() || self.name.contains(std::path::MAIN_SEPARATOR) || self.name.contains('.') {
+            return Err(format!("Invalid output stream name '{}' (it cannot be empty nor contain dots or path separators)", self.name));
+        }
+        if self.fields.is_empty() {
+            return Err(format!("Output stream '{}' does not have any field to store", self.name));
+        }
+        match &self.schedule {
+            OutputSchedule::Linear { period } => {
+                if !(period.is_finite() && *period > 0.) {
+                    return Err(format!("Output stream '{}' has a non-positive period", self.name));
+                }
+            },
+            OutputSchedule::Logarithmic { first_time, snapshots_per_decade } => {
+                if !(first_time.is_finite() && *first_time > 0. && snapshots_per_decade.is_finite() && *snapshots_per_decade > 0.) {
+                    return Err(format!("Output stream '{}' requires a positive first time and number of snapshots per decade", self.name));
+                }
+            },
+            OutputSchedule::Explicit { times } => {
+                if times.iter().any(|time| time.is_nan()) {
+                    return Err(format!("Output stream '{}' has NaN scheduled times", self.name));
+                }
+                if times.windows(2).any(|pair| pair[0] > pair[1]) {
+                    return Err(format!("Output stream '{}' has unsorted scheduled times", self.name));
+                }
+            },
+        }
+        Ok(())
+    }
+
+    pub fn n_bytes_per_particle(&self) -> u64 {
+        // current_time, time_step, particle id and one f64 per stored value (three if averaged: mean, min, max)
+        let n_values : usize = self.n_values();
+        let n_aggregates = if self.averaged { 3 } else { 1 };
+        8+8+4+8*(n_values as u64)*n_aggregates
+    }
+
+    pub fn n_values(&self) -> usize {
+        self.fields.iter().map(|field| field.n_values()).sum()
+    }
+
+    fn scheduled_time(&self, index: usize) -> Option<f64> {
+        match &self.schedule {
+            // Computed from the index and not accumulated to avoid small deviations
+            OutputSchedule::Linear { period } => Some(self.start_time + (index as f64)*period),
+            OutputSchedule::Logarithmic { first_time, snapshots_per_decade } => Some(self.start_time + first_time * 10_f64.powf((index as f64)/snapshots_per_decade)),
+            OutputSchedule::Explicit { times } => times.get(index).copied(),
+        }
+    }
+
+    pub fn next_time(&self) -> Option<f64> {
+        self.scheduled_time(self.next_index)
+    }
+
+    pub fn is_due(&self, current_time: f64) -> bool {
+        if current_time < self.start_time || (self.stop_time >= 0. && current_time > self.stop_time) {
+            return false;
+        }
+        match self.next_time() {
+            Some(next_time) => next_time <= current_time,
+            None => false,
+        }
+    }
+
+    pub fn is_accumulating(&self, current_time: f64) -> bool {
+        // Averaged streams sample every time step from the start time until the last snapshot
+        self.averaged && current_time >= self.start_time
+            && (self.stop_time < 0. || current_time <= self.stop_time)
+            && self.next_time().is_some()
+    }
+
+    pub fn schedule_next(&mut self, current_time: f64) {
+        // If several scheduled times were covered by a single time step (e.g., the first points of a
+        // logarithmic schedule), only one snapshot is written and the rest are skipped
+        while let Some(next_time) = self.next_time() {
+            if next_time > current_time {
+                break;
+            }
+            self.next_index += 1;
+        }
+    }
+}
+
+/// Problems with the output streams (e.g., from a case generated by python), every stream
+/// must be valid and their names (used for the filenames) must be unique
+pub fn validate_output_streams(output_streams: &[OutputStream]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for (i, output_stream) in output_streams.iter().enumerate() {
+        if let Err(why) = output_stream.validate() {
+            errors.push(why);
+        }
+        if output_streams[..i].iter().any(|previous_output_stream| previous_output_stream.name == output_stream.name) {
+            errors.push(format!("Output stream name '{}' is used more than once", output_stream.name));
+        }
+    }
+    errors
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triggered_times(output_stream: &mut OutputStream, time_step: f64, time_limit: f64) -> Vec<f64> {
+        let mut times = Vec::new();
+        let mut current_time = 0.;
+        while current_time <= time_limit {
+            if output_stream.is_due(current_time) {
+                times.push(current_time);
+                output_stream.schedule_next(current_time);
+            }
+            current_time += time_step;
+        }
+        times
+    }
+
+    #[test]
+    fn linear_schedule_with_stop_time() {
+        let mut output_stream = OutputStream::new("dense", OutputSchedule::Linear { period: 10. }, vec![OutputField::Position], 0., 30., false);
+        assert_eq!(triggered_times(&mut output_stream, 1., 100.), vec![0., 10., 20., 30.]);
+    }
+
+    #[test]
+    fn logarithmic_schedule_skips_times_within_one_step() {
+        let mut output_stream = OutputStream::new("sparse", OutputSchedule::Logarithmic { first_time: 1., snapshots_per_decade: 2. }, vec![OutputField::SemiMajorAxis], 0., -1., false);
+        // Scheduled: 1, 3.16, 10, 31.6, 100
+        assert_eq!(triggered_times(&mut output_stream, 5., 100.), vec![5., 10., 35., 100.]);
+    }
+
+    #[test]
+    fn logarithmic_schedule_from_start_time() {
+        let mut output_stream = OutputStream::new("sparse", OutputSchedule::Logarithmic { first_time: 1., snapshots_per_decade: 1. }, vec![OutputField::SemiMajorAxis], 100., -1., false);
+        // Scheduled: 101, 110, 200
+        assert_eq!(triggered_times(&mut output_stream, 1., 200.), vec![101., 110., 200.]);
+    }
+
+    #[test]
+    fn explicit_schedule_is_sorted() {
+        let mut output_stream = OutputStream::new("events", OutputSchedule::Explicit { times: vec![20., 5.] }, vec![OutputField::Mass, OutputField::Spin], 0., -1., false);
+        assert_eq!(output_stream.n_bytes_per_particle(), 8+8+4+8*4);
+        assert_eq!(triggered_times(&mut output_stream, 5., 100.), vec![5., 20.]);
+    }
+
+    #[test]
+    fn invalid_streams_are_reported() {
+        let output_stream = OutputStream::new("events", OutputSchedule::Explicit { times: vec![5., 20.] }, vec![OutputField::Mass], 0., -1., false);
+        assert!(validate_output_streams(&[output_stream.clone()]).is_empty());
+        assert_eq!(validate_output_streams(&[output_stream.clone(), output_stream.clone()]), vec!["Output stream name 'events' is used more than once".to_string()]);
+        // Streams of the cases generated by python are not built with `OutputStream::new`
+        let mut invalid_output_stream = output_stream.clone();
+        invalid_output_stream.schedule = OutputSchedule::Explicit { times: vec![5., f64::NAN] };
+        assert_eq!(invalid_output_stream.validate(), Err("Output stream 'events' has NaN scheduled times".to_string()));
+        invalid_output_stream.schedule = OutputSchedule::Explicit { times: vec![20., 5.] };
+        assert!(invalid_output_stream.validate().is_err());
+        invalid_output_stream.schedule = OutputSchedule::Linear { period: 0. };
+        assert!(invalid_output_stream.validate().is_err());
+        invalid_output_stream.schedule = OutputSchedule::Logarithmic { first_time: 1., snapshots_per_decade: -1. };
+        assert!(invalid_output_stream.validate().is_err());
+        invalid_output_stream = output_stream;
+        invalid_output_stream.fields.clear();
+        assert!(invalid_output_stream.validate().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "NaN scheduled times")]
+    fn explicit_schedule_with_nan_times() {
+        OutputStream::new("events", OutputSchedule::Explicit { times: vec![f64::NAN, 5.] }, vec![OutputField::Mass], 0., -1., false);
+    }
+
+    #[test]
+    fn averaged_accumulator_is_time_weighted() {
+        let output_stream = OutputStream::new("secular", OutputSchedule::Linear { period: 10. }, vec![OutputField::Eccentricity, OutputField::Spin], 0., -1., true);
+        assert_eq!(output_stream.n_bytes_per_particle(), 8+8+4+8*4*3);
+        let mut accumulator = OutputAccumulator::default();
+        accumulator.add(&[1., -2.], 1.);
+        accumulator.add(&[4., 2.], 2.);
+        assert_eq!(accumulator.mean(0), 3.);
+        assert_eq!(accumulator.mean(1), 2./3.);
+        assert_eq!(accumulator.min, vec![1., -2.]);
+        assert_eq!(accumulator.max, vec![4., 2.]);
+        accumulator.reset();
+        assert!(accumulator.is_empty());
+    }
+}
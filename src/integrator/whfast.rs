@@ -1,4 +1,3 @@
-extern crate time;
 use std;
 use std::iter;
 use std::io::{Write, BufWriter};
@@ -13,11 +12,15 @@ use super::super::particles::IgnoreGravityTerms;
 use super::super::effects::GeneralRelativityImplementation;
 use super::super::effects::EvolutionType;
 use super::super::particles::Axes;
-use super::output::{write_recovery_snapshot, write_historic_snapshot};
-use time::{OffsetDateTime, format_description};
+use super::output::{write_recovery_snapshot, backup_recovery_snapshot, sync_writer, write_historic_snapshot, is_accumulating_output_field, sample_output_streams, accumulate_output_streams, write_output_streams};
+use super::stream::{OutputStream, OutputField};
+use super::progress::{IntegratorStatistics, ConservationReference};
+use super::reconfigure::HistoryCounters;
+use super::events::{EventTimeline, apply_event};
+use super::stopping::StoppingConditions;
+use super::particle_set::ParticleSetChange;
+use super::snapshot::{IntegratorType, TaggedSnapshot};
 use std::path::Path;
-use std::hash::{Hash, Hasher};
-use std::collections::hash_map::DefaultHasher;
 use std::any::Any;
 
 /// Source: Rein & Tamayo, 2015
@@ -107,7 +110,6 @@ pub struct WHFast {
     last_recovery_snapshot_time: f64,
     last_historic_snapshot_time: f64,
     pub n_historic_snapshots: usize,
-    pub hash: u64,
     /// Internal data structures below. Nothing to be changed by the user.
     #[serde(with = "BigArray")]
     particles_alternative_coordinates: [AlternativeCoordinates; MAX_PARTICLES], // Jacobi, democractic-heliocentric or WHDS
@@ -117,6 +119,16 @@ pub struct WHFast {
     inertial_velocity_errors: [Axes; MAX_PARTICLES], // A running compensation for lost low-order bits (Kahan 1965; Higham 2002; Hairer et al. 2006) 
     #[serde(with = "BigArray")]
     particle_angular_momentum_errors: [Axes; MAX_PARTICLES], // A running compensation for lost low-order bits (Kahan 1965; Higham 2002; Hairer et al. 2006) 
+    #[serde(default)]
+    pub event_timeline: EventTimeline, // Scheduled events (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub stopping_conditions: StoppingConditions, // Conditions to end the simulation early (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub particle_set_changes: Vec<ParticleSetChange>, // Particles added/removed during the simulation (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub output_streams: Vec<OutputStream>, // Additional historic outputs with their own schedule, fields and file (kept last, see snapshot format version 1)
+    #[serde(default)]
+    pub conservation_reference: Option<ConservationReference>, // Total energy and angular momentum at the start (kept last, see snapshot format version 1)
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -129,15 +141,6 @@ pub struct AlternativeCoordinates {
     acceleration: Axes,
 }
 
-impl Hash for WHFast {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        // Hash only works with certain types (for instance, it does not work with f64 values)
-        // thus we convert the whole integrator to a string thanks to the debug trait
-        // and we hash that value
-        format!("{:?}", self).hash(state); 
-    }
-}
-
 impl WHFast {
 
     pub fn new(time_step: f64, recovery_snapshot_period: f64, historic_snapshot_period: f64, universe: Universe, alternative_coordinates_type: CoordinatesType) -> WHFast {
@@ -150,7 +153,11 @@ impl WHFast {
                     last_recovery_snapshot_time:-1.,
                     last_historic_snapshot_time:-1.,
                     n_historic_snapshots:0,
-                    hash: 0,
+                    event_timeline: EventTimeline::default(),
+                    stopping_conditions: StoppingConditions::default(),
+                    particle_set_changes: Vec::new(),
+                    output_streams: Vec::new(),
+                    conservation_reference: None,
                     universe:universe,
                     current_time:0.,
                     current_iteration:0,
@@ -164,6 +171,18 @@ impl WHFast {
         universe_integrator
     }
 
+    pub fn get_alternative_coordinates_type(&self) -> CoordinatesType {
+        self.alternative_coordinates_type
+    }
+
+    pub fn set_alternative_coordinates_type(&mut self, alternative_coordinates_type: CoordinatesType) {
+        // Alternative coordinates are re-computed from the inertial ones at the beginning of every step
+        if self.alternative_coordinates_type != alternative_coordinates_type {
+            log_info!("The WHFast coordinates changed from {:?} to {:?}", self.alternative_coordinates_type, alternative_coordinates_type);
+            self.alternative_coordinates_type = alternative_coordinates_type;
+        }
+    }
+
 }
 
 impl Integrator for WHFast {
@@ -184,6 +203,82 @@ impl Integrator for WHFast {
         self.current_time
     }
 
+    fn get_last_historic_snapshot_time(&self) -> f64 {
+        self.last_historic_snapshot_time
+    }
+
+    fn get_output_streams(&self) -> &[OutputStream] {
+        &self.output_streams
+    }
+
+    fn get_event_timeline(&self) -> &EventTimeline {
+        &self.event_timeline
+    }
+
+    fn get_stopping_conditions(&self) -> &StoppingConditions {
+        &self.stopping_conditions
+    }
+
+    fn get_particle_set_changes(&self) -> &[ParticleSetChange] {
+        &self.particle_set_changes
+    }
+
+    fn get_integrator_type(&self) -> IntegratorType {
+        self.integrator_type()
+    }
+
+    fn get_time_step(&self) -> f64 {
+        self.time_step
+    }
+
+    fn get_snapshot_periods(&self) -> (f64, f64) {
+        (self.historic_snapshot_period, self.recovery_snapshot_period)
+    }
+
+    fn get_statistics(&self) -> IntegratorStatistics {
+        IntegratorStatistics {
+            n_steps: self.current_iteration as u64,
+            time_step: self.time_step,
+            n_rejected_steps: 0, // Fixed time step
+            n_iterations_max_exceeded: 0,
+        }
+    }
+
+    fn get_conservation_reference(&self) -> Option<ConservationReference> {
+        self.conservation_reference
+    }
+
+    fn get_history_counters(&self) -> HistoryCounters {
+        HistoryCounters {
+            current_time: self.current_time,
+            current_iteration: self.current_iteration as u64,
+            last_recovery_snapshot_time: self.last_recovery_snapshot_time,
+            last_historic_snapshot_time: self.last_historic_snapshot_time,
+            n_historic_snapshots: self.n_historic_snapshots,
+            output_streams: self.output_streams.clone(),
+            event_timeline: self.event_timeline.clone(),
+            stopping_conditions: self.stopping_conditions.clone(),
+            particle_set_changes: self.particle_set_changes.clone(),
+            conservation_reference: self.conservation_reference,
+        }
+    }
+
+    fn get_universe(&self) -> &Universe {
+        &self.universe
+    }
+
+    fn get_universe_mut(&mut self) -> &mut Universe {
+        &mut self.universe
+    }
+
+    fn to_json_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn write_snapshot(&self, snapshot_path: &Path) -> Result<(), String> {
+        write_recovery_snapshot(snapshot_path, self)
+    }
+
     fn set_time_limit(&mut self, time_limit: f64) {
         if time_limit > 0. && self.universe.time_limit != time_limit {
             if time_limit > self.universe.time_limit && self.universe.consider_effects.evolution {
@@ -202,27 +297,52 @@ impl Integrator for WHFast {
                     panic!("Your new time limit ({} days) is smaller than the current time ({} days)", time_limit, self.current_time);
                 }
             }
-            println!("[INFO {} UTC] The time limit changed from {} to {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.universe.time_limit, time_limit);
+            log_info!("The time limit changed from {} to {} days", self.universe.time_limit, time_limit);
             self.universe.time_limit = time_limit;
         }
     }
 
     fn set_snapshot_periods(&mut self, historic_snapshot_period: f64, recovery_snapshot_period: f64) {
         if historic_snapshot_period > 0. && self.historic_snapshot_period != historic_snapshot_period {
-            println!("[INFO {} UTC] The historic snapshot period changed from {} to {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.historic_snapshot_period, historic_snapshot_period);
+            log_info!("The historic snapshot period changed from {} to {} days", self.historic_snapshot_period, historic_snapshot_period);
             self.historic_snapshot_period = historic_snapshot_period;
         } else {
-            println!("[INFO {} UTC] A historic snapshot will be saved every {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.historic_snapshot_period);
+            log_info!("A historic snapshot will be saved every {} days", self.historic_snapshot_period);
         }
         
         if recovery_snapshot_period > 0. && self.recovery_snapshot_period != recovery_snapshot_period {
-            println!("[INFO {} UTC] The recovery snapshot period changed from {} to {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.recovery_snapshot_period, recovery_snapshot_period);
+            log_info!("The recovery snapshot period changed from {} to {} days", self.recovery_snapshot_period, recovery_snapshot_period);
             self.recovery_snapshot_period = recovery_snapshot_period;
         } else {
-            println!("[INFO {} UTC] A recovery snapshot will be saved every {} days", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), self.recovery_snapshot_period);
+            log_info!("A recovery snapshot will be saved every {} days", self.recovery_snapshot_period);
+        }
+    }
+
+    fn set_time_step(&mut self, time_step: f64) {
+        if time_step > 0. && self.time_step != time_step {
+            log_info!("The time step changed from {} to {} days", self.time_step, time_step);
+            self.time_step = time_step;
+            self.half_time_step = 0.5*time_step;
         }
     }
 
+    fn set_conservation_reference(&mut self, conservation_reference: ConservationReference) {
+        self.conservation_reference = Some(conservation_reference);
+    }
+
+    fn set_history_counters(&mut self, history_counters: HistoryCounters) {
+        self.current_time = history_counters.current_time;
+        self.current_iteration = history_counters.current_iteration as usize;
+        self.last_recovery_snapshot_time = history_counters.last_recovery_snapshot_time;
+        self.last_historic_snapshot_time = history_counters.last_historic_snapshot_time;
+        self.n_historic_snapshots = history_counters.n_historic_snapshots;
+        self.output_streams = history_counters.output_streams;
+        self.event_timeline = history_counters.event_timeline;
+        self.stopping_conditions = history_counters.stopping_conditions;
+        self.particle_set_changes = history_counters.particle_set_changes;
+        self.conservation_reference = history_counters.conservation_reference;
+    }
+
     fn initialize_physical_values(&mut self) {
         if self.current_time != 0. {
             panic!("Physical values cannot be initialized on a resumed simulation");
@@ -230,36 +350,83 @@ impl Integrator for WHFast {
         let evolution = true;
         self.universe.calculate_spin_and_evolving_quantities(self.current_time, evolution); // Make sure we start with the good initial values
         self.universe.calculate_roche_radiuses(); // Needed for collision detection
+        self.universe.calculate_all_love_number_tables(); // Kaula tables of the particles defined by their interior structure
+    }
+
+    fn reset_internal_state(&mut self) {
+        // Compensated summation errors belong to the previous particle set
+        self.inertial_velocity_errors = [Axes{x:0., y:0., z:0. }; MAX_PARTICLES];
+        self.particle_angular_momentum_errors = [Axes{x:0., y:0., z:0. }; MAX_PARTICLES];
+        self.inertial_to_alternative_posvel();
     }
 
-    fn iterate(&mut self, universe_history_writer: &mut BufWriter<File>, silent_mode: bool) -> Result<bool, String> {
+    fn iterate(&mut self, universe_history_writer: &mut BufWriter<File>, output_stream_writers: &mut [BufWriter<File>], silent_mode: bool) -> Result<bool, String> {
+        // Stopped simulations do not advance (see StoppingConditions::clear_stop_reason)
+        if let Some(stop_reason) = self.stopping_conditions.stop_reason {
+            return Err(stop_reason.to_string());
+        }
+        // Scheduled events (applied before the output to record their effect in the history)
+        let due_events = self.event_timeline.take_due_events(self.current_time);
+        for event in due_events.iter() {
+            apply_event(self, event);
+        }
+        let event_trigger = !due_events.is_empty();
+
         // Output
         let first_snapshot_trigger = self.last_historic_snapshot_time < 0.;
         let historic_snapshot_time_trigger = self.last_historic_snapshot_time + self.historic_snapshot_period <= self.current_time;
         let recovery_snapshot_time_trigger = self.last_recovery_snapshot_time + self.recovery_snapshot_period <= self.current_time;
-        if first_snapshot_trigger || historic_snapshot_time_trigger {
+        let output_stream_time_trigger = self.output_streams.iter().any(|output_stream| output_stream.is_due(self.current_time));
+        let output_stream_averaging_trigger = self.output_streams.iter().any(|output_stream| output_stream.is_accumulating(self.current_time));
+        let output_snapshot_trigger = first_snapshot_trigger || historic_snapshot_time_trigger || event_trigger || output_stream_time_trigger;
+        if output_snapshot_trigger {
             self.universe.inertial_to_heliocentric();
             let evolution = true;
             self.universe.calculate_spin_and_evolving_quantities(self.current_time, evolution);
             if self.universe.consider_effects.tides {
                 self.universe.calculate_denergy_dt();
             }
-            write_historic_snapshot(universe_history_writer, &self.universe, self.current_time, self.time_step);
-            if !first_snapshot_trigger {
-                // Do not use `self.current_time` to avoid small deviations 
-                // Do not use `self.n_historic_snapshots as f64*self.historic_snapshot_period` because `historic_snapshot_period` can be changed by the user when resuming an already started simulation
-                self.last_historic_snapshot_time += self.historic_snapshot_period; 
-            } else {
-                self.last_historic_snapshot_time = 0.;
+        }
+        let step_start_time = self.current_time;
+        let mut output_stream_samples = Vec::new();
+        if output_stream_averaging_trigger {
+            // Averaged streams sample every time step (the sample taken when a snapshot is written belongs to the
+            // next interval) from the integrated state, only the dissipated power has to be computed when it is not synchronized
+            if !output_snapshot_trigger && self.universe.consider_effects.tides && is_accumulating_output_field(&self.output_streams, &OutputField::DenergyDt, self.current_time) {
+                self.universe.calculate_denergy_dt();
+            }
+            output_stream_samples = sample_output_streams(&self.output_streams, &self.universe, self.current_time);
+        }
+        if output_snapshot_trigger {
+            if first_snapshot_trigger || historic_snapshot_time_trigger || event_trigger {
+                write_historic_snapshot(universe_history_writer, &self.universe, self.current_time, self.time_step);
+                if first_snapshot_trigger {
+                    self.last_historic_snapshot_time = 0.;
+                } else if historic_snapshot_time_trigger {
+                    // Do not use `self.current_time` to avoid small deviations 
+                    // Do not use `self.n_historic_snapshots as f64*self.historic_snapshot_period` because `historic_snapshot_period` can be changed by the user when resuming an already started simulation
+                    self.last_historic_snapshot_time += self.historic_snapshot_period; 
+                }
+                self.n_historic_snapshots += 1;
+                let current_time_years = self.current_time/365.25;
+                if ! silent_mode {
+                    print!("Year: {:0.0} ({:0.1e}) | Time step: {:0.3} days                    \r", current_time_years, current_time_years, self.time_step);
+                    let _ = std::io::stdout().flush();
+                }
             }
-            self.n_historic_snapshots += 1;
-            let current_time_years = self.current_time/365.25;
-            if ! silent_mode {
-                print!("Year: {:0.0} ({:0.1e}) | Time step: {:0.3} days                    \r", current_time_years, current_time_years, self.time_step);
-                let _ = std::io::stdout().flush();
+            if output_stream_time_trigger {
+                write_output_streams(&mut self.output_streams, output_stream_writers, &self.universe, self.current_time, self.time_step);
             }
         }
         
+        // Shorten the step to reach the next scheduled event exactly
+        let full_time_step = self.time_step;
+        let next_event_time = self.event_timeline.next_time_within(self.current_time, self.time_step);
+        if let Some(next_event_time) = next_event_time {
+            self.time_step = next_event_time - self.current_time;
+            self.half_time_step = 0.5*self.time_step;
+        }
+
         let ignored_gravity_terms = match self.alternative_coordinates_type {
             CoordinatesType::Jacobi => IgnoreGravityTerms::WHFastOne,
             CoordinatesType::DemocraticHeliocentric => IgnoreGravityTerms::WHFastTwo,
@@ -292,27 +459,37 @@ impl Integrator for WHFast {
         let evolution = false; // Only evolve once per full step (optimization)
         self.integrate_velocity_dependent_forces(self.half_time_step, integrate_spin, evolution); // Corrects the inertial velocity and computes spin
         self.current_time += self.time_step;
+        if let Some(next_event_time) = next_event_time {
+            self.current_time = next_event_time; // Avoid round-off deviations
+            self.time_step = full_time_step;
+            self.half_time_step = 0.5*full_time_step;
+        }
+
+        accumulate_output_streams(&mut self.output_streams, output_stream_samples, self.current_time - step_start_time);
 
         // ---------------------------------------------------------------------
         self.current_iteration += 1;
 
         // Return
-        if self.current_time+self.time_step > self.universe.time_limit {
+        let historic_snapshot_written = first_snapshot_trigger || historic_snapshot_time_trigger || event_trigger;
+        if let Some(stop_reason) = self.stopping_conditions.evaluate_iteration(&self.universe, self.current_time, historic_snapshot_written) {
+            Err(stop_reason.to_string())
+        } else if self.current_time+self.time_step > self.universe.time_limit {
             Err("Simulation completed".to_string())
         } else {
             Ok(first_snapshot_trigger || recovery_snapshot_time_trigger)
         }
     }
 
-    fn write_recovery_snapshot(&mut self, snapshot_path: &Path, universe_history_writer: &mut BufWriter<File>) {
+    fn write_recovery_snapshot(&mut self, snapshot_path: &Path, universe_history_writer: &mut BufWriter<File>, output_stream_writers: &mut [BufWriter<File>]) -> Result<(), String> {
         self.last_recovery_snapshot_time = self.current_time;
-        universe_history_writer.flush().unwrap();
-        // Compute hash for this universe at this moment of time
-        self.hash = 0;
-        let mut s = DefaultHasher::new();
-        self.hash(&mut s);
-        self.hash = s.finish();
-        write_recovery_snapshot(&snapshot_path, &self);
+        // The history must be on disk before the recovery snapshot that refers to it
+        sync_writer(universe_history_writer)?;
+        for output_stream_writer in output_stream_writers.iter_mut() {
+            sync_writer(output_stream_writer)?;
+        }
+        write_recovery_snapshot(snapshot_path, &*self)?;
+        backup_recovery_snapshot(snapshot_path, self.current_time)
     }
 
 }
@@ -387,7 +564,7 @@ impl WHFast {
             self.average_particles_for_velocity_dependent_forces_integration(&particles_orig, &particles_final, integrate_spin);
         }
         if !converged {
-            println!("[WARNING {} UTC] WHFast convergence issue with the integration of the additional forces. Most probably the perturbation is too strong.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_warning!("WHFast convergence issue with the integration of the additional forces. Most probably the perturbation is too strong.");
         }
         // 
         for ((((((particle, particle_final), particle_orig), inertial_velocity_error), angular_momentum_error), inertial_velocity_change), angular_momentum_change) in self.universe.particles[..self.universe.n_particles].iter_mut().zip(particles_final[..self.universe.n_particles].iter()).zip(particles_orig[..self.universe.n_particles].iter()).zip(self.inertial_velocity_errors[..self.universe.n_particles].iter_mut()).zip(self.particle_angular_momentum_errors[..self.universe.n_particles].iter_mut()).zip(inertial_velocity_changes[..self.universe.n_particles].iter()).zip(angular_momentum_changes[..self.universe.n_particles].iter_mut()) {
@@ -702,7 +879,7 @@ impl WHFast {
             if _dt.abs()*invperiod > 1. && self.timestep_warning == 0 {
                 // Ignoring const qualifiers. This warning should not have any effect on
                 // other parts of the code, nor is it vital to show it.
-                println!("[WARNING {} UTC] WHFast convergence issue. Timestep is larger than at least one orbital period.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+                log_warning!("WHFast convergence issue. Timestep is larger than at least one orbital period.");
                 self.timestep_warning += 1;
             }
             //x = _dt*invperiod*x_per_period; // first order guess 
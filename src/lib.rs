@@ -8,11 +8,14 @@ extern crate csv;
 extern crate time;
 extern crate math;
 
+#[macro_use]
+pub mod logging;
 pub mod constants;
 
 mod particles;
 pub use self::particles::Universe;
 pub use self::particles::ConsiderEffects;
+pub use self::particles::ValidationReport;
 pub use self::particles::Particle;
 pub use self::particles::Reference;
 pub use self::particles::Axes;
@@ -21,14 +24,32 @@ mod effects;
 pub use self::effects::Tides;
 pub use self::effects::TidesEffect;
 pub use self::effects::TidalModel;
+pub use self::effects::TidalResponse;
+pub use self::effects::TidalContext;
+pub use self::effects::Tide;
 pub use self::effects::ConstantTimeLagParameters;
+pub use self::effects::ConstantPhaseLagParameters;
+pub use self::effects::CreepParameters;
 pub use self::effects::CreepCoplanarParameters;
 pub use self::effects::KaulaParameters;
-pub use self::effects::Polynomials;
+pub use self::effects::KaulaFunctions;
+pub use self::effects::KaulaFunctionsCache;
+pub use self::effects::inclination_function;
+pub use self::effects::eccentricity_function;
+pub use self::effects::hansen_coefficients;
+pub use self::effects::LoveNumberTable;
+pub use self::effects::LoveNumberTableSource;
+pub use self::effects::LoveNumberData;
+pub use self::effects::LoveNumberInterpolation;
+pub use self::effects::TidalPair;
+pub use self::effects::Rheology;
+pub use self::effects::RheologicalLayer;
+pub use self::effects::InteriorStructure;
 pub use self::effects::RotationalFlattening;
 pub use self::effects::RotationalFlatteningEffect;
 pub use self::effects::RotationalFlatteningModel;
 pub use self::effects::OblateSpheroidParameters;
+pub use self::effects::RotationalFlatteningPair;
 pub use self::effects::GeneralRelativity;
 pub use self::effects::GeneralRelativityEffect;
 pub use self::effects::GeneralRelativityImplementation;
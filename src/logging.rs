@@ -0,0 +1,177 @@
+extern crate time;
+use std::fs::{File, OpenOptions};
+use std::io::{Write, BufWriter};
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use serde::{Serialize, Deserialize};
+use time::{OffsetDateTime, format_description};
+
+////////////////////////////////////////////////////////////////////////////////
+//- Central logger
+//
+// Messages are printed to stdout by default ("[INFO 2024.01.01 00:00:00 UTC] message"),
+// applications embedding the library can capture them with a handler and/or
+// silence the console output.
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+}
+
+impl LogLevel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warning => "WARNING",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LogLevel> {
+        match name.to_lowercase().as_str() {
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warning" => Some(LogLevel::Warning),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogRecord {
+    pub utc: String,
+    pub level: LogLevel,
+    pub target: String, // Module that emitted the message
+    pub message: String,
+}
+
+impl LogRecord {
+    pub fn to_text(&self) -> String {
+        format!("[{} {} UTC] {}", self.level.name(), self.utc, self.message)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+// Handlers are called while the logger is in use, thus they cannot log messages themselves
+pub type LogHandler = Box<dyn Fn(&LogRecord) + Send>;
+
+struct Logger {
+    level: LogLevel,
+    json: bool,
+    console: bool,
+    file: Option<BufWriter<File>>,
+    handler: Option<LogHandler>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger { level: LogLevel::Info, json: false, console: true, file: None, handler: None });
+
+fn logger() -> MutexGuard<'static, Logger> {
+    // A panic while logging must not disable the logger for the rest of the execution
+    LOGGER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub fn utc_timestamp() -> String {
+    OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap()
+}
+
+/// Messages below this level are ignored (default: info)
+pub fn set_level(level: LogLevel) {
+    logger().level = level;
+}
+
+/// Write JSON lines instead of text to the console and log file
+pub fn set_json_format(json: bool) {
+    logger().json = json;
+}
+
+/// Enable/disable (i.e., silence) the stdout output
+pub fn set_console_output(console: bool) {
+    logger().console = console;
+}
+
+/// Also append every message to a log file (e.g., one per run)
+pub fn set_log_file(path: &Path) -> std::io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    logger().file = Some(BufWriter::new(file));
+    Ok(())
+}
+
+/// Receive every message (e.g., to capture them in an embedding application)
+pub fn set_handler(handler: Option<LogHandler>) {
+    logger().handler = handler;
+}
+
+pub fn log(level: LogLevel, target: &str, message: String) {
+    let mut logger = logger();
+    if level < logger.level {
+        return;
+    }
+    let record = LogRecord { utc: utc_timestamp(), level, target: target.to_string(), message };
+    let line = if logger.json { record.to_json() } else { record.to_text() };
+    if logger.console {
+        // Unlike println!, a closed stdout (e.g., piped to head) does not panic while holding the logger
+        let _ = writeln!(std::io::stdout(), "{}", line);
+    }
+    if let Some(file) = logger.file.as_mut() {
+        let _ = writeln!(file, "{}", line);
+        let _ = file.flush();
+    }
+    if let Some(handler) = logger.handler.as_ref() {
+        handler(&record);
+    }
+}
+
+/// Record a panic message in the log file and handler (the console already shows it)
+pub fn log_panic(target: &str, message: String) {
+    // The panic could have happened while the logger was in use (e.g., in a handler)
+    let mut logger = match LOGGER.try_lock() {
+        Ok(logger) => logger,
+        Err(std::sync::TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(std::sync::TryLockError::WouldBlock) => return,
+    };
+    let record = LogRecord { utc: utc_timestamp(), level: LogLevel::Error, target: target.to_string(), message };
+    let line = if logger.json { record.to_json() } else { record.to_text() };
+    if let Some(file) = logger.file.as_mut() {
+        let _ = writeln!(file, "{}", line);
+        let _ = file.flush();
+    }
+    if let Some(handler) = logger.handler.as_ref() {
+        handler(&record);
+    }
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg:tt)*) => { $crate::logging::log($crate::logging::LogLevel::Debug, module_path!(), format!($($arg)*)) }
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg:tt)*) => { $crate::logging::log($crate::logging::LogLevel::Info, module_path!(), format!($($arg)*)) }
+}
+
+#[macro_export]
+macro_rules! log_warning {
+    ($($arg:tt)*) => { $crate::logging::log($crate::logging::LogLevel::Warning, module_path!(), format!($($arg)*)) }
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg:tt)*) => { $crate::logging::log($crate::logging::LogLevel::Error, module_path!(), format!($($arg)*)) }
+}
+
+/// Unrecoverable error: abort with the message prefixed by the UTC time (the panic hook of the
+/// executable also records it in the log file)
+#[macro_export]
+macro_rules! fatal {
+    ($($arg:tt)*) => { panic!("[PANIC {} UTC] {}", $crate::logging::utc_timestamp(), format!($($arg)*)) }
+}
@@ -1,11 +1,22 @@
 extern crate posidonius;
 extern crate time;
 extern crate clap;
+extern crate signal_hook;
 use clap::{Arg, ArgAction, Command};
 use clap::value_parser;
 use std::path::Path;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
 use std::time::{Duration, Instant};
-use time::{OffsetDateTime, format_description};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use signal_hook::consts::signal::{SIGINT, SIGTERM};
+#[cfg(unix)]
+use signal_hook::consts::signal::SIGUSR1;
+
+// Interrupted simulations exit following the shell convention (128 + signal number, e.g., 130 for SIGINT
+// and 143 for SIGTERM) after writing a recovery snapshot
+const EXIT_CODE_SIGNAL_BASE: i32 = 128;
 
 fn main() {
     let timer = Instant::now();
@@ -14,6 +25,21 @@ fn main() {
                             .version("2017.01.27")
                             .author("Sergi Blanco-Cuaresma - http://www.blancocuaresma.com/s/")
                             .about("N-Body simulator for planetary system affected by tidal effects. Based on Mercury-T from Emeline Bolmont.")
+                            .arg(Arg::new("log_file")
+                                .long("log-file")
+                                .value_name("filename")
+                                .global(true)
+                                .help("Also append all the messages to a log file"))
+                            .arg(Arg::new("log_format")
+                                .long("log-format")
+                                .value_parser(["text", "json"])
+                                .global(true)
+                                .help("Format of the messages (default: text)"))
+                            .arg(Arg::new("log_level")
+                                .long("log-level")
+                                .value_parser(["debug", "info", "warning", "error"])
+                                .global(true)
+                                .help("Minimum level of the messages (default: info)"))
                             .subcommand(Command::new("start")
                                     .about("Start a simulation")
                                     .arg(Arg::new("start_case_filename")
@@ -39,6 +65,30 @@ fn main() {
                                         .long("silent")
                                         .action(ArgAction::SetTrue)
                                         .help("Only print INFO/WARNING/ERROR messages"))
+                                    .arg(Arg::new("checkpoint_period")
+                                        .long("checkpoint-period")
+                                        .value_name("seconds")
+                                        .value_parser(value_parser!(u64))
+                                        .help("Also save a recovery snapshot every given execution time (default: only based on the simulation time)"))
+                                    .arg(Arg::new("progress")
+                                        .long("progress")
+                                        .value_name("filename")
+                                        .help("Report the progress as JSON lines in a file (appended) or in stderr ('-')"))
+                                    .arg(Arg::new("progress_period")
+                                        .long("progress-period")
+                                        .value_name("seconds")
+                                        .value_parser(value_parser!(u64))
+                                        .help("Execution time between progress reports (default: 10)"))
+                                    .arg(Arg::new("keep_backups")
+                                        .long("keep-backups")
+                                        .value_name("n")
+                                        .value_parser(value_parser!(usize))
+                                        .help("Number of most recent recovery snapshot backups to keep (default: all)"))
+                                    .arg(Arg::new("keep_backup_every")
+                                        .long("keep-backup-every")
+                                        .value_name("days")
+                                        .value_parser(value_parser!(f64))
+                                        .help("Also keep the first recovery snapshot backup of every simulation time interval"))
                                      )
                             .subcommand(Command::new("resume")
                                     .about("Resume a simulation")
@@ -59,6 +109,34 @@ fn main() {
                                         .long("silent")
                                         .action(ArgAction::SetTrue)
                                         .help("Only print INFO/WARNING/ERROR messages"))
+                                    .arg(Arg::new("checkpoint_period")
+                                        .long("checkpoint-period")
+                                        .value_name("seconds")
+                                        .value_parser(value_parser!(u64))
+                                        .help("Also save a recovery snapshot every given execution time (default: only based on the simulation time)"))
+                                    .arg(Arg::new("progress")
+                                        .long("progress")
+                                        .value_name("filename")
+                                        .help("Report the progress as JSON lines in a file (appended) or in stderr ('-')"))
+                                    .arg(Arg::new("progress_period")
+                                        .long("progress-period")
+                                        .value_name("seconds")
+                                        .value_parser(value_parser!(u64))
+                                        .help("Execution time between progress reports (default: 10)"))
+                                    .arg(Arg::new("keep_backups")
+                                        .long("keep-backups")
+                                        .value_name("n")
+                                        .value_parser(value_parser!(usize))
+                                        .help("Number of most recent recovery snapshot backups to keep (default: all)"))
+                                    .arg(Arg::new("keep_backup_every")
+                                        .long("keep-backup-every")
+                                        .value_name("days")
+                                        .value_parser(value_parser!(f64))
+                                        .help("Also keep the first recovery snapshot backup of every simulation time interval"))
+                                    .arg(Arg::new("use_backup")
+                                        .long("use-backup")
+                                        .action(ArgAction::SetTrue)
+                                        .help("Resume from the most recent valid backup if the recovery snapshot is corrupted"))
                                     .arg(Arg::new("change_historic_snapshot_period")
                                         .long("historic-snapshot-period")
                                         .value_name("days")
@@ -72,22 +150,132 @@ fn main() {
                                     .arg(Arg::new("change_time_limit")
                                         .long("time-limit")
                                         .value_name("days")
-                                        .value_parser(value_parser!(u64))
+                                        .value_parser(value_parser!(f64))
                                         .help("Set new simulation time limit in days."))
+                                    .arg(Arg::new("change_time_step")
+                                        .long("time-step")
+                                        .value_name("days")
+                                        .value_parser(value_parser!(f64))
+                                        .help("Set new time step in days (initial time step for IAS15)."))
+                                    .arg(Arg::new("change_integrator")
+                                        .long("integrator")
+                                        .value_name("integrator")
+                                        .value_parser(["whfast", "ias15", "leapfrog"])
+                                        .help("Continue the simulation with another integrator."))
+                                    .arg(Arg::new("change_whfast_coordinates")
+                                        .long("whfast-coordinates")
+                                        .value_name("coordinates")
+                                        .value_parser(["jacobi", "democratic-heliocentric", "whds"])
+                                        .help("Set new WHFast coordinates (default when converting to WHFast: democratic-heliocentric)."))
+                                    )
+                            .subcommand(Command::new("inspect")
+                                    .about("Print a summary of a snapshot")
+                                    .arg(Arg::new("snapshot_filename")
+                                        .required(true)
+                                        .index(1)
+                                        .help("Recovery snapshot filename (JSON or bin)"))
+                                    )
+                            .subcommand(Command::new("edit")
+                                    .about("Apply a JSON merge patch (RFC 7386) to a snapshot")
+                                    .arg(Arg::new("snapshot_filename")
+                                        .required(true)
+                                        .index(1)
+                                        .help("Recovery snapshot filename (JSON or bin)"))
+                                    .arg(Arg::new("patch")
+                                        .required(true)
+                                        .index(2)
+                                        .help("JSON merge patch filename or inline JSON (e.g., '{\"universe\": {\"time_limit\": 3652500.0}}')"))
+                                    .arg(Arg::new("output_snapshot_filename")
+                                        .required(true)
+                                        .index(3)
+                                        .help("Edited recovery snapshot filename (JSON or bin)"))
+                                    )
+                            .subcommand(Command::new("add-particle")
+                                    .about("Add a particle to a snapshot (heliocentric position/velocity or orbital elements)")
+                                    .arg(Arg::new("snapshot_filename")
+                                        .required(true)
+                                        .index(1)
+                                        .help("Recovery snapshot filename (JSON or bin)"))
+                                    .arg(Arg::new("particle")
+                                        .required(true)
+                                        .index(2)
+                                        .help("JSON filename or inline JSON with the particle and, optionally, its orbital elements (e.g., '{\"particle\": {...}, \"orbital_elements\": {...}}')"))
+                                    .arg(Arg::new("output_snapshot_filename")
+                                        .required(true)
+                                        .index(3)
+                                        .help("Edited recovery snapshot filename (JSON or bin)"))
+                                    )
+                            .subcommand(Command::new("remove-particle")
+                                    .about("Remove a particle from a snapshot (the following particles are shifted one position)")
+                                    .arg(Arg::new("snapshot_filename")
+                                        .required(true)
+                                        .index(1)
+                                        .help("Recovery snapshot filename (JSON or bin)"))
+                                    .arg(Arg::new("particle_index")
+                                        .required(true)
+                                        .index(2)
+                                        .value_parser(value_parser!(usize))
+                                        .help("Index of the particle to remove"))
+                                    .arg(Arg::new("output_snapshot_filename")
+                                        .required(true)
+                                        .index(3)
+                                        .help("Edited recovery snapshot filename (JSON or bin)"))
+                                    )
+                            .subcommand(Command::new("convert")
+                                    .about("Convert a snapshot between JSON and bin formats")
+                                    .arg(Arg::new("input_snapshot_filename")
+                                        .required(true)
+                                        .index(1)
+                                        .help("Recovery snapshot filename (JSON or bin)"))
+                                    .arg(Arg::new("output_snapshot_filename")
+                                        .required(true)
+                                        .index(2)
+                                        .help("Converted recovery snapshot filename (JSON or bin)"))
+                                    )
+                            .subcommand(Command::new("validate")
+                                    .about("Report errors and warnings of a case before running it")
+                                    .arg(Arg::new("case_filename")
+                                        .required(true)
+                                        .index(1)
+                                        .help("JSON case description or recovery snapshot filename"))
                                     )
                             .subcommand_required(true)
                             .arg_required_else_help(true)
                           .get_matches();
 
+    if let Some(log_level) = matches.get_one::<String>("log_level") {
+        posidonius::logging::set_level(posidonius::logging::LogLevel::from_name(log_level).unwrap());
+    }
+    posidonius::logging::set_json_format(matches.get_one::<String>("log_format").map(|log_format| log_format == "json").unwrap_or(false));
+    if let Some(log_filename) = matches.get_one::<String>("log_file") {
+        if let Err(e) = posidonius::logging::set_log_file(Path::new(log_filename)) {
+            posidonius::fatal!("Impossible to open log file '{}': {}", log_filename, e);
+        }
+        // Panics are printed by the default hook, but they should also be recorded in the log file
+        let default_panic_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            posidonius::logging::log_panic(module_path!(), panic_info.to_string());
+            default_panic_hook(panic_info);
+        }));
+    }
+
     let first_universe_integrator_snapshot_filename;
     let universe_integrator_snapshot_filename;
     let universe_history_filename;
     let silent_mode;
     let resume;
+    let use_backup;
     let new_historic_snapshot_period;
     let new_recovery_snapshot_period;
     let new_time_limit;
+    let new_time_step;
+    let new_integrator_type;
+    let new_whfast_coordinates;
     let execution_time_limit;
+    let checkpoint_period;
+    let backup_retention;
+    let progress_filename;
+    let progress_period;
 
     match matches.subcommand() {
         Some(("start", start_matches)) => {
@@ -96,10 +284,21 @@ fn main() {
             universe_history_filename = start_matches.get_one::<String>("historic_snapshot_filename").unwrap();
             silent_mode = start_matches.get_flag("silent");
             resume = false;
+            use_backup = false;
             new_historic_snapshot_period = -1.0;
             new_recovery_snapshot_period = -1.0;
             new_time_limit = -1.0;
+            new_time_step = -1.0;
+            new_integrator_type = None;
+            new_whfast_coordinates = None;
             execution_time_limit = Duration::from_secs(start_matches.get_one::<u64>("limit").copied().unwrap_or(0));
+            checkpoint_period = Duration::from_secs(start_matches.get_one::<u64>("checkpoint_period").copied().unwrap_or(0));
+            progress_filename = start_matches.get_one::<String>("progress").cloned();
+            progress_period = Duration::from_secs(start_matches.get_one::<u64>("progress_period").copied().unwrap_or(10));
+            backup_retention = posidonius::output::BackupRetention {
+                keep_last: start_matches.get_one::<usize>("keep_backups").copied(),
+                keep_every: start_matches.get_one::<f64>("keep_backup_every").copied(),
+            };
         },
         Some(("resume", resume_matches)) => {
             universe_integrator_snapshot_filename = resume_matches.get_one::<String>("resume_case_filename").unwrap();
@@ -107,47 +306,281 @@ fn main() {
             universe_history_filename = resume_matches.get_one::<String>("historic_snapshot_filename").unwrap();
             silent_mode = resume_matches.get_flag("silent");
             resume = true;
+            use_backup = resume_matches.get_flag("use_backup");
 
             new_historic_snapshot_period = resume_matches.get_one::<f64>("change_historic_snapshot_period").copied().unwrap_or(-1.);
             new_recovery_snapshot_period = resume_matches.get_one::<f64>("change_recovery_snapshot_period").copied().unwrap_or(-1.);
             new_time_limit = resume_matches.get_one::<f64>("change_time_limit").copied().unwrap_or(-1.);
+            new_time_step = resume_matches.get_one::<f64>("change_time_step").copied().unwrap_or(-1.);
+            new_integrator_type = resume_matches.get_one::<String>("change_integrator").map(|integrator| match integrator.as_str() {
+                "whfast" => posidonius::IntegratorType::WHFast,
+                "ias15" => posidonius::IntegratorType::Ias15,
+                _ => posidonius::IntegratorType::LeapFrog,
+            });
+            new_whfast_coordinates = resume_matches.get_one::<String>("change_whfast_coordinates").map(|coordinates| match coordinates.as_str() {
+                "jacobi" => posidonius::whfast::CoordinatesType::Jacobi,
+                "whds" => posidonius::whfast::CoordinatesType::WHDS,
+                _ => posidonius::whfast::CoordinatesType::DemocraticHeliocentric,
+            });
             execution_time_limit = Duration::from_secs(resume_matches.get_one::<u64>("limit").copied().unwrap_or(0));
+            checkpoint_period = Duration::from_secs(resume_matches.get_one::<u64>("checkpoint_period").copied().unwrap_or(0));
+            progress_filename = resume_matches.get_one::<String>("progress").cloned();
+            progress_period = Duration::from_secs(resume_matches.get_one::<u64>("progress_period").copied().unwrap_or(10));
+            backup_retention = posidonius::output::BackupRetention {
+                keep_last: resume_matches.get_one::<usize>("keep_backups").copied(),
+                keep_every: resume_matches.get_one::<f64>("keep_backup_every").copied(),
+            };
+        },
+        Some(("inspect", inspect_matches)) => {
+            let snapshot_path = Path::new(inspect_matches.get_one::<String>("snapshot_filename").unwrap());
+            match posidonius::output::read_snapshot(snapshot_path) {
+                Ok(universe_integrator) => print!("{}", posidonius::inspect::inspect_snapshot(&*universe_integrator)),
+                Err(e) => exit_with_error(format!("Impossible to read snapshot '{}': {}", snapshot_path.display(), e)),
+            }
+            return;
+        },
+        Some(("edit", edit_matches)) => {
+            let snapshot_path = Path::new(edit_matches.get_one::<String>("snapshot_filename").unwrap());
+            let patch_argument = edit_matches.get_one::<String>("patch").unwrap();
+            let output_snapshot_path = Path::new(edit_matches.get_one::<String>("output_snapshot_filename").unwrap());
+            let patch_json = if Path::new(patch_argument).is_file() {
+                match std::fs::read_to_string(patch_argument) {
+                    Ok(patch_json) => patch_json,
+                    Err(e) => exit_with_error(format!("Impossible to read patch '{}': {}", patch_argument, e)),
+                }
+            } else {
+                patch_argument.clone()
+            };
+            let patch: serde_json::Value = match serde_json::from_str(&patch_json) {
+                Ok(patch) => patch,
+                Err(e) => exit_with_error(format!("Invalid JSON patch: {}", e)),
+            };
+            let universe_integrator = match posidonius::output::read_snapshot(snapshot_path) {
+                Ok(universe_integrator) => universe_integrator,
+                Err(e) => exit_with_error(format!("Impossible to read snapshot '{}': {}", snapshot_path.display(), e)),
+            };
+            match posidonius::inspect::edit_snapshot(&*universe_integrator, &patch) {
+                Ok(edited_universe_integrator) => {
+                    if let Err(e) = edited_universe_integrator.write_snapshot(output_snapshot_path) {
+                        exit_with_error(format!("Impossible to write snapshot '{}': {}", output_snapshot_path.display(), e));
+                    }
+                    posidonius::log_info!("Edited snapshot written to '{}'.", output_snapshot_path.display());
+                },
+                Err(e) => exit_with_error(format!("Impossible to apply patch: {}", e)),
+            }
+            return;
+        },
+        Some(("add-particle", add_particle_matches)) => {
+            let snapshot_path = Path::new(add_particle_matches.get_one::<String>("snapshot_filename").unwrap());
+            let particle_argument = add_particle_matches.get_one::<String>("particle").unwrap();
+            let output_snapshot_path = Path::new(add_particle_matches.get_one::<String>("output_snapshot_filename").unwrap());
+            let particle_json = if Path::new(particle_argument).is_file() {
+                match std::fs::read_to_string(particle_argument) {
+                    Ok(particle_json) => particle_json,
+                    Err(e) => exit_with_error(format!("Impossible to read particle '{}': {}", particle_argument, e)),
+                }
+            } else {
+                particle_argument.clone()
+            };
+            let description: serde_json::Value = match serde_json::from_str(&particle_json) {
+                Ok(description) => description,
+                Err(e) => exit_with_error(format!("Invalid JSON particle: {}", e)),
+            };
+            let universe_integrator = match posidonius::output::read_snapshot(snapshot_path) {
+                Ok(universe_integrator) => universe_integrator,
+                Err(e) => exit_with_error(format!("Impossible to read snapshot '{}': {}", snapshot_path.display(), e)),
+            };
+            match posidonius::inspect::add_particle_to_snapshot(&*universe_integrator, &description) {
+                Ok(edited_universe_integrator) => {
+                    if let Err(e) = edited_universe_integrator.write_snapshot(output_snapshot_path) {
+                        exit_with_error(format!("Impossible to write snapshot '{}': {}", output_snapshot_path.display(), e));
+                    }
+                    posidonius::log_info!("Edited snapshot written to '{}'.", output_snapshot_path.display());
+                },
+                Err(e) => exit_with_error(format!("Impossible to add particle: {}", e)),
+            }
+            return;
+        },
+        Some(("remove-particle", remove_particle_matches)) => {
+            let snapshot_path = Path::new(remove_particle_matches.get_one::<String>("snapshot_filename").unwrap());
+            let particle_index = *remove_particle_matches.get_one::<usize>("particle_index").unwrap();
+            let output_snapshot_path = Path::new(remove_particle_matches.get_one::<String>("output_snapshot_filename").unwrap());
+            let universe_integrator = match posidonius::output::read_snapshot(snapshot_path) {
+                Ok(universe_integrator) => universe_integrator,
+                Err(e) => exit_with_error(format!("Impossible to read snapshot '{}': {}", snapshot_path.display(), e)),
+            };
+            match posidonius::inspect::remove_particle_from_snapshot(&*universe_integrator, particle_index) {
+                Ok(edited_universe_integrator) => {
+                    if let Err(e) = edited_universe_integrator.write_snapshot(output_snapshot_path) {
+                        exit_with_error(format!("Impossible to write snapshot '{}': {}", output_snapshot_path.display(), e));
+                    }
+                    posidonius::log_info!("Edited snapshot written to '{}'.", output_snapshot_path.display());
+                },
+                Err(e) => exit_with_error(format!("Impossible to remove particle: {}", e)),
+            }
+            return;
+        },
+        Some(("convert", convert_matches)) => {
+            let input_snapshot_path = Path::new(convert_matches.get_one::<String>("input_snapshot_filename").unwrap());
+            let output_snapshot_path = Path::new(convert_matches.get_one::<String>("output_snapshot_filename").unwrap());
+            match posidonius::output::convert_snapshot(input_snapshot_path, output_snapshot_path) {
+                Ok(_) => posidonius::log_info!("Converted snapshot written to '{}'.", output_snapshot_path.display()),
+                Err(e) => posidonius::fatal!("Impossible to convert snapshot '{}': {}", input_snapshot_path.display(), e),
+            }
+            return;
+        },
+        Some(("validate", validate_matches)) => {
+            let case_path = Path::new(validate_matches.get_one::<String>("case_filename").unwrap());
+            let universe_integrator = match posidonius::output::read_snapshot(case_path) {
+                Ok(universe_integrator) => universe_integrator,
+                Err(e) => posidonius::fatal!("Impossible to read case '{}': {}", case_path.display(), e),
+            };
+            let mut report = universe_integrator.get_universe().validate(universe_integrator.get_time_step());
+            report.errors.extend(posidonius::events::validate_events(universe_integrator.get_event_timeline(), universe_integrator.get_universe()));
+            report.errors.extend(posidonius::stopping::validate_stopping_conditions(universe_integrator.get_stopping_conditions(), universe_integrator.get_universe()));
+            report.errors.extend(posidonius::validate_output_streams(universe_integrator.get_output_streams()));
+            for warning in report.warnings.iter() {
+                posidonius::log_warning!("{}", warning);
+            }
+            for error in report.errors.iter() {
+                posidonius::log_error!("{}", error);
+            }
+            posidonius::log_info!("Found {} errors and {} warnings in '{}'.", report.errors.len(), report.warnings.len(), case_path.display());
+            if !report.is_valid() {
+                std::process::exit(1);
+            }
+            return;
         },
         _ => unreachable!(),
     }
 
     let universe_integrator_snapshot_path = Path::new(&universe_integrator_snapshot_filename);
+    // The format of the recovery snapshots is given by their extension
+    if let Err(e) = posidonius::output::snapshot_extension(universe_integrator_snapshot_path) {
+        posidonius::fatal!("{}", e);
+    }
 
     let first_universe_integrator_snapshot_path = Path::new(&first_universe_integrator_snapshot_filename);
     
     // Start/Resume from snapshot
     let mut boxed_universe_integrator : Box<dyn posidonius::Integrator> = match posidonius::output::restore_snapshot(&first_universe_integrator_snapshot_path) {
         Ok(restored_case) => { restored_case },
-        Err(_) => { 
+        Err(e) => { 
+            posidonius::log_warning!("{}", e);
             if resume {
-                panic!("[PANIC {} UTC] It was not possible to resume the simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+                match posidonius::output::restore_most_recent_valid_backup(&first_universe_integrator_snapshot_path) {
+                    Some((backup_path, restored_case)) => {
+                        if use_backup {
+                            posidonius::log_warning!("Resuming from backup '{}'", backup_path.display());
+                            // Keep the corrupted snapshot aside to avoid it being taken as a backup in the next recovery snapshot
+                            let corrupted_snapshot_path = first_universe_integrator_snapshot_path.with_extension("corrupted");
+                            if first_universe_integrator_snapshot_path.exists() {
+                                std::fs::rename(first_universe_integrator_snapshot_path, &corrupted_snapshot_path).unwrap();
+                            }
+                            restored_case
+                        } else {
+                            posidonius::fatal!("It was not possible to resume the simulation, the most recent valid backup is '{}' (use the flag --use-backup to resume from it)", backup_path.display());
+                        }
+                    },
+                    None => {
+                        posidonius::fatal!("It was not possible to resume the simulation");
+                    },
+                }
             } else {
-                panic!("[PANIC {} UTC] It was not possible to start the simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+                posidonius::fatal!("It was not possible to start the simulation");
             }
         },
     };
 
+    let current_integrator_type = boxed_universe_integrator.get_integrator_type();
+    let current_whfast_coordinates = boxed_universe_integrator.as_any().downcast_ref::<posidonius::WHFast>().map(|whfast| whfast.get_alternative_coordinates_type());
+    if new_whfast_coordinates.is_some() && new_integrator_type.unwrap_or(current_integrator_type) != posidonius::IntegratorType::WHFast {
+        posidonius::fatal!("WHFast coordinates can only be changed for the WHFast integrator");
+    }
+    let integrator_type = new_integrator_type.unwrap_or(current_integrator_type);
+    let whfast_coordinates = new_whfast_coordinates.or(current_whfast_coordinates).unwrap_or(posidonius::whfast::CoordinatesType::DemocraticHeliocentric);
+    let reconfigured = integrator_type != current_integrator_type || (integrator_type == posidonius::IntegratorType::WHFast && Some(whfast_coordinates) != current_whfast_coordinates);
+    if reconfigured {
+        boxed_universe_integrator = posidonius::convert_integrator(boxed_universe_integrator.as_ref(), integrator_type, whfast_coordinates);
+    }
+    boxed_universe_integrator.set_time_step(new_time_step);
     boxed_universe_integrator.set_snapshot_periods(new_historic_snapshot_period, new_recovery_snapshot_period);
     boxed_universe_integrator.set_time_limit(new_time_limit);
+    if let Some(stop_reason) = boxed_universe_integrator.get_stopping_conditions().stop_reason {
+        // The conditions are evaluated again, thus the simulation stops right away if the condition is still met
+        // (it can be removed/changed first with `posidonius edit`)
+        posidonius::log_warning!("Resuming a simulation stopped by the condition '{}' at {} days", stop_reason.condition.reason_code(), stop_reason.time);
+        let mut history_counters = boxed_universe_integrator.get_history_counters();
+        history_counters.stopping_conditions.clear_stop_reason();
+        boxed_universe_integrator.set_history_counters(history_counters);
+    }
 
     // Create/recover historic snapshot
-    let expected_n_bytes = (boxed_universe_integrator.get_n_historic_snapshots() as u64) 
-                                * posidonius::output::n_bytes_per_particle_in_historic_snapshot()
-                                * (boxed_universe_integrator.get_n_particles() as u64);
+    let expected_n_bytes = posidonius::output::expected_universe_history_n_bytes(boxed_universe_integrator.as_ref());
     let universe_history_path = Path::new(&universe_history_filename);
 
     if !resume && universe_integrator_snapshot_path.exists() {
-        panic!("[PANIC {} UTC] File '{}' already exists.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), universe_integrator_snapshot_filename);
+        posidonius::fatal!("File '{}' already exists.", universe_integrator_snapshot_filename);
     } else if !resume && universe_history_path.exists() {
-        panic!("[PANIC {} UTC] File '{}' already exists.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), universe_history_filename);
+        posidonius::fatal!("File '{}' already exists.", universe_history_filename);
+    }
+    let particle_set_changes_path = posidonius::output::particle_set_changes_path(universe_history_path);
+    if !resume && particle_set_changes_path.exists() {
+        posidonius::fatal!("File '{}' already exists.", particle_set_changes_path.display());
+    }
+    let applied_events_path = posidonius::output::applied_events_path(universe_history_path);
+    if !resume && applied_events_path.exists() {
+        posidonius::fatal!("File '{}' already exists.", applied_events_path.display());
+    }
+    for output_stream in boxed_universe_integrator.get_output_streams().iter() {
+        let output_stream_path = posidonius::output::output_stream_path(universe_history_path, output_stream);
+        if !resume && output_stream_path.exists() {
+            posidonius::fatal!("File '{}' already exists.", output_stream_path.display());
+        }
+    }
+
+    if resume {
+        if let Err(e) = posidonius::output::verify_universe_history(universe_history_path, boxed_universe_integrator.as_ref()) {
+            posidonius::fatal!("{}", e);
+        }
     }
 
     let mut universe_history_writer = posidonius::output::get_universe_history_writer(universe_history_path, expected_n_bytes);
+    let mut output_stream_writers = posidonius::output::get_output_stream_writers(universe_history_path, boxed_universe_integrator.get_output_streams(), boxed_universe_integrator.get_n_particles(), boxed_universe_integrator.get_particle_set_changes());
+    // Same as the historic snapshots, changes beyond the recovery snapshot are discarded
+    posidonius::output::write_particle_set_changes(universe_history_path, boxed_universe_integrator.get_particle_set_changes());
+    let mut n_particle_set_changes = boxed_universe_integrator.get_particle_set_changes().len();
+    posidonius::output::write_applied_events(universe_history_path, boxed_universe_integrator.get_event_timeline().applied_events());
+    let mut n_applied_events = boxed_universe_integrator.get_event_timeline().applied_events().len();
+
+    // SIGINT/SIGTERM stop the simulation after the current step (a second one terminates the process
+    // right away, without recovery snapshot), SIGUSR1 requests a recovery snapshot
+    let received_stop_signal = Arc::new(AtomicUsize::new(0));
+    let received_any_stop_signal = Arc::new(AtomicBool::new(false));
+    let received_checkpoint_signal = Arc::new(AtomicBool::new(false));
+    for signal in [SIGINT, SIGTERM].iter() {
+        // The conditional shutdown is registered first so that it only acts from the second signal
+        signal_hook::flag::register_conditional_shutdown(*signal, EXIT_CODE_SIGNAL_BASE + *signal, Arc::clone(&received_any_stop_signal)).unwrap();
+        signal_hook::flag::register(*signal, Arc::clone(&received_any_stop_signal)).unwrap();
+        signal_hook::flag::register_usize(*signal, Arc::clone(&received_stop_signal), *signal as usize).unwrap();
+    }
+    #[cfg(unix)]
+    signal_hook::flag::register(SIGUSR1, Arc::clone(&received_checkpoint_signal)).unwrap();
+
+    // Kept in the recovery snapshots even without progress channel, thus the conservation errors
+    // reported after resuming are relative to the start of the simulation
+    posidonius::progress::initialize_conservation_reference(boxed_universe_integrator.as_mut());
+    let mut progress_reporter = progress_filename.map(|progress_filename| {
+        let progress_writer: Box<dyn Write> = if progress_filename == "-" {
+            Box::new(std::io::stderr())
+        } else {
+            match OpenOptions::new().create(true).append(true).open(&progress_filename) {
+                Ok(progress_file) => Box::new(progress_file),
+                Err(why) => posidonius::fatal!("Couldn't open progress file '{}': {}", progress_filename, why),
+            }
+        };
+        posidonius::ProgressReporter::new(progress_writer, progress_period, boxed_universe_integrator.as_mut())
+    });
 
     // Simulate
     let instant = Instant::now();
@@ -155,31 +588,93 @@ fn main() {
         0 => false,
         _ => true,
     };
+    let enabled_checkpoint_period = checkpoint_period.as_secs() > 0;
+    let mut last_checkpoint_instant = Instant::now();
     loop {
-        match boxed_universe_integrator.iterate(&mut universe_history_writer, silent_mode) {
+        let iteration = boxed_universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, silent_mode);
+        if boxed_universe_integrator.get_particle_set_changes().len() != n_particle_set_changes {
+            // Particles added/removed by scheduled events
+            posidonius::output::write_particle_set_changes(universe_history_path, boxed_universe_integrator.get_particle_set_changes());
+            n_particle_set_changes = boxed_universe_integrator.get_particle_set_changes().len();
+        }
+        if boxed_universe_integrator.get_event_timeline().applied_events().len() != n_applied_events {
+            posidonius::output::write_applied_events(universe_history_path, boxed_universe_integrator.get_event_timeline().applied_events());
+            n_applied_events = boxed_universe_integrator.get_event_timeline().applied_events().len();
+        }
+        match iteration {
             Ok(recovery_snapshot_time_trigger) => {
+                if let Some(progress_reporter) = progress_reporter.as_mut() {
+                    progress_reporter.update(boxed_universe_integrator.as_ref());
+                }
+                let stop_signal = received_stop_signal.load(Ordering::Relaxed);
+                if stop_signal != 0 {
+                    // Save a universe snapshot so that we can resume later on
+                    write_recovery_snapshot(boxed_universe_integrator.as_mut(), universe_integrator_snapshot_path, &mut universe_history_writer, &mut output_stream_writers, &backup_retention);
+                    if let Some(progress_reporter) = progress_reporter.as_mut() {
+                        progress_reporter.report(boxed_universe_integrator.as_ref(), "interrupted");
+                    }
+                    posidonius::log_warning!("Received signal {} before simulation completion, recovery snapshot written to '{}'", stop_signal, universe_integrator_snapshot_path.display());
+                    std::process::exit(EXIT_CODE_SIGNAL_BASE + stop_signal as i32);
+                }
+                let checkpoint_time_trigger = (enabled_checkpoint_period && last_checkpoint_instant.elapsed() >= checkpoint_period)
+                                                || received_checkpoint_signal.swap(false, Ordering::Relaxed);
                 if enabled_execution_time_limit {
                     let elapsed = instant.elapsed();
                     if elapsed >= execution_time_limit {
                         // Save a universe snapshot so that we can resume later on
-                        boxed_universe_integrator.write_recovery_snapshot(&universe_integrator_snapshot_path, &mut universe_history_writer);
-                        println!("[WARNING {} UTC] Reached execution time limit before simulation completion", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap()); 
+                        write_recovery_snapshot(boxed_universe_integrator.as_mut(), universe_integrator_snapshot_path, &mut universe_history_writer, &mut output_stream_writers, &backup_retention);
+                        if let Some(progress_reporter) = progress_reporter.as_mut() {
+                            progress_reporter.report(boxed_universe_integrator.as_ref(), "time_limit");
+                        }
+                        posidonius::log_warning!("Reached execution time limit before simulation completion"); 
                         break;
+                    } else if checkpoint_time_trigger {
+                        write_recovery_snapshot(boxed_universe_integrator.as_mut(), universe_integrator_snapshot_path, &mut universe_history_writer, &mut output_stream_writers, &backup_retention);
+                        last_checkpoint_instant = Instant::now();
                     }
-                } else if recovery_snapshot_time_trigger {
+                } else if recovery_snapshot_time_trigger || checkpoint_time_trigger {
                     // Save a universe snapshot so that we can resume in case of failure
-                    boxed_universe_integrator.write_recovery_snapshot(&universe_integrator_snapshot_path, &mut universe_history_writer);
+                    write_recovery_snapshot(boxed_universe_integrator.as_mut(), universe_integrator_snapshot_path, &mut universe_history_writer, &mut output_stream_writers, &backup_retention);
+                    last_checkpoint_instant = Instant::now();
                 }
             },
-            Err(e) => { println!("[INFO {} UTC] {} '{}'.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), e, first_universe_integrator_snapshot_filename); break; }
+            Err(e) => {
+                if let Some(stop_reason) = boxed_universe_integrator.get_stopping_conditions().stop_reason {
+                    // Final snapshot, it keeps the reason and it can be inspected/edited
+                    write_recovery_snapshot(boxed_universe_integrator.as_mut(), universe_integrator_snapshot_path, &mut universe_history_writer, &mut output_stream_writers, &backup_retention);
+                    if let Some(progress_reporter) = progress_reporter.as_mut() {
+                        progress_reporter.report(boxed_universe_integrator.as_ref(), "stopped");
+                    }
+                    posidonius::log_info!("{}, final recovery snapshot written to '{}'", e, universe_integrator_snapshot_path.display());
+                    std::process::exit(stop_reason.condition.exit_code());
+                }
+                if let Some(progress_reporter) = progress_reporter.as_mut() {
+                    progress_reporter.report(boxed_universe_integrator.as_ref(), "completed");
+                }
+                posidonius::log_info!("{} '{}'.", e, first_universe_integrator_snapshot_filename);
+                break;
+            }
         };
     }
 
     let duration = timer.elapsed(); // duration
     if !resume {
-        println!("[INFO {} UTC] Execution time: {} seconds", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), duration.as_secs_f64());
+        posidonius::log_info!("Execution time: {} seconds", duration.as_secs_f64());
     } else {
-        println!("[INFO {} UTC] Execution time since last resume: {} seconds", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), duration.as_secs_f64());
+        posidonius::log_info!("Execution time since last resume: {} seconds", duration.as_secs_f64());
     }
 }
 
+/// Invalid input of the snapshot subcommands is reported as an error (not a panic) with a non-zero exit status
+fn exit_with_error(message: String) -> ! {
+    posidonius::log_error!("{}", message);
+    std::process::exit(1);
+}
+
+/// Recovery snapshot (and its backup) of the simulation, older backups are removed following the retention policy
+fn write_recovery_snapshot(universe_integrator: &mut dyn posidonius::Integrator, snapshot_path: &Path, universe_history_writer: &mut BufWriter<File>, output_stream_writers: &mut [BufWriter<File>], backup_retention: &posidonius::output::BackupRetention) {
+    if let Err(e) = universe_integrator.write_recovery_snapshot(snapshot_path, universe_history_writer, output_stream_writers) {
+        posidonius::fatal!("Impossible to write recovery snapshot: {}", e);
+    }
+    posidonius::output::apply_backup_retention(snapshot_path, backup_retention);
+}
@@ -8,4 +8,5 @@ pub use self::particle::Reference;
 pub use self::universe::Universe;
 pub use self::universe::IgnoreGravityTerms;
 pub use self::universe::ConsiderEffects;
+pub use self::universe::ValidationReport;
 pub use self::axes::Axes;
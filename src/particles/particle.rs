@@ -4,7 +4,6 @@ use super::super::constants::{K2};
 use super::{Axes};
 use super::super::{Tides, RotationalFlattening, GeneralRelativity, Disk, Wind, EvolutionType};
 use super::super::{TidesEffect, TidalModel, RotationalFlatteningEffect, RotationalFlatteningModel, GeneralRelativityEffect, DiskEffect, WindEffect};
-use time::{OffsetDateTime, format_description};
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Reference {
@@ -141,8 +140,14 @@ impl Particle {
     }
 
     pub fn check_uniform_viscosity_coefficient(&mut self) {
-        // If creep coplanar tides and rotational flattening are set, both need to have the same
-        // uniform viscosity coefficient parameter
+        if let Err(why) = self.validate_uniform_viscosity_coefficient() {
+            fatal!("{}", why);
+        }
+    }
+
+    pub fn validate_uniform_viscosity_coefficient(&self) -> Result<(), String> {
+        // If creep (coplanar or not) tides and rotational flattening are set, both need to use the
+        // same creep model with the same uniform viscosity coefficient parameter
         let disabled_tides = match self.tides.effect {
             TidesEffect::Disabled => true,
             _ => false
@@ -152,35 +157,43 @@ impl Particle {
             _ => false
         };
         if !disabled_tides && !disabled_rotational_flattening {
-            let (creep_coplanar_tides, particle_uniform_viscosity_coefficient_for_tides) = match &self.tides.effect {
+            let (creep_tides, particle_uniform_viscosity_coefficient_for_tides) = match &self.tides.effect {
                 TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model) => {
-                    if let TidalModel::CreepCoplanar(params) = tidal_model {
-                        (true, params.uniform_viscosity_coefficient)
-                    } else {
-                        (false, 0.)
+                    match tidal_model {
+                        TidalModel::CreepCoplanar(params) => (Some("Creep Coplanar"), params.uniform_viscosity_coefficient),
+                        TidalModel::Creep(params) => (Some("Creep"), params.uniform_viscosity_coefficient),
+                        _ => (None, 0.),
                     }
                 },
-                _ => (false, 0.)
+                _ => (None, 0.)
             };
-            let (creep_coplanar_rotational_flattening, particle_uniform_viscosity_coefficient_for_rotational_flattenning) = match self.rotational_flattening.effect {
+            let (creep_rotational_flattening, particle_uniform_viscosity_coefficient_for_rotational_flattenning) = match self.rotational_flattening.effect {
                 RotationalFlatteningEffect::CentralBody(rotational_flattening_model) | RotationalFlatteningEffect::OrbitingBody(rotational_flattening_model) => {
-                    if let RotationalFlatteningModel::CreepCoplanar(params) = rotational_flattening_model {
-                        (true, params.uniform_viscosity_coefficient)
-                    } else {
-                        (false, 0.)
+                    match rotational_flattening_model {
+                        RotationalFlatteningModel::CreepCoplanar(params) => (Some("Creep Coplanar"), params.uniform_viscosity_coefficient),
+                        RotationalFlatteningModel::Creep(params) => (Some("Creep"), params.uniform_viscosity_coefficient),
+                        _ => (None, 0.),
                     }
                 },
-                _ => (false, 0.),
+                _ => (None, 0.),
             };
-            if (creep_coplanar_tides && !creep_coplanar_rotational_flattening) || (!creep_coplanar_tides && creep_coplanar_rotational_flattening) {
-                panic!("[ERROR {} UTC] When using Creep Coplanar Tidal or rotational flattening effects, both effects need to be Creep Coplanar and not just one of them (e.g., it cannot be mixed with ConstantTimeLag or OblateSpheroid).", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-            } else if creep_coplanar_tides && creep_coplanar_rotational_flattening {
-                let diff_uniform_viscosity_coefficient = (particle_uniform_viscosity_coefficient_for_tides - particle_uniform_viscosity_coefficient_for_rotational_flattenning).abs();
-                if diff_uniform_viscosity_coefficient > 1.0e-16 {
-                    panic!("[ERROR {} UTC] When using Creep Coplanar Tidal and rotational flattening effects, the uniform viscosity coefficient must be identical {:.16}.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), diff_uniform_viscosity_coefficient);
-                }
+            match (creep_tides, creep_rotational_flattening) {
+                (Some(creep_model), None) | (None, Some(creep_model)) => {
+                    return Err(format!("When using {0} Tidal or rotational flattening effects, both effects need to be {0} and not just one of them (e.g., it cannot be mixed with ConstantTimeLag or OblateSpheroid).", creep_model));
+                },
+                (Some(creep_tidal_model), Some(creep_rotational_flattening_model)) if creep_tidal_model != creep_rotational_flattening_model => {
+                    return Err(format!("When using creep Tidal and rotational flattening effects, both effects need to use the same model (i.e., {} tides cannot be mixed with {} rotational flattening).", creep_tidal_model, creep_rotational_flattening_model));
+                },
+                (Some(creep_model), Some(_)) => {
+                    let diff_uniform_viscosity_coefficient = (particle_uniform_viscosity_coefficient_for_tides - particle_uniform_viscosity_coefficient_for_rotational_flattenning).abs();
+                    if diff_uniform_viscosity_coefficient > 1.0e-16 {
+                        return Err(format!("When using {} Tidal and rotational flattening effects, the uniform viscosity coefficient must be identical {:.16}.", creep_model, diff_uniform_viscosity_coefficient));
+                    }
+                },
+                (None, None) => {},
             }
         }
+        Ok(())
     }
 
     pub fn set_general_relativity(&mut self, general_relativity: GeneralRelativity) {
@@ -204,20 +217,20 @@ impl Particle {
 fn evolution_warnings(evolution: EvolutionType) {
     match evolution {
         EvolutionType::GalletBolmont2017(_) => {
-            println!("[WARNING {} UTC] Bodies with GalletBolmont2017 evolution will ignore initial radius and dissipation factor.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-            println!("[WARNING {} UTC] GalletBolmont2017 prescription theoretically only works for circular orbits and non inclined orbits, use carefully.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap())
+            log_warning!("Bodies with GalletBolmont2017 evolution will ignore initial radius and dissipation factor.");
+            log_warning!("GalletBolmont2017 prescription theoretically only works for circular orbits and non inclined orbits, use carefully.")
         },
         EvolutionType::BolmontMathis2016(_) => {
-            println!("[WARNING {} UTC] Bodies with Baraffe2015 evolution will ignore initial radius and radius of gyration.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-            println!("[WARNING {} UTC] BolmontMathis2016 prescription theoretically only works for circular orbits and non inclined orbits, use carefully. ", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap())
+            log_warning!("Bodies with Baraffe2015 evolution will ignore initial radius and radius of gyration.");
+            log_warning!("BolmontMathis2016 prescription theoretically only works for circular orbits and non inclined orbits, use carefully. ")
         },
-        EvolutionType::Baraffe2015(_) => println!("[WARNING {} UTC]  ", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap()),
-        EvolutionType::Leconte2011(_) => println!("[WARNING {} UTC] Bodies with Leconte2011 evolution will ignore initial radius and radius of gyration.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap()),
-        EvolutionType::Baraffe1998(_) => println!("[WARNING {} UTC] Bodies with Baraffe1998 evolution will ignore initial radius. ", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap()),
-        EvolutionType::LeconteChabrier2013(false) => println!("[WARNING {} UTC] Bodies with Jupiter evolution will ignore initial radius, radius of gyration and love number.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap()),
+        EvolutionType::Baraffe2015(_) => log_warning!("Bodies with Baraffe2015 evolution will ignore initial radius and radius of gyration."),
+        EvolutionType::Leconte2011(_) => log_warning!("Bodies with Leconte2011 evolution will ignore initial radius and radius of gyration."),
+        EvolutionType::Baraffe1998(_) => log_warning!("Bodies with Baraffe1998 evolution will ignore initial radius. "),
+        EvolutionType::LeconteChabrier2013(false) => log_warning!("Bodies with Jupiter evolution will ignore initial radius, radius of gyration and love number."),
         EvolutionType::LeconteChabrier2013(true) => {
-            println!("[WARNING {} UTC] Bodies with Jupiter evolution will ignore initial radius, radius of gyration, love number and dissipation factor.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-            println!("[WARNING {} UTC] LeconteChabrier2013(true) prescription theoretically only works for circular orbits and non inclined orbits, use carefully.", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_warning!("Bodies with Jupiter evolution will ignore initial radius, radius of gyration, love number and dissipation factor.");
+            log_warning!("LeconteChabrier2013(true) prescription theoretically only works for circular orbits and non inclined orbits, use carefully.");
         },
         EvolutionType::NonEvolving => {},
     }
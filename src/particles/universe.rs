@@ -1,24 +1,24 @@
 extern crate time;
-use time::{OffsetDateTime, format_description};
 use std::collections::HashMap;
 use serde::{Serialize, Deserialize};
 use serde_big_array::BigArray;
-use super::super::constants::{G, MAX_PARTICLES, MAX_DISTANCE_2};
+use super::super::constants::{G, AU, MAX_PARTICLES, MAX_DISTANCE_2, MIN_ORBITAL_PERIOD_TIME_STEP_RATIO, RECOMMENDED_ORBITAL_PERIOD_TIME_STEP_RATIO, CREEP_COPLANAR_MAX_TILT};
+use super::super::tools::calculate_keplerian_orbital_elements;
 use super::super::{Evolver, EvolutionType};
-use super::{Particle};
+use super::{Particle, Reference};
 use super::{Axes};
 use super::{common};
 use super::super::effects::{tides, rotational_flattening, general_relativity, evolution, wind, disk};
-use super::super::{TidesEffect, RotationalFlatteningEffect, DiskEffect, WindEffect};
+use super::super::{TidesEffect, TidalModel, TidalContext, RotationalFlatteningEffect, RotationalFlatteningModel, DiskEffect, WindEffect, TidalPair, InteriorStructure, LoveNumberTable, LoveNumberTableSource, KaulaFunctionsCache};
 use super::super::{GeneralRelativityImplementation, GeneralRelativityEffect};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HostIndices {
     pub most_massive: usize,
-    tides: usize, // Particle that is the main one for tidal effects
-    rotational_flattening: usize, // Particle that is the main one for rotational flattenning effects
-    general_relativity: usize, // Central particle for general relativity effects
-    disk: usize, // Particle with disk
+    pub tides: usize, // Particle that is the main one for tidal effects
+    pub rotational_flattening: usize, // Particle that is the main one for rotational flattenning effects
+    pub general_relativity: usize, // Central particle for general relativity effects
+    pub disk: usize, // Particle with disk
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -60,6 +60,28 @@ pub struct Universe {
     pair_dependent_scaled_dissipation_factor : HashMap<usize, f64>, // Central body specific
     #[serde(with = "BigArray")]
     roche_radiuses : [f64; MAX_PARTICLES * MAX_PARTICLES],
+    #[serde(default)]
+    pub tidal_pairs: Vec<TidalPair>, // Tides between particles other than the central host (see snapshot format version 1)
+    #[serde(default)]
+    pub interior_structures: Vec<InteriorStructure>, // Rheology used to compute the Kaula Love numbers (see snapshot format version 1)
+    #[serde(default)]
+    pub love_number_tables: Vec<LoveNumberTable>, // Variable-size Kaula Love number tables (kept last, see snapshot format version 1)
+    #[serde(skip)]
+    kaula_functions_cache: KaulaFunctionsCache, // Not stored in snapshots, computed again when needed
+}
+
+/// Problems found in a universe before running a simulation: errors would make the
+/// simulation panic or produce wrong results, warnings are probably unintended
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
 }
 
 #[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
@@ -71,6 +93,27 @@ pub enum IgnoreGravityTerms {
 
 impl Universe {
     pub fn new(initial_time: f64, time_limit: f64, mut particles: Vec<Particle>, mut consider_effects: ConsiderEffects) -> Universe {
+        // Report all the problems at once instead of panicking with the first one
+        let mut report = validate_effects(&particles, &consider_effects);
+        let mut particles_evolvers : Vec<Evolver> = Vec::with_capacity(MAX_PARTICLES);
+        for (i, particle) in particles.iter().enumerate() {
+            match Evolver::new(particle.evolution, initial_time, time_limit) {
+                Ok(evolver) => particles_evolvers.push(evolver),
+                Err(why) => {
+                    // Unsupported masses are already reported
+                    let error = format!("Particle {}: {}", i, why);
+                    if !report.errors.contains(&error) {
+                        report.errors.push(error);
+                    }
+                },
+            }
+        }
+        for error in report.errors.iter() {
+            log_error!("{}", error);
+        }
+        if !report.is_valid() {
+            fatal!("Invalid universe ({} errors)!", report.errors.len());
+        }
         disable_unnecessary_effects(&mut consider_effects, &particles);
         check_effects_vs_central_and_orbiting(&particles, &consider_effects);
         let hosts = find_indices(&particles, &consider_effects);
@@ -113,15 +156,16 @@ impl Universe {
             panic!("Only {} bodies are allowed, you need to increase the MAX_PARTICLE constant.", MAX_PARTICLES);
         }
         let mut transformed_particles = [Particle::new_dummy(); MAX_PARTICLES];
-        let mut particles_evolvers : Vec<Evolver> = Vec::with_capacity(n_particles);
         for i in 0..n_particles {
             transformed_particles[i] = particles[i];
             transformed_particles[i].id = i;
-            particles_evolvers.push(Evolver::new(transformed_particles[i].evolution, initial_time, time_limit));
         }
         for _i in n_particles..MAX_PARTICLES {
             // For dummy particles
-            particles_evolvers.push(Evolver::new(EvolutionType::NonEvolving, initial_time, time_limit));
+            match Evolver::new(EvolutionType::NonEvolving, initial_time, time_limit) {
+                Ok(evolver) => particles_evolvers.push(evolver),
+                Err(why) => fatal!("{}", why),
+            }
         }
 
         if consider_effects.evolution {
@@ -129,30 +173,8 @@ impl Universe {
             // proper state of the selected evolving body model (i.e., initial radius and radius of
             // gyration are good given the initial time)
             for (particle, evolver) in transformed_particles.iter_mut().zip(particles_evolvers.iter_mut()) {
-                if EvolutionType::NonEvolving == particle.evolution {
-                    continue;
-                }
-                let mut update_angular_momentum = false;
                 let current_time = 0.;
-                let new_radius = evolver.radius(current_time, particle.radius);
-                if (new_radius - particle.radius).abs() > 1e-6 {
-                    println!("[WARNING {} UTC] Changed radius value from '{:.6}' to '{:.6}' to match expected state following the selected evolving body model", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), particle.radius, new_radius);
-                    particle.radius = new_radius;
-                    update_angular_momentum = true;
-                }
-                let new_radius_of_gyration_2 = evolver.radius_of_gyration_2(current_time, particle.radius_of_gyration_2);
-                if (new_radius_of_gyration_2 - particle.radius_of_gyration_2).abs() > 1e-6 {
-                    println!("[WARNING {} UTC] Changed radius of gyration value from '{:.6}' to '{:.6}' to match expected state following the selected evolving body model", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), particle.radius_of_gyration_2.sqrt(), new_radius_of_gyration_2.sqrt());
-                    particle.radius_of_gyration_2 = new_radius_of_gyration_2;
-                    update_angular_momentum = true;
-                }
-                if update_angular_momentum {
-                    println!("[WARNING {} UTC] Recomputed moment of inertia and angular momentum to match expected state following the selected evolving body model", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
-                    particle.moment_of_inertia = particle.mass * particle.radius_of_gyration_2 * particle.radius.powi(2);
-                    particle.angular_momentum.x = particle.spin.x * particle.moment_of_inertia;
-                    particle.angular_momentum.y = particle.spin.y * particle.moment_of_inertia;
-                    particle.angular_momentum.z = particle.spin.z * particle.moment_of_inertia;
-                }
+                match_evolving_body_model(particle, evolver, current_time);
             }
         }
 
@@ -170,10 +192,394 @@ impl Universe {
                     hosts: hosts,
                     pair_dependent_scaled_dissipation_factor:HashMap::new(),
                     roche_radiuses: roche_radiuses,
+                    tidal_pairs: Vec::new(),
+                    interior_structures: Vec::new(),
+                    love_number_tables: Vec::new(),
+                    kaula_functions_cache: KaulaFunctionsCache::default(),
                     };
         universe
     }
     
+    /// Full list of errors and warnings of the universe (without printing or panicking), the
+    /// time step is used to verify that the shortest orbits can be integrated (negative to disable)
+    pub fn validate(&self, time_step: f64) -> ValidationReport {
+        let particles = &self.particles[..self.n_particles];
+        let mut report = validate_effects(particles, &self.consider_effects);
+
+        // Hosts stored in the universe (e.g., computed by the python package or an edited snapshot)
+        let mut most_massive_particle_index = MAX_PARTICLES+1;
+        let mut max_mass_found = 0.;
+        for (i, particle) in particles.iter().enumerate() {
+            if particle.mass > max_mass_found {
+                max_mass_found = particle.mass;
+                most_massive_particle_index = i;
+            }
+        }
+        if self.hosts.index.most_massive != most_massive_particle_index {
+            report.errors.push(format!("Inconsistent hosts: the most massive particle is {} but {} is stored", most_massive_particle_index, self.hosts.index.most_massive));
+        }
+        let central_bodies = [
+            ("tidal", self.consider_effects.tides, self.hosts.index.tides, central_body_indices(particles, |particle| matches!(particle.tides.effect, TidesEffect::CentralBody(_)))),
+            ("rotational flattening", self.consider_effects.rotational_flattening, self.hosts.index.rotational_flattening, central_body_indices(particles, |particle| matches!(particle.rotational_flattening.effect, RotationalFlatteningEffect::CentralBody(_)))),
+            ("general relativity", self.consider_effects.general_relativity, self.hosts.index.general_relativity, central_body_indices(particles, has_general_relativity_central_body)),
+        ];
+        for (effect_name, considered, stored_index, indices) in central_bodies.iter() {
+            if *considered && indices.len() == 1 && indices[0] != *stored_index {
+                report.errors.push(format!("Inconsistent hosts: the central body for {} effects is particle {} but {} is stored", effect_name, indices[0], stored_index));
+            }
+        }
+
+        // Tides and rotational flattening between other pairs of particles
+        let tidal_pairs_report = validate_tidal_pairs(particles, &self.tidal_pairs, &self.consider_effects, self.hosts.index.tides);
+        report.errors.extend(tidal_pairs_report.errors);
+        report.warnings.extend(tidal_pairs_report.warnings);
+        let interior_structures_report = validate_interior_structures(particles, &self.interior_structures);
+        report.errors.extend(interior_structures_report.errors);
+        report.warnings.extend(interior_structures_report.warnings);
+        let love_number_tables_report = validate_love_number_tables(particles, &self.love_number_tables, &self.interior_structures);
+        report.errors.extend(love_number_tables_report.errors);
+        report.warnings.extend(love_number_tables_report.warnings);
+        let satellites_report = validate_satellites(particles, &self.consider_effects, &self.hosts);
+        report.errors.extend(satellites_report.errors);
+        report.warnings.extend(satellites_report.warnings);
+
+        // Evolution tracks
+        if self.consider_effects.evolution {
+            for (i, particle) in particles.iter().enumerate() {
+                if particle.evolution == EvolutionType::NonEvolving {
+                    continue;
+                }
+                match self.particles_evolvers.get(i) {
+                    Some(evolver) if evolver.evolution == particle.evolution && !evolver.time.is_empty() => {
+                        if evolver.time[0] > 0. {
+                            report.errors.push(format!("Particle {} initial time ({} days) is smaller than the minimum age of its evolution track ({} days)", i, self.initial_time, evolver.time[0]+self.initial_time));
+                        }
+                        if evolver.time[evolver.time.len()-1] < self.time_limit {
+                            report.errors.push(format!("Particle {} time limit ({} days) is greater than the maximum time of its evolution track ({} days)", i, self.time_limit, evolver.time[evolver.time.len()-1]));
+                        }
+                    },
+                    _ => report.errors.push(format!("Particle {} evolution track ({:?}) is not loaded", i, particle.evolution)),
+                }
+            }
+        }
+
+        // Time step vs innermost orbit
+        if time_step > 0. {
+            for (i, particle) in particles.iter().enumerate() {
+                let reference_particle_index = match particle.reference {
+                    Reference::MostMassiveParticle => self.hosts.index.most_massive,
+                    Reference::Particle(index) => index,
+                };
+                if reference_particle_index == i || reference_particle_index >= self.n_particles {
+                    continue;
+                }
+                let orbital_period = calculate_orbital_period_around_reference(&self.particles[reference_particle_index], particle);
+                if !orbital_period.is_finite() || orbital_period <= 0. {
+                    continue;
+                }
+                if MIN_ORBITAL_PERIOD_TIME_STEP_RATIO > 0. && orbital_period <= time_step*MIN_ORBITAL_PERIOD_TIME_STEP_RATIO {
+                    report.errors.push(format!("Time step is too large: particle {} has an orbital period around particle {} of {:0.3} days which is less than the limit ({:0.3} days) based on the time step ({:0.3} days)", i, reference_particle_index, orbital_period, time_step*MIN_ORBITAL_PERIOD_TIME_STEP_RATIO, time_step));
+                } else if orbital_period <= time_step*RECOMMENDED_ORBITAL_PERIOD_TIME_STEP_RATIO {
+                    report.warnings.push(format!("Time step is too coarse: particle {} has an orbital period around particle {} of {:0.3} days which is less than {} time steps ({:0.3} days)", i, reference_particle_index, orbital_period, RECOMMENDED_ORBITAL_PERIOD_TIME_STEP_RATIO, time_step));
+                }
+            }
+        }
+        report
+    }
+
+    /// Validate an edited universe and apply again the consistency checks (e.g., hosts are re-computed),
+    /// the problems that would make the consistency checks panic are reported without modifying it
+    pub fn validate_and_check_consistency(&mut self, time_step: f64) -> ValidationReport {
+        let report = validate_effects(&self.particles[..self.n_particles], &self.consider_effects);
+        if !report.is_valid() {
+            return report;
+        }
+        self.check_consistency();
+        self.validate(time_step)
+    }
+
+    /// Apply again the consistency checks done when the universe is created (e.g., after a snapshot was edited)
+    pub fn check_consistency(&mut self) {
+        let particles = self.particles[..self.n_particles].to_vec();
+        disable_unnecessary_effects(&mut self.consider_effects, &particles);
+        check_effects_vs_central_and_orbiting(&particles, &self.consider_effects);
+        self.hosts = find_indices(&particles, &self.consider_effects);
+        self.general_relativity_implementation = GeneralRelativityImplementation::Disabled;
+        if self.consider_effects.general_relativity {
+            if let GeneralRelativityEffect::CentralBody(implementation) = self.particles[self.hosts.index.general_relativity].general_relativity.effect {
+                self.general_relativity_implementation = implementation;
+            }
+        }
+    }
+
+    /// Tides between two particles in addition to the ones with the central host (e.g., the
+    /// second star of a circumbinary system, planet-planet or planet-moon tides)
+    pub fn add_tidal_pair(&mut self, tidal_pair: TidalPair) -> Result<(), String> {
+        let mut tidal_pairs = self.tidal_pairs.clone();
+        tidal_pairs.push(tidal_pair);
+        let report = validate_tidal_pairs(&self.particles[..self.n_particles], &tidal_pairs, &self.consider_effects, self.hosts.index.tides);
+        if !report.is_valid() {
+            return Err(report.errors.join(", "));
+        }
+        for warning in report.warnings.iter() {
+            log_warning!("{}", warning);
+        }
+        self.tidal_pairs = tidal_pairs;
+        Ok(())
+    }
+
+    /// Tides between a particle (e.g., the secondary star of a binary) and every other orbiting body,
+    /// in addition to the ones with the central host. The particle plays the role of the central
+    /// body with the same tidal model it uses as orbiting body of the central host, thus both stars
+    /// dissipate (including their dynamical tides if they evolve). Returns the number of tidal pairs added.
+    pub fn add_tidal_host(&mut self, index: usize) -> Result<usize, String> {
+        if index >= self.n_particles {
+            return Err(format!("Particle {} cannot be a tidal host because there are only {} particles", index, self.n_particles));
+        }
+        let host_tidal_model = match self.particles[index].tides.effect {
+            TidesEffect::OrbitingBody(tidal_model) => tidal_model,
+            _ => return Err(format!("Particle {} cannot be a tidal host because it has no tidal effect (orbiting body)", index)),
+        };
+        let satellite_tidal_pairs = tides::satellite_tidal_pairs(&self.particles[..self.n_particles], self.hosts.index.tides);
+        let mut n_tidal_pairs = 0;
+        for i in 0..self.n_particles {
+            if let TidesEffect::OrbitingBody(orbiting_tidal_model) = self.particles[i].tides.effect {
+                let already_included = self.tidal_pairs.iter().chain(satellite_tidal_pairs.iter()).any(|tidal_pair| tidal_pair.involves(index) && tidal_pair.involves(i));
+                if i == index || already_included {
+                    continue;
+                }
+                self.add_tidal_pair(TidalPair::new(index, i, host_tidal_model, orbiting_tidal_model))?;
+                n_tidal_pairs += 1;
+            }
+        }
+        Ok(n_tidal_pairs)
+    }
+
+    /// Interior structure of a particle with the Kaula tidal model, its Love number tables are
+    /// computed from the rheology of the layers (replacing any previous structure of the particle)
+    pub fn set_interior_structure(&mut self, interior_structure: InteriorStructure) -> Result<(), String> {
+        let mut interior_structures: Vec<InteriorStructure> = self.interior_structures.iter().filter(|existing| existing.particle != interior_structure.particle).cloned().collect();
+        let index = interior_structure.particle;
+        interior_structures.push(interior_structure);
+        let report = validate_interior_structures(&self.particles[..self.n_particles], &interior_structures);
+        if !report.is_valid() {
+            return Err(report.errors.join(", "));
+        }
+        for warning in report.warnings.iter() {
+            log_warning!("{}", warning);
+        }
+        self.interior_structures = interior_structures;
+        self.calculate_love_number_tables(index);
+        Ok(())
+    }
+
+    /// Change the viscosity of a layer of the interior structure of a particle (e.g., following its
+    /// thermal evolution) and compute again its Love number tables
+    pub fn set_layer_viscosity(&mut self, particle: usize, layer: usize, viscosity: f64) -> Result<(), String> {
+        let mut interior_structure = match self.interior_structures.iter().find(|interior_structure| interior_structure.particle == particle) {
+            Some(interior_structure) => interior_structure.clone(),
+            None => return Err(format!("Particle {} has no interior structure", particle)),
+        };
+        match interior_structure.layers.get_mut(layer) {
+            Some(rheological_layer) => rheological_layer.viscosity = viscosity,
+            None => return Err(format!("The interior structure of particle {} has only {} layers", particle, interior_structure.layers.len())),
+        }
+        self.set_interior_structure(interior_structure)
+    }
+
+    /// Love number tables of every particle with an interior structure (e.g., for new simulations
+    /// generated by the python package, where only the layers are defined)
+    pub fn calculate_all_love_number_tables(&mut self) {
+        let indices: Vec<usize> = self.interior_structures.iter().map(|interior_structure| interior_structure.particle).collect();
+        for index in indices {
+            self.calculate_love_number_tables(index);
+        }
+    }
+
+    /// Love number table of a particle from its interior structure (if any)
+    pub fn calculate_love_number_tables(&mut self, index: usize) {
+        let love_number_table = match self.interior_structures.iter().find(|interior_structure| interior_structure.particle == index) {
+            Some(interior_structure) => interior_structure.love_number_table(),
+            None => return,
+        };
+        self.love_number_tables.retain(|existing| existing.particle != index);
+        self.love_number_tables.push(love_number_table);
+    }
+
+    /// Variable-size Love number table of a particle with the Kaula tidal model (replacing any
+    /// previous table of the particle)
+    pub fn set_love_number_table(&mut self, love_number_table: LoveNumberTable) -> Result<(), String> {
+        let mut love_number_tables: Vec<LoveNumberTable> = self.love_number_tables.iter().filter(|existing| existing.particle != love_number_table.particle).cloned().collect();
+        love_number_tables.push(love_number_table);
+        let report = validate_love_number_tables(&self.particles[..self.n_particles], &love_number_tables, &self.interior_structures);
+        if !report.is_valid() {
+            return Err(report.errors.join(", "));
+        }
+        self.love_number_tables = love_number_tables;
+        Ok(())
+    }
+
+    /// Read the Love number tables stored in files (their content is not part of the snapshots)
+    pub fn load_love_number_tables(&mut self) -> Result<(), String> {
+        for love_number_table in self.love_number_tables.iter_mut() {
+            love_number_table.load()?;
+        }
+        Ok(())
+    }
+
+    /// Insert a particle at the end of the particles array during a simulation. Its heliocentric
+    /// position/velocity are with respect to the current most massive particle, all the inertial
+    /// positions/velocities are re-centered on the new center of mass. Returns the particle index.
+    pub fn add_particle(&mut self, mut particle: Particle, current_time: f64) -> Result<usize, String> {
+        if self.n_particles >= MAX_PARTICLES {
+            return Err(format!("Only {} bodies are allowed, you need to increase the MAX_PARTICLE constant", MAX_PARTICLES));
+        }
+        if particle.mass <= 0. {
+            return Err(format!("The mass of the new particle must be positive ({} found)", particle.mass));
+        }
+        if let Reference::Particle(reference_index) = particle.reference {
+            if reference_index >= self.n_particles {
+                return Err(format!("The new particle refers to particle {} but there are only {} particles", reference_index, self.n_particles));
+            }
+        }
+        let index = self.n_particles;
+        let mut particles = self.particles[..self.n_particles].to_vec();
+        particles.push(particle);
+        let report = validate_effects(&particles, &self.consider_effects);
+        if !report.is_valid() {
+            return Err(report.errors.join(", "));
+        }
+
+        let host_particle = self.particles[self.hosts.index.most_massive];
+        particle.id = index;
+        particle.inertial_position.x = host_particle.inertial_position.x + particle.heliocentric_position.x;
+        particle.inertial_position.y = host_particle.inertial_position.y + particle.heliocentric_position.y;
+        particle.inertial_position.z = host_particle.inertial_position.z + particle.heliocentric_position.z;
+        particle.inertial_velocity.x = host_particle.inertial_velocity.x + particle.heliocentric_velocity.x;
+        particle.inertial_velocity.y = host_particle.inertial_velocity.y + particle.heliocentric_velocity.y;
+        particle.inertial_velocity.z = host_particle.inertial_velocity.z + particle.heliocentric_velocity.z;
+        if particle.evolution != EvolutionType::NonEvolving {
+            // Dummy slots already have a non-evolving evolver
+            self.particles_evolvers[index] = Evolver::new(particle.evolution, self.initial_time, self.time_limit).map_err(|why| format!("Particle {}: {}", index, why))?;
+            match_evolving_body_model(&mut particle, &mut self.particles_evolvers[index], current_time);
+        }
+        self.particles[index] = particle;
+        self.n_particles += 1;
+        self.move_to_center_of_mass();
+        self.rebuild_particle_dependent_values();
+        Ok(index)
+    }
+
+    /// Remove a particle during a simulation, the following particles are shifted one position
+    /// (their indices and references are decreased by one). Returns the removed particle.
+    pub fn remove_particle(&mut self, index: usize) -> Result<Particle, String> {
+        if index >= self.n_particles {
+            return Err(format!("Particle {} cannot be removed because there are only {} particles", index, self.n_particles));
+        }
+        if self.n_particles == 1 {
+            return Err("The last particle of the universe cannot be removed".to_string());
+        }
+        for (i, particle) in self.particles[..self.n_particles].iter().enumerate() {
+            if i != index && particle.reference == Reference::Particle(index) {
+                return Err(format!("Particle {} cannot be removed because particle {} uses it as reference", index, i));
+            }
+        }
+        let mut particles = self.particles[..self.n_particles].to_vec();
+        let removed_particle = particles.remove(index);
+        let report = validate_effects(&particles, &self.consider_effects);
+        if !report.is_valid() {
+            return Err(report.errors.join(", "));
+        }
+
+        self.particles[index..self.n_particles].rotate_left(1);
+        self.particles[self.n_particles-1] = Particle::new_dummy();
+        let removed_evolver = self.particles_evolvers.remove(index);
+        self.particles_evolvers.push(removed_evolver.clone()); // Cloned evolvers are non-evolving (i.e., dummy)
+        self.n_particles -= 1;
+        for particle in self.particles[..self.n_particles].iter_mut() {
+            if let Reference::Particle(reference_index) = particle.reference {
+                if reference_index > index {
+                    particle.reference = Reference::Particle(reference_index - 1);
+                }
+            }
+        }
+        for tidal_pair in self.tidal_pairs.iter().filter(|tidal_pair| tidal_pair.involves(index)) {
+            log_warning!("Discarded tides between particles {} and {} because particle {} was removed", tidal_pair.host, tidal_pair.orbiting, index);
+        }
+        self.tidal_pairs.retain(|tidal_pair| !tidal_pair.involves(index));
+        for tidal_pair in self.tidal_pairs.iter_mut() {
+            if tidal_pair.host > index {
+                tidal_pair.host -= 1;
+            }
+            if tidal_pair.orbiting > index {
+                tidal_pair.orbiting -= 1;
+            }
+        }
+        self.interior_structures.retain(|interior_structure| interior_structure.particle != index);
+        for interior_structure in self.interior_structures.iter_mut() {
+            if interior_structure.particle > index {
+                interior_structure.particle -= 1;
+            }
+        }
+        self.love_number_tables.retain(|love_number_table| love_number_table.particle != index);
+        for love_number_table in self.love_number_tables.iter_mut() {
+            if love_number_table.particle > index {
+                love_number_table.particle -= 1;
+            }
+        }
+        self.move_to_center_of_mass();
+        self.rebuild_particle_dependent_values();
+        Ok(removed_particle)
+    }
+
+    fn move_to_center_of_mass(&mut self) {
+        let mut total_mass = 0.;
+        let mut center_of_mass_position = Axes{x: 0., y: 0., z: 0.};
+        let mut center_of_mass_velocity = Axes{x: 0., y: 0., z: 0.};
+        for particle in self.particles[..self.n_particles].iter() {
+            total_mass += particle.mass;
+            center_of_mass_position.x += particle.mass * particle.inertial_position.x;
+            center_of_mass_position.y += particle.mass * particle.inertial_position.y;
+            center_of_mass_position.z += particle.mass * particle.inertial_position.z;
+            center_of_mass_velocity.x += particle.mass * particle.inertial_velocity.x;
+            center_of_mass_velocity.y += particle.mass * particle.inertial_velocity.y;
+            center_of_mass_velocity.z += particle.mass * particle.inertial_velocity.z;
+        }
+        for particle in self.particles[..self.n_particles].iter_mut() {
+            particle.inertial_position.x -= center_of_mass_position.x / total_mass;
+            particle.inertial_position.y -= center_of_mass_position.y / total_mass;
+            particle.inertial_position.z -= center_of_mass_position.z / total_mass;
+            particle.inertial_velocity.x -= center_of_mass_velocity.x / total_mass;
+            particle.inertial_velocity.y -= center_of_mass_velocity.y / total_mass;
+            particle.inertial_velocity.z -= center_of_mass_velocity.z / total_mass;
+        }
+    }
+
+    /// Values that depend on the particle set (identifiers, hosts, general relativity factors,
+    /// Roche radiuses and the host/particle pair dissipation factors)
+    fn rebuild_particle_dependent_values(&mut self) {
+        for (i, particle) in self.particles[..self.n_particles].iter_mut().enumerate() {
+            particle.id = i;
+        }
+        self.check_consistency();
+        if self.general_relativity_implementation != GeneralRelativityImplementation::Disabled {
+            let general_relativity_host_index = self.hosts.index.general_relativity;
+            let local_copy_star_mass_g = self.particles[general_relativity_host_index].mass_g;
+            for (i, particle) in self.particles[..self.n_particles].iter_mut().enumerate() {
+                if i != general_relativity_host_index {
+                    particle.general_relativity.parameters.internal.factor = local_copy_star_mass_g*particle.mass_g / (local_copy_star_mass_g + particle.mass_g).powi(2);
+                }
+            }
+        }
+        for roche_radius in self.roche_radiuses.iter_mut() {
+            *roche_radius = 0.; // The number of particles is also the stride of the array
+        }
+        self.calculate_roche_radiuses();
+        // Keys are based on the particle identifiers, they are re-computed in the next step
+        self.pair_dependent_scaled_dissipation_factor.clear();
+        self.inertial_to_heliocentric();
+    }
+
     pub fn calculate_roche_radiuses(&mut self) {
         let (particles, _) = self.particles.split_at_mut(self.n_particles);
         let (roche_radiuses, _) = self.roche_radiuses.split_at_mut(self.n_particles*self.n_particles);
@@ -224,16 +630,16 @@ impl Universe {
                 if i < j {
                     if distance_2 <= roche_radius.powi(2) {
                         println!("\n");
-                        panic!("[PANIC {} UTC] Particle {} was destroyed by particle {} due to close encounter!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i, j);
+                        fatal!("Particle {} was destroyed by particle {} due to close encounter!", i, j);
                     }
                     // Check if particles are overlapping
                     if distance_2 <= (particle_a.radius + particle_b.radius).powi(2) {
                         println!("\n");
-                        panic!("[PANIC {} UTC] Collision between particle {} and {}!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i, j);
+                        fatal!("Collision between particle {} and {}!", i, j);
                     }
                     if MAX_DISTANCE_2 > 0. && i == self.hosts.index.most_massive && distance_2 > MAX_DISTANCE_2 {
                         println!("\n");
-                        panic!("[PANIC {} UTC] Particle {} has been ejected!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), j);
+                        fatal!("Particle {} has been ejected!", j);
                     }
                 }
                 //////////////////////////////////////////////////////////////////////
@@ -441,52 +847,52 @@ impl Universe {
                     (accelerations && (self.consider_effects.tides || self.consider_effects.disk || self.consider_effects.rotational_flattening || 
                                        self.consider_effects.general_relativity)) {
 
-                    {
-                        //// calculate_orthogonal_components
-
-                        tides::calculate_pair_dependent_scaled_dissipation_factors(&mut tidal_host_particle, &mut particles_left, &mut particles_right, &mut self.pair_dependent_scaled_dissipation_factor); // Needed by calculate_orthogonal_component_of_the_tidal_force and calculate_orthogonal_component_of_the_tidal_force if BolmontMathis2016/GalletBolmont2017/LeconteChabrier2013(true)
-                        
-                        if self.consider_effects.tides {
-                            tides::calculate_orthogonal_component_of_the_tidal_force(&mut tidal_host_particle, &mut particles_left, &mut particles_right, &mut self.pair_dependent_scaled_dissipation_factor);
-                            tides::calculate_creep_coplanar_shapes(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                        }
+                    if self.consider_effects.tides && (dangular_momentum_dt || accelerations) {
+                        //// Tidal forces and torques of the model of each body
+                        let mut context = TidalContext {
+                            pair_dependent_scaled_dissipation_factor: &mut self.pair_dependent_scaled_dissipation_factor,
+                            love_number_tables: &self.love_number_tables,
+                            kaula_functions_cache: &mut self.kaula_functions_cache,
+                        };
+                        tides::calculate_tides(tidal_host_particle, particles_left, particles_right, &mut context, dangular_momentum_dt, accelerations);
+                    }
 
-                        if self.consider_effects.rotational_flattening {
-                            rotational_flattening::calculate_orthogonal_component_of_the_force_induced_by_rotational_flattening(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                            rotational_flattening::calculate_creep_coplanar_shapes(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                        }
+                    if self.consider_effects.rotational_flattening {
+                        //// calculate_orthogonal_components
+                        rotational_flattening::calculate_orthogonal_component_of_the_force_induced_by_rotational_flattening(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
+                        rotational_flattening::calculate_creep_coplanar_shapes(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
                     }
 
-                    if dangular_momentum_dt && (self.consider_effects.tides || self.consider_effects.rotational_flattening) {
+                    if dangular_momentum_dt && self.consider_effects.rotational_flattening {
                         // Not needed for additional accelerations
-                        {
-                            //// calculate_torques // Needed for dangular_momentum_dt
-                            if self.consider_effects.tides {
-                                tides::calculate_dangular_momentum_dt_due_to_tides(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                            }
-
-                            if self.consider_effects.rotational_flattening {
-                                rotational_flattening::calculate_dangular_momentum_dt_induced_by_rotational_flattening(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                            }
-                        }
+                        //// calculate_torques // Needed for dangular_momentum_dt
+                        rotational_flattening::calculate_dangular_momentum_dt_induced_by_rotational_flattening(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
                     }
 
-                    if accelerations && (self.consider_effects.tides || self.consider_effects.rotational_flattening) {
-                        if self.consider_effects.tides {
-                            tides::calculate_radial_component_of_the_tidal_force(&mut tidal_host_particle, &mut particles_left, &mut particles_right, &mut self.pair_dependent_scaled_dissipation_factor);  // Needed for calculate_tidal_acceleration
-                            tides::calculate_tidal_acceleration(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                        }
-
-                        if self.consider_effects.rotational_flattening {
-                            rotational_flattening::calculate_radial_component_of_the_force_induced_by_rotational_flattening(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                            rotational_flattening::calculate_acceleration_induced_by_rotational_flattering(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
-                        }
-
-
+                    if accelerations && self.consider_effects.rotational_flattening {
+                        rotational_flattening::calculate_radial_component_of_the_force_induced_by_rotational_flattening(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
+                        rotational_flattening::calculate_acceleration_induced_by_rotational_flattering(&mut tidal_host_particle, &mut particles_left, &mut particles_right);
                     }
                 }
             }
         }
+        if self.consider_effects.tides && (dangular_momentum_dt || accelerations) {
+            // Added to the tides with the central host
+            if !self.tidal_pairs.is_empty() {
+                tides::calculate_tidal_pairs(particles, &mut self.tidal_pairs, &mut self.pair_dependent_scaled_dissipation_factor, &self.love_number_tables, &mut self.kaula_functions_cache, dangular_momentum_dt, accelerations);
+            }
+            let mut satellite_tidal_pairs = tides::satellite_tidal_pairs(particles, self.hosts.index.tides);
+            if !satellite_tidal_pairs.is_empty() {
+                tides::calculate_tidal_pairs(particles, &mut satellite_tidal_pairs, &mut self.pair_dependent_scaled_dissipation_factor, &self.love_number_tables, &mut self.kaula_functions_cache, dangular_momentum_dt, accelerations);
+            }
+        }
+        if self.consider_effects.rotational_flattening && (dangular_momentum_dt || accelerations) {
+            // Added to the rotational flattening of the central host
+            let satellite_rotational_flattening_pairs = rotational_flattening::satellite_rotational_flattening_pairs(particles, self.hosts.index.rotational_flattening);
+            if !satellite_rotational_flattening_pairs.is_empty() {
+                rotational_flattening::calculate_rotational_flattening_pairs(particles, &satellite_rotational_flattening_pairs, dangular_momentum_dt, accelerations);
+            }
+        }
         if accelerations && self.consider_effects.general_relativity {
             let (mut particles_left, particles_right) = particles.split_at_mut(self.hosts.index.general_relativity);
             if let Some((mut general_relativity_host_particle, mut particles_right)) = particles_right.split_first_mut() {
@@ -615,8 +1021,20 @@ impl Universe {
 
     pub fn calculate_denergy_dt(&mut self) {
         let (particles_left, particles_right) = self.particles[..self.n_particles].split_at_mut(self.hosts.index.tides);
-        if let Some((_, particles_right)) = particles_right.split_first_mut() {
-            tides::calculate_denergy_dt(particles_left, particles_right);
+        if let Some((tidal_host_particle, particles_right)) = particles_right.split_first_mut() {
+            let mut context = TidalContext {
+                pair_dependent_scaled_dissipation_factor: &mut self.pair_dependent_scaled_dissipation_factor,
+                love_number_tables: &self.love_number_tables,
+                kaula_functions_cache: &mut self.kaula_functions_cache,
+            };
+            tides::calculate_denergy_dt(tidal_host_particle, particles_left, particles_right, &mut context);
+        }
+        if !self.tidal_pairs.is_empty() {
+            tides::calculate_tidal_pairs_denergy_dt(&mut self.particles[..self.n_particles], &mut self.tidal_pairs, &mut self.pair_dependent_scaled_dissipation_factor, &self.love_number_tables, &mut self.kaula_functions_cache);
+        }
+        let mut satellite_tidal_pairs = tides::satellite_tidal_pairs(&self.particles[..self.n_particles], self.hosts.index.tides);
+        if !satellite_tidal_pairs.is_empty() {
+            tides::calculate_tidal_pairs_denergy_dt(&mut self.particles[..self.n_particles], &mut satellite_tidal_pairs, &mut self.pair_dependent_scaled_dissipation_factor, &self.love_number_tables, &mut self.kaula_functions_cache);
         }
     }
 
@@ -660,6 +1078,35 @@ impl Universe {
 
 
 
+/// Check if moment of inertia and angular momentum needs to be recomputed to match the
+/// proper state of the selected evolving body model (i.e., radius and radius of gyration
+/// are good given the current time)
+fn match_evolving_body_model(particle: &mut Particle, evolver: &mut Evolver, current_time: f64) {
+    if EvolutionType::NonEvolving == particle.evolution {
+        return;
+    }
+    let mut update_angular_momentum = false;
+    let new_radius = evolver.radius(current_time, particle.radius);
+    if (new_radius - particle.radius).abs() > 1e-6 {
+        log_warning!("Changed radius value from '{:.6}' to '{:.6}' to match expected state following the selected evolving body model", particle.radius, new_radius);
+        particle.radius = new_radius;
+        update_angular_momentum = true;
+    }
+    let new_radius_of_gyration_2 = evolver.radius_of_gyration_2(current_time, particle.radius_of_gyration_2);
+    if (new_radius_of_gyration_2 - particle.radius_of_gyration_2).abs() > 1e-6 {
+        log_warning!("Changed radius of gyration value from '{:.6}' to '{:.6}' to match expected state following the selected evolving body model", particle.radius_of_gyration_2.sqrt(), new_radius_of_gyration_2.sqrt());
+        particle.radius_of_gyration_2 = new_radius_of_gyration_2;
+        update_angular_momentum = true;
+    }
+    if update_angular_momentum {
+        log_warning!("Recomputed moment of inertia and angular momentum to match expected state following the selected evolving body model");
+        particle.moment_of_inertia = particle.mass * particle.radius_of_gyration_2 * particle.radius.powi(2);
+        particle.angular_momentum.x = particle.spin.x * particle.moment_of_inertia;
+        particle.angular_momentum.y = particle.spin.y * particle.moment_of_inertia;
+        particle.angular_momentum.z = particle.spin.z * particle.moment_of_inertia;
+    }
+}
+
 fn get_center_of_mass_of_pair(center_of_mass_position: &mut Axes, center_of_mass_velocity: &mut Axes, center_of_mass_mass: f64, particle: &Particle) -> f64 {
     center_of_mass_position.x      = center_of_mass_position.x*center_of_mass_mass + particle.heliocentric_position.x*particle.mass;
     center_of_mass_position.y      = center_of_mass_position.y*center_of_mass_mass + particle.heliocentric_position.y*particle.mass;
@@ -696,6 +1143,249 @@ pub fn calculate_center_of_mass(particles: &Vec<Particle>) -> (Axes, Axes) {
     (center_of_mass_position, center_of_mass_velocity)
 }
 
+fn central_body_indices<F: Fn(&Particle) -> bool>(particles: &[Particle], is_central_body: F) -> Vec<usize> {
+    particles.iter().enumerate().filter(|(_, particle)| is_central_body(particle)).map(|(i, _)| i).collect()
+}
+
+fn has_general_relativity_central_body(particle: &Particle) -> bool {
+    match particle.general_relativity.effect {
+        GeneralRelativityEffect::CentralBody(implementation) => implementation != GeneralRelativityImplementation::Disabled,
+        _ => false,
+    }
+}
+
+fn calculate_orbital_period_around_reference(reference_particle: &Particle, particle: &Particle) -> f64 {
+    let position = Axes{
+        x: particle.inertial_position.x - reference_particle.inertial_position.x,
+        y: particle.inertial_position.y - reference_particle.inertial_position.y,
+        z: particle.inertial_position.z - reference_particle.inertial_position.z,
+    };
+    let velocity = Axes{
+        x: particle.inertial_velocity.x - reference_particle.inertial_velocity.x,
+        y: particle.inertial_velocity.y - reference_particle.inertial_velocity.y,
+        z: particle.inertial_velocity.z - reference_particle.inertial_velocity.z,
+    };
+    let (_semimajor_axis, _perihelion_distance, _eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, orbital_period) = calculate_keplerian_orbital_elements(reference_particle.mass_g+particle.mass_g, position, velocity);
+    orbital_period
+}
+
+/// Problems with the effects of the particles that do not depend on the state of the universe
+fn validate_effects(particles: &[Particle], consider_effects: &ConsiderEffects) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let tides_central_bodies = central_body_indices(particles, |particle| matches!(particle.tides.effect, TidesEffect::CentralBody(_)));
+    let rotational_flattening_central_bodies = central_body_indices(particles, |particle| matches!(particle.rotational_flattening.effect, RotationalFlatteningEffect::CentralBody(_)));
+    let general_relativity_central_bodies = central_body_indices(particles, has_general_relativity_central_body);
+    let disk_central_bodies = central_body_indices(particles, |particle| matches!(particle.disk.effect, DiskEffect::CentralBody(_)));
+    let effects = [
+        ("tidal", consider_effects.tides, &tides_central_bodies, central_body_indices(particles, |particle| particle.tides.effect != TidesEffect::Disabled)),
+        ("rotational flattening", consider_effects.rotational_flattening, &rotational_flattening_central_bodies, central_body_indices(particles, |particle| particle.rotational_flattening.effect != RotationalFlatteningEffect::Disabled)),
+        ("general relativity", consider_effects.general_relativity, &general_relativity_central_bodies, central_body_indices(particles, |particle| has_general_relativity_central_body(particle) || particle.general_relativity.effect == GeneralRelativityEffect::OrbitingBody)),
+        ("disk", consider_effects.disk, &disk_central_bodies, central_body_indices(particles, |particle| particle.disk.effect != DiskEffect::Disabled)),
+    ];
+    for (effect_name, considered, central_bodies, affected_particles) in effects.iter() {
+        if central_bodies.len() > 1 {
+            report.errors.push(format!("Only one central body is allowed for {} effects (found particles {:?})", effect_name, central_bodies));
+        }
+        if *considered && central_bodies.is_empty() {
+            report.warnings.push(format!("The {} effect is enabled but no central host was included (it will be disabled)", effect_name));
+        }
+        if !*considered {
+            for i in affected_particles.iter() {
+                report.warnings.push(format!("Particle {} has {} effect but the {} effect is disabled for this simulation", i, effect_name, effect_name));
+            }
+        }
+    }
+    let wind_particles = central_body_indices(particles, |particle| particle.wind.effect != WindEffect::Disabled);
+    let evolving_particles = central_body_indices(particles, |particle| particle.evolution != EvolutionType::NonEvolving);
+    for (effect_name, considered, affected_particles) in [("wind", consider_effects.wind, &wind_particles), ("evolution", consider_effects.evolution, &evolving_particles)].iter() {
+        if *considered && affected_particles.is_empty() {
+            report.warnings.push(format!("The {} effect is enabled but no particle has it (it will be disabled)", effect_name));
+        }
+        if !*considered {
+            for i in affected_particles.iter() {
+                report.warnings.push(format!("Particle {} has {} effect but the {} effect is disabled for this simulation", i, effect_name, effect_name));
+            }
+        }
+    }
+
+    // Hosts
+    if consider_effects.general_relativity && general_relativity_central_bodies.len() == 1 {
+        let max_mass = particles.iter().map(|particle| particle.mass).fold(0., f64::max);
+        if particles[general_relativity_central_bodies[0]].mass < max_mass {
+            report.errors.push(format!("The central body for general relativity (particle {}) should be the most massive one", general_relativity_central_bodies[0]));
+        }
+    }
+    if consider_effects.tides && consider_effects.rotational_flattening && tides_central_bodies.len() == 1 && rotational_flattening_central_bodies.len() == 1
+        && tides_central_bodies[0] != rotational_flattening_central_bodies[0] {
+        report.errors.push(format!("The central body for tidal (particle {}) & rotational flattening (particle {}) effects needs to be the same", tides_central_bodies[0], rotational_flattening_central_bodies[0]));
+    }
+
+    // Models
+    for (i, particle) in particles.iter().enumerate() {
+        if let Err(why) = particle.validate_uniform_viscosity_coefficient() {
+            report.errors.push(format!("Particle {}: {}", i, why));
+        }
+        if let TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model) = particle.tides.effect {
+            if let Err(why) = tidal_model.response().validate() {
+                report.errors.push(format!("Particle {}: {}", i, why));
+            }
+        }
+        if particle.evolution != EvolutionType::NonEvolving {
+            if let Err(why) = evolution::evolution_track_filename(particle.evolution) {
+                report.errors.push(format!("Particle {}: {}", i, why));
+            }
+        }
+        // The evolving dissipation (dynamical tides) and Love number only change the constant time lag model
+        let evolving_tidal_parameters = match particle.evolution {
+            EvolutionType::BolmontMathis2016(_) | EvolutionType::GalletBolmont2017(_) => Some("dissipation"),
+            EvolutionType::LeconteChabrier2013(true) => Some("dissipation and Love number"),
+            EvolutionType::LeconteChabrier2013(false) => Some("Love number"),
+            _ => None,
+        };
+        if let (Some(evolving_tidal_parameters), TidesEffect::CentralBody(tidal_model) | TidesEffect::OrbitingBody(tidal_model)) = (evolving_tidal_parameters, particle.tides.effect) {
+            if !matches!(tidal_model, TidalModel::ConstantTimeLag(_)) {
+                report.errors.push(format!("Particle {}: the evolution type {:?} evolves the {} of the ConstantTimeLag tidal model, it cannot be mixed with the {} tidal model", i, particle.evolution, evolving_tidal_parameters, tidal_model.response().name()));
+            }
+        }
+        // Creep coplanar models only consider the z components of the spin and the orbit
+        let creep_coplanar = matches!(particle.tides.effect, TidesEffect::CentralBody(TidalModel::CreepCoplanar(_)) | TidesEffect::OrbitingBody(TidalModel::CreepCoplanar(_)))
+            || matches!(particle.rotational_flattening.effect, RotationalFlatteningEffect::CentralBody(RotationalFlatteningModel::CreepCoplanar(_)) | RotationalFlatteningEffect::OrbitingBody(RotationalFlatteningModel::CreepCoplanar(_)));
+        if creep_coplanar {
+            let orbital_angular_momentum = Axes{
+                x: particle.heliocentric_position.y * particle.heliocentric_velocity.z - particle.heliocentric_position.z * particle.heliocentric_velocity.y,
+                y: particle.heliocentric_position.z * particle.heliocentric_velocity.x - particle.heliocentric_position.x * particle.heliocentric_velocity.z,
+                z: particle.heliocentric_position.x * particle.heliocentric_velocity.y - particle.heliocentric_position.y * particle.heliocentric_velocity.x,
+            };
+            for (vector_name, vector) in [("spin", particle.spin), ("orbit", orbital_angular_momentum)].iter() {
+                let tilt = (vector.x.powi(2) + vector.y.powi(2)).sqrt().atan2(vector.z.abs());
+                if tilt > CREEP_COPLANAR_MAX_TILT {
+                    report.warnings.push(format!("Particle {} uses the CreepCoplanar model but its {} is tilted {:e} rad with respect to the z axis (only the z components are considered)", i, vector_name, tilt));
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Problems with the pairs of particles with tides that do not involve the central host
+fn validate_tidal_pairs(particles: &[Particle], tidal_pairs: &[TidalPair], consider_effects: &ConsiderEffects, tides_host_index: usize) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    let satellite_tidal_pairs = tides::satellite_tidal_pairs(particles, tides_host_index);
+    if !tidal_pairs.is_empty() && !consider_effects.tides {
+        report.warnings.push(format!("There are {} pairs of particles with tides but the tidal effect is disabled for this simulation", tidal_pairs.len()));
+    }
+    for (i, tidal_pair) in tidal_pairs.iter().enumerate() {
+        if tidal_pair.host >= particles.len() || tidal_pair.orbiting >= particles.len() {
+            report.errors.push(format!("Tidal pair {} refers to particles {} and {} but there are only {} particles", i, tidal_pair.host, tidal_pair.orbiting, particles.len()));
+            continue;
+        }
+        if tidal_pair.host == tidal_pair.orbiting {
+            report.errors.push(format!("Tidal pair {} refers twice to particle {}", i, tidal_pair.host));
+            continue;
+        }
+        if tidal_pairs[..i].iter().any(|previous_tidal_pair| previous_tidal_pair.involves(tidal_pair.host) && previous_tidal_pair.involves(tidal_pair.orbiting)) {
+            report.errors.push(format!("Tides between particles {} and {} are included more than once", tidal_pair.host, tidal_pair.orbiting));
+        }
+        for (central_body, other) in [(tidal_pair.host, tidal_pair.orbiting), (tidal_pair.orbiting, tidal_pair.host)].iter() {
+            if *central_body == tides_host_index && matches!(particles[*other].tides.effect, TidesEffect::OrbitingBody(_)) {
+                report.errors.push(format!("Tides between particles {} and {} are already computed with the central host", tidal_pair.host, tidal_pair.orbiting));
+            }
+        }
+        if satellite_tidal_pairs.iter().any(|satellite_tidal_pair| satellite_tidal_pair.involves(tidal_pair.host) && satellite_tidal_pair.involves(tidal_pair.orbiting)) {
+            report.errors.push(format!("Tides between particles {} and {} are already computed because one orbits the other", tidal_pair.host, tidal_pair.orbiting));
+        }
+    }
+    report
+}
+
+/// Problems with the interior structures used to compute the Love numbers of the Kaula model
+fn validate_interior_structures(particles: &[Particle], interior_structures: &[InteriorStructure]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    for (i, interior_structure) in interior_structures.iter().enumerate() {
+        let index = interior_structure.particle;
+        if index >= particles.len() {
+            report.errors.push(format!("Interior structure {} refers to particle {} but there are only {} particles", i, index, particles.len()));
+            continue;
+        }
+        if interior_structures[..i].iter().any(|previous_interior_structure| previous_interior_structure.particle == index) {
+            report.errors.push(format!("Particle {} has more than one interior structure", index));
+        }
+        if let Err(e) = interior_structure.validate() {
+            report.errors.push(e);
+            continue;
+        }
+        if !matches!(particles[index].tides.effect, TidesEffect::CentralBody(TidalModel::Kaula(_)) | TidesEffect::OrbitingBody(TidalModel::Kaula(_))) {
+            report.errors.push(format!("Particle {} has an interior structure but it does not use the Kaula tidal model", index));
+            continue;
+        }
+        let radius = particles[index].radius * AU;
+        if (interior_structure.radius() - radius).abs() > 1.0e-3 * radius {
+            report.warnings.push(format!("The interior structure of particle {} has a radius of {:.0} m but the particle radius is {:.0} m", index, interior_structure.radius(), radius));
+        }
+    }
+    report
+}
+
+/// Problems with the variable-size Love number tables of the Kaula model
+fn validate_love_number_tables(particles: &[Particle], love_number_tables: &[LoveNumberTable], interior_structures: &[InteriorStructure]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    for (i, love_number_table) in love_number_tables.iter().enumerate() {
+        let index = love_number_table.particle;
+        if index >= particles.len() {
+            report.errors.push(format!("Love number table {} refers to particle {} but there are only {} particles", i, index, particles.len()));
+            continue;
+        }
+        if love_number_tables[..i].iter().any(|previous_love_number_table| previous_love_number_table.particle == index) {
+            report.errors.push(format!("Particle {} has more than one Love number table", index));
+        }
+        if let Err(e) = love_number_table.validate() {
+            report.errors.push(e);
+        }
+        if !matches!(particles[index].tides.effect, TidesEffect::CentralBody(TidalModel::Kaula(_)) | TidesEffect::OrbitingBody(TidalModel::Kaula(_))) {
+            report.errors.push(format!("Particle {} has a Love number table but it does not use the Kaula tidal model", index));
+        }
+        let has_interior_structure = interior_structures.iter().any(|interior_structure| interior_structure.particle == index);
+        match love_number_table.source {
+            LoveNumberTableSource::InteriorStructure(_) if !has_interior_structure => {
+                report.errors.push(format!("Particle {} has a Love number table computed from an interior structure that it does not have", index));
+            },
+            LoveNumberTableSource::Embedded(_) | LoveNumberTableSource::File { .. } if has_interior_structure => {
+                report.errors.push(format!("Particle {} has both a Love number table and an interior structure (which computes its Love numbers)", index));
+            },
+            _ => {},
+        }
+    }
+    for (index, particle) in particles.iter().enumerate() {
+        let has_love_numbers = love_number_tables.iter().any(|love_number_table| love_number_table.particle == index)
+            || interior_structures.iter().any(|interior_structure| interior_structure.particle == index);
+        if !has_love_numbers && matches!(particle.tides.effect, TidesEffect::CentralBody(TidalModel::Kaula(_)) | TidesEffect::OrbitingBody(TidalModel::Kaula(_))) {
+            report.warnings.push(format!("Particle {} uses the Kaula tidal model without a Love number table or an interior structure, it will not be deformed", index));
+        }
+    }
+    report
+}
+
+fn validate_satellites(particles: &[Particle], consider_effects: &ConsiderEffects, hosts: &Hosts) -> ValidationReport {
+    // Particles that orbit another particle (e.g., moons) interact with it in addition to the central host
+    let mut report = ValidationReport::default();
+    for (i, particle) in particles.iter().enumerate() {
+        let host = match particle.reference {
+            Reference::Particle(index) if index != i && index < particles.len() => index,
+            _ => continue,
+        };
+        if consider_effects.tides && host != hosts.index.tides && matches!(particle.tides.effect, TidesEffect::OrbitingBody(_))
+            && !matches!(particles[host].tides.effect, TidesEffect::OrbitingBody(_)) {
+            report.warnings.push(format!("Particle {} orbits particle {} but tides between them are not computed because particle {} has no tidal effect (orbiting body)", i, host, host));
+        }
+        if consider_effects.rotational_flattening && host != hosts.index.rotational_flattening && matches!(particle.rotational_flattening.effect, RotationalFlatteningEffect::OrbitingBody(_))
+            && !matches!(particles[host].rotational_flattening.effect, RotationalFlatteningEffect::OrbitingBody(_)) {
+            report.warnings.push(format!("Particle {} orbits particle {} but rotational flattening between them is not computed because particle {} has no rotational flattening effect (orbiting body)", i, host, host));
+        }
+    }
+    report
+}
+
 fn disable_unnecessary_effects(consider_effects: &mut ConsiderEffects, particles: &Vec<Particle>) {
     let mut found_central_body_tides = false;
     let mut found_central_body_rotational_flattening = false;
@@ -706,27 +1396,27 @@ fn disable_unnecessary_effects(consider_effects: &mut ConsiderEffects, particles
     for particle in particles.iter() {
         if let TidesEffect::CentralBody(_) = particle.tides.effect {
             if found_central_body_tides {
-                panic!("[PANIC {} UTC] Only one central body is allowed for tidal effect!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+                fatal!("Only one central body is allowed for tidal effect!");
             }
             found_central_body_tides = true;
         }
         if let RotationalFlatteningEffect::CentralBody(_) = particle.rotational_flattening.effect {
             if found_central_body_rotational_flattening {
-                panic!("[PANIC {} UTC] Only one central body is allowed for rotational flattening effects!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+                fatal!("Only one central body is allowed for rotational flattening effects!");
             }
             found_central_body_rotational_flattening = true;
         }
         if let GeneralRelativityEffect::CentralBody(implementation) = particle.general_relativity.effect {
             if implementation != GeneralRelativityImplementation::Disabled {
                 if found_central_body_general_relativity {
-                    panic!("[PANIC {} UTC] Only one central body is allowed for general relativity effects!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+                    fatal!("Only one central body is allowed for general relativity effects!");
                 }
                 found_central_body_general_relativity = true;
             }
         }
         if let DiskEffect::CentralBody(_) = particle.disk.effect {
             if found_central_body_disk {
-                panic!("[PANIC {} UTC] Only one central body is allowed for disk effects!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+                fatal!("Only one central body is allowed for disk effects!");
             }
             found_central_body_disk = true;
         }
@@ -738,27 +1428,27 @@ fn disable_unnecessary_effects(consider_effects: &mut ConsiderEffects, particles
         }
     }
     if consider_effects.tides && !found_central_body_tides {
-        println!("[WARNING {} UTC] Disabled tides because no central host was included!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+        log_warning!("Disabled tides because no central host was included!");
         consider_effects.tides = false;
     }
     if consider_effects.rotational_flattening && !found_central_body_rotational_flattening {
-        println!("[WARNING {} UTC] Disabled rotational flattening because no central host was included!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+        log_warning!("Disabled rotational flattening because no central host was included!");
         consider_effects.rotational_flattening = false;
     }
     if consider_effects.general_relativity && !found_central_body_general_relativity {
-        println!("[WARNING {} UTC] Disabled general relativity because no central host was included!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+        log_warning!("Disabled general relativity because no central host was included!");
         consider_effects.general_relativity = false;
     }
     if consider_effects.disk && !found_central_body_disk {
-        println!("[WARNING {} UTC] Disabled disk because no central host was included!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+        log_warning!("Disabled disk because no central host was included!");
         consider_effects.disk = false;
     }
     if consider_effects.wind && !found_wind {
-        println!("[WARNING {} UTC] Disabled wind because no wind was included!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+        log_warning!("Disabled wind because no wind was included!");
         consider_effects.wind = false;
     }
     if consider_effects.evolution && !found_evolving_body {
-        println!("[WARNING {} UTC] Disabled evolution because no evolving body was included!", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+        log_warning!("Disabled evolution because no evolving body was included!");
         consider_effects.evolution = false;
     }
 }
@@ -770,22 +1460,22 @@ fn check_effects_vs_central_and_orbiting(particles: &Vec<Particle>, consider_eff
         if let TidesEffect::CentralBody(_) = particle.tides.effect {
             found_tides_central_body = true;
             if !consider_effects.tides {
-                println!("[WARNING {} UTC] Particle {} has tidal effect (central body) but the tidal effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has tidal effect (central body) but the tidal effect is disabled for this simulation", i);
             }
         }
         if let TidesEffect::OrbitingBody(_) = particle.tides.effect {
             found_tides_orbiting_body = true;
             if !consider_effects.tides {
-                println!("[WARNING {} UTC] Particle {} has tidal effect (orbiting body) but the tidal effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has tidal effect (orbiting body) but the tidal effect is disabled for this simulation", i);
             }
         }
     }
     if consider_effects.tides {
         if !found_tides_central_body {
-            println!("[INFO {} UTC] No central body for tidal effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No central body for tidal effects");
         } 
         if !found_tides_orbiting_body {
-            println!("[INFO {} UTC] No orbiting body for tidal effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No orbiting body for tidal effects");
         }
     }
 
@@ -795,22 +1485,22 @@ fn check_effects_vs_central_and_orbiting(particles: &Vec<Particle>, consider_eff
         if let RotationalFlatteningEffect::CentralBody(_) = particle.rotational_flattening.effect {
             found_rotational_flattening_central_body = true;
             if !consider_effects.rotational_flattening {
-                println!("[WARNING {} UTC] Particle {} has rotational flattening effect (central body) but the rotational flattening effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has rotational flattening effect (central body) but the rotational flattening effect is disabled for this simulation", i);
             }
         }
         if let RotationalFlatteningEffect::OrbitingBody(_) = particle.rotational_flattening.effect {
             found_rotational_flattening_orbiting_body = true;
             if !consider_effects.rotational_flattening {
-                println!("[WARNING {} UTC] Particle {} has rotatial flattening effect (orbiting body) but the rotatial flattening effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has rotatial flattening effect (orbiting body) but the rotatial flattening effect is disabled for this simulation", i);
             }
         }
     }
     if consider_effects.rotational_flattening {
         if !found_rotational_flattening_central_body {
-            println!("[INFO {} UTC] No central body for rotational flattening effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No central body for rotational flattening effects");
         } 
         if !found_rotational_flattening_orbiting_body {
-            println!("[INFO {} UTC] No orbiting body for rotational flattening effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No orbiting body for rotational flattening effects");
         }
     }
 
@@ -821,23 +1511,23 @@ fn check_effects_vs_central_and_orbiting(particles: &Vec<Particle>, consider_eff
             if implementation != GeneralRelativityImplementation::Disabled {
                 found_general_relativity_central_body = true;
                 if !consider_effects.general_relativity {
-                    println!("[WARNING {} UTC] Particle {} has general relativity effect (central body) but the general relativity effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                    log_warning!("Particle {} has general relativity effect (central body) but the general relativity effect is disabled for this simulation", i);
                 }
             }
         }
         if let GeneralRelativityEffect::OrbitingBody = particle.general_relativity.effect {
             found_general_relativity_orbiting_body = true;
             if !consider_effects.general_relativity {
-                println!("[WARNING {} UTC] Particle {} has general relativity effect (orbiting body) but the general relativity effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has general relativity effect (orbiting body) but the general relativity effect is disabled for this simulation", i);
             }
         }
     }
     if consider_effects.general_relativity {
         if !found_general_relativity_central_body {
-            println!("[INFO {} UTC] No central body for general relativity effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No central body for general relativity effects");
         } 
         if !found_general_relativity_orbiting_body {
-            println!("[INFO {} UTC] No orbiting body for general relativity effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No orbiting body for general relativity effects");
         }
     }
 
@@ -847,22 +1537,22 @@ fn check_effects_vs_central_and_orbiting(particles: &Vec<Particle>, consider_eff
         if let DiskEffect::CentralBody( _disk ) = particle.disk.effect {
             found_disk_central_body = true;
             if !consider_effects.disk {
-                println!("[WARNING {} UTC] Particle {} has disk effect (central body) but the disk effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has disk effect (central body) but the disk effect is disabled for this simulation", i);
             }
         }
         if let DiskEffect::OrbitingBody = particle.disk.effect {
             found_disk_orbiting_body = true;
             if !consider_effects.disk {
-                println!("[WARNING {} UTC] Particle {} has disk effect (orbiting body) but the disk effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has disk effect (orbiting body) but the disk effect is disabled for this simulation", i);
             }
         }
     }
     if consider_effects.disk {
         if !found_disk_central_body {
-            println!("[INFO {} UTC] No central body for disk effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No central body for disk effects");
         } 
         if !found_disk_orbiting_body {
-            println!("[INFO {} UTC] No orbiting body for disk effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No orbiting body for disk effects");
         }
     }
 
@@ -871,13 +1561,13 @@ fn check_effects_vs_central_and_orbiting(particles: &Vec<Particle>, consider_eff
         if let WindEffect::Interaction = particle.wind.effect {
             found_wind = true;
             if !consider_effects.wind {
-                println!("[WARNING {} UTC] Particle {} has wind effect (central body) but the wind effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has wind effect (central body) but the wind effect is disabled for this simulation", i);
             }
         }
     }
     if consider_effects.wind {
         if !found_wind {
-            println!("[INFO {} UTC] No wind effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No wind effects");
         } 
     }
 
@@ -886,13 +1576,13 @@ fn check_effects_vs_central_and_orbiting(particles: &Vec<Particle>, consider_eff
         if particle.evolution != EvolutionType::NonEvolving {
             found_evolution = true;
             if !consider_effects.evolution {
-                println!("[WARNING {} UTC] Particle {} has evolution effect but the evolution effect is disabled for this simulation", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap(), i);
+                log_warning!("Particle {} has evolution effect but the evolution effect is disabled for this simulation", i);
             }
         }
     }
     if consider_effects.evolution {
         if !found_evolution {
-            println!("[INFO {} UTC] No evolution effects", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_info!("No evolution effects");
         } 
     }
 }
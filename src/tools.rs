@@ -1,6 +1,4 @@
-extern crate time;
 use std;
-use time::{OffsetDateTime, format_description};
 use super::constants::*;
 use super::particles::Axes;
 //use std::cmp::Ord;
@@ -25,6 +23,10 @@ pub fn calculate_inclination_orbital_equatorial_plane(position: Axes, velocity:
     let hy = z * u - x * w;
     let hz = x * v - y * u;
     let h = (hx.powi(2) + hy.powi(2) + hz.powi(2)).sqrt();
+    if s == 0. || h == 0. {
+        // Non-rotating body or radial motion: the equatorial/orbital plane is undefined
+        return 0.;
+    }
 
     let hx_rel = hx / h;
     let hy_rel = hy / h;
@@ -715,7 +717,7 @@ fn kepler_solution_for_a_hyperbola_hybrid_approach_for_low_n(e: f64, capn0: f64)
                orbel_flon = -orbel_flon;
                capn = -capn;
             }
-            println!("[WARNING {} UTC] FLON : RETURNING WITHOUT COMPLETE CONVERGENCE", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+            log_warning!("FLON : RETURNING WITHOUT COMPLETE CONVERGENCE");
             diff = e * orbel_flon.sinh()  - orbel_flon - capn;
             println!("N, F, ecc * F.sinh() - F - N : ");
             println!("{} {} {}", capn,orbel_flon,diff);
@@ -797,7 +799,7 @@ fn kepler_solution_for_a_hyperbola_hybrid_approach(e: f64, capn: f64) -> f64 {
        x = orbel_fget;
     }
   
-    println!("[WARNING {} UTC] FGET : RETURNING WITHOUT COMPLETE CONVERGENCE", OffsetDateTime::now_utc().format(&format_description::parse("[year].[month].[day] [hour]:[minute]:[second]").unwrap()).unwrap());
+    log_warning!("FGET : RETURNING WITHOUT COMPLETE CONVERGENCE");
     return orbel_fget;
 }
 
@@ -24,12 +24,12 @@ pub fn get_data_dirname(test_name: &String) -> (String, String) {
 }
 
 #[allow(dead_code)]
-pub fn load_kaula_parameters(file_path: &str) -> Result<posidonius::KaulaParameters, Box<dyn Error>> {
+pub fn load_love_number_data(file_path: &str) -> Result<posidonius::LoveNumberData, Box<dyn Error>> {
     // Open the file
     let file = File::open(file_path)?;
     let reader = BufReader::new(file);
 
-    // Temporary vectors to hold the columns before assigning them to the struct
+    // Columns of the file (any number of points)
     let mut w_lm: Vec<f64> = Vec::new();
     let mut im_k2: Vec<f64> = Vec::new();
     let mut re_k2: Vec<f64> = Vec::new();
@@ -53,26 +53,9 @@ pub fn load_kaula_parameters(file_path: &str) -> Result<posidonius::KaulaParamet
         }
     }
 
-    // Create a zeroed out array for the Kaula parameters (size: 32*32 = 1024)
-    let mut love_number_excitation_frequency = [0.0; 32 * 32];
-    let mut real_part_love_number = [0.0; 32 * 32];
-    let mut imaginary_part_love_number = [0.0; 32 * 32];
-
-    // Fill the arrays with data from the vectors, up to the size of 1024 or the length of the data
-    let num_datapoints = w_lm.len().min(32 * 32);  // Use the minimum of the two lengths
-
-    // Copy data from the vectors into the fixed-size arrays
-    love_number_excitation_frequency[..num_datapoints].copy_from_slice(&w_lm[..num_datapoints]);
-    real_part_love_number[..num_datapoints].copy_from_slice(&re_k2[..num_datapoints]);
-    imaginary_part_love_number[..num_datapoints].copy_from_slice(&im_k2[..num_datapoints]);
-
-    // Build and return the KaulaParameters struct
-    Ok(posidonius::KaulaParameters {
-        love_number_excitation_frequency,
-        real_part_love_number,
-        imaginary_part_love_number,
-        num_datapoints: num_datapoints as f64,
-        polynomials: posidonius::Polynomials::new(),
-        kaula_tidal_force: posidonius::Axes { x: 0.0, y: 0.0, z: 0.0 },
+    Ok(posidonius::LoveNumberData {
+        excitation_frequency: w_lm,
+        real_part: re_k2,
+        imaginary_part: im_k2,
     })
 }
@@ -34,12 +34,12 @@ pub fn store_positions_unless_files_exist(universe: &posidonius::Universe, dirna
 }
 
 #[allow(dead_code)]
-pub fn store_unless_files_exist<I: serde::ser::Serialize>(universe_integrator: &I, dirname: &String) {
+pub fn store_unless_files_exist<I: serde::ser::Serialize + posidonius::TaggedSnapshot>(universe_integrator: &I, dirname: &String) {
     let _ = fs::create_dir(&dirname);
     let snapshot_filename = format!("{0}/case.json", dirname);
     let snapshot_path = Path::new(&snapshot_filename);
     if ! Path::new(&snapshot_path).exists() {
-        posidonius::output::write_recovery_snapshot(snapshot_path, &universe_integrator);
+        posidonius::output::write_recovery_snapshot(snapshot_path, universe_integrator).unwrap();
     }
 }
 
@@ -80,7 +80,7 @@ pub fn iterate<T>(universe_integrator: &mut T) where T: posidonius::Integrator {
     let mut universe_history_writer = posidonius::output::get_universe_history_writer(universe_history_path, expected_n_bytes);
     universe_integrator.initialize_physical_values();
     loop {
-        match universe_integrator.iterate(&mut universe_history_writer, silent_mode) {
+        match universe_integrator.iterate(&mut universe_history_writer, &mut [], silent_mode) {
             Ok(_) => { },
             Err(_) => { break; }
         };
@@ -96,7 +96,7 @@ pub fn one_step<T>(universe_integrator: &mut T) where T: posidonius::Integrator
     let silent_mode = true;
     let mut universe_history_writer = posidonius::output::get_universe_history_writer(universe_history_path, expected_n_bytes);
     universe_integrator.initialize_physical_values();
-    let _ = universe_integrator.iterate(&mut universe_history_writer, silent_mode);
+    let _ = universe_integrator.iterate(&mut universe_history_writer, &mut [], silent_mode);
     let _ = fs::remove_file(universe_history_filename);
 }
 
@@ -108,7 +108,7 @@ fn one_step_box(universe_integrator: &mut Box<dyn posidonius::Integrator>) {
     let silent_mode = true;
     let mut universe_history_writer = posidonius::output::get_universe_history_writer(universe_history_path, expected_n_bytes);
     universe_integrator.initialize_physical_values();
-    let _ = universe_integrator.iterate(&mut universe_history_writer, silent_mode);
+    let _ = universe_integrator.iterate(&mut universe_history_writer, &mut [], silent_mode);
     let _ = fs::remove_file(universe_history_filename);
 }
 
@@ -122,7 +122,7 @@ fn iterate_box(universe_integrator: &mut Box<dyn posidonius::Integrator>) {
     let mut universe_history_writer = posidonius::output::get_universe_history_writer(universe_history_path, expected_n_bytes);
     universe_integrator.initialize_physical_values();
     loop {
-        match universe_integrator.iterate(&mut universe_history_writer, silent_mode) {
+        match universe_integrator.iterate(&mut universe_history_writer, &mut [], silent_mode) {
             Ok(_) => { break; },
             Err(_) => { break; }
         };
@@ -130,12 +130,26 @@ fn iterate_box(universe_integrator: &mut Box<dyn posidonius::Integrator>) {
     let _ = fs::remove_file(universe_history_filename);
 }
 
+#[allow(dead_code)]
+pub fn initialized_integrator_from_json(test_name: &str) -> Box<dyn posidonius::Integrator> {
+    // Ready to compute the effects without integrating (e.g., after changing the models of some particles)
+    let snapshot_filename = format!("tests/data/{0}/case.json", test_name);
+    let mut boxed_universe_integrator : Box<dyn posidonius::Integrator> = posidonius::output::read_snapshot(Path::new(&snapshot_filename)).unwrap();
+    boxed_universe_integrator.initialize_physical_values();
+    boxed_universe_integrator
+}
+
+#[allow(dead_code)]
+pub fn initialized_universe_from_json(test_name: &str) -> posidonius::Universe {
+    initialized_integrator_from_json(test_name).get_universe().clone()
+}
+
 #[allow(dead_code)]
 pub fn iterate_universe_from_json(dirname: &String) -> posidonius::Universe {
     let snapshot_from_python_filename = format!("{0}/case.json", dirname);
     let snapshot_from_python_path = Path::new(&snapshot_from_python_filename);
    
-    let mut boxed_universe_integrator_from_python : Box<dyn posidonius::Integrator> = posidonius::output::restore_snapshot(&snapshot_from_python_path).unwrap();
+    let mut boxed_universe_integrator_from_python : Box<dyn posidonius::Integrator> = posidonius::output::restore_snapshot(snapshot_from_python_path).unwrap();
     iterate_box(&mut boxed_universe_integrator_from_python);
     // Extract universe from the integrator
     let universe_from_python: posidonius::Universe = match boxed_universe_integrator_from_python.as_any().downcast_ref::<posidonius::WHFast>() {
@@ -162,7 +176,7 @@ pub fn one_step_from_json(dirname: &String) -> posidonius::Universe {
     let snapshot_from_python_filename = format!("{0}/case.json", dirname);
     let snapshot_from_python_path = Path::new(&snapshot_from_python_filename);
    
-    let mut boxed_universe_integrator_from_python : Box<dyn posidonius::Integrator> = posidonius::output::restore_snapshot(&snapshot_from_python_path).unwrap();
+    let mut boxed_universe_integrator_from_python : Box<dyn posidonius::Integrator> = posidonius::output::restore_snapshot(snapshot_from_python_path).unwrap();
     one_step_box(&mut boxed_universe_integrator_from_python);
     // Extract universe from the integrator
     let universe_from_python: posidonius::Universe = match boxed_universe_integrator_from_python.as_any().downcast_ref::<posidonius::WHFast>() {
@@ -0,0 +1,119 @@
+extern crate posidonius;
+
+use std::fs;
+use std::path::Path;
+use posidonius::{EventTimeline, ScheduledEvent, EventAction, EventEffect, TidesEffect, TidalModel, GeneralRelativityImplementation};
+
+#[test]
+fn events_applied_at_scheduled_time() {
+    for case in ["test_integrator-whfast_jacobi", "test_integrator-ias15", "test_integrator-leapfrog"].iter() {
+        check_events_applied_at_scheduled_time(case);
+    }
+}
+
+fn check_events_applied_at_scheduled_time(case: &str) {
+    let case_path = Path::new("tests/data").join(case).join("case.json");
+    let patch: serde_json::Value = serde_json::from_str(r#"{"event_timeline": {"events": [
+            {"time": 1.0, "action": {"DisableEffect": {"effect": "GeneralRelativity"}}},
+            {"time": 1.0, "action": {"SetDissipationFactor": {"particle": 1, "dissipation_factor": 1.0e15}}},
+            {"time": 2.0, "action": {"SetTimeStep": {"time_step": 0.05}}}
+        ], "next_index": 0}}"#).unwrap();
+    let universe_integrator = posidonius::output::read_snapshot(&case_path).unwrap();
+    let mut universe_integrator = posidonius::inspect::edit_snapshot(universe_integrator.as_ref(), &patch).unwrap();
+    universe_integrator.initialize_physical_values();
+    assert!(posidonius::events::validate_events(universe_integrator.get_event_timeline(), universe_integrator.get_universe()).is_empty());
+    assert_eq!(universe_integrator.get_event_timeline().next_time(), Some(1.0));
+
+    let dirname = format!("target/tests/test_events-events_applied_at_scheduled_time-{}/", case);
+    let _ = fs::remove_dir_all(&dirname);
+    fs::create_dir_all(&dirname).unwrap();
+    let history_path = Path::new(&dirname).join("history.bin");
+    let mut universe_history_writer = posidonius::output::get_universe_history_writer(&history_path, 0);
+    let mut output_stream_writers = posidonius::output::get_output_stream_writers(&history_path, universe_integrator.get_output_streams(), universe_integrator.get_n_particles(), universe_integrator.get_particle_set_changes());
+
+    let mut times = Vec::new();
+    while universe_integrator.get_current_time() < 2.0 {
+        universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap();
+        times.push(universe_integrator.get_current_time());
+    }
+    // The steps that would go beyond the events are shortened to reach them exactly
+    assert!(times.contains(&1.0));
+    assert_eq!(times.last(), Some(&2.0));
+    assert_eq!(universe_integrator.get_event_timeline().applied_events().len(), 2);
+    assert_eq!(universe_integrator.get_event_timeline().next_time(), Some(2.0)); // Applied at the beginning of the next step
+    universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap();
+    assert_eq!(universe_integrator.get_event_timeline().applied_events().len(), 3);
+    if universe_integrator.get_integrator_type() != posidonius::IntegratorType::Ias15 {
+        // IAS15 adapts the time step after every step
+        assert_eq!(universe_integrator.get_time_step(), 0.05);
+    }
+
+    let universe = universe_integrator.get_universe();
+    assert!(!universe.consider_effects.general_relativity);
+    assert_eq!(universe.general_relativity_implementation, GeneralRelativityImplementation::Disabled);
+    match universe.particles[1].tides.effect {
+        TidesEffect::OrbitingBody(TidalModel::ConstantTimeLag(params)) => assert_eq!(params.dissipation_factor, 1.0e15),
+        _ => panic!("Unexpected tidal model"),
+    }
+    assert_eq!(universe.particles[1].tides.parameters.internal.scaled_dissipation_factor, 1.0e15);
+    // Initial snapshot plus one for each event time
+    assert_eq!(universe_integrator.get_n_historic_snapshots(), 3);
+    assert!(matches!(universe_integrator.get_event_timeline().applied_events()[1].action, EventAction::SetDissipationFactor { .. }));
+    assert_eq!(universe_integrator.get_event_timeline().applied_events()[0].action, EventAction::DisableEffect { effect: EventEffect::GeneralRelativity });
+    let _ = fs::remove_dir_all(&dirname);
+}
+
+#[test]
+fn dissipation_factor_events_require_constant_time_lag() {
+    let case_path = Path::new("tests/data/test_integrator-ias15/case.json");
+    let universe_integrator = posidonius::output::read_snapshot(case_path).unwrap();
+    // Particle 1 uses the constant time lag model until its tides are disabled
+    let patch: serde_json::Value = serde_json::from_str(r#"{"event_timeline": {"events": [
+            {"time": 1.0, "action": {"SetDissipationFactor": {"particle": 1, "dissipation_factor": 1.0e15}}},
+            {"time": 2.0, "action": {"SetTidesEffect": {"particle": 1, "effect": "Disabled"}}},
+            {"time": 3.0, "action": {"SetDissipationFactor": {"particle": 1, "dissipation_factor": 1.0e15}}}
+        ], "next_index": 0}}"#).unwrap();
+    let mut rejected = posidonius::inspect::edit_snapshot(universe_integrator.as_ref(), &patch).unwrap();
+    rejected.initialize_physical_values();
+    let errors = posidonius::events::validate_events(rejected.get_event_timeline(), rejected.get_universe());
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("Event at 3 days"));
+
+    // Valid again once a later event sets the constant time lag model back
+    let patch: serde_json::Value = serde_json::from_str(r#"{"event_timeline": {"events": [
+            {"time": 2.0, "action": {"SetTidesEffect": {"particle": 1, "effect": "Disabled"}}},
+            {"time": 3.0, "action": {"SetTidesEffect": {"particle": 1, "effect": {"OrbitingBody": {"ConstantTimeLag": {"dissipation_factor": 2.0e15, "dissipation_factor_scale": 1.0, "love_number": 0.3}}}}}},
+            {"time": 4.0, "action": {"SetDissipationFactor": {"particle": 1, "dissipation_factor": 1.0e15}}}
+        ], "next_index": 0}}"#).unwrap();
+    let mut accepted = posidonius::inspect::edit_snapshot(universe_integrator.as_ref(), &patch).unwrap();
+    accepted.initialize_physical_values();
+    assert!(posidonius::events::validate_events(accepted.get_event_timeline(), accepted.get_universe()).is_empty());
+}
+
+#[test]
+fn nan_event_times_are_rejected() {
+    let case_path = Path::new("tests/data/test_integrator-ias15/case.json");
+    let universe_integrator = posidonius::output::read_snapshot(case_path).unwrap();
+    let event_timeline = EventTimeline::new(vec![
+        ScheduledEvent { time: 1.0, action: EventAction::SetTimeStep { time_step: 0.1 } },
+        ScheduledEvent { time: f64::NAN, action: EventAction::SetTimeStep { time_step: 0.2 } },
+    ]);
+    let errors = posidonius::events::validate_events(&event_timeline, universe_integrator.get_universe());
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].contains("NaN scheduled time"));
+}
+
+#[test]
+fn applied_events_written_next_to_history() {
+    let dirname = "target/tests/test_events-applied_events_written_next_to_history/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let history_path = Path::new(dirname).join("history.bin");
+    let applied_events = vec![posidonius::events::ScheduledEvent { time: 1.0, action: EventAction::SetTimeStep { time_step: 0.05 } }];
+    posidonius::output::write_applied_events(&history_path, &applied_events);
+    let applied_events_path = posidonius::output::applied_events_path(&history_path);
+    assert_eq!(applied_events_path, Path::new(dirname).join("history.events.json"));
+    let recorded: Vec<posidonius::events::ScheduledEvent> = serde_json::from_str(&fs::read_to_string(&applied_events_path).unwrap()).unwrap();
+    assert_eq!(recorded, applied_events);
+    let _ = fs::remove_dir_all(dirname);
+}
@@ -0,0 +1,105 @@
+extern crate posidonius;
+
+use posidonius::{KaulaFunctions, KaulaFunctionsCache, inclination_function, eccentricity_function, hansen_coefficients};
+
+fn assert_close(value: f64, expected: f64, tolerance: f64) {
+    assert!((value - expected).abs() <= tolerance, "{} != {}", value, expected);
+}
+
+#[test]
+fn eccentricity_functions_of_low_eccentricity() {
+    // Series expansions from Kaula (1961), the neglected terms are of order e^5
+    let e = 0.01_f64;
+    assert_close(eccentricity_function(2, 0, 0, e), 1. - 5./2. * e.powi(2) + 13./16. * e.powi(4), 1e-9);
+    assert_close(eccentricity_function(2, 0, 1, e), 7./2. * e - 123./16. * e.powi(3), 1e-9);
+    assert_close(eccentricity_function(2, 0, -1, e), -1./2. * e + 1./16. * e.powi(3), 1e-9);
+    assert_close(eccentricity_function(2, 0, 2, e), 17./2. * e.powi(2) - 115./6. * e.powi(4), 1e-9);
+    assert_close(eccentricity_function(3, 1, 0, e), 1. + 2. * e.powi(2) + 239./64. * e.powi(4), 1e-9);
+    assert_close(eccentricity_function(3, 1, 1, e), 3. * e + 11./4. * e.powi(3), 1e-9);
+    // Circular orbits only keep q = 0
+    assert_eq!(hansen_coefficients(-3, 2, 0..=4, 0.), vec![0., 0., 1., 0., 0.]);
+}
+
+#[test]
+fn eccentricity_functions_of_high_eccentricity() {
+    // Closed forms of the time averages of (a/r)^(l+1) (i.e., G_lpq with l = 2p and q = 0)
+    for e in [0.3_f64, 0.6, 0.9].iter() {
+        assert_close(eccentricity_function(2, 1, 0, *e), (1. - e.powi(2)).powf(-3./2.), 1e-9);
+        assert_close(eccentricity_function(4, 2, 0, *e), (1. + 3./2. * e.powi(2)) * (1. - e.powi(2)).powf(-7./2.), 1e-9 * (1. - e.powi(2)).powf(-7./2.));
+        // G_l(l-p)(-q) = G_lpq
+        for q in -5..=5 {
+            assert_close(eccentricity_function(3, 3, -q, *e), eccentricity_function(3, 0, q, *e), 1e-9);
+        }
+    }
+}
+
+#[test]
+fn inclination_functions() {
+    // Kaula (1966), Table 1
+    let i = 0.4_f64;
+    let (s, c) = (i.sin(), i.cos());
+    let expected_f_2mp = [
+        [-3./8. * s.powi(2), 3./4. * s.powi(2) - 1./2., -3./8. * s.powi(2)],
+        [3./4. * s * (1. + c), -3./2. * s * c, -3./4. * s * (1. - c)],
+        [3./4. * (1. + c).powi(2), 3./2. * s.powi(2), 3./4. * (1. - c).powi(2)],
+    ];
+    let expected_f_3mp = [
+        [-5./16. * s.powi(3), 15./16. * s.powi(3) - 3./4. * s, -15./16. * s.powi(3) + 3./4. * s, 5./16. * s.powi(3)],
+        [-15./16. * s.powi(2) * (1. + c), 15./16. * s.powi(2) * (1. + 3. * c) - 3./4. * (1. + c), 15./16. * s.powi(2) * (1. - 3. * c) - 3./4. * (1. - c), -15./16. * s.powi(2) * (1. - c)],
+        [15./8. * s * (1. + c).powi(2), 15./8. * s * (1. - 2. * c - 3. * c.powi(2)), -15./8. * s * (1. + 2. * c - 3. * c.powi(2)), -15./8. * s * (1. - c).powi(2)],
+        [15./8. * (1. + c).powi(3), 45./8. * s.powi(2) * (1. + c), 45./8. * s.powi(2) * (1. - c), 15./8. * (1. - c).powi(3)],
+    ];
+    let kaula_functions_2 = KaulaFunctions::new(2, 0.1, i, 1e-8);
+    let kaula_functions_3 = KaulaFunctions::new(3, 0.1, i, 1e-8);
+    for m in 0..=2 {
+        for p in 0..=2 {
+            assert_close(inclination_function(2, m, p, i), expected_f_2mp[m][p], 1e-14);
+            assert_close(kaula_functions_2.inclination_function(m, p), expected_f_2mp[m][p], 1e-14);
+        }
+    }
+    for m in 0..=3 {
+        for p in 0..=3 {
+            assert_close(inclination_function(3, m, p, i), expected_f_3mp[m][p], 1e-14);
+            assert_close(kaula_functions_3.inclination_function(m, p), expected_f_3mp[m][p], 1e-14);
+        }
+    }
+    // Degree 4
+    assert_close(inclination_function(4, 0, 0, i), 35./128. * s.powi(4), 1e-14);
+    assert_close(inclination_function(4, 4, 0, i), 105./16. * (1. + c).powi(4), 1e-12);
+    assert_close(inclination_function(4, 4, 4, i), 105./16. * (1. - c).powi(4), 1e-12);
+}
+
+#[test]
+fn truncation_grows_with_eccentricity() {
+    let mut previous_q_max = 0;
+    for e in [0., 0.01, 0.1, 0.3, 0.5].iter() {
+        let kaula_functions = KaulaFunctions::new(2, *e, 0., 1e-8);
+        assert!(kaula_functions.q_max >= previous_q_max);
+        previous_q_max = kaula_functions.q_max;
+        // The neglected eccentricity functions are below the tolerance
+        let neglected_q = kaula_functions.q_max as i32 + 1;
+        for p in 0..=2 {
+            assert!(eccentricity_function(2, p, neglected_q, *e).abs() <= 1e-8 * (1. - e.powi(2)).powf(-3.));
+            assert!(eccentricity_function(2, p, -neglected_q, *e).abs() <= 1e-8 * (1. - e.powi(2)).powf(-3.));
+            assert_eq!(kaula_functions.eccentricity_function(p, neglected_q), 0.);
+        }
+    }
+    assert_eq!(KaulaFunctions::new(2, 0., 0., 1e-8).q_max, 0);
+    assert!(previous_q_max > 7);
+}
+
+#[test]
+fn cached_kaula_functions() {
+    let tolerance = posidonius::constants::KAULA_FUNCTIONS_CACHE_TOLERANCE;
+    let mut kaula_functions_cache = KaulaFunctionsCache::default();
+    assert_eq!(kaula_functions_cache.kaula_functions(1, 0, 2, 0.1, 0.2), &KaulaFunctions::new(2, 0.1, 0.2, 1e-8));
+    // Small changes of the orbit keep the functions
+    assert_eq!(kaula_functions_cache.kaula_functions(1, 0, 2, 0.1 + 0.5 * tolerance, 0.2 - 0.5 * tolerance).eccentricity, 0.1);
+    // Larger changes compute them again
+    assert_eq!(kaula_functions_cache.kaula_functions(1, 0, 2, 0.1 + 2. * tolerance, 0.2), &KaulaFunctions::new(2, 0.1 + 2. * tolerance, 0.2, 1e-8));
+    assert_eq!(kaula_functions_cache.kaula_functions(1, 0, 2, 0.1 + 2. * tolerance, 0.2 + 2. * tolerance).inclination, 0.2 + 2. * tolerance);
+    // Each pair of bodies and degree has its own functions
+    assert_eq!(kaula_functions_cache.kaula_functions(2, 0, 2, 0.3, 0.), &KaulaFunctions::new(2, 0.3, 0., 1e-8));
+    assert_eq!(kaula_functions_cache.kaula_functions(1, 0, 3, 0.1, 0.2), &KaulaFunctions::new(3, 0.1, 0.2, 1e-8));
+    assert_eq!(kaula_functions_cache.len(), 3);
+}
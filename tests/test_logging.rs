@@ -0,0 +1,28 @@
+extern crate posidonius;
+
+use std::sync::{Arc, Mutex};
+use posidonius::logging::{LogLevel, LogRecord};
+
+#[test]
+fn capture_and_silence_messages() {
+    let records: Arc<Mutex<Vec<LogRecord>>> = Arc::new(Mutex::new(Vec::new()));
+    let captured_records = Arc::clone(&records);
+    posidonius::logging::set_console_output(false);
+    posidonius::logging::set_level(LogLevel::Warning);
+    posidonius::logging::set_handler(Some(Box::new(move |record| captured_records.lock().unwrap().push(record.clone()))));
+
+    posidonius::log_info!("Ignored below the minimum level");
+    let mut particle = posidonius::Particle::new_dummy();
+    particle.set_evolution(posidonius::EvolutionType::Leconte2011(0.0800)); // Library warning
+
+    posidonius::logging::set_handler(None);
+    posidonius::logging::set_level(LogLevel::Info);
+    posidonius::logging::set_console_output(true);
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].level, LogLevel::Warning);
+    assert_eq!(records[0].target, "posidonius::particles::particle");
+    assert_eq!(records[0].message, "Bodies with Leconte2011 evolution will ignore initial radius and radius of gyration.");
+    assert!(records[0].to_text().starts_with("[WARNING "));
+}
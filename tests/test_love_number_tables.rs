@@ -0,0 +1,185 @@
+extern crate posidonius;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use posidonius::{LoveNumberTable, LoveNumberData, LoveNumberInterpolation, KaulaParameters, TidesEffect, TidalModel, IgnoreGravityTerms};
+use posidonius::{InteriorStructure, RheologicalLayer, Rheology};
+use posidonius::constants::AU;
+
+/// Universe where the first planet uses the Kaula model
+fn kaula_universe() -> posidonius::Universe {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    universe.particles[1].tides.effect = TidesEffect::OrbitingBody(TidalModel::Kaula(KaulaParameters {}));
+    universe
+}
+
+/// Love numbers of a Maxwell planet with the radius of the first planet (1024 points)
+fn maxwell_love_numbers(universe: &posidonius::Universe) -> LoveNumberData {
+    let radius = universe.particles[1].radius * AU;
+    let layers = vec![RheologicalLayer { outer_radius: radius, density: 5500., rigidity: 8.0e10, viscosity: 1.0e19, bulk_modulus: 0., rheology: Rheology::Maxwell }];
+    InteriorStructure::new(1, layers).love_number_table().data().clone()
+}
+
+fn tidal_accelerations(universe: &mut posidonius::Universe) -> Vec<[f64; 3]> {
+    universe.inertial_to_heliocentric();
+    universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+    universe.particles[..universe.n_particles].iter().map(|particle| {
+        let acceleration = particle.tides.parameters.output.acceleration;
+        [acceleration.x, acceleration.y, acceleration.z]
+    }).collect()
+}
+
+#[test]
+fn love_number_table_interpolation() {
+    // Power law (e.g., Maxwell rheology at high frequencies) with a sign change at the end
+    let frequencies = vec![1.0e-8, 1.0e-6, 1.0e-4, 1.0e-2, 1.0];
+    let values: Vec<f64> = frequencies.iter().map(|frequency: &f64| 2. * frequency.powf(-0.5)).collect();
+    let mut real_part = values.clone();
+    real_part[4] = -1.;
+    let data = LoveNumberData { excitation_frequency: frequencies, real_part, imaginary_part: values };
+    let table = |interpolation| LoveNumberTable::new(1, data.clone(), interpolation);
+    let power_law = |frequency: f64| 2. * frequency.powf(-0.5);
+
+    let frequency = 1.0e-5;
+    let (_, linear) = table(LoveNumberInterpolation::Linear).love_number(frequency);
+    let (_, log_log) = table(LoveNumberInterpolation::LogLog).love_number(frequency);
+    let (_, spline) = table(LoveNumberInterpolation::Spline).love_number(frequency);
+    assert!(((log_log - power_law(frequency)) / power_law(frequency)).abs() < 1.0e-12);
+    assert!((log_log - power_law(frequency)).abs() < (linear - power_law(frequency)).abs());
+    // No overshooting between the tabulated points
+    assert!(spline < data.imaginary_part[1] && spline > data.imaginary_part[2]);
+    // Smooth data
+    let parabola = LoveNumberData { excitation_frequency: vec![0., 1., 2., 3., 4.], real_part: vec![0., 1., 4., 9., 16.], imaginary_part: vec![0.; 5] };
+    let (linear, _) = LoveNumberTable::new(1, parabola.clone(), LoveNumberInterpolation::Linear).love_number(2.5);
+    let (spline, _) = LoveNumberTable::new(1, parabola, LoveNumberInterpolation::Spline).love_number(2.5);
+    assert!((linear - 6.25).abs() > 10. * (spline - 6.25).abs());
+
+    for interpolation in [LoveNumberInterpolation::Linear, LoveNumberInterpolation::LogLog, LoveNumberInterpolation::Spline].iter() {
+        let table = table(*interpolation);
+        assert!(table.validate().is_ok());
+        // Tabulated points and clamping outside the table
+        assert_eq!(table.love_number(1.0e-4), (data.real_part[2], data.imaginary_part[2]));
+        assert_eq!(table.love_number(1.0e-12), (data.real_part[0], data.imaginary_part[0]));
+        assert_eq!(table.love_number(1.0e3), (data.real_part[4], data.imaginary_part[4]));
+    }
+    // Different signs are linearly interpolated
+    let (real_part, _) = table(LoveNumberInterpolation::LogLog).love_number(0.5);
+    assert_eq!(real_part, table(LoveNumberInterpolation::Linear).love_number(0.5).0);
+
+    let mut unsorted_data = data.clone();
+    unsorted_data.excitation_frequency.swap(1, 2);
+    assert!(LoveNumberTable::new(1, unsorted_data, LoveNumberInterpolation::Linear).validate().unwrap_err().contains("increasing order"));
+}
+
+#[test]
+fn love_number_table_files() {
+    let data = LoveNumberData { excitation_frequency: vec![-1.0e-4, 1.0e-6, 1.0e-4], real_part: vec![-1.2, -1.4, -1.2], imaginary_part: vec![0.01, -0.1, -0.01] };
+    let dirname = "target/tests/test_love_number_tables-love_number_table_files/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+
+    let text_path = Path::new(dirname).join("love_numbers.txt");
+    let mut text = String::from("# frequency imaginary real\n");
+    let mut binary = b"POSLOVE1".to_vec();
+    binary.extend_from_slice(&(data.excitation_frequency.len() as u64).to_le_bytes());
+    for i in 0..data.excitation_frequency.len() {
+        text.push_str(&format!("{:e} {:e} {:e}\n", data.excitation_frequency[i], data.imaginary_part[i], data.real_part[i]));
+        for value in [data.excitation_frequency[i], data.imaginary_part[i], data.real_part[i]].iter() {
+            binary.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    fs::write(&text_path, text).unwrap();
+    let binary_path = Path::new(dirname).join("love_numbers.bin");
+    fs::write(&binary_path, &binary).unwrap();
+
+    for path in [&text_path, &binary_path].iter() {
+        let mut table = LoveNumberTable::from_file(1, path, LoveNumberInterpolation::Spline).unwrap();
+        assert_eq!(table.data(), &data);
+        assert!(table.load().is_ok());
+    }
+
+    // Files that changed after the creation of the simulation are detected
+    let mut table = LoveNumberTable::from_file(1, &binary_path, LoveNumberInterpolation::Linear).unwrap();
+    fs::write(&binary_path, &binary[..binary.len() - 8]).unwrap();
+    assert!(table.load().unwrap_err().contains("changed"));
+    assert!(LoveNumberTable::from_file(1, &binary_path, LoveNumberInterpolation::Linear).unwrap_err().contains("should contain 3 points"));
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn love_number_tables_of_any_size() {
+    // Bodies without Love numbers are not deformed
+    let mut universe = kaula_universe();
+    assert!(universe.validate(-1.).warnings.iter().any(|warning| warning.contains("Particle 1 uses the Kaula tidal model without a Love number table")));
+    let undeformed_accelerations = tidal_accelerations(&mut universe);
+
+    let data = maxwell_love_numbers(&universe);
+    universe.set_love_number_table(LoveNumberTable::new(1, data.clone(), LoveNumberInterpolation::Linear)).unwrap();
+    assert!(universe.validate(-1.).warnings.iter().all(|warning| !warning.contains("Kaula")));
+    let reference_accelerations = tidal_accelerations(&mut universe);
+    // Only the tide raised on the star remains without a table, it is much weaker than the planetary tide
+    let norm = |acceleration: &[f64; 3]| acceleration.iter().map(|value| value.powi(2)).sum::<f64>().sqrt();
+    assert!(norm(&undeformed_accelerations[1]) < 1.0e-2 * norm(&reference_accelerations[1]));
+
+    // Tables are not limited to 1024 points
+    let mut refined_data = data.clone();
+    for i in (1..data.excitation_frequency.len()).rev() {
+        refined_data.excitation_frequency.insert(i, 0.5 * (data.excitation_frequency[i - 1] + data.excitation_frequency[i]));
+        refined_data.real_part.insert(i, 0.5 * (data.real_part[i - 1] + data.real_part[i]));
+        refined_data.imaginary_part.insert(i, 0.5 * (data.imaginary_part[i - 1] + data.imaginary_part[i]));
+    }
+    universe.set_love_number_table(LoveNumberTable::new(1, refined_data, LoveNumberInterpolation::Linear)).unwrap();
+    assert_eq!(universe.love_number_tables.len(), 1);
+    assert_eq!(universe.love_number_tables[0].data().excitation_frequency.len(), 2047);
+    let accelerations = tidal_accelerations(&mut universe);
+    for (acceleration, reference_acceleration) in accelerations.iter().zip(reference_accelerations.iter()) {
+        // Relative to the norm, since small components result from the cancellation of many terms
+        for i in 0..3 {
+            assert!((acceleration[i] - reference_acceleration[i]).abs() <= 1.0e-12 * norm(reference_acceleration));
+        }
+    }
+
+    // Only for particles with the Kaula model and without interior structure
+    assert!(universe.set_love_number_table(LoveNumberTable::new(2, data.clone(), LoveNumberInterpolation::Linear)).unwrap_err().contains("Kaula"));
+    universe.interior_structures.push(InteriorStructure::new(1, vec![RheologicalLayer { outer_radius: 1.0e7, density: 5500., rigidity: 8.0e10, viscosity: 1.0e19, bulk_modulus: 0., rheology: Rheology::Maxwell }]));
+    assert!(universe.validate(-1.).errors.iter().any(|error| error.contains("both a Love number table and an interior structure")));
+    universe.interior_structures.clear();
+
+    // Tables follow the particles
+    universe.remove_particle(2).unwrap();
+    assert_eq!(universe.love_number_tables[0].particle, 1);
+    universe.remove_particle(1).unwrap();
+    assert!(universe.love_number_tables.is_empty());
+}
+
+#[test]
+fn love_number_table_in_snapshot() {
+    let dirname = "target/tests/test_love_number_tables-love_number_table_in_snapshot/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let table_path = Path::new(dirname).join("love_numbers.txt");
+    fs::write(&table_path, "-1.0e-4 0.01 -1.2\n1.0e-6 -0.1 -1.4\n1.0e-4 -0.01 -1.2\n").unwrap();
+
+    let case_path = Path::new("tests/data/test_integrator-ias15/case.json");
+    let mut universe_integrator = posidonius::output::read_snapshot(case_path).unwrap();
+    universe_integrator.get_universe_mut().particles[1].tides.effect = TidesEffect::OrbitingBody(TidalModel::Kaula(KaulaParameters {}));
+    let love_number_table = LoveNumberTable::from_file(1, &table_path, LoveNumberInterpolation::LogLog).unwrap();
+    universe_integrator.get_universe_mut().set_love_number_table(love_number_table.clone()).unwrap();
+    let universe_integrator = universe_integrator.as_any().downcast_ref::<posidonius::Ias15>().unwrap();
+
+    for extension in ["json", "bin"].iter() {
+        let snapshot_path = Path::new(dirname).join(format!("case.{}", extension));
+        posidonius::output::write_recovery_snapshot(&snapshot_path, universe_integrator).unwrap();
+        let restored_universe_integrator = posidonius::output::restore_snapshot(&snapshot_path).unwrap();
+        assert_eq!(restored_universe_integrator.get_universe().love_number_tables, vec![love_number_table.clone()]);
+        assert!(posidonius::inspect::inspect_snapshot(restored_universe_integrator.as_ref()).contains("Love number tables: 1 (3 points, LogLog)"));
+    }
+    // The file is not embedded in the snapshot
+    fs::write(&table_path, "-1.0e-4 0.01 -1.2\n1.0e-6 -0.2 -1.4\n1.0e-4 -0.01 -1.2\n").unwrap();
+    assert!(posidonius::output::restore_snapshot(&Path::new(dirname).join("case.bin")).is_err());
+    let _ = fs::remove_dir_all(dirname);
+}
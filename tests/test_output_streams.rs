@@ -0,0 +1,104 @@
+extern crate posidonius;
+extern crate serde_json;
+
+use std::fs;
+use std::io::BufWriter;
+use std::path::Path;
+use posidonius::Integrator;
+use posidonius::{OutputStream, OutputSchedule, OutputField, EventTimeline, ScheduledEvent, EventAction};
+
+fn restore_ias15(snapshot_path: &Path) -> posidonius::Ias15 {
+    let boxed_universe_integrator : Box<dyn posidonius::Integrator> = posidonius::output::restore_snapshot(snapshot_path).unwrap();
+    match boxed_universe_integrator.as_any().downcast_ref::<posidonius::Ias15>() {
+        Some(universe_integrator) => { universe_integrator.clone() },
+        None => { panic!("Wrong integrator!"); }
+    }
+}
+
+fn simulate_averaged_stream(dirname: &str, name: &str, synchronize_every_step: bool) -> Vec<f64> {
+    let fields = vec![OutputField::HeliocentricPosition, OutputField::HeliocentricVelocity, OutputField::Spin, OutputField::SpinRate,
+                        OutputField::Obliquity, OutputField::SemiMajorAxis, OutputField::DenergyDt];
+    let mut universe_integrator = restore_ias15(Path::new("tests/data/test_integrator-ias15/case.json"));
+    universe_integrator.output_streams.push(OutputStream::new("averaged", OutputSchedule::Linear { period: 4. }, fields.clone(), 0., -1., true));
+    if synchronize_every_step {
+        // An instantaneous stream due at every time step synchronizes the whole universe before sampling
+        universe_integrator.output_streams.push(OutputStream::new("instantaneous", OutputSchedule::Linear { period: 1.0e-6 }, fields, 0., -1., false));
+    }
+    let history_path = Path::new(dirname).join(format!("{}_history.bin", name));
+    let mut universe_history_writer = BufWriter::new(fs::File::create(&history_path).unwrap());
+    let mut output_stream_writers: Vec<BufWriter<fs::File>> = universe_integrator.output_streams.iter()
+                                        .map(|output_stream| BufWriter::new(fs::File::create(posidonius::output::output_stream_path(&history_path, output_stream)).unwrap()))
+                                        .collect();
+    while universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).is_ok() {}
+    drop(output_stream_writers);
+
+    // Rows: current time, time step and particle id followed by the mean, minimum and maximum of every value
+    let output_stream = &universe_integrator.output_streams[0];
+    let bytes = fs::read(posidonius::output::output_stream_path(&history_path, output_stream)).unwrap();
+    assert_eq!(bytes.len() as u64 % output_stream.n_bytes_per_particle(), 0);
+    let mut values = Vec::new();
+    for row in bytes.chunks(output_stream.n_bytes_per_particle() as usize) {
+        values.extend(row[20..].chunks(8).map(|value| f64::from_le_bytes([value[0], value[1], value[2], value[3], value[4], value[5], value[6], value[7]])));
+    }
+    values
+}
+
+#[test]
+fn averaged_stream_without_synchronization() {
+    let dirname = "target/tests/test_output_streams-averaged_stream_without_synchronization/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let values = simulate_averaged_stream(dirname, "integrated_state", false);
+    let expected_values = simulate_averaged_stream(dirname, "synchronized", true);
+    assert!(!values.is_empty());
+    assert_eq!(values.len(), expected_values.len());
+    for (value, expected_value) in values.iter().zip(expected_values.iter()) {
+        if expected_value.is_finite() {
+            assert!((value - expected_value).abs() <= 1.0e-8 * expected_value.abs(), "{} != {}", value, expected_value);
+        } else {
+            // The dissipated power is not defined before the first step
+            assert_eq!(value.is_nan(), expected_value.is_nan());
+        }
+    }
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn averaged_stream_weighted_by_the_time_step_taken() {
+    let dirname = "target/tests/test_output_streams-averaged_stream_weighted_by_the_time_step_taken/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let mut universe_integrator = restore_ias15(Path::new("tests/data/test_integrator-ias15/case.json"));
+    universe_integrator.output_streams.push(OutputStream::new("averaged", OutputSchedule::Linear { period: 4. }, vec![OutputField::SemiMajorAxis], 0., -1., true));
+    // The event shortens the step that would go beyond it
+    universe_integrator.event_timeline = EventTimeline::new(vec![ScheduledEvent { time: 1.03, action: EventAction::SetTimeStep { time_step: 0.08 } }]);
+    let history_path = Path::new(dirname).join("case_history.bin");
+    let mut universe_history_writer = BufWriter::new(fs::File::create(&history_path).unwrap());
+    let mut output_stream_writers = vec![BufWriter::new(fs::File::create(posidonius::output::output_stream_path(&history_path, &universe_integrator.output_streams[0])).unwrap())];
+    while universe_integrator.current_time < 2. {
+        universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap();
+    }
+    // The snapshot at the start time is written before its sample is accumulated
+    let accumulator = &universe_integrator.output_streams[0].accumulator;
+    assert!((accumulator.weight - universe_integrator.current_time).abs() <= 1.0e-12);
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn invalid_output_streams_of_a_case() {
+    // Cases generated by python write the output streams directly, they are validated when the case is loaded
+    let dirname = "target/tests/test_output_streams-invalid_output_streams_of_a_case/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let mut case: serde_json::Value = serde_json::from_str(&fs::read_to_string("tests/data/test_integrator-ias15/case.json").unwrap()).unwrap();
+    let output_stream = serde_json::to_value(OutputStream::new("sparse", OutputSchedule::Linear { period: 10. }, vec![OutputField::Mass], 0., -1., false)).unwrap();
+    let mut invalid_output_stream = output_stream.clone();
+    invalid_output_stream["schedule"] = serde_json::json!({"Linear": {"period": -1.}});
+    case["output_streams"] = serde_json::json!([output_stream, invalid_output_stream]);
+    let case_path = Path::new(dirname).join("case.json");
+    fs::write(&case_path, serde_json::to_string(&case).unwrap()).unwrap();
+    let error = posidonius::output::restore_snapshot(&case_path).err().unwrap();
+    assert!(error.contains("Output stream 'sparse' has a non-positive period"));
+    assert!(error.contains("Output stream name 'sparse' is used more than once"));
+    let _ = fs::remove_dir_all(dirname);
+}
@@ -0,0 +1,105 @@
+extern crate posidonius;
+
+use std::fs;
+use std::path::Path;
+use posidonius::{Integrator, EventAction, StoppingCondition, ParticleSetAction};
+
+// Same physical properties (and effects) as the first planet of the test cases
+fn new_particle_description(universe_integrator: &dyn Integrator) -> serde_json::Value {
+    let particle = universe_integrator.get_universe().particles[1];
+    serde_json::json!({
+        "particle": serde_json::to_value(particle).unwrap(),
+        "orbital_elements": {"semi_major_axis": 0.1, "eccentricity": 0.05, "inclination": 0.0, "argument_of_perihelion": 0.0, "longitude_of_ascending_node": 0.0, "mean_anomaly": 0.0},
+    })
+}
+
+#[test]
+fn particles_added_and_removed_by_events() {
+    for case in ["test_integrator-whfast_jacobi", "test_integrator-ias15", "test_integrator-leapfrog"].iter() {
+        check_particles_added_and_removed_by_events(case);
+    }
+}
+
+fn check_particles_added_and_removed_by_events(case: &str) {
+    let case_path = Path::new("tests/data").join(case).join("case.json");
+    let universe_integrator = posidonius::output::read_snapshot(&case_path).unwrap();
+    let description = new_particle_description(universe_integrator.as_ref());
+    let patch = serde_json::json!({
+        "event_timeline": {"events": [
+            {"time": 1.0, "action": {"RemoveParticle": {"particle": 2}}},
+            {"time": 2.0, "action": {"AddParticle": description}},
+            {"time": 5.0, "action": {"SetDissipationFactor": {"particle": 4, "dissipation_factor": 1.0e15}}},
+        ], "next_index": 0},
+        "stopping_conditions": {"conditions": [
+            {"EccentricityAbove": {"particle": 2, "eccentricity": 0.99}},
+            {"EccentricityAbove": {"particle": 3, "eccentricity": 0.99}},
+        ], "evaluation": "EveryStep", "reference_energy": null, "elapsed_wall_time": 0.0, "stop_reason": null},
+    });
+    let mut universe_integrator = posidonius::inspect::edit_snapshot(universe_integrator.as_ref(), &patch).unwrap();
+    universe_integrator.initialize_physical_values();
+    // Particle 4 will not exist after the removal, but a particle is added before the last event
+    assert!(posidonius::events::validate_events(universe_integrator.get_event_timeline(), universe_integrator.get_universe()).is_empty());
+
+    let dirname = format!("target/tests/test_particle_set-particles_added_and_removed_by_events-{}/", case);
+    let _ = fs::remove_dir_all(&dirname);
+    fs::create_dir_all(&dirname).unwrap();
+    let history_path = Path::new(&dirname).join("history.bin");
+    let mut universe_history_writer = posidonius::output::get_universe_history_writer(&history_path, 0);
+    let mut output_stream_writers = posidonius::output::get_output_stream_writers(&history_path, universe_integrator.get_output_streams(), universe_integrator.get_n_particles(), universe_integrator.get_particle_set_changes());
+
+    while universe_integrator.get_event_timeline().applied_events().len() < 2 {
+        universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap();
+    }
+    universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap();
+
+    let changes = universe_integrator.get_particle_set_changes();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].action, ParticleSetAction::Removed { index: 2 });
+    assert_eq!((changes[0].time, changes[0].previous_n_particles, changes[0].n_particles, changes[0].n_historic_snapshots), (1.0, 5, 4, 1));
+    assert_eq!(changes[1].action, ParticleSetAction::Added { index: 4 });
+    assert_eq!((changes[1].time, changes[1].previous_n_particles, changes[1].n_particles, changes[1].n_historic_snapshots), (2.0, 4, 5, 2));
+
+    let universe = universe_integrator.get_universe();
+    assert_eq!(universe.n_particles, 5);
+    assert!(universe.particles[..universe.n_particles].iter().enumerate().all(|(i, particle)| particle.id == i));
+    let (semimajor_axis, _perihelion_distance, eccentricity, _inclination, _longitude_of_perihelion, _longitude_of_ascending_node, _mean_anomaly, _orbital_period) = posidonius::output::calculate_keplerian_orbital_elements_around_reference(universe, &universe.particles[4]);
+    assert!((semimajor_axis - 0.1).abs() < 1e-3);
+    assert!((eccentricity - 0.05).abs() < 1e-2);
+    let total_mass: f64 = universe.particles[..universe.n_particles].iter().map(|particle| particle.mass).sum();
+    let center_of_mass_x: f64 = universe.particles[..universe.n_particles].iter().map(|particle| particle.mass * particle.inertial_position.x).sum::<f64>() / total_mass;
+    assert!(center_of_mass_x.abs() < 1e-10);
+
+    // Pending events and stopping conditions follow the shifted particles
+    assert_eq!(universe_integrator.get_event_timeline().events[2].action, EventAction::SetDissipationFactor { particle: 3, dissipation_factor: 1.0e15 });
+    assert_eq!(universe_integrator.get_stopping_conditions().conditions, vec![StoppingCondition::EccentricityAbove { particle: 2, eccentricity: 0.99 }]);
+
+    // Historic snapshots with 5, 4 and 5 particles
+    let snapshot_path = Path::new(&dirname).join("case.bin");
+    universe_integrator.write_recovery_snapshot(&snapshot_path, &mut universe_history_writer, &mut output_stream_writers).unwrap();
+    let n_bytes_per_particle = posidonius::output::n_bytes_per_particle_in_historic_snapshot();
+    assert_eq!(universe_integrator.get_n_historic_snapshots(), 3);
+    assert_eq!(posidonius::output::expected_universe_history_n_bytes(universe_integrator.as_ref()), (5 + 4 + 5) * n_bytes_per_particle);
+    assert_eq!(fs::metadata(&history_path).unwrap().len(), (5 + 4 + 5) * n_bytes_per_particle);
+    let restored_universe_integrator = posidonius::output::read_snapshot(&snapshot_path).unwrap();
+    assert_eq!(restored_universe_integrator.get_particle_set_changes(), universe_integrator.get_particle_set_changes());
+    assert!(posidonius::output::verify_universe_history(&history_path, restored_universe_integrator.as_ref()).is_ok());
+    let _ = fs::remove_dir_all(&dirname);
+}
+
+#[test]
+fn particles_added_and_removed_in_snapshot() {
+    let case_path = Path::new("tests/data/test_integrator-whfast_jacobi/case.json");
+    let universe_integrator = posidonius::output::read_snapshot(case_path).unwrap();
+
+    let edited_universe_integrator = posidonius::inspect::remove_particle_from_snapshot(universe_integrator.as_ref(), 1).unwrap();
+    assert_eq!(edited_universe_integrator.get_n_particles(), 4);
+    assert_eq!(edited_universe_integrator.get_universe().particles[1].mass, universe_integrator.get_universe().particles[2].mass);
+    assert!(posidonius::inspect::inspect_snapshot(edited_universe_integrator.as_ref()).contains("Particle set changes: 1"));
+
+    let edited_universe_integrator = posidonius::inspect::add_particle_to_snapshot(edited_universe_integrator.as_ref(), &new_particle_description(universe_integrator.as_ref())).unwrap();
+    assert_eq!(edited_universe_integrator.get_n_particles(), 5);
+    assert_eq!(edited_universe_integrator.get_particle_set_changes()[1].action, ParticleSetAction::Added { index: 4 });
+
+    assert!(posidonius::inspect::remove_particle_from_snapshot(universe_integrator.as_ref(), 7).is_err());
+    assert!(posidonius::inspect::add_particle_to_snapshot(universe_integrator.as_ref(), &serde_json::json!({})).is_err());
+}
@@ -0,0 +1,45 @@
+extern crate posidonius;
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+#[test]
+fn progress_records() {
+    let mut universe_integrator = posidonius::output::read_snapshot(Path::new("tests/data/test_integrator-ias15/case.json")).unwrap();
+    let _ = fs::create_dir("target/tests/");
+    let progress_filename = "target/tests/test_progress-progress_records.jsonl";
+    let _ = fs::remove_file(progress_filename);
+    {
+        let progress_writer = Box::new(fs::File::create(progress_filename).unwrap());
+        let mut progress_reporter = posidonius::ProgressReporter::new(progress_writer, Duration::from_secs(3600), universe_integrator.as_mut());
+        progress_reporter.update(universe_integrator.as_ref()); // Period not elapsed
+        progress_reporter.report(universe_integrator.as_ref(), "completed");
+    }
+    let progress = fs::read_to_string(progress_filename).unwrap();
+    let records: Vec<posidonius::progress::ProgressRecord> = progress.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].status, "completed");
+    assert_eq!(records[0].steps, 0);
+    assert_eq!(records[0].eta, None);
+    assert_eq!(records[0].relative_energy_error, 0.);
+    assert_eq!(records[0].time_step_min, universe_integrator.get_time_step());
+    let _ = fs::remove_file(progress_filename);
+}
+
+#[test]
+fn conservation_reference_survives_resuming() {
+    let mut universe_integrator = posidonius::output::read_snapshot(Path::new("tests/data/test_integrator-ias15/case.json")).unwrap();
+    assert_eq!(universe_integrator.get_conservation_reference(), None);
+    let conservation_reference = posidonius::progress::initialize_conservation_reference(universe_integrator.as_mut());
+    assert!(conservation_reference.energy != 0. && conservation_reference.angular_momentum != 0.);
+    let _ = fs::create_dir("target/tests/");
+    let snapshot_path = Path::new("target/tests/test_progress-conservation_reference_survives_resuming.bin");
+    universe_integrator.write_snapshot(snapshot_path).unwrap();
+    let mut restored_universe_integrator = posidonius::output::restore_snapshot(snapshot_path).unwrap();
+    assert_eq!(restored_universe_integrator.get_conservation_reference(), Some(conservation_reference));
+    // The reference of the snapshot is kept even if the state changed since the start
+    restored_universe_integrator.get_universe_mut().particles[1].inertial_velocity.x *= 1.01;
+    assert_eq!(posidonius::progress::initialize_conservation_reference(restored_universe_integrator.as_mut()), conservation_reference);
+    let _ = fs::remove_file(snapshot_path);
+}
@@ -0,0 +1,126 @@
+extern crate posidonius;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use posidonius::{InteriorStructure, RheologicalLayer, Rheology, KaulaParameters, LoveNumberData, LoveNumberTableSource, TidesEffect, TidalModel};
+use posidonius::{EventAction, ScheduledEvent};
+use posidonius::constants::AU;
+
+const RADIUS: f64 = 6.371e6; // m
+const DENSITY: f64 = 5500.; // kg/m^3
+
+fn layer(outer_radius: f64, rigidity: f64, viscosity: f64, rheology: Rheology) -> RheologicalLayer {
+    RheologicalLayer { outer_radius, density: DENSITY, rigidity, viscosity, bulk_modulus: 0., rheology }
+}
+
+fn relative_difference(a: (f64, f64), b: (f64, f64)) -> f64 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt() / (b.0.powi(2) + b.1.powi(2)).sqrt()
+}
+
+/// Integrator where the first planet uses the Kaula model without Love number table
+fn kaula_integrator() -> Box<dyn posidonius::Integrator> {
+    let mut universe_integrator = common::universe::initialized_integrator_from_json("test_integrator-ias15");
+    universe_integrator.get_universe_mut().particles[1].tides.effect = TidesEffect::OrbitingBody(TidalModel::Kaula(KaulaParameters {}));
+    universe_integrator
+}
+
+fn love_number_data(universe: &posidonius::Universe, index: usize) -> LoveNumberData {
+    let love_number_table = universe.love_number_tables.iter().find(|love_number_table| love_number_table.particle == index).unwrap();
+    assert!(matches!(love_number_table.source, LoveNumberTableSource::InteriorStructure(_)));
+    love_number_table.data().clone()
+}
+
+#[test]
+fn rheology_multilayer_matches_homogeneous_body() {
+    let frequency = 1.0e-5; // rad/s
+    for rheology in [Rheology::Maxwell, Rheology::Andrade { alpha: 0.3, andrade_timescale: 1.0e3 }].iter() {
+        let homogeneous = InteriorStructure::new(0, vec![layer(RADIUS, 8.0e10, 1.0e20, *rheology)]);
+        let two_layers = InteriorStructure::new(0, vec![layer(0.5*RADIUS, 8.0e10, 1.0e20, *rheology), layer(RADIUS, 8.0e10, 1.0e20, *rheology)]);
+        assert!(homogeneous.validate().is_ok() && two_layers.validate().is_ok());
+        assert!(relative_difference(two_layers.love_number(2, frequency), homogeneous.love_number(2, frequency)) < 1.0e-4);
+    }
+    // Elastic limit: k2 = 1.5 / (1 + 19*rigidity/(2*density*g*R))
+    let elastic = InteriorStructure::new(0, vec![layer(RADIUS, 8.0e10, 1.0e30, Rheology::Maxwell)]);
+    let gravity = posidonius::constants::G_SI * 4. / 3. * posidonius::constants::PI * DENSITY * RADIUS;
+    let expected_love_number = 1.5 / (1. + 19. * 8.0e10 / (2. * DENSITY * gravity * RADIUS));
+    assert!(relative_difference(elastic.love_number(2, 1.0e-5), (expected_love_number, 0.)) < 1.0e-6);
+}
+
+#[test]
+fn rheology_fluid_limit() {
+    let fluid_limit = (1.5, 0.);
+    let weak_mantle = layer(RADIUS, 1.0e3, 1.0e30, Rheology::Maxwell);
+    let two_layers = InteriorStructure::new(0, vec![layer(0.5*RADIUS, 1.0e3, 1.0e30, Rheology::Maxwell), weak_mantle]);
+    assert!(relative_difference(two_layers.love_number(2, 1.0e-5), fluid_limit) < 1.0e-4);
+    let fluid_core = InteriorStructure::new(0, vec![layer(0.5*RADIUS, 0., 0., Rheology::Fluid), weak_mantle]);
+    assert!(fluid_core.validate().is_ok());
+    assert!(relative_difference(fluid_core.love_number(2, 1.0e-5), fluid_limit) < 1.0e-4);
+    // Long after the excitation (i.e., low frequencies), viscous bodies also relax to the fluid limit
+    for rheology in [Rheology::Maxwell, Rheology::Burgers { transient_rigidity: 8.0e10, transient_viscosity: 1.0e16 }, Rheology::SundbergCooper { alpha: 0.3, andrade_timescale: 1.0e6, transient_rigidity: 8.0e10, transient_viscosity: 1.0e16 }].iter() {
+        let viscous = InteriorStructure::new(0, vec![layer(RADIUS, 8.0e10, 1.0e18, *rheology)]);
+        assert!(relative_difference(viscous.love_number(2, 1.0e-14), fluid_limit) < 1.0e-3);
+    }
+}
+
+#[test]
+fn rheology_phase_lag() {
+    let frequency = 1.0e-5; // rad/s
+    for rheology in [Rheology::Maxwell, Rheology::Andrade { alpha: 0.3, andrade_timescale: 1.0e8 }].iter() {
+        let interior_structure = InteriorStructure::new(0, vec![layer(0.5*RADIUS, 0., 0., Rheology::Fluid), layer(RADIUS, 8.0e10, 1.0e17, *rheology)]);
+        let (real_part, imaginary_part) = interior_structure.love_number(2, frequency);
+        // The bulge lags behind the perturber
+        assert!(real_part > 0. && imaginary_part < 0.);
+        assert_eq!(interior_structure.love_number(2, -frequency), (real_part, -imaginary_part));
+    }
+    // Anelasticity (Andrade) increases the dissipation at high frequencies with respect to Maxwell
+    let maxwell = InteriorStructure::new(0, vec![layer(RADIUS, 8.0e10, 1.0e21, Rheology::Maxwell)]);
+    let andrade = InteriorStructure::new(0, vec![layer(RADIUS, 8.0e10, 1.0e21, Rheology::Andrade { alpha: 0.3, andrade_timescale: 1.0e21/8.0e10 })]);
+    assert!(andrade.love_number(2, 1.0e-4).1.abs() > 10. * maxwell.love_number(2, 1.0e-4).1.abs());
+}
+
+#[test]
+fn rheology_validation() {
+    let fluid_mantle = InteriorStructure::new(3, vec![layer(0.5*RADIUS, 8.0e10, 1.0e20, Rheology::Maxwell), layer(RADIUS, 0., 0., Rheology::Fluid)]);
+    assert!(fluid_mantle.validate().unwrap_err().contains("fluid"));
+    let unsorted_layers = InteriorStructure::new(3, vec![layer(RADIUS, 8.0e10, 1.0e20, Rheology::Maxwell), layer(0.5*RADIUS, 8.0e10, 1.0e20, Rheology::Maxwell)]);
+    assert!(unsorted_layers.validate().unwrap_err().contains("outer radius"));
+    let andrade = InteriorStructure::new(3, vec![layer(RADIUS, 8.0e10, 1.0e20, Rheology::Andrade { alpha: 1.5, andrade_timescale: 1.0e3 })]);
+    assert!(andrade.validate().unwrap_err().contains("Andrade exponent"));
+}
+
+#[test]
+fn rheology_computes_love_number_tables() {
+    let mut universe_integrator = kaula_integrator();
+    let radius = universe_integrator.get_universe().particles[1].radius * AU;
+    let layers = vec![layer(0.55*radius, 0., 0., Rheology::Fluid), layer(radius, 8.0e10, 1.0e19, Rheology::Maxwell)];
+    let universe = universe_integrator.get_universe_mut();
+    assert!(universe.set_interior_structure(InteriorStructure::new(0, layers.clone())).is_err()); // Not a Kaula model
+    universe.set_interior_structure(InteriorStructure::new(1, layers.clone())).unwrap();
+    assert!(universe.validate(-1.).is_valid());
+
+    let data = love_number_data(universe, 1);
+    assert_eq!(data.excitation_frequency.len(), 1024);
+    assert!(data.excitation_frequency.windows(2).all(|frequencies| frequencies[0] < frequencies[1]));
+    // The Kaula model expects the opposite of the complex Love number
+    let (real_part, imaginary_part) = InteriorStructure::new(1, layers).love_number(2, data.excitation_frequency[700]);
+    assert_eq!((data.real_part[700], data.imaginary_part[700]), (-real_part, -imaginary_part));
+    assert_eq!(data.excitation_frequency[323], -data.excitation_frequency[700]);
+    assert_eq!((data.real_part[323], data.imaginary_part[323]), (-real_part, imaginary_part));
+
+    // Lower viscosity, more dissipation at tidal frequencies (tidal periods shorter than the Maxwell time)
+    let event = ScheduledEvent { time: 0., action: EventAction::SetViscosity { particle: 1, layer: 1, viscosity: 1.0e17 } };
+    posidonius::events::apply_event(universe_integrator.as_mut(), &event);
+    let universe = universe_integrator.get_universe_mut();
+    assert_eq!(universe.interior_structures[0].layers[1].viscosity, 1.0e17);
+    assert!(love_number_data(universe, 1).imaginary_part[895] > data.imaginary_part[895]);
+    assert_eq!(universe.love_number_tables.len(), 1);
+
+    // Interior structures follow the particles
+    universe.remove_particle(2).unwrap();
+    assert_eq!(universe.interior_structures[0].particle, 1);
+    universe.remove_particle(1).unwrap();
+    assert!(universe.interior_structures.is_empty());
+    assert!(universe.love_number_tables.is_empty());
+}
@@ -0,0 +1,57 @@
+#![cfg(unix)]
+extern crate posidonius;
+extern crate serde_json;
+
+use std::fs;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+fn send_signal(child: &Child, signal: &str) {
+    let status = Command::new("kill").arg(format!("-{}", signal)).arg(child.id().to_string()).status().unwrap();
+    assert!(status.success());
+}
+
+fn wait_until<F: Fn() -> bool>(condition: F) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < Duration::from_secs(60), "Timeout waiting for the simulation");
+        sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn checkpoint_and_stop_signals() {
+    let dirname = "target/tests/test_signals-checkpoint_and_stop_signals/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    // Long enough simulation to receive the signals before it completes
+    let mut case: serde_json::Value = serde_json::from_str(&fs::read_to_string("tests/data/test_integrator-ias15/case.json").unwrap()).unwrap();
+    case["universe"]["time_limit"] = serde_json::json!(1.0e9);
+    let case_path = Path::new(dirname).join("case.json");
+    fs::write(&case_path, serde_json::to_string(&case).unwrap()).unwrap();
+    let snapshot_path = Path::new(dirname).join("case.bin");
+    let history_path = Path::new(dirname).join("case_history.bin");
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_posidonius"))
+                        .arg("start").arg(&case_path).arg(&snapshot_path).arg(&history_path).arg("--silent")
+                        .stdout(Stdio::null())
+                        .spawn().unwrap();
+    // The first recovery snapshot is written right after the first step
+    wait_until(|| posidonius::output::find_backup_snapshots(&snapshot_path).len() == 1);
+
+    // SIGUSR1 writes a recovery snapshot (and its backup) without stopping
+    send_signal(&child, "USR1");
+    wait_until(|| posidonius::output::find_backup_snapshots(&snapshot_path).len() == 2);
+    assert!(child.try_wait().unwrap().is_none());
+
+    // SIGTERM stops after the current step with a recovery snapshot that can be resumed
+    send_signal(&child, "TERM");
+    let status = child.wait().unwrap();
+    assert_eq!(status.code(), Some(128 + 15));
+    let universe_integrator = posidonius::output::restore_snapshot(&snapshot_path).unwrap();
+    assert!(universe_integrator.get_current_time() > 0.);
+    assert_eq!(posidonius::output::find_backup_snapshots(&snapshot_path).len(), 3);
+    let _ = fs::remove_dir_all(dirname);
+}
@@ -0,0 +1,276 @@
+extern crate posidonius;
+extern crate serde_json;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use posidonius::Integrator;
+
+fn restore_ias15(snapshot_path: &Path) -> posidonius::Ias15 {
+    let boxed_universe_integrator : Box<dyn posidonius::Integrator> = posidonius::output::restore_snapshot(snapshot_path).unwrap();
+    match boxed_universe_integrator.as_any().downcast_ref::<posidonius::Ias15>() {
+        Some(universe_integrator) => { universe_integrator.clone() },
+        None => { panic!("Wrong integrator!"); }
+    }
+}
+
+#[test]
+fn versioned_snapshots() {
+    // Snapshot without envelope (format version 0)
+    let universe_integrator = restore_ias15(Path::new("tests/data/test_integrator-ias15/case.json"));
+    // Restoring a snapshot at time zero initializes the physical values (again)
+    let mut expected_universe_integrator = universe_integrator.clone();
+    expected_universe_integrator.initialize_physical_values();
+
+    let _ = fs::create_dir("target/tests/");
+    for extension in ["json", "bin"].iter() {
+        let snapshot_filename = format!("target/tests/test_snapshot-versioned_snapshots.{}", extension);
+        let snapshot_path = Path::new(&snapshot_filename);
+        let _ = fs::remove_file(snapshot_path);
+        posidonius::output::write_recovery_snapshot(snapshot_path, &universe_integrator).unwrap();
+        let restored_universe_integrator = restore_ias15(snapshot_path);
+        assert_eq!(restored_universe_integrator, expected_universe_integrator);
+        let _ = fs::remove_file(snapshot_path);
+    }
+}
+
+#[test]
+fn version_0_bin_snapshots() {
+    // Recovery snapshots written by the release without snapshot envelope (format version 0) while integrating
+    // the cases in tests/data/test_integrator-*/ for 20 time steps (snapshot periods of 10 time steps)
+    let _ = fs::create_dir("target/tests/");
+    for (name, integrator_type) in [("ias15", posidonius::IntegratorType::Ias15), ("leapfrog", posidonius::IntegratorType::LeapFrog), ("whfast_jacobi", posidonius::IntegratorType::WHFast)].iter() {
+        let snapshot_filename = format!("target/tests/test_snapshot-version_0_bin_snapshots-{}.bin", name);
+        let snapshot_path = Path::new(&snapshot_filename);
+        fs::copy(format!("tests/data/test_snapshot-version_0/{}.bin", name), snapshot_path).unwrap();
+        let restored_universe_integrator = posidonius::output::restore_snapshot(snapshot_path).unwrap();
+        assert_eq!(restored_universe_integrator.get_integrator_type(), *integrator_type);
+        assert_eq!(restored_universe_integrator.get_n_particles(), 5);
+        let current_time = restored_universe_integrator.get_current_time();
+        assert!(current_time > 0. && current_time < restored_universe_integrator.get_universe().time_limit);
+        assert!(restored_universe_integrator.get_n_historic_snapshots() > 0);
+        assert!(restored_universe_integrator.get_event_timeline().events.is_empty());
+        let universe = restored_universe_integrator.get_universe();
+        for particle in universe.particles[..universe.n_particles].iter() {
+            assert!(particle.inertial_position.x.is_finite() && particle.inertial_velocity.x.is_finite());
+        }
+        if let Some(universe_integrator) = restored_universe_integrator.as_any().downcast_ref::<posidonius::WHFast>() {
+            assert_eq!(universe_integrator.get_alternative_coordinates_type(), posidonius::whfast::CoordinatesType::Jacobi);
+        }
+        let _ = fs::remove_file(snapshot_path);
+    }
+}
+
+#[test]
+fn corrupted_snapshot_and_backup() {
+    let universe_integrator = restore_ias15(Path::new("tests/data/test_integrator-ias15/case.json"));
+
+    let dirname = "target/tests/test_snapshot-corrupted_snapshot_and_backup/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let snapshot_path = Path::new(dirname).join("case.bin");
+    let older_backup_path = Path::new(dirname).join("case.20240101TPM.bin");
+    let newer_backup_path = Path::new(dirname).join("case.20240102TAM.bin");
+    posidonius::output::write_recovery_snapshot(&snapshot_path, &universe_integrator).unwrap();
+    fs::copy(&snapshot_path, &older_backup_path).unwrap();
+    fs::copy(&snapshot_path, &newer_backup_path).unwrap();
+
+    // Flip one byte in the middle of the current snapshot and the most recent backup
+    for path in [&snapshot_path, &newer_backup_path].iter() {
+        let mut bytes = fs::read(path).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xFF;
+        fs::write(path, bytes).unwrap();
+    }
+    assert!(posidonius::output::restore_snapshot(&snapshot_path).is_err());
+
+    assert_eq!(posidonius::output::find_backup_snapshots(&snapshot_path), vec![newer_backup_path.clone(), older_backup_path.clone()]);
+    let (backup_path, _) = posidonius::output::restore_most_recent_valid_backup(&snapshot_path).unwrap();
+    assert_eq!(backup_path, older_backup_path);
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn backup_retention() {
+    let backups: Vec<(f64, PathBuf)> = [500., 400., 350., 300., 250., 120., 110., 10.].iter()
+                                            .map(|current_time| (*current_time, PathBuf::from(format!("case.backup-{:013}.bin", *current_time as u64))))
+                                            .collect();
+    let backup_retention = posidonius::output::BackupRetention { keep_last: Some(2), keep_every: Some(100.) };
+    let to_remove = posidonius::output::backups_to_remove(&backups, &backup_retention);
+    // Keep the last two (500, 400) plus the first one of each 100 days interval (300, 250, 110, 10)
+    assert_eq!(to_remove, vec![backups[2].1.clone(), backups[5].1.clone()]);
+
+    let backup_retention = posidonius::output::BackupRetention { keep_last: None, keep_every: None };
+    assert!(posidonius::output::backups_to_remove(&backups, &backup_retention).is_empty());
+}
+
+#[test]
+fn atomic_snapshot_with_backups() {
+    let universe_integrator = restore_ias15(Path::new("tests/data/test_integrator-ias15/case.json"));
+
+    let dirname = "target/tests/test_snapshot-atomic_snapshot_with_backups/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let snapshot_path = Path::new(dirname).join("case.bin");
+    for current_time in [10., 20., 30.].iter() {
+        posidonius::output::write_recovery_snapshot(&snapshot_path, &universe_integrator).unwrap();
+        posidonius::output::backup_recovery_snapshot(&snapshot_path, *current_time).unwrap();
+        posidonius::output::apply_backup_retention(&snapshot_path, &posidonius::output::BackupRetention { keep_last: Some(2), keep_every: None });
+    }
+    let mut filenames: Vec<String> = fs::read_dir(dirname).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().to_string()).collect();
+    filenames.sort();
+    assert_eq!(filenames, vec!["case.backup-0000000000020.bin", "case.backup-0000000000030.bin", "case.bin"]);
+    assert_eq!(fs::read(&snapshot_path).unwrap(), fs::read(Path::new(dirname).join("case.backup-0000000000030.bin")).unwrap());
+    #[cfg(unix)]
+    {
+        // The most recent backup is a hard link of the snapshot, the older one is not anymore
+        use std::os::unix::fs::MetadataExt;
+        assert_eq!(fs::metadata(&snapshot_path).unwrap().nlink(), 2);
+        assert_eq!(fs::metadata(Path::new(dirname).join("case.backup-0000000000020.bin")).unwrap().nlink(), 1);
+    }
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn snapshot_without_extension() {
+    // The format of the snapshots is given by their extension
+    let universe_integrator = restore_ias15(Path::new("tests/data/test_integrator-ias15/case.json"));
+    let dirname = "target/tests/test_snapshot-snapshot_without_extension/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let snapshot_path = Path::new(dirname).join("case");
+    assert!(posidonius::output::write_recovery_snapshot(&snapshot_path, &universe_integrator).unwrap_err().contains("does not have an extension"));
+    assert!(posidonius::output::backup_recovery_snapshot(&snapshot_path, 10.).is_err());
+    assert!(universe_integrator.write_snapshot(&snapshot_path).is_err());
+    assert_eq!(fs::read_dir(dirname).unwrap().count(), 0);
+    fs::copy("tests/data/test_integrator-ias15/case.json", &snapshot_path).unwrap();
+    assert!(posidonius::output::read_snapshot(&snapshot_path).err().unwrap().contains("does not have an extension"));
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn inspect_and_edit_snapshot() {
+    let universe_integrator = posidonius::output::read_snapshot(Path::new("tests/data/test_integrator-ias15/case.json")).unwrap();
+    let summary = posidonius::inspect::inspect_snapshot(&*universe_integrator);
+    assert!(summary.starts_with("Integrator: Ias15\n"));
+    assert!(summary.contains(&format!("Particles: {}\n", universe_integrator.get_n_particles())));
+
+    let patch: serde_json::Value = serde_json::from_str(r#"{"universe": {"time_limit": 1234.5}, "time_step": 0.25}"#).unwrap();
+    let edited_universe_integrator = posidonius::inspect::edit_snapshot(&*universe_integrator, &patch).unwrap();
+    let _ = fs::create_dir("target/tests/");
+    let snapshot_path = Path::new("target/tests/test_snapshot-inspect_and_edit_snapshot.bin");
+    edited_universe_integrator.write_snapshot(snapshot_path).unwrap();
+    let restored_universe_integrator = posidonius::output::read_snapshot(snapshot_path).unwrap();
+    assert_eq!(restored_universe_integrator.get_universe().time_limit, 1234.5);
+    assert_eq!(restored_universe_integrator.get_time_step(), 0.25);
+    assert_eq!(restored_universe_integrator.get_universe().particles[..], universe_integrator.get_universe().particles[..]);
+    let _ = fs::remove_file(snapshot_path);
+
+    let invalid_patch: serde_json::Value = serde_json::from_str(r#"{"time_step": "fast"}"#).unwrap();
+    assert!(posidonius::inspect::edit_snapshot(&*universe_integrator, &invalid_patch).is_err());
+
+    // Invalid edits are reported instead of panicking
+    let mut particles = universe_integrator.to_json_value()["universe"]["particles"].clone();
+    particles[1]["tides"]["effect"] = particles[0]["tides"]["effect"].clone();
+    let invalid_patch = serde_json::json!({"universe": {"particles": particles}});
+    let error = posidonius::inspect::edit_snapshot(&*universe_integrator, &invalid_patch).err().unwrap();
+    assert!(error.contains("Only one central body is allowed for tidal effects"));
+    let invalid_patch: serde_json::Value = serde_json::from_str(r#"{"universe": {"n_particles": 1000}}"#).unwrap();
+    let error = posidonius::inspect::edit_snapshot(&*universe_integrator, &invalid_patch).err().unwrap();
+    assert!(error.contains("Invalid number of particles"));
+
+    // Values derived from the time step are updated
+    for case in ["test_integrator-leapfrog", "test_integrator-whfast_jacobi"].iter() {
+        let universe_integrator = posidonius::output::read_snapshot(&Path::new("tests/data").join(case).join("case.json")).unwrap();
+        let patch: serde_json::Value = serde_json::from_str(r#"{"time_step": 0.25}"#).unwrap();
+        let edited_universe_integrator = posidonius::inspect::edit_snapshot(&*universe_integrator, &patch).unwrap();
+        assert_eq!(edited_universe_integrator.to_json_value()["half_time_step"], 0.125);
+    }
+}
+
+#[test]
+fn convert_snapshot_round_trip() {
+    let dirname = "target/tests/test_snapshot-convert_snapshot_round_trip/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    for case in ["test_integrator-whfast_jacobi", "test_integrator-ias15", "test_integrator-leapfrog"].iter() {
+        let case_path = Path::new("tests/data").join(case).join("case.json");
+        let bin_path = Path::new(dirname).join(format!("{}.bin", case));
+        let json_path = Path::new(dirname).join(format!("{}.json", case));
+        let round_trip_bin_path = Path::new(dirname).join(format!("{}.round_trip.bin", case));
+        posidonius::output::convert_snapshot(&case_path, &bin_path).unwrap();
+        posidonius::output::convert_snapshot(&bin_path, &json_path).unwrap();
+        posidonius::output::convert_snapshot(&json_path, &round_trip_bin_path).unwrap();
+        assert_eq!(fs::read(&bin_path).unwrap(), fs::read(&round_trip_bin_path).unwrap());
+    }
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn convert_integrator() {
+    let dirname = "target/tests/test_snapshot-convert_integrator/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let history_path = Path::new(dirname).join("history.bin");
+    let mut universe_integrator = posidonius::output::read_snapshot(Path::new("tests/data/test_integrator-whfast_jacobi/case.json")).unwrap();
+    universe_integrator.initialize_physical_values();
+    let mut universe_history_writer = posidonius::output::get_universe_history_writer(&history_path, 0);
+    let mut output_stream_writers = posidonius::output::get_output_stream_writers(&history_path, universe_integrator.get_output_streams(), universe_integrator.get_n_particles(), universe_integrator.get_particle_set_changes());
+    for _ in 0..10 {
+        universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap();
+    }
+    let history_counters = universe_integrator.get_history_counters();
+
+    // WHFast to IAS15 (with a new time step) and back
+    let mut ias15 = posidonius::convert_integrator(universe_integrator.as_ref(), posidonius::IntegratorType::Ias15, posidonius::whfast::CoordinatesType::Jacobi);
+    ias15.set_time_step(0.01);
+    assert_eq!(ias15.get_integrator_type(), posidonius::IntegratorType::Ias15);
+    assert_eq!(ias15.get_time_step(), 0.01);
+    assert_eq!(ias15.get_history_counters(), history_counters);
+    // Debug representation instead of PartialEq because of NaN values (e.g., disabled effects)
+    assert_eq!(format!("{:?}", ias15.get_universe()), format!("{:?}", universe_integrator.get_universe()));
+    ias15.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap();
+    assert!(ias15.get_current_time() > history_counters.current_time);
+
+    let whfast = posidonius::convert_integrator(ias15.as_ref(), posidonius::IntegratorType::WHFast, posidonius::whfast::CoordinatesType::WHDS);
+    assert_eq!(whfast.get_integrator_type(), posidonius::IntegratorType::WHFast);
+    assert_eq!(whfast.get_history_counters(), ias15.get_history_counters());
+    let whfast = whfast.as_any().downcast_ref::<posidonius::WHFast>().unwrap();
+    assert_eq!(whfast.get_alternative_coordinates_type(), posidonius::whfast::CoordinatesType::WHDS);
+
+    // WHFast coordinates switch keeps the rest of the integrator untouched
+    let jacobi = posidonius::convert_integrator(whfast, posidonius::IntegratorType::WHFast, posidonius::whfast::CoordinatesType::Jacobi);
+    let jacobi = jacobi.as_any().downcast_ref::<posidonius::WHFast>().unwrap();
+    assert_eq!(jacobi.get_alternative_coordinates_type(), posidonius::whfast::CoordinatesType::Jacobi);
+    assert_eq!(format!("{:?}", jacobi.universe), format!("{:?}", whfast.universe));
+    assert_eq!(jacobi.n_historic_snapshots, whfast.n_historic_snapshots);
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn version_0_kaula_love_number_tables() {
+    // In snapshot format version 0, the Love numbers of the Kaula model were stored in
+    // fixed-size tables of 1024 points (padded with zeros) inside the tidal model
+    let dirname = "target/tests/test_snapshot-version_0_kaula_love_number_tables/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let mut integrator: serde_json::Value = serde_json::from_str(&fs::read_to_string("tests/data/test_integrator-ias15/case.json").unwrap()).unwrap();
+    let excitation_frequency = [1.0e-8, 1.0e-6, 1.0e-4];
+    let real_part = [0.9, 0.5, 0.1];
+    let imaginary_part = [0.01, 0.05, 0.02];
+    let padded = |values: &[f64]| -> Vec<f64> { values.iter().cloned().chain(std::iter::repeat_n(0., 1024 - values.len())).collect() };
+    integrator["universe"]["particles"][1]["tides"]["effect"] = serde_json::json!({"OrbitingBody": {"Kaula": {
+        "love_number_excitation_frequency": padded(&excitation_frequency),
+        "real_part_love_number": padded(&real_part),
+        "imaginary_part_love_number": padded(&imaginary_part),
+        "num_datapoints": 3.,
+    }}});
+    let snapshot_path = Path::new(dirname).join("case.json");
+    fs::write(&snapshot_path, serde_json::to_string(&integrator).unwrap()).unwrap();
+
+    let restored_universe_integrator = posidonius::output::restore_snapshot(&snapshot_path).unwrap();
+    let universe = restored_universe_integrator.get_universe();
+    assert_eq!(universe.particles[1].tides.effect, posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::Kaula(posidonius::KaulaParameters {})));
+    let expected_data = posidonius::LoveNumberData { excitation_frequency: excitation_frequency.to_vec(), real_part: real_part.to_vec(), imaginary_part: imaginary_part.to_vec() };
+    assert_eq!(universe.love_number_tables, vec![posidonius::LoveNumberTable::new(1, expected_data, posidonius::LoveNumberInterpolation::Linear)]);
+    let _ = fs::remove_dir_all(dirname);
+}
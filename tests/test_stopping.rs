@@ -0,0 +1,77 @@
+extern crate posidonius;
+
+use std::fs;
+use std::path::Path;
+use posidonius::StoppingCondition;
+
+#[test]
+fn stopping_condition_met_and_kept_in_snapshot() {
+    let case_path = Path::new("tests/data/test_integrator-whfast_jacobi/case.json");
+    // Period ratio of the planets at 5 and 10 AU is 2^1.5 ~ 2.83
+    let patch: serde_json::Value = serde_json::from_str(r#"{"stopping_conditions": {"conditions": [
+            {"SemiMajorAxisBelow": {"particle": 1, "semi_major_axis": 0.1}},
+            {"PeriodRatioWindow": {"inner_particle": 2, "outer_particle": 3, "min_period_ratio": 2.5, "max_period_ratio": 3.0}},
+            {"WallTime": {"seconds": 3600.0}}
+        ], "evaluation": "HistoricSnapshot", "reference_energy": null, "elapsed_wall_time": 0.0, "stop_reason": null}}"#).unwrap();
+    let universe_integrator = posidonius::output::read_snapshot(case_path).unwrap();
+    let mut universe_integrator = posidonius::inspect::edit_snapshot(universe_integrator.as_ref(), &patch).unwrap();
+    universe_integrator.initialize_physical_values();
+    assert!(posidonius::stopping::validate_stopping_conditions(universe_integrator.get_stopping_conditions(), universe_integrator.get_universe()).is_empty());
+
+    let dirname = "target/tests/test_stopping-stopping_condition_met_and_kept_in_snapshot/";
+    let _ = fs::remove_dir_all(dirname);
+    fs::create_dir_all(dirname).unwrap();
+    let history_path = Path::new(dirname).join("history.bin");
+    let mut universe_history_writer = posidonius::output::get_universe_history_writer(&history_path, 0);
+    let mut output_stream_writers = posidonius::output::get_output_stream_writers(&history_path, universe_integrator.get_output_streams(), universe_integrator.get_n_particles(), universe_integrator.get_particle_set_changes());
+
+    // Only evaluated when a historic snapshot has been written (but the wall time is always accumulated)
+    let mut stopping_conditions = universe_integrator.get_stopping_conditions().clone();
+    assert_eq!(stopping_conditions.evaluate(universe_integrator.get_universe(), 0., 10., false), None);
+    assert_eq!(stopping_conditions.elapsed_wall_time, 10.);
+
+    // Evaluated by the integrator (the first iteration writes a historic snapshot)
+    let error = universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap_err();
+    let stop_reason = universe_integrator.get_stopping_conditions().stop_reason.unwrap();
+    assert_eq!(error, stop_reason.to_string());
+    assert!(matches!(stop_reason.condition, StoppingCondition::PeriodRatioWindow { .. }));
+    assert_eq!(stop_reason.condition.reason_code(), "period_ratio_window");
+    assert_eq!(stop_reason.time, universe_integrator.get_current_time());
+    assert!((stop_reason.value - 2f64.powf(1.5)).abs() < 0.2);
+    // Stopped simulations do not advance
+    let current_time = universe_integrator.get_current_time();
+    assert_eq!(universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).unwrap_err(), error);
+    assert_eq!(universe_integrator.get_current_time(), current_time);
+
+    let snapshot_path = Path::new(dirname).join("case.bin");
+    universe_integrator.write_recovery_snapshot(&snapshot_path, &mut universe_history_writer, &mut output_stream_writers).unwrap();
+    let restored_universe_integrator = posidonius::output::read_snapshot(&snapshot_path).unwrap();
+    assert_eq!(restored_universe_integrator.get_stopping_conditions().stop_reason, Some(stop_reason));
+    assert!(posidonius::inspect::inspect_snapshot(restored_universe_integrator.as_ref()).contains("Stopped: period_ratio_window"));
+
+    // Resuming evaluates the conditions again
+    let mut restored_universe_integrator = restored_universe_integrator;
+    let mut history_counters = restored_universe_integrator.get_history_counters();
+    history_counters.stopping_conditions.clear_stop_reason();
+    history_counters.stopping_conditions.conditions.remove(1);
+    restored_universe_integrator.set_history_counters(history_counters);
+    assert!(restored_universe_integrator.iterate(&mut universe_history_writer, &mut output_stream_writers, true).is_ok());
+    assert!(restored_universe_integrator.get_current_time() > current_time);
+    assert_eq!(restored_universe_integrator.get_stopping_conditions().stop_reason, None);
+    let _ = fs::remove_dir_all(dirname);
+}
+
+#[test]
+fn invalid_stopping_conditions() {
+    let case_path = Path::new("tests/data/test_integrator-whfast_jacobi/case.json");
+    let patch: serde_json::Value = serde_json::from_str(r#"{"stopping_conditions": {"conditions": [
+            {"EccentricityAbove": {"particle": 0, "eccentricity": 0.5}},
+            {"SpinSynchronization": {"particle": 7, "tolerance": 0.01}},
+            {"EnergyError": {"tolerance": -1.0}}
+        ], "evaluation": "EveryStep", "reference_energy": null, "elapsed_wall_time": 0.0, "stop_reason": null}}"#).unwrap();
+    let universe_integrator = posidonius::output::read_snapshot(case_path).unwrap();
+    let universe_integrator = posidonius::inspect::edit_snapshot(universe_integrator.as_ref(), &patch).unwrap();
+    // Central body does not orbit anything, there is no particle 7 and the tolerance is negative
+    let errors = posidonius::stopping::validate_stopping_conditions(universe_integrator.get_stopping_conditions(), universe_integrator.get_universe());
+    assert_eq!(errors.len(), 3);
+}
@@ -0,0 +1,128 @@
+extern crate posidonius;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use posidonius::{Axes, TidesEffect, TidalModel, Tide, IgnoreGravityTerms};
+use posidonius::{ConstantTimeLagParameters, ConstantPhaseLagParameters, CreepCoplanarParameters, CreepParameters};
+
+fn set_tidal_model(universe: &mut posidonius::Universe, index: usize, tidal_model: TidalModel) {
+    let particle = &mut universe.particles[index];
+    particle.tides.effect = match particle.tides.effect {
+        TidesEffect::CentralBody(_) => TidesEffect::CentralBody(tidal_model),
+        TidesEffect::OrbitingBody(_) => TidesEffect::OrbitingBody(tidal_model),
+        TidesEffect::Disabled => TidesEffect::Disabled,
+    };
+}
+
+fn calculate_tides(universe: &mut posidonius::Universe) {
+    universe.inertial_to_heliocentric();
+    universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+}
+
+fn norm(vector: Axes) -> f64 {
+    (vector.x.powi(2) + vector.y.powi(2) + vector.z.powi(2)).sqrt()
+}
+
+#[test]
+fn tidal_model_responses() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    calculate_tides(&mut universe);
+    let host = universe.hosts.index.tides;
+    let planet = 1;
+    let tidal_host_particle = &universe.particles[host];
+    let particle = &universe.particles[planet];
+
+    let constant_time_lag = TidalModel::ConstantTimeLag(ConstantTimeLagParameters { dissipation_factor: 2.006*3.845764e4, dissipation_factor_scale: 1., love_number: 0.3 });
+    let constant_phase_lag = TidalModel::ConstantPhaseLag(ConstantPhaseLagParameters { quality_factor: 100., love_number: 0.3 });
+    let creep_coplanar = TidalModel::CreepCoplanar(CreepCoplanarParameters { uniform_viscosity_coefficient: 1.0e10 });
+    let creep = TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 1.0e10 });
+    assert_eq!(constant_time_lag.response().name(), "ConstantTimeLag");
+    assert_eq!(constant_phase_lag.response().name(), "ConstantPhaseLag");
+    assert_eq!(creep_coplanar.response().name(), "CreepCoplanar");
+    assert_eq!(creep.response().name(), "Creep");
+    assert_eq!(constant_time_lag.response().love_number(), 0.3);
+    assert_eq!(creep_coplanar.response().love_number(), 0.);
+    assert_eq!(constant_time_lag.response().scaled_dissipation_factor(), 2.006*3.845764e4);
+    assert!(constant_phase_lag.response().validate().is_ok());
+    assert!(TidalModel::ConstantPhaseLag(ConstantPhaseLagParameters { quality_factor: 0., love_number: 0.3 }).response().validate().is_err());
+    assert!(TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: -1. }).response().validate().is_err());
+
+    // The semi-diurnal frequency depends on the spin of the tidally deformed body
+    for tidal_model in [constant_time_lag, constant_phase_lag, creep_coplanar, creep].iter() {
+        let stellar_frequencies = tidal_model.response().excitation_frequencies(tidal_host_particle, particle, Tide::Stellar);
+        let planetary_frequencies = tidal_model.response().excitation_frequencies(tidal_host_particle, particle, Tide::Planetary);
+        assert!(!stellar_frequencies.is_empty());
+        assert_eq!(stellar_frequencies.len(), planetary_frequencies.len());
+        assert!(stellar_frequencies.iter().chain(planetary_frequencies.iter()).all(|frequency| frequency.is_finite()));
+        assert_ne!(stellar_frequencies, planetary_frequencies);
+    }
+    // The tidal modes of the constant phase lag model have signed frequencies
+    let planetary_frequencies = constant_phase_lag.response().excitation_frequencies(tidal_host_particle, particle, Tide::Planetary);
+    assert_eq!(planetary_frequencies.len(), 3 * 3 * 5);
+    assert!(planetary_frequencies.iter().any(|frequency| *frequency > 0.) && planetary_frequencies.iter().any(|frequency| *frequency < 0.));
+}
+
+#[test]
+fn host_and_orbiting_bodies_with_different_models() {
+    let planet = 1;
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let host = universe.hosts.index.tides;
+    for index in (0..universe.n_particles).filter(|index| *index != host) {
+        set_tidal_model(&mut universe, index, TidalModel::ConstantPhaseLag(ConstantPhaseLagParameters { quality_factor: 100., love_number: 0.3 }));
+    }
+    calculate_tides(&mut universe);
+    let acceleration = universe.particles[planet].tides.parameters.output.acceleration;
+    let host_torque = universe.particles[host].tides.parameters.output.dangular_momentum_dt;
+    assert!(norm(acceleration) > 0.);
+    assert!(norm(universe.particles[planet].tides.parameters.output.dangular_momentum_dt) > 0.);
+
+    // The tide raised on the host is computed with its own model (constant time lag)
+    assert!(norm(host_torque) > 0.);
+    let mut universe_without_stellar_tide = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    for index in (0..universe_without_stellar_tide.n_particles).filter(|index| *index != host) {
+        set_tidal_model(&mut universe_without_stellar_tide, index, TidalModel::ConstantPhaseLag(ConstantPhaseLagParameters { quality_factor: 100., love_number: 0.3 }));
+    }
+    universe_without_stellar_tide.particles[host].tides.parameters.internal.scaled_dissipation_factor = 0.;
+    set_tidal_model(&mut universe_without_stellar_tide, host, TidalModel::ConstantTimeLag(ConstantTimeLagParameters { dissipation_factor: 0., dissipation_factor_scale: 1., love_number: 0. }));
+    calculate_tides(&mut universe_without_stellar_tide);
+    assert_eq!(norm(universe_without_stellar_tide.particles[host].tides.parameters.output.dangular_momentum_dt), 0.);
+    let acceleration_without_stellar_tide = universe_without_stellar_tide.particles[planet].tides.parameters.output.acceleration;
+    assert!(norm(Axes { x: acceleration.x - acceleration_without_stellar_tide.x, y: acceleration.y - acceleration_without_stellar_tide.y, z: acceleration.z - acceleration_without_stellar_tide.z }) > 0.);
+}
+
+#[test]
+fn dissipated_power_from_the_planetary_tide_of_each_model() {
+    let planet = 1;
+    let creep_coplanar = TidalModel::CreepCoplanar(CreepCoplanarParameters { uniform_viscosity_coefficient: 1.0e10 });
+    let creep = TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 1.0e10 });
+    let constant_phase_lag = TidalModel::ConstantPhaseLag(ConstantPhaseLagParameters { quality_factor: 100., love_number: 0.3 });
+    for tidal_model in [creep_coplanar, creep, constant_phase_lag].iter() {
+        // Without stellar tide (null host viscosity or love number), the tidal force acting on the planet is due to the planetary tide
+        let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+        let host = universe.hosts.index.tides;
+        for index in (0..universe.n_particles).filter(|index| *index != host) {
+            set_tidal_model(&mut universe, index, *tidal_model);
+        }
+        let host_tidal_model = match tidal_model {
+            TidalModel::CreepCoplanar(_) => TidalModel::CreepCoplanar(CreepCoplanarParameters { uniform_viscosity_coefficient: 0. }),
+            TidalModel::ConstantPhaseLag(_) => TidalModel::ConstantPhaseLag(ConstantPhaseLagParameters { quality_factor: 100., love_number: 0. }),
+            _ => TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 0. }),
+        };
+        set_tidal_model(&mut universe, host, host_tidal_model);
+        calculate_tides(&mut universe);
+        universe.calculate_denergy_dt();
+
+        // - Work of the tidal force and torque: -F.v - T.w
+        let particle = &universe.particles[planet];
+        let acceleration = particle.tides.parameters.output.acceleration;
+        let torque = particle.tides.parameters.output.dangular_momentum_dt;
+        let velocity = particle.tides.coordinates.velocity;
+        let expected_denergy_dt = -particle.mass * (acceleration.x*velocity.x + acceleration.y*velocity.y + acceleration.z*velocity.z)
+                                    - (torque.x*particle.spin.x + torque.y*particle.spin.y + torque.z*particle.spin.z);
+        let denergy_dt = particle.tides.parameters.internal.denergy_dt;
+        assert!(expected_denergy_dt != 0.);
+        assert!((denergy_dt - expected_denergy_dt).abs() <= 1.0e-10 * expected_denergy_dt.abs(), "{}: {} vs {}", tidal_model.response().name(), denergy_dt, expected_denergy_dt);
+    }
+}
@@ -0,0 +1,140 @@
+extern crate posidonius;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+use posidonius::{TidalPair, TidalModel, TidesEffect, IgnoreGravityTerms};
+
+fn orbiting_tidal_model(universe: &posidonius::Universe, index: usize) -> TidalModel {
+    match universe.particles[index].tides.effect {
+        TidesEffect::OrbitingBody(tidal_model) => tidal_model,
+        _ => panic!("Particle {} is not an orbiting body!", index),
+    }
+}
+
+#[test]
+fn tidal_pairs_validation() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let tidal_model = orbiting_tidal_model(&universe, 2);
+    assert!(universe.add_tidal_pair(TidalPair::new(1, 2, tidal_model, tidal_model)).is_ok());
+    // Duplicated pair (in any order)
+    assert!(universe.add_tidal_pair(TidalPair::new(1, 2, tidal_model, tidal_model)).is_err());
+    assert!(universe.add_tidal_pair(TidalPair::new(2, 1, tidal_model, tidal_model)).is_err());
+    // Already computed with the central host
+    assert!(universe.add_tidal_pair(TidalPair::new(0, 3, tidal_model, tidal_model)).is_err());
+    // Same particle or out of range
+    assert!(universe.add_tidal_pair(TidalPair::new(3, 3, tidal_model, tidal_model)).is_err());
+    assert!(universe.add_tidal_pair(TidalPair::new(3, 7, tidal_model, tidal_model)).is_err());
+    assert_eq!(universe.tidal_pairs.len(), 1);
+}
+
+#[test]
+fn tidal_pairs_accelerations() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let (host, orbiting) = (1, 2);
+    universe.inertial_to_heliocentric();
+    universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+    let reference_particles = universe.particles;
+
+    let host_tidal_model = orbiting_tidal_model(&universe, host);
+    let orbiting_tidal_model = orbiting_tidal_model(&universe, orbiting);
+    universe.add_tidal_pair(TidalPair::new(host, orbiting, host_tidal_model, orbiting_tidal_model)).unwrap();
+    universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+
+    // Only the pair is affected and the total momentum is conserved (action-reaction)
+    let tidal_acceleration = |particle: &posidonius::Particle| {
+        let acceleration = particle.tides.parameters.output.acceleration;
+        [acceleration.x, acceleration.y, acceleration.z]
+    };
+    assert_ne!(tidal_acceleration(&universe.particles[orbiting]), tidal_acceleration(&reference_particles[orbiting]));
+    for index in [0, 3, 4].iter() {
+        assert_eq!(tidal_acceleration(&universe.particles[*index]), tidal_acceleration(&reference_particles[*index]));
+    }
+    for i in 0..3 {
+        let momentum_derivatives: Vec<f64> = universe.particles[..universe.n_particles].iter().map(|particle| particle.mass * tidal_acceleration(particle)[i]).collect();
+        let total: f64 = momentum_derivatives.iter().sum();
+        let largest = momentum_derivatives.iter().fold(0_f64, |largest, value| largest.max(value.abs()));
+        assert!(total.is_finite());
+        assert!(total.abs() <= 1e-10 * largest);
+    }
+}
+
+#[test]
+fn tidal_pairs_follow_removed_particles() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let tidal_model = orbiting_tidal_model(&universe, 2);
+    universe.add_tidal_pair(TidalPair::new(1, 2, tidal_model, tidal_model)).unwrap();
+    universe.add_tidal_pair(TidalPair::new(3, 4, tidal_model, tidal_model)).unwrap();
+
+    universe.remove_particle(2).unwrap();
+    assert_eq!(universe.tidal_pairs, vec![TidalPair::new(2, 3, tidal_model, tidal_model)]);
+}
+
+#[test]
+fn tidal_pairs_in_snapshot() {
+    let case_path = Path::new("tests/data/test_integrator-ias15/case.json");
+    let universe_integrator = posidonius::output::read_snapshot(case_path).unwrap();
+    let tidal_model = orbiting_tidal_model(universe_integrator.get_universe(), 2);
+    let tidal_pair = TidalPair::new(1, 2, tidal_model, tidal_model);
+    let patch = serde_json::json!({"universe": {"tidal_pairs": [serde_json::to_value(tidal_pair).unwrap()]}});
+    let universe_integrator = posidonius::inspect::edit_snapshot(universe_integrator.as_ref(), &patch).unwrap();
+    let universe_integrator = universe_integrator.as_any().downcast_ref::<posidonius::Ias15>().unwrap();
+
+    let _ = fs::create_dir("target/tests/");
+    for extension in ["json", "bin"].iter() {
+        let snapshot_filename = format!("target/tests/test_tidal_pairs-tidal_pairs_in_snapshot.{}", extension);
+        let snapshot_path = Path::new(&snapshot_filename);
+        posidonius::output::write_recovery_snapshot(snapshot_path, universe_integrator).unwrap();
+        let restored_universe_integrator = posidonius::output::restore_snapshot(snapshot_path).unwrap();
+        assert_eq!(restored_universe_integrator.get_universe().tidal_pairs, vec![tidal_pair]);
+        assert!(posidonius::inspect::inspect_snapshot(restored_universe_integrator.as_ref()).contains("Tidal pairs: 1-2"));
+        let _ = fs::remove_file(snapshot_path);
+    }
+}
+
+#[test]
+fn satellites_interact_with_their_reference() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let (planet, moon) = (1, 3);
+    universe.inertial_to_heliocentric();
+    universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+    let reference_particles = universe.particles;
+
+    universe.particles[moon].reference = posidonius::Reference::Particle(planet);
+    assert!(universe.validate(-1.).errors.iter().all(|error| !error.contains("orbits")));
+    let tidal_model = orbiting_tidal_model(&universe, moon);
+    assert!(universe.add_tidal_pair(TidalPair::new(planet, moon, tidal_model, tidal_model)).is_err());
+    universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+
+    // Tides and rotational flattening with the planet are added to the ones with the star
+    let tidal_acceleration = |particle: &posidonius::Particle| particle.tides.parameters.output.acceleration;
+    let rotational_flattening_acceleration = |particle: &posidonius::Particle| particle.rotational_flattening.parameters.output.acceleration;
+    for index in [planet, moon].iter() {
+        assert_ne!(tidal_acceleration(&universe.particles[*index]), tidal_acceleration(&reference_particles[*index]));
+        assert_ne!(rotational_flattening_acceleration(&universe.particles[*index]), rotational_flattening_acceleration(&reference_particles[*index]));
+    }
+    for index in [0, 2, 4].iter() {
+        assert_eq!(tidal_acceleration(&universe.particles[*index]), tidal_acceleration(&reference_particles[*index]));
+        assert_eq!(rotational_flattening_acceleration(&universe.particles[*index]), rotational_flattening_acceleration(&reference_particles[*index]));
+    }
+    let total_tidal_force_x: f64 = universe.particles[..universe.n_particles].iter().map(|particle| particle.mass * tidal_acceleration(particle).x).sum();
+    let largest_tidal_force_x = universe.particles[..universe.n_particles].iter().fold(0_f64, |largest, particle| largest.max((particle.mass * tidal_acceleration(particle).x).abs()));
+    assert!(total_tidal_force_x.abs() <= 1e-10 * largest_tidal_force_x);
+}
+
+#[test]
+fn secondary_tidal_host() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    // E.g., the secondary star of a binary also raises tides on (and dissipates the ones raised by) the other bodies
+    assert_eq!(universe.add_tidal_host(1), Ok(3));
+    assert!(universe.tidal_pairs.iter().all(|tidal_pair| tidal_pair.host == 1));
+    assert_eq!(universe.add_tidal_host(2), Ok(2));
+    assert_eq!(universe.add_tidal_host(2), Ok(0));
+    assert_eq!(universe.tidal_pairs.len(), 5);
+    // The central host already interacts with every orbiting body
+    assert!(universe.add_tidal_host(0).is_err());
+    assert!(universe.add_tidal_host(7).is_err());
+}
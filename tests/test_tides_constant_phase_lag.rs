@@ -0,0 +1,89 @@
+extern crate posidonius;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use posidonius::{TidalModel, TidesEffect, ConstantPhaseLagParameters, IgnoreGravityTerms};
+
+fn constant_phase_lag_universe(quality_factor: f64) -> posidonius::Universe {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    for particle in universe.particles[..universe.n_particles].iter_mut() {
+        let tidal_model = TidalModel::ConstantPhaseLag(ConstantPhaseLagParameters { quality_factor, love_number: 0.3 });
+        particle.tides.effect = match particle.tides.effect {
+            TidesEffect::CentralBody(_) => TidesEffect::CentralBody(tidal_model),
+            TidesEffect::OrbitingBody(_) => TidesEffect::OrbitingBody(tidal_model),
+            TidesEffect::Disabled => TidesEffect::Disabled,
+        };
+    }
+    universe.inertial_to_heliocentric();
+    universe
+}
+
+/// Set the spin of the particle along its orbital angular momentum, with the given ratio with
+/// respect to its (circular) orbital angular velocity
+fn set_aligned_spin(universe: &mut posidonius::Universe, index: usize, ratio: f64) {
+    let host_index = universe.hosts.index.tides;
+    let (host, particle) = (universe.particles[host_index], universe.particles[index]);
+    let position = [particle.inertial_position.x - host.inertial_position.x, particle.inertial_position.y - host.inertial_position.y, particle.inertial_position.z - host.inertial_position.z];
+    let velocity = [particle.inertial_velocity.x - host.inertial_velocity.x, particle.inertial_velocity.y - host.inertial_velocity.y, particle.inertial_velocity.z - host.inertial_velocity.z];
+    let distance_2 = position.iter().map(|value| value * value).sum::<f64>();
+    let orbital_angular_velocity = [
+        (position[1]*velocity[2] - position[2]*velocity[1]) / distance_2,
+        (position[2]*velocity[0] - position[0]*velocity[2]) / distance_2,
+        (position[0]*velocity[1] - position[1]*velocity[0]) / distance_2,
+    ];
+    let particle = &mut universe.particles[index];
+    particle.angular_momentum.x = particle.moment_of_inertia * ratio * orbital_angular_velocity[0];
+    particle.angular_momentum.y = particle.moment_of_inertia * ratio * orbital_angular_velocity[1];
+    particle.angular_momentum.z = particle.moment_of_inertia * ratio * orbital_angular_velocity[2];
+}
+
+fn spin_change(particle: &posidonius::Particle) -> f64 {
+    let dangular_momentum_dt = particle.tides.parameters.output.dangular_momentum_dt;
+    dangular_momentum_dt.x * particle.angular_momentum.x + dangular_momentum_dt.y * particle.angular_momentum.y + dangular_momentum_dt.z * particle.angular_momentum.z
+}
+
+#[test]
+fn constant_phase_lag_spin_evolves_towards_synchronization() {
+    let planet = 1;
+    for (ratio, spins_down) in [(10., true), (0.1, false)].iter() {
+        let mut universe = constant_phase_lag_universe(100.);
+        assert!(universe.validate(-1.).errors.iter().all(|error| !error.contains("quality factor")));
+        set_aligned_spin(&mut universe, planet, *ratio);
+        universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+        // The sign of the torque follows the sign of the tidal frequency
+        assert_eq!(spin_change(&universe.particles[planet]) < 0., *spins_down);
+        // Action-reaction
+        for i in 0..3 {
+            let tidal_forces: Vec<f64> = universe.particles[..universe.n_particles].iter().map(|particle| {
+                let acceleration = particle.tides.parameters.output.acceleration;
+                particle.mass * [acceleration.x, acceleration.y, acceleration.z][i]
+            }).collect();
+            let largest = tidal_forces.iter().fold(0_f64, |largest, value| largest.max(value.abs()));
+            assert!(largest > 0.);
+            assert!(tidal_forces.iter().sum::<f64>().abs() <= 1e-10 * largest);
+        }
+        universe.calculate_denergy_dt();
+        assert!(universe.particles[planet].tides.parameters.internal.denergy_dt.is_finite());
+    }
+}
+
+#[test]
+fn constant_phase_lag_dissipation_scales_with_inverse_quality_factor() {
+    let planet = 1;
+    let planetary_spin_change = |quality_factor: f64| {
+        let mut universe = constant_phase_lag_universe(quality_factor);
+        set_aligned_spin(&mut universe, planet, 10.);
+        universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+        spin_change(&universe.particles[planet])
+    };
+    let ratio = planetary_spin_change(10.) / planetary_spin_change(100.);
+    assert!((ratio - 10.).abs() < 1e-10);
+}
+
+#[test]
+fn constant_phase_lag_validation() {
+    let universe = constant_phase_lag_universe(0.);
+    assert!(universe.validate(-1.).errors.iter().any(|error| error.contains("quality factor")));
+}
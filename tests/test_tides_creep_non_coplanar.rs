@@ -0,0 +1,261 @@
+extern crate posidonius;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use std::collections::HashMap;
+use std::path::Path;
+use posidonius::{Axes, Particle, Tides, TidesEffect, TidalModel, TidalContext, KaulaFunctionsCache, Tide, IgnoreGravityTerms, Integrator};
+use posidonius::{RotationalFlattening, RotationalFlatteningEffect, RotationalFlatteningModel, OblateSpheroidParameters};
+use posidonius::{CreepParameters, CreepCoplanarParameters, ConstantTimeLagParameters};
+
+const HOST_MASS: f64 = 0.08;
+const PLANET_MASS: f64 = 3.0e-6;
+const SEMIMAJOR_AXIS: f64 = 0.02;
+
+fn mean_motion() -> f64 {
+    (posidonius::constants::K2 * (HOST_MASS + PLANET_MASS) / SEMIMAJOR_AXIS.powi(3)).sqrt()
+}
+
+/// Planet at the pericentre of an orbit around the host (rotated by the given rotation)
+fn host_and_planet(tidal_model: TidalModel, eccentricity: f64, inclination: f64, spin: Axes, rotation: &[[f64; 3]; 3]) -> (Particle, Particle) {
+    let gm = posidonius::constants::K2 * (HOST_MASS + PLANET_MASS);
+    let distance = SEMIMAJOR_AXIS * (1. - eccentricity);
+    let speed = (gm * (1. + eccentricity) / distance).sqrt();
+    let position = rotate(rotation, Axes{x: distance, y: 0., z: 0.});
+    let velocity = rotate(rotation, Axes{x: 0., y: speed * inclination.cos(), z: speed * inclination.sin()});
+    let mut host = Particle::new(HOST_MASS, 1.0e-3, 0.4, Axes{x: 0., y: 0., z: 0.}, Axes{x: 0., y: 0., z: 0.}, rotate(rotation, Axes{x: 0., y: 0., z: 3. * mean_motion()}));
+    let mut planet = Particle::new(PLANET_MASS, 4.26e-5, 0.57, position, velocity, rotate(rotation, spin));
+    host.tides = Tides::new(TidesEffect::CentralBody(tidal_model));
+    planet.tides = Tides::new(TidesEffect::OrbitingBody(tidal_model));
+    planet.tides.coordinates.position = position;
+    planet.tides.coordinates.velocity = velocity;
+    planet.tides.parameters.internal.distance = distance;
+    (host, planet)
+}
+
+fn calculate_tidal_force_and_torque(tidal_model: TidalModel, host: &mut Particle, planet: &mut Particle, tide: Tide) -> (Axes, Axes) {
+    let mut pair_dependent_scaled_dissipation_factor = HashMap::new();
+    let mut context = TidalContext {
+        pair_dependent_scaled_dissipation_factor: &mut pair_dependent_scaled_dissipation_factor,
+        love_number_tables: &[],
+        kaula_functions_cache: &mut KaulaFunctionsCache::default(),
+    };
+    let mut tidal_model = tidal_model;
+    tidal_model.response_mut().calculate_tidal_force_and_torque(host, planet, tide, &mut context)
+}
+
+fn rotate(rotation: &[[f64; 3]; 3], vector: Axes) -> Axes {
+    Axes{x: rotation[0][0] * vector.x + rotation[0][1] * vector.y + rotation[0][2] * vector.z,
+         y: rotation[1][0] * vector.x + rotation[1][1] * vector.y + rotation[1][2] * vector.z,
+         z: rotation[2][0] * vector.x + rotation[2][1] * vector.y + rotation[2][2] * vector.z}
+}
+
+fn rotation_around_x(angle: f64) -> [[f64; 3]; 3] {
+    [[1., 0., 0.], [0., angle.cos(), -angle.sin()], [0., angle.sin(), angle.cos()]]
+}
+
+fn norm(vector: Axes) -> f64 {
+    (vector.x.powi(2) + vector.y.powi(2) + vector.z.powi(2)).sqrt()
+}
+
+fn assert_close(value: Axes, expected: Axes, tolerance: f64) {
+    let difference = Axes{x: value.x - expected.x, y: value.y - expected.y, z: value.z - expected.z};
+    assert!(norm(difference) <= tolerance * norm(expected), "{:?} != {:?}", value, expected);
+}
+
+#[test]
+fn coplanar_limit() {
+    // Above the pseudo-synchronous rotation both implementations solve the same creep equation
+    let identity = rotation_around_x(0.);
+    let spin = Axes{x: 0., y: 0., z: 3. * mean_motion()};
+    for uniform_viscosity_coefficient in [11., 110., 1100.].iter() {
+        for eccentricity in [0., 0.05, 0.1].iter() {
+            let creep = TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: *uniform_viscosity_coefficient });
+            let creep_coplanar = TidalModel::CreepCoplanar(CreepCoplanarParameters { uniform_viscosity_coefficient: *uniform_viscosity_coefficient });
+            let (mut host, mut planet) = host_and_planet(creep, *eccentricity, 0., spin, &identity);
+            let (force, torque) = calculate_tidal_force_and_torque(creep, &mut host, &mut planet, Tide::Planetary);
+            let (mut host, mut planet) = host_and_planet(creep_coplanar, *eccentricity, 0., spin, &identity);
+            let (expected_force, expected_torque) = calculate_tidal_force_and_torque(creep_coplanar, &mut host, &mut planet, Tide::Planetary);
+            assert_close(force, expected_force, 1.0e-4);
+            assert_close(torque, expected_torque, 1.0e-4);
+            // The planet spins down
+            assert!(torque.z < 0.);
+        }
+    }
+}
+
+#[test]
+fn obliquity_and_inclination() {
+    let creep = TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 110. });
+    let identity = rotation_around_x(0.);
+    let obliquity = 0.5_f64;
+    let spin = Axes{x: 0., y: 3. * mean_motion() * obliquity.sin(), z: 3. * mean_motion() * obliquity.cos()};
+    for tide in [Tide::Planetary, Tide::Stellar].iter() {
+        let (mut host, mut planet) = host_and_planet(creep, 0.05, 0., spin, &identity);
+        let deformed_spin = match tide {
+            Tide::Planetary => planet.spin,
+            Tide::Stellar => host.spin,
+        };
+        let (force, torque) = calculate_tidal_force_and_torque(creep, &mut host, &mut planet, *tide);
+        assert!(norm(force) > 0.);
+        // The deformed body spins down
+        assert!(torque.x * deformed_spin.x + torque.y * deformed_spin.y + torque.z * deformed_spin.z < 0.);
+        if let Tide::Planetary = tide {
+            // The torque is not aligned with the orbit normal, thus the obliquity evolves
+            assert!(torque.x.abs() + torque.y.abs() > 1.0e-3 * torque.z.abs());
+        }
+    }
+
+    // Only the relative orientation of the orbit and the spin matters: an inclined orbit around a
+    // spin along the z-axis is equivalent to the obliquity of a coplanar orbit
+    let (mut host, mut planet) = host_and_planet(creep, 0.05, 0., spin, &identity);
+    let (force, torque) = calculate_tidal_force_and_torque(creep, &mut host, &mut planet, Tide::Planetary);
+    let rotation = rotation_around_x(obliquity);
+    let (mut host, mut planet) = host_and_planet(creep, 0.05, 0., spin, &rotation);
+    let (rotated_force, rotated_torque) = calculate_tidal_force_and_torque(creep, &mut host, &mut planet, Tide::Planetary);
+    assert!(planet.spin.y.abs() < 1.0e-12 * planet.spin.z && planet.tides.coordinates.velocity.z > 0.);
+    assert_close(rotated_force, rotate(&rotation, force), 1.0e-9);
+    assert_close(rotated_torque, rotate(&rotation, torque), 1.0e-9);
+}
+
+#[test]
+fn rotational_flattening_of_a_fluid_body() {
+    // The creep rotational flattening is the one of an oblate spheroid with the fluid Love number of
+    // a homogeneous body (3/2), also for spins that are not aligned with the orbit normal
+    let universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let mut outputs = Vec::new();
+    for rotational_flattening_model in [RotationalFlatteningModel::OblateSpheroid(OblateSpheroidParameters { love_number: 1.5 }), RotationalFlatteningModel::Creep(CreepParameters { uniform_viscosity_coefficient: 110. })].iter() {
+        let mut universe = universe.clone();
+        for particle in universe.particles[..universe.n_particles].iter_mut() {
+            let effect = match particle.rotational_flattening.effect {
+                RotationalFlatteningEffect::CentralBody(_) => RotationalFlatteningEffect::CentralBody(*rotational_flattening_model),
+                RotationalFlatteningEffect::OrbitingBody(_) => RotationalFlatteningEffect::OrbitingBody(*rotational_flattening_model),
+                RotationalFlatteningEffect::Disabled => RotationalFlatteningEffect::Disabled,
+            };
+            particle.rotational_flattening = RotationalFlattening::new(effect);
+            particle.spin = rotate(&rotation_around_x(0.3), particle.spin);
+            particle.angular_momentum = rotate(&rotation_around_x(0.3), particle.angular_momentum);
+        }
+        universe.inertial_to_heliocentric();
+        universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+        outputs.push(universe.particles[..universe.n_particles].iter().map(|particle| particle.rotational_flattening.parameters.output).collect::<Vec<_>>());
+    }
+    for (oblate_spheroid, creep) in outputs[0].iter().zip(outputs[1].iter()) {
+        assert!(norm(creep.acceleration) > 0.);
+        assert_close(creep.acceleration, oblate_spheroid.acceleration, 1.0e-9);
+        assert_close(creep.dangular_momentum_dt, oblate_spheroid.dangular_momentum_dt, 1.0e-9);
+    }
+    assert!(norm(outputs[1][0].dangular_momentum_dt) > 0.);
+}
+
+/// Orbital energy of the two bodies and rotational energy of the planet
+fn orbital_and_planet_rotational_energy(universe: &posidonius::Universe) -> f64 {
+    // Two-body energy with the reduced mass (the heliocentric kinetic energy is not conserved)
+    let (host, planet) = (&universe.particles[0], &universe.particles[1]);
+    let reduced_mass = host.mass * planet.mass / (host.mass + planet.mass);
+    let velocity = Axes{x: planet.inertial_velocity.x - host.inertial_velocity.x, y: planet.inertial_velocity.y - host.inertial_velocity.y, z: planet.inertial_velocity.z - host.inertial_velocity.z};
+    let position = Axes{x: planet.inertial_position.x - host.inertial_position.x, y: planet.inertial_position.y - host.inertial_position.y, z: planet.inertial_position.z - host.inertial_position.z};
+    let orbital_energy = 0.5 * reduced_mass * norm(velocity).powi(2) - host.mass_g * planet.mass / norm(position);
+    orbital_energy + 0.5 * (planet.angular_momentum.x * planet.spin.x + planet.angular_momentum.y * planet.spin.y + planet.angular_momentum.z * planet.spin.z)
+}
+
+/// Energy dissipated per unit of time in the planet at the current positions and velocities
+fn planet_denergy_dt(universe: &posidonius::Universe, current_time: f64) -> f64 {
+    let mut universe = universe.clone();
+    universe.inertial_to_heliocentric();
+    universe.calculate_additional_effects(current_time, false, true, true, IgnoreGravityTerms::None);
+    universe.calculate_denergy_dt();
+    universe.particles[1].tides.parameters.internal.denergy_dt
+}
+
+#[test]
+fn energy_balance() {
+    // Only tides raised on the planet, on an eccentric orbit with a spin that is not aligned with the orbit normal
+    let obliquity = 0.5_f64;
+    let spin = Axes{x: 0., y: 3. * mean_motion() * obliquity.sin(), z: 3. * mean_motion() * obliquity.cos()};
+    let (mut host, planet) = host_and_planet(TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 110. }), 0.1, 0., spin, &rotation_around_x(0.));
+    host.tides = Tides::new(TidesEffect::CentralBody(TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 0. })));
+    let consider_effects = posidonius::ConsiderEffects {
+        tides: true,
+        rotational_flattening: false,
+        general_relativity: false,
+        disk: false,
+        wind: false,
+        evolution: false,
+    };
+    let orbital_period = 2. * posidonius::constants::PI / mean_motion();
+    let time_step = orbital_period / 200.;
+    let time_limit = 2. * orbital_period;
+    // Universe of the test case with only the host and the planet
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    universe.time_limit = time_limit;
+    // Barycentric inertial coordinates as Universe::new would compute them
+    let total_mass = host.mass + planet.mass;
+    for (i, particle) in [host, planet].iter().enumerate() {
+        let mut particle = *particle;
+        particle.id = i;
+        particle.inertial_position = Axes{x: particle.heliocentric_position.x - planet.mass / total_mass * planet.heliocentric_position.x,
+                                          y: particle.heliocentric_position.y - planet.mass / total_mass * planet.heliocentric_position.y,
+                                          z: particle.heliocentric_position.z - planet.mass / total_mass * planet.heliocentric_position.z};
+        particle.inertial_velocity = Axes{x: particle.heliocentric_velocity.x - planet.mass / total_mass * planet.heliocentric_velocity.x,
+                                          y: particle.heliocentric_velocity.y - planet.mass / total_mass * planet.heliocentric_velocity.y,
+                                          z: particle.heliocentric_velocity.z - planet.mass / total_mass * planet.heliocentric_velocity.z};
+        universe.particles[i] = particle;
+    }
+    universe.n_particles = 2;
+    universe.consider_effects = consider_effects;
+    universe.check_consistency();
+    universe.calculate_roche_radiuses();
+    let mut universe_integrator = posidonius::Ias15::new(time_step, time_limit, time_limit, universe);
+    universe_integrator.initialize_physical_values();
+    let mut universe_history_writer = posidonius::output::get_universe_history_writer(Path::new("/tmp/delete_me.dump"), 0);
+
+    let initial_energy = orbital_and_planet_rotational_energy(&universe_integrator.universe);
+    let mut current_time = universe_integrator.get_current_time();
+    let mut denergy_dt = planet_denergy_dt(&universe_integrator.universe, current_time);
+    let mut dissipated_energy = 0.;
+    loop {
+        // Small steps for an accurate integration of the dissipated power (IAS15 would increase them)
+        universe_integrator.set_time_step(time_step);
+        if universe_integrator.iterate(&mut universe_history_writer, &mut [], true).is_err() {
+            break;
+        }
+        let next_time = universe_integrator.get_current_time();
+        let next_denergy_dt = planet_denergy_dt(&universe_integrator.universe, next_time);
+        dissipated_energy += 0.5 * (denergy_dt + next_denergy_dt) * (next_time - current_time);
+        current_time = next_time;
+        denergy_dt = next_denergy_dt;
+    }
+    let energy_variation = orbital_and_planet_rotational_energy(&universe_integrator.universe) - initial_energy;
+
+    // The energy lost by the orbit and the spin is dissipated in the planet
+    assert!(dissipated_energy > 0.);
+    assert!((energy_variation + dissipated_energy).abs() <= 1.0e-2 * dissipated_energy, "{} vs {}", -energy_variation, dissipated_energy);
+}
+
+#[test]
+fn uniform_viscosity_coefficient_consistency() {
+    let creep = TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 110. });
+    let (_, mut planet) = host_and_planet(creep, 0.05, 0., Axes{x: 0., y: 0., z: mean_motion()}, &rotation_around_x(0.));
+    let rotational_flattening = |rotational_flattening_model| RotationalFlattening::new(RotationalFlatteningEffect::OrbitingBody(rotational_flattening_model));
+    planet.rotational_flattening = rotational_flattening(RotationalFlatteningModel::Creep(CreepParameters { uniform_viscosity_coefficient: 110. }));
+    assert!(planet.validate_uniform_viscosity_coefficient().is_ok());
+    planet.rotational_flattening = rotational_flattening(RotationalFlatteningModel::Creep(CreepParameters { uniform_viscosity_coefficient: 120. }));
+    assert!(planet.validate_uniform_viscosity_coefficient().is_err());
+    planet.rotational_flattening = rotational_flattening(RotationalFlatteningModel::CreepCoplanar(CreepCoplanarParameters { uniform_viscosity_coefficient: 110. }));
+    assert!(planet.validate_uniform_viscosity_coefficient().is_err());
+    planet.rotational_flattening = rotational_flattening(RotationalFlatteningModel::OblateSpheroid(OblateSpheroidParameters { love_number: 1.5 }));
+    assert!(planet.validate_uniform_viscosity_coefficient().is_err());
+    planet.tides = Tides::new(TidesEffect::OrbitingBody(TidalModel::ConstantTimeLag(ConstantTimeLagParameters { dissipation_factor: 0., dissipation_factor_scale: 1., love_number: 0.3 })));
+    assert!(planet.validate_uniform_viscosity_coefficient().is_ok());
+}
+
+#[test]
+#[should_panic]
+fn mixed_creep_models() {
+    let creep = TidalModel::Creep(CreepParameters { uniform_viscosity_coefficient: 110. });
+    let (_, mut planet) = host_and_planet(creep, 0.05, 0., Axes{x: 0., y: 0., z: mean_motion()}, &rotation_around_x(0.));
+    planet.set_rotational_flattening(RotationalFlattening::new(RotationalFlatteningEffect::OrbitingBody(RotationalFlatteningModel::CreepCoplanar(CreepCoplanarParameters { uniform_viscosity_coefficient: 110. }))));
+}
@@ -46,8 +46,8 @@ fn enabled_planet_tides_case() -> posidonius::WHFast {
     let star_tides = disabled_tides;
     // Load planet data
     let planet_file = "./input/love_numbers/TRAPPIST-1_Earth-like/Results_Trappist1_b_Fe_90_Si_02_670K_freq_Imk2_posidonius.txt";
-    let planet_tidal_model_params = common::load_kaula_parameters(planet_file).unwrap();
-    let planet_tides = posidonius::Tides::new(posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::Kaula(planet_tidal_model_params)));
+    let planet_love_number_data = common::load_love_number_data(planet_file).unwrap();
+    let planet_tides = posidonius::Tides::new(posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::Kaula(posidonius::KaulaParameters {})));
     //
     if let Some((star, planets)) = particles.split_first_mut() {
         // Change from constant time lag to kaula:
@@ -57,7 +57,10 @@ fn enabled_planet_tides_case() -> posidonius::WHFast {
         }
     }
     //////////////////////////////////////////////////////////////////////////////////
-    let universe = posidonius::Universe::new(initial_time, time_limit, particles, consider_effects);
+    let mut universe = posidonius::Universe::new(initial_time, time_limit, particles, consider_effects);
+    for planet_index in 1..universe.n_particles {
+        universe.set_love_number_table(posidonius::LoveNumberTable::new(planet_index, planet_love_number_data.clone(), posidonius::LoveNumberInterpolation::Linear)).unwrap();
+    }
 
     let alternative_coordinates_type = posidonius::whfast::CoordinatesType::Jacobi;
     //let alternative_coordinates_type = posidonius::whfast::CoordinatesType::DemocraticHeliocentric;
@@ -134,12 +137,12 @@ fn enabled_both_tides_case() -> posidonius::WHFast {
     //////////////////////////////////////////////////////////////////////////////////
     // Load star data
     let star_file = "./input/love_numbers/Aurelie_Stellar/alpha0.51600_P1p2_Ek1p5em6.txt";
-    let star_tidal_model_params = common::load_kaula_parameters(star_file).unwrap();
-    let star_tides = posidonius::Tides::new(posidonius::TidesEffect::CentralBody(posidonius::TidalModel::Kaula(star_tidal_model_params)));
+    let star_love_number_data = common::load_love_number_data(star_file).unwrap();
+    let star_tides = posidonius::Tides::new(posidonius::TidesEffect::CentralBody(posidonius::TidalModel::Kaula(posidonius::KaulaParameters {})));
     // Load planet data
     let planet_file = "./input/love_numbers/TRAPPIST-1_Earth-like/Results_Trappist1_b_Fe_90_Si_02_670K_freq_Imk2_posidonius.txt";
-    let planet_tidal_model_params = common::load_kaula_parameters(planet_file).unwrap();
-    let planet_tides = posidonius::Tides::new(posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::Kaula(planet_tidal_model_params)));
+    let planet_love_number_data = common::load_love_number_data(planet_file).unwrap();
+    let planet_tides = posidonius::Tides::new(posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::Kaula(posidonius::KaulaParameters {})));
     //
     if let Some((star, planets)) = particles.split_first_mut() {
         // Change from constant time lag to kaula:
@@ -149,7 +152,11 @@ fn enabled_both_tides_case() -> posidonius::WHFast {
         }
     }
     //////////////////////////////////////////////////////////////////////////////////
-    let universe = posidonius::Universe::new(initial_time, time_limit, particles, consider_effects);
+    let mut universe = posidonius::Universe::new(initial_time, time_limit, particles, consider_effects);
+    universe.set_love_number_table(posidonius::LoveNumberTable::new(0, star_love_number_data, posidonius::LoveNumberInterpolation::Linear)).unwrap();
+    for planet_index in 1..universe.n_particles {
+        universe.set_love_number_table(posidonius::LoveNumberTable::new(planet_index, planet_love_number_data.clone(), posidonius::LoveNumberInterpolation::Linear)).unwrap();
+    }
 
     let alternative_coordinates_type = posidonius::whfast::CoordinatesType::Jacobi;
     //let alternative_coordinates_type = posidonius::whfast::CoordinatesType::DemocraticHeliocentric;
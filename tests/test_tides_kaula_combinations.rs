@@ -0,0 +1,142 @@
+extern crate posidonius;
+#[macro_use]
+extern crate serde_derive;
+
+mod common;
+
+use posidonius::{KaulaParameters, Axes, TidesEffect, TidalModel, ConstantTimeLagParameters, IgnoreGravityTerms};
+use posidonius::{InteriorStructure, RheologicalLayer, Rheology};
+use posidonius::constants::{AU, G};
+
+/// Kaula model with the Love numbers of a homogeneous Maxwell body with the radius of the particle
+fn set_kaula(universe: &mut posidonius::Universe, index: usize) {
+    let tidal_model = TidalModel::Kaula(KaulaParameters {});
+    let particle = &mut universe.particles[index];
+    particle.tides.effect = match particle.tides.effect {
+        TidesEffect::CentralBody(_) => TidesEffect::CentralBody(tidal_model),
+        TidesEffect::OrbitingBody(_) => TidesEffect::OrbitingBody(tidal_model),
+        TidesEffect::Disabled => TidesEffect::Disabled,
+    };
+    let layers = vec![RheologicalLayer { outer_radius: particle.radius * AU, density: 5500., rigidity: 8.0e10, viscosity: 1.0e19, bulk_modulus: 0., rheology: Rheology::Maxwell }];
+    universe.set_interior_structure(InteriorStructure::new(index, layers)).unwrap();
+}
+
+fn calculate_tides(universe: &mut posidonius::Universe) {
+    universe.inertial_to_heliocentric();
+    universe.calculate_additional_effects(0., false, true, true, IgnoreGravityTerms::None);
+}
+
+fn norm(vector: Axes) -> f64 {
+    (vector.x.powi(2) + vector.y.powi(2) + vector.z.powi(2)).sqrt()
+}
+
+/// Tidal forces and torques are finite and the tidal forces cancel out (action-reaction)
+fn assert_consistent_tides(universe: &posidonius::Universe) {
+    let particles = &universe.particles[..universe.n_particles];
+    for particle in particles.iter() {
+        let output = particle.tides.parameters.output;
+        assert!(norm(output.acceleration).is_finite(), "Particle {}: {:?}", particle.id, output.acceleration);
+        assert!(norm(output.dangular_momentum_dt).is_finite(), "Particle {}: {:?}", particle.id, output.dangular_momentum_dt);
+    }
+    for i in 0..3 {
+        let tidal_forces: Vec<f64> = particles.iter().map(|particle| {
+            let acceleration = particle.tides.parameters.output.acceleration;
+            particle.mass * [acceleration.x, acceleration.y, acceleration.z][i]
+        }).collect();
+        let largest = tidal_forces.iter().fold(0_f64, |largest, value| largest.max(value.abs()));
+        assert!(largest > 0.);
+        assert!(tidal_forces.iter().sum::<f64>().abs() <= 1e-10 * largest);
+    }
+}
+
+#[test]
+fn kaula_planet_with_constant_time_lag_star() {
+    let planet = 1;
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    set_kaula(&mut universe, planet);
+    calculate_tides(&mut universe);
+    assert_consistent_tides(&universe);
+    let acceleration = universe.particles[planet].tides.parameters.output.acceleration;
+    assert!(norm(universe.particles[planet].tides.parameters.output.dangular_momentum_dt) > 0.);
+
+    // The stellar tide of the constant time lag model is included
+    let mut universe_without_stellar_tide = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    set_kaula(&mut universe_without_stellar_tide, planet);
+    let host = universe_without_stellar_tide.hosts.index.tides;
+    universe_without_stellar_tide.particles[host].tides.effect = TidesEffect::CentralBody(TidalModel::ConstantTimeLag(ConstantTimeLagParameters { dissipation_factor: 0., dissipation_factor_scale: 1., love_number: 0. }));
+    calculate_tides(&mut universe_without_stellar_tide);
+    let acceleration_without_stellar_tide = universe_without_stellar_tide.particles[planet].tides.parameters.output.acceleration;
+    assert!(norm(acceleration_without_stellar_tide) > 0.);
+    assert!(norm(Axes { x: acceleration.x - acceleration_without_stellar_tide.x, y: acceleration.y - acceleration_without_stellar_tide.y, z: acceleration.z - acceleration_without_stellar_tide.z }) > 0.);
+}
+
+#[test]
+fn kaula_star_with_constant_time_lag_planets() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let host = universe.hosts.index.tides;
+    set_kaula(&mut universe, host);
+    calculate_tides(&mut universe);
+    assert_consistent_tides(&universe);
+    let host_torque = universe.particles[host].tides.parameters.output.dangular_momentum_dt;
+    assert!(norm(host_torque) > 0.);
+
+    // The torque on the star adds the contribution of every planet
+    let mut single_planet_torques = Axes { x: 0., y: 0., z: 0. };
+    for planet in 1..universe.n_particles {
+        let mut universe_with_one_planet = common::universe::initialized_universe_from_json("test_integrator-ias15");
+        set_kaula(&mut universe_with_one_planet, host);
+        for other in (1..universe_with_one_planet.n_particles).filter(|other| *other != planet) {
+            universe_with_one_planet.particles[other].tides.effect = TidesEffect::Disabled;
+        }
+        calculate_tides(&mut universe_with_one_planet);
+        let torque = universe_with_one_planet.particles[host].tides.parameters.output.dangular_momentum_dt;
+        single_planet_torques.x += torque.x;
+        single_planet_torques.y += torque.y;
+        single_planet_torques.z += torque.z;
+    }
+    let difference = Axes { x: host_torque.x - single_planet_torques.x, y: host_torque.y - single_planet_torques.y, z: host_torque.z - single_planet_torques.z };
+    assert!(norm(difference) <= 1e-10 * norm(host_torque));
+}
+
+#[test]
+fn kaula_star_and_planets_without_host_spin() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    for index in 0..universe.n_particles {
+        set_kaula(&mut universe, index);
+    }
+    calculate_tides(&mut universe);
+    assert_consistent_tides(&universe);
+
+    let host = universe.hosts.index.tides;
+    let particle = &mut universe.particles[host];
+    particle.angular_momentum = Axes { x: 0., y: 0., z: 0. };
+    particle.spin = Axes { x: 0., y: 0., z: 0. };
+    particle.norm_spin_vector_2 = 0.;
+    calculate_tides(&mut universe);
+    assert_consistent_tides(&universe);
+}
+
+#[test]
+fn kaula_eccentric_orbits() {
+    let mut universe = common::universe::initialized_universe_from_json("test_integrator-ias15");
+    let host = universe.hosts.index.tides;
+    for index in 0..universe.n_particles {
+        set_kaula(&mut universe, index);
+    }
+    // Highly eccentric orbits (e > 0.5), beyond the range of validity of truncated series expansions
+    let host_velocity = universe.particles[host].inertial_velocity;
+    for index in (0..universe.n_particles).filter(|index| *index != host) {
+        let particle = &mut universe.particles[index];
+        particle.inertial_velocity = Axes {
+            x: host_velocity.x + 1.25 * (particle.inertial_velocity.x - host_velocity.x),
+            y: host_velocity.y + 1.25 * (particle.inertial_velocity.y - host_velocity.y),
+            z: host_velocity.z + 1.25 * (particle.inertial_velocity.z - host_velocity.z),
+        };
+    }
+    calculate_tides(&mut universe);
+    let planet = &universe.particles[1];
+    let gm = G * (universe.particles[host].mass + planet.mass);
+    let eccentricity = posidonius::tools::calculate_keplerian_orbital_elements(gm, planet.heliocentric_position, planet.heliocentric_velocity).2;
+    assert!(eccentricity > 0.5, "{}", eccentricity);
+    assert_consistent_tides(&universe);
+}
@@ -0,0 +1,54 @@
+extern crate posidonius;
+
+use std::path::Path;
+
+#[test]
+fn valid_case() {
+    let universe_integrator = posidonius::output::read_snapshot(Path::new("tests/data/test_evolution-solar_like_baraffe2015/case.json")).unwrap();
+    let report = universe_integrator.get_universe().validate(universe_integrator.get_time_step());
+    assert!(report.is_valid(), "{:?}", report.errors);
+}
+
+#[test]
+fn invalid_case() {
+    let mut universe_integrator = posidonius::output::read_snapshot(Path::new("tests/data/test_evolution-solar_like_baraffe2015/case.json")).unwrap();
+    let time_step = 100.*universe_integrator.get_time_step();
+    {
+        let universe = universe_integrator.get_universe_mut();
+        universe.hosts.index.tides = 1; // Inconsistent host
+        universe.particles[0].evolution = posidonius::EvolutionType::Baraffe2015(0.95); // Mass without evolution track
+        universe.time_limit = 3.0e12; // Beyond the evolution track of the planet
+    }
+    let report = universe_integrator.get_universe().validate(time_step);
+    assert!(!report.is_valid());
+    assert!(report.errors.iter().any(|error| error.starts_with("Inconsistent hosts: the central body for tidal effects")));
+    assert!(report.errors.iter().any(|error| error.contains("does not support a mass of 0.95 Msun")));
+    assert!(report.errors.iter().any(|error| error.starts_with("Particle 2 time limit")));
+    assert!(report.warnings.iter().any(|warning| warning.starts_with("Time step is too coarse")));
+}
+
+#[test]
+fn incompatible_models() {
+    let mut universe_integrator = posidonius::output::read_snapshot(Path::new("tests/data/test_evolution-solar_like_baraffe2015/case.json")).unwrap();
+    let time_step = universe_integrator.get_time_step();
+    {
+        let universe = universe_integrator.get_universe_mut();
+        // The evolving Love number of LeconteChabrier2013 is only used by the constant time lag model
+        universe.particles[2].tides.effect = posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::ConstantPhaseLag(posidonius::ConstantPhaseLagParameters{ quality_factor: 100., love_number: 0.38 }));
+        // Creep coplanar models with a spin that is not aligned with the z axis
+        let creep_coplanar = posidonius::CreepCoplanarParameters{ uniform_viscosity_coefficient: 1.0e10 };
+        universe.particles[1].tides.effect = posidonius::TidesEffect::OrbitingBody(posidonius::TidalModel::CreepCoplanar(creep_coplanar));
+        universe.particles[1].rotational_flattening.effect = posidonius::RotationalFlatteningEffect::OrbitingBody(posidonius::RotationalFlatteningModel::CreepCoplanar(creep_coplanar));
+    }
+    let report = universe_integrator.get_universe().validate(time_step);
+    assert_eq!(report.errors.len(), 1, "{:?}", report.errors);
+    assert!(report.errors[0].starts_with("Particle 2: the evolution type LeconteChabrier2013(false) evolves the Love number"));
+    assert!(report.warnings.iter().any(|warning| warning.starts_with("Particle 1 uses the CreepCoplanar model but its spin is tilted")));
+}
+
+#[test]
+fn evolution_track_errors() {
+    // Reported instead of panicking
+    assert!(posidonius::Evolver::new(posidonius::EvolutionType::Baraffe2015(0.95), 0., 365.25e6).is_err());
+    assert!(posidonius::Evolver::new(posidonius::EvolutionType::Leconte2011(0.09), 0., 365.25e6).is_err());
+}